@@ -0,0 +1,32 @@
+//! Compares looking elements up by name in the index of `Elements` with scanning them, the way
+//! blocks stored them in a `Vec` before, on blocks of growing width.
+
+use criterion::{ black_box, criterion_group, criterion_main, BenchmarkId, Criterion };
+use bem::{ parse, BEMBlock };
+
+fn wide_block(width: usize) -> BEMBlock {
+	let elements: Vec<String> = (0..width).map(|i| format!("element-{}[modifier]", i)).collect();
+
+	parse(&format!("media-player\n{}", elements.join("\n"))).unwrap()
+}
+
+fn lookup(c: &mut Criterion) {
+	let mut group = c.benchmark_group("element lookup");
+
+	for width in [10, 100, 1000] {
+		let bem_block = wide_block(width);
+		let names: Vec<String> = bem_block.elements.iter().map(|element| element.name.clone()).collect();
+
+		group.bench_with_input(BenchmarkId::new("index", width), &names, |b, names| {
+			b.iter(|| names.iter().filter(|name| bem_block.elements.get(black_box(name)).is_some()).count())
+		});
+		group.bench_with_input(BenchmarkId::new("scan", width), &names, |b, names| {
+			b.iter(|| names.iter().filter(|name| bem_block.elements.iter().any(|element| &element.name == *black_box(name))).count())
+		});
+	}
+
+	group.finish();
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+//! Audits every block of a design system against the others and prints a Markdown report of the
+//! modifiers spelled differently across blocks, the elements named like other blocks, and the
+//! names longer than the team allows.
+//!
+//! ```text
+//! $ cargo run --example audit_report -- fixtures/examples/design 16
+//! ```
+
+use std::env;
+use std::path::Path;
+use bem::{ parse_dir, validate_with_options, ValidationOptions };
+
+/// Returns the report of the blocks of `dir`, and the number of findings.
+fn audit(dir: &Path, max_name_length: Option<usize>) -> Result<(String, usize), bem::Error> {
+	let document = parse_dir(dir)?;
+	let options = ValidationOptions { max_name_length, ..ValidationOptions::default() };
+	let mut report = format!("# Audit of {} blocks\n", document.blocks.len());
+	let mut count = 0;
+
+	for (position, bem_block) in document.blocks.iter().enumerate() {
+		let peers: Vec<_> = document.blocks
+			.iter()
+			.enumerate()
+			.filter(|(peer, _)| *peer != position)
+			.map(|(_, peer)| peer.clone())
+			.collect();
+		let audit = bem_block.to_bem_design_audit(&peers);
+		let mut findings = Vec::new();
+
+		for modifier in &audit.inconsistent_modifier_names {
+			findings.push(format!("modifier `{}` is spelled differently by other blocks", modifier));
+		}
+		for element in &audit.duplicate_element_names {
+			findings.push(format!("element `{}` is also the name of a block", element));
+		}
+		for error in validate_with_options(bem_block, &options) {
+			findings.push(error.to_string());
+		}
+
+		report.push_str(&format!("\n## {}\n\n", bem_block.name));
+
+		if findings.is_empty() {
+			report.push_str("No findings\n");
+		}
+		for finding in &findings {
+			report.push_str(&format!("- {}\n", finding));
+		}
+
+		count += findings.len();
+	}
+
+	Ok((report, count))
+}
+
+fn main() {
+	let args: Vec<String> = env::args().skip(1).collect();
+
+	let Some(dir) = args.first() else {
+		eprintln!("usage: audit_report DESIGN_DIR [MAX_NAME_LENGTH]");
+		std::process::exit(2);
+	};
+	let max_name_length = args.get(1).and_then(|max| max.parse().ok());
+
+	match audit(Path::new(dir), max_name_length) {
+		Ok((report, count)) => {
+			print!("{}", report);
+
+			if count > 0 {
+				std::process::exit(1);
+			}
+		}
+		Err(e) => {
+			eprintln!("An error occurred: {}", e);
+			std::process::exit(1);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+	use super::audit;
+
+	#[test]
+	fn test_audit_fixtures() {
+		let (report, count) = audit(Path::new("fixtures/examples/design"), Some(16)).unwrap();
+
+		assert_eq!(count, 4);
+		insta::assert_snapshot!(report);
+	}
+
+	#[test]
+	fn test_audit_without_length_limit() {
+		let (report, count) = audit(Path::new("fixtures/examples/design"), None).unwrap();
+
+		assert_eq!(count, 3);
+		assert!(report.ends_with("## search-box\n\nNo findings\n"));
+	}
+}
@@ -0,0 +1,92 @@
+//! Converts every `.bem` file of a directory into a file of another format, one output file per
+//! input, and prints what was written.
+//!
+//! ```text
+//! $ cargo run --example batch_convert -- fixtures/examples/design out/ scss
+//! ```
+
+use std::env;
+use std::fs;
+use std::path::{ Path, PathBuf };
+use bem::pipeline::{ run, RunOptions, StdIo, StdinReader };
+
+/// Returns the extension of the files written in `format`.
+fn extension(format: &str) -> &str {
+	match format {
+		"json" | "json-with-ids" | "jcs" => "json",
+		"classes" | "safelist" => "txt",
+		format => format,
+	}
+}
+
+/// Converts the `.bem` files of `input` into `out_dir`, returning the paths written in file name
+/// order.
+fn convert(input: &Path, out_dir: &Path, format: &str) -> Result<Vec<PathBuf>, bem::Error> {
+	let mut paths: Vec<PathBuf> = fs::read_dir(input)?
+		.map(|entry| entry.map(|entry| entry.path()))
+		.collect::<Result<_, _>>()?;
+	let mut outputs = Vec::new();
+
+	paths.retain(|path| path.extension().is_some_and(|extension| extension == "bem"));
+	paths.sort();
+	fs::create_dir_all(out_dir)?;
+
+	for path in paths {
+		let stem = path.file_stem().unwrap_or_default();
+		let options = RunOptions {
+			input: Some(path.clone()),
+			out: Some(out_dir.join(stem).with_extension(extension(format))),
+			format: format.to_string(),
+			..RunOptions::default()
+		};
+
+		outputs.extend(run(&options, &mut StdIo { stdin: StdinReader, in_place: false })?.outputs);
+	}
+
+	Ok(outputs)
+}
+
+fn main() {
+	let args: Vec<String> = env::args().skip(1).collect();
+
+	let [input, out_dir, rest @ ..] = args.as_slice() else {
+		eprintln!("usage: batch_convert INPUT_DIR OUT_DIR [FORMAT]");
+		std::process::exit(2);
+	};
+
+	match convert(Path::new(input), Path::new(out_dir), rest.first().map_or("json", String::as_str)) {
+		Ok(outputs) => outputs.iter().for_each(|output| println!("{}", output.display())),
+		Err(e) => {
+			eprintln!("An error occurred: {}", e);
+			std::process::exit(1);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::path::Path;
+	use tempfile::tempdir;
+	use super::convert;
+
+	#[test]
+	fn test_convert_fixtures() {
+		let dir = tempdir().unwrap();
+		let outputs = convert(Path::new("fixtures/examples/design"), dir.path(), "scss").unwrap();
+		let names: Vec<_> = outputs.iter().map(|output| output.file_name().unwrap().to_str().unwrap()).collect();
+
+		assert_eq!(names, vec!["media-player.scss", "play-list.scss", "search-box.scss"]);
+		assert!(fs::read_to_string(&outputs[1]).unwrap().contains("\t.play-list__item {\n\t\t&--playing { }"));
+	}
+
+	#[test]
+	fn test_convert_round_trips_json() {
+		let dir = tempdir().unwrap();
+		let outputs = convert(Path::new("fixtures/examples/design"), dir.path(), "json").unwrap();
+		let bem_block = bem::from_json(&fs::read_to_string(&outputs[0]).unwrap()).unwrap();
+
+		assert_eq!(bem_block, bem::parse_file("fixtures/examples/design/media-player.bem").unwrap());
+		assert!(convert(Path::new("fixtures/missing"), dir.path(), "json").is_err());
+	}
+}
@@ -0,0 +1,23 @@
+import React from "react";
+import classNames from 'classnames';
+import { PlayList } from "./PlayList";
+
+// Sizes are picked at runtime, e.g. "media-player--small".
+type Size = "small" | "large";
+
+export function MediaPlayer(props: { dark?: boolean; mode?: string; size: Size }) {
+	return (
+		<div className={classNames("media-player", { "media-player--dark": props.dark })}>
+			<button
+				className={classNames('media-player__button', {
+					'media-player__button--fast-forward': props.mode === "fast-forward",
+					'media-player__button--rewind': props.mode === "rewind",
+				})}
+			/>
+			<div className="media-player__timeline media-player__timeline--live" />
+			<div className={`media-player__volume media-player__volume--muted`} />
+			<div className={`media-player__screen media-player__screen--${props.size}`} />
+			<PlayList className="play-list play-list--compact" />
+		</div>
+	);
+}
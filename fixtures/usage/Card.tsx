@@ -0,0 +1,10 @@
+import clsx from "clsx";
+
+export function Card({ active, size }: { active: boolean; size: string }) {
+	return (
+		<article className={clsx("card", { "card--active": active })}>
+			<h2 className="card__title">Title</h2>
+			<p className={`card__body--${size}`} />
+		</article>
+	);
+}
@@ -0,0 +1,146 @@
+//! This module suggests accessibility attributes for BEM elements, based on what their names
+//! say about their role in the component.
+
+use std::collections::HashMap;
+use crate::models::BEMBlock;
+
+/// The built-in suggestions, keyed by element name (or the last dash-separated word of it).
+const DEFAULT_ROLE_MAP: &[(&str, &[&str])] = &[
+	("button", &["aria-label", "aria-pressed"]),
+	("toggle", &["aria-label", "aria-pressed"]),
+	("link", &["aria-label", "aria-current"]),
+	("menu", &["role=menu", "aria-expanded"]),
+	("dialog", &["role=dialog", "aria-modal", "aria-labelledby"]),
+	("modal", &["role=dialog", "aria-modal", "aria-labelledby"]),
+	("tab", &["role=tab", "aria-selected", "aria-controls"]),
+	("tabs", &["role=tablist"]),
+	("panel", &["role=tabpanel", "aria-labelledby"]),
+	("input", &["aria-label", "aria-invalid", "aria-describedby"]),
+	("list", &["role=list"]),
+	("item", &["role=listitem"]),
+	("image", &["aria-label", "role=img"]),
+	("icon", &["aria-hidden"]),
+	("progress", &["role=progressbar", "aria-valuenow", "aria-valuemin", "aria-valuemax"]),
+	("timeline", &["role=slider", "aria-valuenow", "aria-valuemin", "aria-valuemax"]),
+	("slider", &["role=slider", "aria-valuenow", "aria-valuemin", "aria-valuemax"]),
+	("alert", &["role=alert"]),
+	("tooltip", &["role=tooltip"]),
+];
+
+/// Customizes the suggestions made by [`BEMBlock::to_bem_a11y_props_with_config`].
+///
+/// Entries of `element_role_map` are looked up by exact element name and take precedence over
+/// the built-in heuristics.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use bem::A11yPropsConfig;
+///
+/// let config = A11yPropsConfig {
+///     element_role_map: HashMap::from([("button".to_string(), vec!["aria-describedby".to_string()])]),
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct A11yPropsConfig {
+	pub element_role_map: HashMap<String, Vec<String>>,
+}
+
+impl BEMBlock {
+	/// Suggests ARIA attributes for each element of the block.
+	///
+	/// Suggestions come from built-in heuristics: an element named like a known widget (e.g.
+	/// `button`, `menu`, `dialog`, `tab`), or whose name ends with one (e.g. `play-button`),
+	/// gets that widget's attributes. Roles are written as `role=<role>`. Elements the
+	/// heuristics know nothing about get `aria-label`.
+	///
+	/// # Returns
+	///
+	/// * `HashMap<String, Vec<String>>` - The suggested attributes, keyed by element name.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let bem_block = parse("media-player\nbutton\nmenu").unwrap();
+	/// let props = bem_block.to_bem_a11y_props();
+	///
+	/// assert_eq!(props["button"], vec!["aria-label", "aria-pressed"]);
+	/// assert_eq!(props["menu"], vec!["role=menu", "aria-expanded"]);
+	/// ```
+	pub fn to_bem_a11y_props(&self) -> HashMap<String, Vec<String>> {
+		self.to_bem_a11y_props_with_config(&A11yPropsConfig::default())
+	}
+
+	/// Suggests ARIA attributes for each element of the block, consulting `config` before the
+	/// built-in heuristics of [`to_bem_a11y_props`](BEMBlock::to_bem_a11y_props).
+	pub fn to_bem_a11y_props_with_config(&self, config: &A11yPropsConfig) -> HashMap<String, Vec<String>> {
+		self.elements
+			.iter()
+			.map(|element| {
+				let attributes = match config.element_role_map.get(&element.name) {
+					Some(attributes) => attributes.clone(),
+					None => default_attributes(&element.name),
+				};
+
+				(element.name.clone(), attributes)
+			})
+			.collect()
+	}
+}
+
+fn default_attributes(element_name: &str) -> Vec<String> {
+	let last_word = element_name.rsplit('-').next().unwrap_or(element_name);
+	let lookup = |name: &str| {
+		DEFAULT_ROLE_MAP.iter()
+			.find(|(key, _)| *key == name)
+			.map(|(_, attributes)| attributes)
+	};
+
+	match lookup(element_name).or_else(|| lookup(last_word)) {
+		Some(attributes) =>
+			attributes
+				.iter()
+				.map(|attribute| attribute.to_string())
+				.collect(),
+		None => vec!["aria-label".to_string()],
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use super::A11yPropsConfig;
+	use crate::parse;
+
+	#[test]
+	fn test_to_bem_a11y_props() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline\nplay-button\ncover").unwrap();
+		let props = bem_block.to_bem_a11y_props();
+
+		assert_eq!(props.len(), 4);
+		assert!(props["button"].contains(&"aria-label".to_string()));
+		assert_eq!(props["play-button"], props["button"]);
+		assert_eq!(props["timeline"][0], "role=slider");
+		assert_eq!(props["cover"], vec!["aria-label"]);
+	}
+
+	#[test]
+	fn test_to_bem_a11y_props_without_elements() {
+		assert!(parse("media-player").unwrap().to_bem_a11y_props().is_empty());
+	}
+
+	#[test]
+	fn test_to_bem_a11y_props_with_config() {
+		let bem_block = parse("media-player\nbutton\nmenu").unwrap();
+		let config = A11yPropsConfig {
+			element_role_map: HashMap::from([("button".to_string(), vec!["aria-describedby".to_string()])]),
+		};
+		let props = bem_block.to_bem_a11y_props_with_config(&config);
+
+		assert_eq!(props["button"], vec!["aria-describedby"]);
+		assert_eq!(props["menu"], vec!["role=menu", "aria-expanded"]);
+	}
+}
@@ -0,0 +1,202 @@
+//! This module shortens the segments of long names before they are turned into identifiers,
+//! following the abbreviation conventions of a team, e.g. `navigation` into `nav`.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use serde::{ Deserialize, Serialize };
+use crate::error::{ Error, Result };
+
+/// Returned when an abbreviation would never apply or would erase its segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbbreviationError {
+	/// The segment being abbreviated.
+	pub segment: String,
+	/// Its abbreviation.
+	pub abbreviation: String,
+}
+
+impl fmt::Display for AbbreviationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.abbreviation.is_empty() {
+			write!(f, "the abbreviation of `{}` is empty", self.segment)
+		} else {
+			write!(f, "`{}` is not a single name segment, so `{}` would never replace it", self.segment, self.abbreviation)
+		}
+	}
+}
+
+impl std::error::Error for AbbreviationError {}
+
+/// Abbreviations of name segments, applied by generators of identifiers before casing, so
+/// `navigation-drawer` becomes `navDrawer` with `navigation → nav`.
+///
+/// Names are split into segments at every character that is not alphanumeric, like for casing,
+/// and only whole segments that are keys of the map are replaced, case-sensitively: `button` is
+/// abbreviated in `toggle-button`, never in `buttons`. Class names are never abbreviated, and
+/// names that end up with the same identifier fail with an
+/// [`IdentifierError`](crate::IdentifierError) naming both, except for React Native keys, which
+/// are numbered instead.
+///
+/// The map deserializes from a JSON object, e.g. the file passed to `--abbreviations`, and is
+/// used by [`to_theme_keys_ts_with_abbreviations`](crate::to_theme_keys_ts_with_abbreviations),
+/// [`to_jsx_with_abbreviations`](crate::to_jsx_with_abbreviations),
+/// [`BEMBlock::to_bem_native_module_with_abbreviations`](crate::BEMBlock::to_bem_native_module_with_abbreviations),
+/// and [`TestIdOptions::abbreviations`](crate::TestIdOptions::abbreviations), and by the
+/// `theme-ts`, `jsx` and `react-native` formats through
+/// [`EmitContext::abbreviations`](crate::EmitContext::abbreviations).
+///
+/// # Example
+///
+/// ```
+/// use bem::AbbreviationMap;
+///
+/// let mut abbreviations = AbbreviationMap::default();
+/// abbreviations.insert("navigation", "nav").unwrap();
+/// abbreviations.insert("button", "btn").unwrap();
+///
+/// assert_eq!(abbreviations.abbreviate("navigation-drawer button"), "nav-drawer btn");
+/// assert_eq!(abbreviations.abbreviate("buttons"), "buttons");
+/// assert!(abbreviations.insert("toggle-button", "tgl").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "BTreeMap<String, String>", into = "BTreeMap<String, String>")]
+pub struct AbbreviationMap {
+	segments: BTreeMap<String, String>,
+}
+
+impl AbbreviationMap {
+	/// Abbreviates `segment` as `abbreviation` from now on. Fails if `segment` is not a single,
+	/// non-empty run of alphanumeric characters, or `abbreviation` is empty.
+	pub fn insert(&mut self, segment: &str, abbreviation: &str) -> std::result::Result<(), AbbreviationError> {
+		if segment.is_empty() || !segment.chars().all(char::is_alphanumeric) || abbreviation.is_empty() {
+			return Err(AbbreviationError { segment: segment.to_string(), abbreviation: abbreviation.to_string() });
+		}
+
+		self.segments.insert(segment.to_string(), abbreviation.to_string());
+		Ok(())
+	}
+
+	/// Returns the abbreviation of `segment`, if any.
+	pub fn get(&self, segment: &str) -> Option<&str> {
+		self.segments.get(segment).map(String::as_str)
+	}
+
+	/// Returns whether the map abbreviates nothing.
+	pub fn is_empty(&self) -> bool {
+		self.segments.is_empty()
+	}
+
+	/// Replaces every whole segment of `name` that has an abbreviation, keeping the separators.
+	/// Returns `name` itself, without allocating, if nothing is abbreviated.
+	pub fn abbreviate<'a>(&self, name: &'a str) -> Cow<'a, str> {
+		if self.is_empty() || !segments(name).any(|segment| self.segments.contains_key(segment)) {
+			return Cow::Borrowed(name);
+		}
+
+		let mut abbreviated = String::with_capacity(name.len());
+		let mut rest = name;
+
+		while let Some(start) = rest.find(char::is_alphanumeric) {
+			let end = rest[start..].find(|c: char| !c.is_alphanumeric()).map_or(rest.len(), |end| start + end);
+			let segment = &rest[start..end];
+
+			abbreviated.push_str(&rest[..start]);
+			abbreviated.push_str(self.get(segment).unwrap_or(segment));
+			rest = &rest[end..];
+		}
+
+		abbreviated.push_str(rest);
+		Cow::Owned(abbreviated)
+	}
+
+	/// Reads a map from a JSON object of segments and their abbreviations, e.g.
+	/// `{"navigation": "nav"}`.
+	///
+	/// # Example
+	///
+	/// ```
+	/// let abbreviations = bem::AbbreviationMap::from_json("{\"navigation\": \"nav\"}").unwrap();
+	///
+	/// assert_eq!(abbreviations.get("navigation"), Some("nav"));
+	/// assert!(bem::AbbreviationMap::from_json("{\"navigation\": \"\"}").is_err());
+	/// ```
+	pub fn from_json(json: &str) -> Result<AbbreviationMap> {
+		serde_json::from_str(json).map_err(Error::Deserialize)
+	}
+}
+
+impl TryFrom<BTreeMap<String, String>> for AbbreviationMap {
+	type Error = AbbreviationError;
+
+	fn try_from(segments: BTreeMap<String, String>) -> std::result::Result<Self, Self::Error> {
+		let mut map = AbbreviationMap::default();
+
+		for (segment, abbreviation) in &segments {
+			map.insert(segment, abbreviation)?;
+		}
+
+		Ok(map)
+	}
+}
+
+impl From<AbbreviationMap> for BTreeMap<String, String> {
+	fn from(map: AbbreviationMap) -> Self {
+		map.segments
+	}
+}
+
+/// Returns the segments of `name`: its runs of alphanumeric characters.
+fn segments(name: &str) -> impl Iterator<Item = &str> {
+	name.split(|c: char| !c.is_alphanumeric()).filter(|segment| !segment.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::borrow::Cow;
+	use serde::Deserialize;
+	use super::AbbreviationMap;
+
+	fn abbreviations() -> AbbreviationMap {
+		AbbreviationMap::from_json("{\"navigation\": \"nav\", \"button\": \"btn\", \"collapsible\": \"collapse\"}").unwrap()
+	}
+
+	#[test]
+	fn test_abbreviate_whole_segments_only() {
+		let abbreviations = abbreviations();
+
+		assert_eq!(abbreviations.abbreviate("navigation-drawer-collapsible-section"), "nav-drawer-collapse-section");
+		assert_eq!(abbreviations.abbreviate("--button__button"), "--btn__btn");
+		assert_eq!(abbreviations.abbreviate("buttons-navigational"), "buttons-navigational");
+		assert_eq!(abbreviations.abbreviate("Button"), "Button");
+		assert!(matches!(abbreviations.abbreviate("drawer"), Cow::Borrowed("drawer")));
+		assert!(matches!(AbbreviationMap::default().abbreviate("button"), Cow::Borrowed("button")));
+	}
+
+	#[test]
+	fn test_insert_rejects_segments_that_never_match() {
+		let mut abbreviations = AbbreviationMap::default();
+
+		assert_eq!(abbreviations.insert("fast-forward", "ff").unwrap_err().to_string(), "`fast-forward` is not a single name segment, so `ff` would never replace it");
+		assert_eq!(abbreviations.insert("button", "").unwrap_err().to_string(), "the abbreviation of `button` is empty");
+		assert!(abbreviations.insert("", "x").is_err());
+		assert!(abbreviations.is_empty());
+	}
+
+	#[test]
+	fn test_load_from_config() {
+		#[derive(Deserialize)]
+		struct Config {
+			#[serde(default)]
+			abbreviations: AbbreviationMap,
+		}
+
+		let config: Config = serde_json::from_str("{\"abbreviations\": {\"navigation\": \"nav\"}, \"format\": \"ts\"}").unwrap();
+		let error = serde_json::from_str::<Config>("{\"abbreviations\": {\"nav-bar\": \"nb\"}}").err().unwrap();
+
+		assert_eq!(config.abbreviations.get("navigation"), Some("nav"));
+		assert!(serde_json::from_str::<Config>("{}").unwrap().abbreviations.is_empty());
+		assert!(error.to_string().contains("`nav-bar` is not a single name segment"));
+		assert_eq!(serde_json::to_string(&abbreviations()).unwrap(), "{\"button\":\"btn\",\"collapsible\":\"collapse\",\"navigation\":\"nav\"}");
+	}
+}
@@ -0,0 +1,204 @@
+//! This module reads and writes BEM files without blocking, for async applications running on
+//! Tokio. It mirrors [`parse_file`](crate::parse_file), [`parse_dir`](crate::parse_dir), and
+//! [`write_json`](crate::write_json): only the file access goes through `tokio::fs`, parsing and
+//! serialization are shared with them, and every error has the path of the file attached.
+//!
+//! Requires the `tokio` feature.
+
+use std::path::{ Path, PathBuf };
+use tokio::fs;
+use crate::error::{ Error, Result };
+use crate::files::{ bem_files, json_source, parse_source };
+use crate::models::{ BEMBlock, BEMDocument };
+
+/// Reads and parses a `.bem` file, see [`parse_file`](crate::parse_file).
+///
+/// # Arguments
+///
+/// * `path`: AsRef<Path> - The file to parse.
+///
+/// # Returns
+///
+/// * `Result<BEMBlock>` - The parsed block, or an [`Error::File`] holding the path and the read
+///   or parse error.
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("media-player.bem");
+/// std::fs::write(&path, "media-player[dark]").unwrap();
+///
+/// assert_eq!(bem::aio::parse_file(&path).await.unwrap(), bem::parse("media-player[dark]").unwrap());
+/// # }
+/// ```
+pub async fn parse_file(path: impl AsRef<Path>) -> Result<BEMBlock> {
+	let path = path.as_ref();
+	let bem_input = fs::read_to_string(path).await.map_err(|error| Error::from(error).in_file(path))?;
+
+	parse_source(path, &bem_input)
+}
+
+/// Parses every `.bem` file of a directory into a document, in file name order, see
+/// [`parse_dir`](crate::parse_dir).
+///
+/// # Arguments
+///
+/// * `path`: AsRef<Path> - The directory to parse.
+///
+/// # Returns
+///
+/// * `Result<BEMDocument>` - One block per file, or an [`Error::File`] for the directory or the
+///   first file that could not be read or parsed.
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let dir = tempfile::tempdir().unwrap();
+/// std::fs::write(dir.path().join("play-list.bem"), "play-list").unwrap();
+/// std::fs::write(dir.path().join("media-player.bem"), "media-player").unwrap();
+///
+/// let document = bem::aio::parse_dir(dir.path()).await.unwrap();
+///
+/// assert_eq!(document.blocks[0].name, "media-player");
+/// assert_eq!(document.blocks[1].name, "play-list");
+/// # }
+/// ```
+pub async fn parse_dir(path: impl AsRef<Path>) -> Result<BEMDocument> {
+	let path = path.as_ref();
+	let (entries, dirs) = read_dir(path).await.map_err(|error| Error::from(error).in_file(path))?;
+	let mut blocks = Vec::new();
+
+	for file in bem_files(entries, |entry| dirs.iter().any(|dir| dir == entry)) {
+		blocks.push(parse_file(file).await?);
+	}
+
+	Ok(BEMDocument::from(blocks))
+}
+
+/// Writes a block to a file as JSON, see [`write_json`](crate::write_json).
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - The block to write.
+/// * `path`: AsRef<Path> - The file to create or overwrite.
+///
+/// # Returns
+///
+/// * `Result<()>` - Nothing, or an [`Error::File`] holding the path and the serialization or
+///   write error.
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("media-player.json");
+/// let bem_block = bem::parse("media-player").unwrap();
+///
+/// bem::aio::write_json(&bem_block, &path).await.unwrap();
+///
+/// assert_eq!(bem::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap(), bem_block);
+/// # }
+/// ```
+pub async fn write_json(bem_block: &BEMBlock, path: impl AsRef<Path>) -> Result<()> {
+	let path = path.as_ref();
+	let json = json_source(bem_block, path)?;
+
+	fs::write(path, json).await.map_err(|error| Error::from(error).in_file(path))
+}
+
+/// Lists the entries of the directory at `path`, and which of them are directories, following
+/// symbolic links like [`Path::is_dir`].
+async fn read_dir(path: &Path) -> std::io::Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+	let mut entries = Vec::new();
+	let mut dirs = Vec::new();
+	let mut listing = fs::read_dir(path).await?;
+
+	while let Some(entry) = listing.next_entry().await? {
+		let entry = entry.path();
+
+		if fs::metadata(&entry).await.is_ok_and(|metadata| metadata.is_dir()) {
+			dirs.push(entry.clone());
+		}
+
+		entries.push(entry);
+	}
+
+	Ok((entries, dirs))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use tempfile::tempdir;
+	use super::{ parse_dir, parse_file, write_json };
+	use crate::error::Error;
+	use crate::parse;
+
+	#[tokio::test]
+	async fn test_parse_file() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("media-player.bem");
+		fs::write(&path, "media-player[dark]\nbutton").unwrap();
+
+		assert_eq!(parse_file(&path).await.unwrap(), crate::parse_file(&path).unwrap());
+
+		fs::write(&path, "media-player(dark)").unwrap();
+
+		assert_eq!(parse_file(&path).await.unwrap_err().to_string(), crate::parse_file(&path).unwrap_err().to_string());
+	}
+
+	#[tokio::test]
+	async fn test_parse_file_missing() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("missing.bem");
+		let error = parse_file(&path).await.unwrap_err();
+
+		assert!(matches!(error, Error::File { ref path, ref error } if path.ends_with("missing.bem") && matches!(**error, Error::Io(_))));
+		assert_eq!(error.to_string(), crate::parse_file(&path).unwrap_err().to_string());
+	}
+
+	#[tokio::test]
+	async fn test_parse_dir() {
+		let dir = tempdir().unwrap();
+		fs::write(dir.path().join("play-list.bem"), "play-list\nitem").unwrap();
+		fs::write(dir.path().join("media-player.bem"), "media-player[dark]").unwrap();
+		fs::write(dir.path().join("notes.txt"), "media-player(dark)").unwrap();
+		fs::create_dir(dir.path().join("nested.bem")).unwrap();
+
+		let document = parse_dir(dir.path()).await.unwrap();
+
+		assert_eq!(document.blocks, vec![parse("media-player[dark]").unwrap(), parse("play-list\nitem").unwrap()]);
+		assert_eq!(document, crate::parse_dir(dir.path()).unwrap());
+	}
+
+	#[tokio::test]
+	async fn test_parse_dir_missing() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("missing");
+		let error = parse_dir(&path).await.unwrap_err();
+
+		assert!(matches!(error, Error::File { .. }));
+		assert_eq!(error.to_string(), crate::parse_dir(&path).unwrap_err().to_string());
+	}
+
+	#[tokio::test]
+	async fn test_write_json() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("media-player.json");
+		let bem_block = parse("media-player[dark]\nbutton").unwrap();
+
+		write_json(&bem_block, &path).await.unwrap();
+		let written = fs::read_to_string(&path).unwrap();
+		crate::write_json(&bem_block, &path).unwrap();
+
+		assert_eq!(written, fs::read_to_string(&path).unwrap());
+		assert!(matches!(write_json(&bem_block, dir.path().join("missing/media-player.json")).await, Err(Error::File { .. })));
+	}
+}
@@ -0,0 +1,108 @@
+//! This module expresses modifiers as data attributes instead of modifier classes. Blocks and
+//! elements keep their classes, so `media-player__button--rewind` becomes the
+//! `media-player__button` class with a `data-rewind` attribute, or with `data-state="rewind"`.
+
+use std::collections::BTreeMap;
+use crate::ident::css_escape;
+
+/// How modifiers are written as attributes.
+///
+/// # Example
+///
+/// ```
+/// use bem::AttributeScheme;
+///
+/// assert_eq!(AttributeScheme::Boolean.selector("rewind"), "[data-rewind]");
+/// assert_eq!(AttributeScheme::KeyValue("data-state".to_string()).selector("dark"), "[data-state~=\"dark\"]");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeScheme {
+	/// One boolean attribute per modifier, named `data-` followed by the modifier, e.g.
+	/// `data-rewind`.
+	Boolean,
+	/// A single attribute, e.g. `data-state`, whose value lists the active modifiers separated
+	/// by spaces, e.g. `data-state="dark"`. Selectors match one word of the value, so any number
+	/// of modifiers can be active at once.
+	KeyValue(String),
+}
+
+impl AttributeScheme {
+	/// Returns the attribute selector matching an entity with `modifier`, e.g. `[data-rewind]`.
+	pub fn selector(&self, modifier: &str) -> String {
+		match self {
+			AttributeScheme::Boolean => format!("[data-{}]", css_escape(modifier)),
+			AttributeScheme::KeyValue(attribute) => format!("[{}~=\"{}\"]", css_escape(attribute), css_string(modifier)),
+		}
+	}
+
+	/// Returns the attributes of an entity with the given modifiers, mapping attribute names to
+	/// values. Boolean attributes have an empty value. Repeated modifiers are only listed once.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::AttributeScheme;
+	///
+	/// let modifiers = vec!["dark".to_string(), "compact".to_string()];
+	/// let attributes = AttributeScheme::KeyValue("data-state".to_string()).attributes(&modifiers);
+	///
+	/// assert_eq!(attributes["data-state"], "dark compact");
+	/// ```
+	pub fn attributes(&self, modifiers: &[String]) -> BTreeMap<String, String> {
+		let mut unique: Vec<&str> = Vec::new();
+
+		for modifier in modifiers {
+			if !unique.contains(&modifier.as_str()) {
+				unique.push(modifier);
+			}
+		}
+
+		match self {
+			AttributeScheme::Boolean => unique
+				.iter()
+				.map(|modifier| (format!("data-{}", modifier), String::new()))
+				.collect(),
+			AttributeScheme::KeyValue(_) if unique.is_empty() => BTreeMap::new(),
+			AttributeScheme::KeyValue(attribute) => BTreeMap::from([(attribute.clone(), unique.join(" "))]),
+		}
+	}
+}
+
+/// Escapes the contents of a double-quoted CSS string.
+fn css_string(text: &str) -> String {
+	text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+	use super::AttributeScheme;
+
+	fn modifiers(names: &[&str]) -> Vec<String> {
+		names.iter().map(|name| name.to_string()).collect()
+	}
+
+	#[test]
+	fn test_selector() {
+		let state = AttributeScheme::KeyValue("data-state".to_string());
+
+		assert_eq!(AttributeScheme::Boolean.selector("fast-forward"), "[data-fast-forward]");
+		assert_eq!(AttributeScheme::Boolean.selector("dark@mobile"), "[data-dark\\@mobile]");
+		assert_eq!(state.selector("fast-forward"), "[data-state~=\"fast-forward\"]");
+		assert_eq!(state.selector("a\"b"), "[data-state~=\"a\\\"b\"]");
+	}
+
+	#[test]
+	fn test_attributes() {
+		let names = modifiers(&["rewind", "fast-forward", "rewind"]);
+
+		assert_eq!(AttributeScheme::Boolean.attributes(&names), BTreeMap::from([
+			("data-fast-forward".to_string(), String::new()),
+			("data-rewind".to_string(), String::new()),
+		]));
+		assert_eq!(AttributeScheme::KeyValue("data-state".to_string()).attributes(&names), BTreeMap::from([
+			("data-state".to_string(), "rewind fast-forward".to_string()),
+		]));
+		assert!(AttributeScheme::KeyValue("data-state".to_string()).attributes(&[]).is_empty());
+	}
+}
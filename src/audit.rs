@@ -0,0 +1,157 @@
+//! This module compares BEM blocks against the rest of a design system to find naming
+//! inconsistencies that are only visible across blocks.
+
+use crate::models::BEMBlock;
+use crate::validator::{ validate, ValidationError };
+
+/// The findings of a cross-block design audit, produced by [`BEMBlock::to_bem_design_audit`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DesignAudit {
+	/// Modifiers of the audited block that peer blocks spell differently, e.g. `Disabled`
+	/// when peers use `disabled`, or `fastforward` when peers use `fast-forward`.
+	pub inconsistent_modifier_names: Vec<String>,
+	/// Element names of the audited block that are also the name of a peer block.
+	pub duplicate_element_names: Vec<String>,
+	/// Naming convention violations of the audited block itself, see [`validate`].
+	pub naming_convention_violations: Vec<ValidationError>,
+}
+
+impl DesignAudit {
+	/// Returns `true` when the audit found nothing to report.
+	pub fn is_clean(&self) -> bool {
+		self.inconsistent_modifier_names.is_empty() &&
+			self.duplicate_element_names.is_empty() &&
+			self.naming_convention_violations.is_empty()
+	}
+}
+
+impl BEMBlock {
+	/// Audits this block's naming against the other blocks of a design system.
+	///
+	/// Two modifier names are considered spelling variants of each other when they are equal
+	/// after lowercasing and dropping everything but letters and digits. A modifier of this
+	/// block (or of one of its elements) is reported when a peer uses a different variant of it.
+	/// Each finding is reported once, in the order it first appears in this block.
+	///
+	/// # Arguments
+	///
+	/// * `peer_blocks`: &[BEMBlock] - The other blocks of the design system to compare against.
+	///
+	/// # Returns
+	///
+	/// * `DesignAudit` - The inconsistencies found.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let peers = vec![parse("button[disabled]").unwrap()];
+	/// let mut bem_block = parse("input").unwrap();
+	/// bem_block.modifiers.push("Disabled".into());
+	///
+	/// let audit = bem_block.to_bem_design_audit(&peers);
+	///
+	/// assert_eq!(audit.inconsistent_modifier_names, vec!["Disabled".to_string()]);
+	/// ```
+	pub fn to_bem_design_audit(&self, peer_blocks: &[BEMBlock]) -> DesignAudit {
+		let peer_modifiers: Vec<&String> = peer_blocks
+			.iter()
+			.flat_map(|peer| {
+				peer.modifiers.iter().chain(peer.elements.iter().flat_map(|element| &element.modifiers))
+			})
+			.map(|modifier| &modifier.name)
+			.collect();
+
+		let mut inconsistent_modifier_names = Vec::new();
+		let own_modifiers = self.modifiers
+			.iter()
+			.chain(self.elements.iter().flat_map(|element| &element.modifiers))
+			.map(|modifier| &modifier.name);
+
+		for modifier in own_modifiers {
+			let key = spelling_key(modifier);
+			let is_inconsistent = peer_modifiers
+				.iter()
+				.any(|peer_modifier| *peer_modifier != modifier && spelling_key(peer_modifier) == key);
+
+			if is_inconsistent && !inconsistent_modifier_names.contains(modifier) {
+				inconsistent_modifier_names.push(modifier.clone());
+			}
+		}
+
+		let mut duplicate_element_names = Vec::new();
+
+		for element in &self.elements {
+			let is_duplicate = peer_blocks.iter().any(|peer| peer.name == element.name);
+
+			if is_duplicate && !duplicate_element_names.contains(&element.name) {
+				duplicate_element_names.push(element.name.clone());
+			}
+		}
+
+		DesignAudit {
+			inconsistent_modifier_names,
+			duplicate_element_names,
+			naming_convention_violations: validate(self),
+		}
+	}
+}
+
+fn spelling_key(name: &str) -> String {
+	name.chars()
+		.filter(|c| c.is_alphanumeric())
+		.flat_map(char::to_lowercase)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::models::BEMBlock;
+	use crate::parse;
+	use crate::validator::ValidationErrorKind;
+
+	fn peers() -> Vec<BEMBlock> {
+		vec![
+			parse("button[disabled,fast-forward]\nicon").unwrap(),
+			parse("input[disabled]\nlabel[disabled]").unwrap()
+		]
+	}
+
+	#[test]
+	fn test_design_audit_clean() {
+		let bem_block = parse("media-player[disabled]\nbutton-group[fast-forward]").unwrap();
+		let audit = bem_block.to_bem_design_audit(&peers());
+
+		assert!(audit.is_clean());
+	}
+
+	#[test]
+	fn test_design_audit_flags_modifier_casing() {
+		let mut bem_block = parse("media-player").unwrap();
+		bem_block.modifiers.push("Disabled".into());
+
+		let audit = bem_block.to_bem_design_audit(&peers());
+
+		assert_eq!(audit.inconsistent_modifier_names, vec!["Disabled".to_string()]);
+		assert_eq!(audit.naming_convention_violations.len(), 1);
+		assert_eq!(audit.naming_convention_violations[0].kind, ValidationErrorKind::Uppercase);
+	}
+
+	#[test]
+	fn test_design_audit_flags_spelling_variants_once() {
+		let bem_block = parse("media-player[fastforward]\ncontrols[fastforward]").unwrap();
+		let audit = bem_block.to_bem_design_audit(&peers());
+
+		assert_eq!(audit.inconsistent_modifier_names, vec!["fastforward".to_string()]);
+		assert!(audit.naming_convention_violations.is_empty());
+	}
+
+	#[test]
+	fn test_design_audit_flags_elements_named_like_blocks() {
+		let bem_block = parse("media-player\nbutton\ntimeline\ninput").unwrap();
+		let audit = bem_block.to_bem_design_audit(&peers());
+
+		assert_eq!(audit.duplicate_element_names, vec!["button".to_string(), "input".to_string()]);
+	}
+}
@@ -0,0 +1,302 @@
+//! This module keeps generated classes within length limits, for targets like email templating
+//! systems that reject long class names or `class` attributes.
+
+use std::collections::{ BTreeMap, HashSet };
+use std::fmt;
+use sha2::{ Digest, Sha256 };
+use crate::length::{ abbreviate_for_display, LengthMode, DISPLAY_LEN };
+
+/// The number of hex characters of the digest appended to abbreviated classes.
+const HASH_LEN: usize = 8;
+
+/// The shortest class an abbreviation fits in: one character of the class, a `-`, and the hash.
+const MIN_ABBREVIATED_LEN: usize = HASH_LEN + 2;
+
+/// Length limits for classes and `class` attributes, counted in `length_mode`.
+///
+/// Classes longer than `max_class_len` are rejected, or with `abbreviate` replaced by a
+/// deterministic abbreviation: the start of the class, a `-`, and the first 8 hex characters of
+/// the SHA-256 digest of the whole class, filling `max_class_len` characters. Separators at the
+/// end of the kept start are dropped, so the hash never reads as a modifier or element and the
+/// abbreviation may be a little shorter, as it is if the start would end within a grapheme
+/// cluster. Abbreviating needs a `max_class_len` of at least 10.
+///
+/// Within the classes of a document, see [`class_map`](ClassBudget::class_map), no two distinct
+/// classes abbreviate to the same string, nor to another class of the document: on a collision,
+/// the digest is salted until it is unique. `class` attributes are never shortened by dropping
+/// classes, so an attribute longer than `max_attr_len` after abbreviation is always an error.
+///
+/// # Example
+///
+/// ```
+/// use bem::ClassBudget;
+///
+/// let budget = ClassBudget { max_class_len: 20, max_attr_len: 255, abbreviate: true, length_mode: Default::default() };
+/// let map = budget.class_map(["media-player", "media-player__fast-forward-button"]).unwrap();
+///
+/// assert_eq!(map["media-player"], "media-player");
+/// assert_eq!(map["media-player__fast-forward-button"].len(), 20);
+/// assert!(map["media-player__fast-forward-button"].starts_with("media-playe-"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassBudget {
+	/// The longest class allowed.
+	pub max_class_len: usize,
+	/// The longest `class` attribute value allowed, with classes separated by spaces.
+	pub max_attr_len: usize,
+	/// Whether to abbreviate classes that are too long instead of failing.
+	pub abbreviate: bool,
+	/// How lengths are counted. Characters by default.
+	pub length_mode: LengthMode,
+}
+
+/// An error raised when classes exceed a [`ClassBudget`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetError {
+	/// A class is longer than `max_class_len`, and is not abbreviated.
+	ClassTooLong {
+		class: String,
+		len: usize,
+		max: usize,
+		mode: LengthMode,
+	},
+	/// A `class` attribute value is longer than `max_attr_len`.
+	AttributeTooLong {
+		value: String,
+		len: usize,
+		max: usize,
+		mode: LengthMode,
+	},
+}
+
+impl fmt::Display for BudgetError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			BudgetError::ClassTooLong { class, len, max, mode } => write!(
+				f,
+				"class `{}` is {} {} long, the maximum is {}",
+				abbreviate_for_display(class, DISPLAY_LEN),
+				len,
+				mode.unit(),
+				max
+			),
+			BudgetError::AttributeTooLong { value, len, max, mode } => write!(
+				f,
+				"class attribute `{}` is {} {} long, the maximum is {}",
+				abbreviate_for_display(value, DISPLAY_LEN),
+				len,
+				mode.unit(),
+				max
+			),
+		}
+	}
+}
+
+impl std::error::Error for BudgetError {}
+
+impl ClassBudget {
+	/// Maps every class of a document to the class to use within the budget: itself if it fits,
+	/// or its abbreviation. Abbreviations are unique within the map, see [`ClassBudget`].
+	///
+	/// # Arguments
+	///
+	/// * `classes`: IntoIterator - Every class of the document. Repeated classes are fine.
+	///
+	/// # Returns
+	///
+	/// * `Result<BTreeMap<String, String>, BudgetError>` - The classes to use, by original class,
+	///   or the first class in input order that is too long and cannot be abbreviated.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::{ BudgetError, ClassBudget };
+	///
+	/// let budget = ClassBudget { max_class_len: 10, max_attr_len: 255, abbreviate: false, length_mode: Default::default() };
+	///
+	/// assert_eq!(budget.class_map(["media-player"]), Err(BudgetError::ClassTooLong {
+	///     class: "media-player".to_string(),
+	///     len: 12,
+	///     max: 10,
+	///     mode: Default::default(),
+	/// }));
+	/// ```
+	pub fn class_map<I, S>(&self, classes: I) -> Result<BTreeMap<String, String>, BudgetError> where I: IntoIterator<Item = S>, S: AsRef<str> {
+		let mut map = BTreeMap::new();
+		let mut long = Vec::new();
+
+		for class in classes {
+			let class = class.as_ref();
+
+			let len = self.length_mode.len(class);
+
+			if len <= self.max_class_len {
+				map.insert(class.to_string(), class.to_string());
+			} else if !self.abbreviate || self.max_class_len < MIN_ABBREVIATED_LEN {
+				return Err(BudgetError::ClassTooLong {
+					class: class.to_string(),
+					len,
+					max: self.max_class_len,
+					mode: self.length_mode,
+				});
+			} else {
+				long.push(class.to_string());
+			}
+		}
+
+		// Abbreviating in sorted order keeps the salts, and so the map, independent of the order
+		// classes appear in.
+		long.sort();
+		long.dedup();
+
+		let mut taken: HashSet<String> = map.values().cloned().collect();
+
+		for class in long {
+			let abbreviation = (0..)
+				.map(|salt| self.abbreviation(&class, salt))
+				.find(|abbreviation| !taken.contains(abbreviation))
+				.expect("salting eventually yields an unused abbreviation");
+
+			taken.insert(abbreviation.clone());
+			map.insert(class, abbreviation);
+		}
+
+		Ok(map)
+	}
+
+	/// Fails if a `class` attribute value is longer than `max_attr_len`.
+	pub fn check_attribute(&self, value: &str) -> Result<(), BudgetError> {
+		let len = self.length_mode.len(value);
+
+		if len > self.max_attr_len {
+			return Err(BudgetError::AttributeTooLong { value: value.to_string(), len, max: self.max_attr_len, mode: self.length_mode });
+		}
+
+		Ok(())
+	}
+
+	/// Maps classes with [`class_map`](ClassBudget::class_map), keeping their order.
+	pub(crate) fn apply(&self, classes: &[String]) -> Result<Vec<String>, BudgetError> {
+		let map = self.class_map(classes)?;
+
+		Ok(classes.iter().map(|class| map[class].clone()).collect())
+	}
+
+	/// Returns the abbreviation of `class`, with the digest salted by `salt` unless it is 0.
+	fn abbreviation(&self, class: &str, salt: usize) -> String {
+		let input = match salt {
+			0 => class.to_string(),
+			salt => format!("{}\0{}", class, salt),
+		};
+		let hash: String = Sha256::digest(input.as_bytes())
+			.iter()
+			.map(|byte| format!("{:02x}", byte))
+			.collect();
+		let start = self.length_mode.prefix(class, self.max_class_len - HASH_LEN - 1);
+		let start = match start.trim_end_matches(['-', '_']) {
+			"" => LengthMode::Graphemes.prefix(class, 1),
+			trimmed => trimmed,
+		};
+
+		format!("{}-{}", start, &hash[..HASH_LEN])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+	use super::{ BudgetError, ClassBudget };
+	use crate::length::LengthMode;
+	use crate::generate::{ generate_document, GenerateConfig };
+	use crate::naming::NamingScheme;
+
+	fn budget(max_class_len: usize, abbreviate: bool) -> ClassBudget {
+		ClassBudget { max_class_len, max_attr_len: 255, abbreviate, length_mode: LengthMode::Chars }
+	}
+
+	#[test]
+	fn test_class_map_rejects_long_classes() {
+		let error = budget(12, false).class_map(["card", "media-player__button", "media-player__button--fast-forward"]).unwrap_err();
+
+		assert_eq!(error, BudgetError::ClassTooLong { class: "media-player__button".to_string(), len: 20, max: 12, mode: LengthMode::Chars });
+		assert_eq!(error.to_string(), "class `media-player__button` is 20 characters long, the maximum is 12");
+		assert!(matches!(budget(9, true).class_map(["media-player"]), Err(BudgetError::ClassTooLong { max: 9, .. })));
+	}
+
+	#[test]
+	fn test_class_map_abbreviates_deterministically() {
+		let classes = ["media-player__button--fast-forward", "media-player", "media-player__button--rewind"];
+		let map = budget(16, true).class_map(classes).unwrap();
+		let mut reversed = classes;
+
+		reversed.reverse();
+
+		assert_eq!(map, budget(16, true).class_map(reversed).unwrap());
+		assert_eq!(map["media-player"], "media-player");
+		assert_eq!(map["media-player__button--fast-forward"], format!("media-p-{}", &map["media-player__button--fast-forward"][8..]));
+		assert!(map.values().all(|class| class.len() <= 16));
+		assert_ne!(map["media-player__button--fast-forward"], map["media-player__button--rewind"]);
+	}
+
+	#[test]
+	fn test_class_map_avoids_existing_classes() {
+		let long = "media-player__button--fast-forward";
+		let abbreviation = budget(20, true).class_map([long]).unwrap()[long].clone();
+		let map = budget(20, true).class_map([long, &abbreviation]).unwrap();
+
+		assert_eq!(map[abbreviation.as_str()], abbreviation);
+		assert_ne!(map[long], abbreviation);
+		assert_eq!(map[long].len(), 20);
+	}
+
+	#[test]
+	fn test_class_map_is_collision_free_across_a_corpus() {
+		let scheme = NamingScheme::default();
+		let classes: Vec<String> = (0..20)
+			.flat_map(|seed| generate_document(seed, &GenerateConfig::default()).blocks)
+			.flat_map(|bem_block| scheme.class_names(&bem_block))
+			.collect();
+		let unique: HashSet<&String> = classes.iter().collect();
+
+		for max_class_len in [10, 12, 16] {
+			let map = budget(max_class_len, true).class_map(&classes).unwrap();
+			let abbreviations: HashSet<&String> = map.values().collect();
+
+			assert_eq!(map.len(), unique.len());
+			assert_eq!(abbreviations.len(), unique.len());
+			assert!(map.values().all(|class| class.chars().count() <= max_class_len));
+		}
+	}
+
+	#[test]
+	fn test_class_map_counts_in_the_length_mode() {
+		// `é` written with a combining accent, then a waving hand with a skin tone modifier.
+		let class = "caf\u{65}\u{301}-\u{1F44B}\u{1F3FD}-player";
+		let in_mode = |length_mode: LengthMode, max_class_len: usize| {
+			ClassBudget { max_class_len, max_attr_len: 255, abbreviate: false, length_mode }.class_map([class])
+		};
+
+		assert!(in_mode(LengthMode::Graphemes, 13).is_ok());
+		assert!(in_mode(LengthMode::Chars, 13).is_err());
+		assert!(in_mode(LengthMode::Chars, 15).is_ok());
+		assert_eq!(
+			in_mode(LengthMode::Bytes, 15).unwrap_err().to_string(),
+			format!("class `{}` is 22 bytes long, the maximum is 15", class)
+		);
+
+		// The first 4 characters end within `é`, so the abbreviation starts with 3.
+		let abbreviating = ClassBudget { max_class_len: 13, max_attr_len: 255, abbreviate: true, length_mode: LengthMode::Chars };
+		let abbreviation = &abbreviating.class_map([class]).unwrap()[class];
+
+		assert!(abbreviation.starts_with("caf-"));
+		assert_eq!(abbreviation.chars().count(), 12);
+	}
+
+	#[test]
+	fn test_check_attribute() {
+		let budget = ClassBudget { max_class_len: 50, max_attr_len: 10, abbreviate: true, length_mode: Default::default() };
+
+		assert!(budget.check_attribute("card card--x").is_err());
+		assert_eq!(budget.check_attribute("card card"), Ok(()));
+	}
+}
@@ -0,0 +1,192 @@
+//! This module composes the classes of a single BEM entity at runtime, e.g. when rendering
+//! markup from Rust.
+
+use std::collections::BTreeMap;
+use crate::attributes::AttributeScheme;
+use crate::budget::{ BudgetError, ClassBudget };
+use crate::naming::NamingScheme;
+
+/// Builds the `class` attribute, and with an [`AttributeScheme`] the data attributes, of a
+/// block or element with a set of modifiers.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ AttributeScheme, ClassBuilder };
+///
+/// let builder = ClassBuilder::new("media-player")
+///     .element("button")
+///     .modifier("rewind")
+///     .modifier_if("fast-forward", false);
+///
+/// assert_eq!(builder.build(), "media-player__button media-player__button--rewind");
+///
+/// let builder = builder.attribute_scheme(AttributeScheme::Boolean);
+///
+/// assert_eq!(builder.build(), "media-player__button");
+/// assert_eq!(builder.attributes()["data-rewind"], "");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassBuilder {
+	block: String,
+	element: Option<String>,
+	modifiers: Vec<String>,
+	naming: NamingScheme,
+	attribute_scheme: Option<AttributeScheme>,
+}
+
+impl ClassBuilder {
+	/// Starts building the classes of a block.
+	pub fn new(block: &str) -> ClassBuilder {
+		ClassBuilder {
+			block: block.to_string(),
+			element: None,
+			modifiers: Vec::new(),
+			naming: NamingScheme::default(),
+			attribute_scheme: None,
+		}
+	}
+
+	/// Builds the classes of an element of the block instead of the block itself.
+	pub fn element(mut self, name: &str) -> ClassBuilder {
+		self.element = Some(name.to_string());
+		self
+	}
+
+	/// Adds a modifier. Repeated modifiers are only output once.
+	pub fn modifier(mut self, name: &str) -> ClassBuilder {
+		if !self.modifiers.iter().any(|modifier| modifier == name) {
+			self.modifiers.push(name.to_string());
+		}
+		self
+	}
+
+	/// Adds a modifier if `enabled` is true, e.g. from a boolean prop.
+	pub fn modifier_if(self, name: &str, enabled: bool) -> ClassBuilder {
+		if enabled {
+			self.modifier(name)
+		} else {
+			self
+		}
+	}
+
+	/// Composes classes with other separators than standard BEM.
+	pub fn naming(mut self, naming: NamingScheme) -> ClassBuilder {
+		self.naming = naming;
+		self
+	}
+
+	/// Writes modifiers as data attributes, see [`attributes`](ClassBuilder::attributes),
+	/// instead of modifier classes.
+	pub fn attribute_scheme(mut self, scheme: AttributeScheme) -> ClassBuilder {
+		self.attribute_scheme = Some(scheme);
+		self
+	}
+
+	/// Returns the value of the `class` attribute: the block or element class, followed by one
+	/// modifier class per modifier unless modifiers are written as attributes.
+	pub fn build(&self) -> String {
+		self.classes().join(" ")
+	}
+
+	/// Returns the value of the `class` attribute like [`build`](ClassBuilder::build), within the
+	/// limits of `budget`: classes that are too long are abbreviated or rejected, and the value
+	/// is rejected if it is still too long.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ ClassBudget, ClassBuilder };
+	///
+	/// let builder = ClassBuilder::new("media-player").element("button").modifier("fast-forward");
+	/// let budget = ClassBudget { max_class_len: 20, max_attr_len: 50, abbreviate: true, length_mode: Default::default() };
+	/// let value = builder.build_with_budget(&budget).unwrap();
+	///
+	/// assert!(value.starts_with("media-player__button media-playe-"));
+	/// assert!(value.split(' ').all(|class| class.len() <= 20));
+	/// ```
+	pub fn build_with_budget(&self, budget: &ClassBudget) -> Result<String, BudgetError> {
+		let value = budget.apply(&self.classes())?.join(" ");
+
+		budget.check_attribute(&value)?;
+
+		Ok(value)
+	}
+
+	/// Returns the classes of [`build`](ClassBuilder::build), in order.
+	pub(crate) fn classes(&self) -> Vec<String> {
+		let base = match &self.element {
+			Some(element) => self.naming.element_class(&self.block, element),
+			None => self.block.clone(),
+		};
+		let mut classes = vec![base.clone()];
+
+		if self.attribute_scheme.is_none() {
+			for modifier in &self.modifiers {
+				classes.push(self.naming.modifier_class(&base, modifier));
+			}
+		}
+
+		classes
+	}
+
+	/// Returns the data attributes expressing the modifiers, mapping attribute names to values,
+	/// see [`AttributeScheme::attributes`]. Without an attribute scheme, modifiers are classes
+	/// and there are no attributes.
+	pub fn attributes(&self) -> BTreeMap<String, String> {
+		match &self.attribute_scheme {
+			Some(scheme) => scheme.attributes(&self.modifiers),
+			None => BTreeMap::new(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+	use super::ClassBuilder;
+	use crate::attributes::AttributeScheme;
+	use crate::budget::{ BudgetError, ClassBudget };
+	use crate::naming::NamingScheme;
+
+	#[test]
+	fn test_build() {
+		let builder = ClassBuilder::new("media-player").modifier("dark").modifier("dark").modifier_if("light", false);
+
+		assert_eq!(builder.build(), "media-player media-player--dark");
+		assert!(builder.attributes().is_empty());
+
+		let scheme = NamingScheme { element_separator: "_".to_string(), modifier_separator: "-".to_string() };
+
+		assert_eq!(builder.element("button").naming(scheme).build(), "media-player_button media-player_button-dark");
+	}
+
+	#[test]
+	fn test_build_with_budget() {
+		let builder = ClassBuilder::new("media-player").element("button").modifier("fast-forward");
+		let strict = ClassBudget { max_class_len: 20, max_attr_len: 255, abbreviate: false, length_mode: Default::default() };
+		let abbreviating = ClassBudget { abbreviate: true, ..strict.clone() };
+
+		assert!(matches!(builder.build_with_budget(&strict), Err(BudgetError::ClassTooLong { len: 34, .. })));
+		assert_eq!(builder.build_with_budget(&abbreviating), builder.build_with_budget(&abbreviating));
+		assert!(matches!(
+			builder.build_with_budget(&ClassBudget { max_attr_len: 30, ..abbreviating }),
+			Err(BudgetError::AttributeTooLong { len: 41, max: 30, .. })
+		));
+	}
+
+	#[test]
+	fn test_attributes() {
+		let builder = ClassBuilder::new("media-player").element("button").modifier("rewind").modifier("fast-forward");
+		let boolean = builder.clone().attribute_scheme(AttributeScheme::Boolean);
+		let state = builder.attribute_scheme(AttributeScheme::KeyValue("data-state".to_string()));
+
+		assert_eq!(boolean.build(), "media-player__button");
+		assert_eq!(boolean.attributes(), BTreeMap::from([
+			("data-fast-forward".to_string(), String::new()),
+			("data-rewind".to_string(), String::new()),
+		]));
+		assert_eq!(state.build(), "media-player__button");
+		assert_eq!(state.attributes(), BTreeMap::from([("data-state".to_string(), "rewind fast-forward".to_string())]));
+	}
+}
@@ -0,0 +1,237 @@
+//! This module writes release notes from the differences between two versions of BEM blocks.
+
+use std::collections::HashMap;
+use crate::diff::diff_documents;
+use crate::models::{ BEMBlock, BEMModifier };
+use crate::naming::NamingScheme;
+
+/// Configures the release notes generated by [`changelog`].
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use bem::ChangelogOptions;
+///
+/// let options = ChangelogOptions {
+///     title: Some("2.0.0".to_string()),
+///     descriptions: HashMap::from([("play-list".to_string(), "A list of tracks".to_string())]),
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangelogOptions {
+	/// The title of the release, rendered as a second-level heading when set.
+	pub title: Option<String>,
+	/// Descriptions of blocks and elements, keyed by class name, included next to them.
+	pub descriptions: HashMap<String, String>,
+}
+
+/// One entry of a changelog section, with the classes listed under it.
+struct Entry {
+	class: String,
+	details: Vec<String>,
+}
+
+/// Generates Markdown release notes for the changes between two versions of a set of blocks.
+///
+/// Blocks are matched by name. Blocks and elements present in only one version are listed
+/// under "Added" or "Removed" with their classes, blocks missing from one side as a whole.
+/// Blocks and elements present in both versions whose modifiers changed are listed under
+/// "Changed". Sections without entries are left out, and entries are sorted by class name so
+/// the notes do not depend on the order of the input.
+///
+/// # Arguments
+///
+/// * `old`: &[BEMBlock] - The published version of the blocks.
+/// * `new`: &[BEMBlock] - The version about to be published.
+/// * `options`: &ChangelogOptions - The release title and the descriptions to include.
+///
+/// # Returns
+///
+/// * `String` - The release notes in Markdown.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ changelog, parse, ChangelogOptions };
+///
+/// let old = vec![parse("media-player").unwrap()];
+/// let new = vec![parse("media-player\nbutton").unwrap()];
+///
+/// assert_eq!(changelog(&old, &new, &ChangelogOptions::default()), "### Added\n\n- `media-player__button`\n");
+/// ```
+pub fn changelog(old: &[BEMBlock], new: &[BEMBlock], options: &ChangelogOptions) -> String {
+	let scheme = NamingScheme::default();
+	let diff = diff_documents(old, new);
+	let mut added = Vec::new();
+	let mut removed = Vec::new();
+	let mut changed = Vec::new();
+
+	for block in &diff.added_blocks {
+		added.push(block_entry(block, &scheme));
+	}
+	for block in &diff.removed_blocks {
+		removed.push(block_entry(block, &scheme));
+	}
+
+	for (block_name, block_diff) in &diff.changed_blocks {
+		let details = modifier_changes(block_name, &block_diff.added_modifiers, &block_diff.removed_modifiers, &scheme);
+
+		if !details.is_empty() {
+			changed.push(Entry { class: block_name.clone(), details });
+		}
+
+		for element in &block_diff.added_elements {
+			let class = scheme.element_class(block_name, &element.name);
+			let details = element.modifiers
+				.iter()
+				.map(|modifier| format!("`{}`", scheme.modifier_class(&class, &modifier.class_name())))
+				.collect();
+
+			added.push(Entry { class, details });
+		}
+
+		let old_block = old
+			.iter()
+			.find(|block| block.name == *block_name)
+			.expect("changed blocks exist in the old version");
+
+		for element in &block_diff.removed_elements {
+			let class = scheme.element_class(block_name, element);
+			let mut details: Vec<String> = Vec::new();
+
+			for old_element in old_block.elements.iter().filter(|old_element| old_element.name == *element) {
+				for modifier in &old_element.modifiers {
+					let modifier_class = format!("`{}`", scheme.modifier_class(&class, &modifier.class_name()));
+
+					if !details.contains(&modifier_class) {
+						details.push(modifier_class);
+					}
+				}
+			}
+
+			removed.push(Entry { class, details });
+		}
+
+		for element in &block_diff.changed_elements {
+			let class = scheme.element_class(block_name, &element.name);
+			let details = modifier_changes(&class, &element.added_modifiers, &element.removed_modifiers, &scheme);
+
+			changed.push(Entry { class, details });
+		}
+	}
+
+	let mut sections = Vec::new();
+
+	if let Some(title) = &options.title {
+		sections.push(format!("## {}\n", title));
+	}
+
+	for (heading, mut entries) in [("Added", added), ("Removed", removed), ("Changed", changed)] {
+		if entries.is_empty() {
+			continue;
+		}
+
+		entries.sort_by(|a, b| a.class.cmp(&b.class));
+
+		let mut section = format!("### {}\n\n", heading);
+
+		for entry in entries {
+			match options.descriptions.get(&entry.class) {
+				Some(description) => section.push_str(&format!("- `{}`: {}\n", entry.class, description)),
+				None => section.push_str(&format!("- `{}`\n", entry.class)),
+			}
+
+			for detail in entry.details {
+				section.push_str(&format!("  - {}\n", detail));
+			}
+		}
+
+		sections.push(section);
+	}
+
+	if sections.len() == usize::from(options.title.is_some()) {
+		sections.push("No structural changes.\n".to_string());
+	}
+
+	sections.join("\n")
+}
+
+/// Lists the classes of a block under the block class.
+fn block_entry(bem_block: &BEMBlock, scheme: &NamingScheme) -> Entry {
+	let details = scheme
+		.class_names(bem_block)
+		.into_iter()
+		.skip(1)
+		.map(|class| format!("`{}`", class))
+		.collect();
+
+	Entry { class: bem_block.name.clone(), details }
+}
+
+fn modifier_changes(class: &str, added: &[BEMModifier], removed: &[BEMModifier], scheme: &NamingScheme) -> Vec<String> {
+	let added = added
+		.iter()
+		.map(|modifier| format!("added `{}`", scheme.modifier_class(class, &modifier.class_name())));
+	let removed = removed
+		.iter()
+		.map(|modifier| format!("removed `{}`", scheme.modifier_class(class, &modifier.class_name())));
+
+	added.chain(removed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use super::{ changelog, ChangelogOptions };
+	use crate::models::BEMBlock;
+	use crate::parse;
+
+	fn old() -> Vec<BEMBlock> {
+		vec![
+			parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline[live]").unwrap(),
+			parse("play-list[compact]\nitem").unwrap(),
+		]
+	}
+
+	fn new() -> Vec<BEMBlock> {
+		vec![
+			parse("volume-slider[vertical]\nhandle").unwrap(),
+			parse("media-player[dark,light]\nbutton[rewind,play]\nvolume").unwrap(),
+		]
+	}
+
+	#[test]
+	fn test_changelog() {
+		let options = ChangelogOptions {
+			title: Some("2.0.0".to_string()),
+			descriptions: HashMap::from([
+				("volume-slider".to_string(), "A slider controlling the volume".to_string()),
+				("media-player__timeline".to_string(), "Replaced by the progress bar".to_string()),
+			]),
+		};
+
+		insta::assert_snapshot!(changelog(&old(), &new(), &options));
+	}
+
+	#[test]
+	fn test_changelog_is_deterministic() {
+		let mut reversed_old = old();
+		let mut reversed_new = new();
+		reversed_old.reverse();
+		reversed_new.reverse();
+
+		assert_eq!(
+			changelog(&old(), &new(), &ChangelogOptions::default()),
+			changelog(&reversed_old, &reversed_new, &ChangelogOptions::default())
+		);
+	}
+
+	#[test]
+	fn test_changelog_without_changes() {
+		let options = ChangelogOptions { title: Some("1.0.1".to_string()), ..ChangelogOptions::default() };
+
+		assert_eq!(changelog(&old(), &old(), &ChangelogOptions::default()), "No structural changes.\n");
+		assert_eq!(changelog(&old(), &old(), &options), "## 1.0.1\n\nNo structural changes.\n");
+	}
+}
@@ -0,0 +1,256 @@
+//! This module reconstructs BEM blocks from flat lists of class names, as found in markup,
+//! stylesheets, or component source code.
+
+use std::collections::BTreeMap;
+use serde::{ Deserialize, Serialize };
+use crate::elements::Elements;
+use crate::models::{ BEMBlock, BEMElement, BEMModifier };
+use crate::parser::parse_class;
+use crate::path::BEMPath;
+
+/// How many times the classes of a block appeared in the input of
+/// [`from_classes_with_counts`], keyed by the path of the entity each class names.
+///
+/// Serialized as a JSON object mapping the normalized paths to their counts, e.g.
+/// `{"media-player":1,"media-player--dark":2}`.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ from_classes_with_counts, BEMPath };
+///
+/// let blocks = from_classes_with_counts(["card--active", "card", "card--active"]);
+/// let (_, counts) = &blocks[0];
+///
+/// assert_eq!(counts.get(&BEMPath::new("card").with_modifier("active")), 2);
+/// assert_eq!(serde_json::to_string(counts).unwrap(), "{\"card\":1,\"card--active\":2}");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UsageCounts {
+	/// The number of occurrences of each class. Entities of the block whose class never appeared
+	/// themselves, like a block only seen through its modifiers, are not listed.
+	pub counts: BTreeMap<BEMPath, usize>,
+}
+
+impl UsageCounts {
+	/// Returns how many times the class of `path` appeared, 0 if it never did.
+	pub fn get(&self, path: &BEMPath) -> usize {
+		self.counts.get(path).copied().unwrap_or(0)
+	}
+
+	/// Returns the modifiers of the block and its elements with their counts, most used first,
+	/// and in path order among equally used modifiers.
+	pub fn most_used_modifiers(&self) -> Vec<(&BEMPath, usize)> {
+		let mut modifiers: Vec<(&BEMPath, usize)> = self.counts
+			.iter()
+			.filter(|(path, _)| path.modifier.is_some())
+			.map(|(path, count)| (path, *count))
+			.collect();
+
+		modifiers.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+		modifiers
+	}
+}
+
+/// Groups BEM class names into the blocks they describe.
+///
+/// Each class is split into its block, element, and modifier parts. Blocks and elements are
+/// created the first time any of their classes appears, modifiers are attached to the block or
+/// element they belong to, and repeated classes are only recorded once. Classes that are not
+/// BEM classes (e.g. utility classes like `Button` or `a__b__c`) are ignored.
+///
+/// # Arguments
+///
+/// * `classes`: IntoIterator - The class names to group, e.g. the tokens of a `class` attribute.
+///
+/// # Returns
+///
+/// * `Vec<BEMBlock>` - The reconstructed blocks, in the order their first class appeared.
+///
+/// # Examples
+///
+/// ```
+/// use bem::from_classes;
+///
+/// let blocks = from_classes(["media-player", "media-player--dark", "media-player__button--rewind"]);
+///
+/// assert_eq!(blocks.len(), 1);
+/// assert_eq!(blocks[0].modifiers, vec!["dark".to_string()]);
+/// assert_eq!(blocks[0].elements[0].modifiers, vec!["rewind".to_string()]);
+/// ```
+pub fn from_classes<I, S>(classes: I) -> Vec<BEMBlock> where I: IntoIterator<Item = S>, S: AsRef<str> {
+	from_classes_with_counts(classes)
+		.into_iter()
+		.map(|(bem_block, _)| bem_block)
+		.collect()
+}
+
+/// Groups BEM class names into the blocks they describe, like [`from_classes`], and counts how
+/// many times each class appeared.
+///
+/// The blocks are deduplicated exactly as with [`from_classes`], but the counts keep every
+/// repetition, e.g. to report the most used modifiers of scraped markup.
+///
+/// # Arguments
+///
+/// * `classes`: IntoIterator - The class names to group, e.g. the tokens of every `class`
+///   attribute of a page.
+///
+/// # Returns
+///
+/// * `Vec<(BEMBlock, UsageCounts)>` - The reconstructed blocks, in the order their first class
+///   appeared, each with the counts of its classes.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ from_classes_with_counts, BEMPath };
+///
+/// let blocks = from_classes_with_counts(["button", "button--primary", "button", "button--primary"]);
+/// let (bem_block, counts) = &blocks[0];
+///
+/// assert_eq!(bem_block.modifiers, vec!["primary".to_string()]);
+/// assert_eq!(counts.get(&BEMPath::new("button")), 2);
+/// assert_eq!(counts.most_used_modifiers(), vec![(&BEMPath::new("button").with_modifier("primary"), 2)]);
+/// ```
+pub fn from_classes_with_counts<I, S>(classes: I) -> Vec<(BEMBlock, UsageCounts)> where I: IntoIterator<Item = S>, S: AsRef<str> {
+	let mut blocks: Vec<(BEMBlock, UsageCounts)> = Vec::new();
+
+	for class in classes {
+		let Some(class_name) = parse_class(class.as_ref()) else {
+			continue;
+		};
+
+		let block_index = match blocks.iter().position(|(block, _)| block.name == class_name.block) {
+			Some(index) => index,
+			None => {
+				blocks.push((BEMBlock {
+					name: class_name.block.clone(),
+					modifiers: vec![],
+					elements: Elements::new(),
+				}, UsageCounts::default()));
+				blocks.len() - 1
+			}
+		};
+		let (block, counts) = &mut blocks[block_index];
+		let path = BEMPath {
+			block: class_name.block,
+			element: class_name.element.clone(),
+			modifier: class_name.modifier.clone(),
+		};
+
+		*counts.counts.entry(path).or_insert(0) += 1;
+
+		let modifiers: Vec<BEMModifier> = class_name.modifier.into_iter().map(BEMModifier::from).collect();
+
+		match class_name.element {
+			Some(element_name) => {
+				block.elements.insert(BEMElement {
+					name: element_name,
+					modifiers,
+				});
+			}
+			None => {
+				for modifier in modifiers {
+					if !block.modifiers.contains(&modifier) {
+						block.modifiers.push(modifier);
+					}
+				}
+			}
+		}
+	}
+
+	blocks
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+	use super::UsageCounts;
+	use crate::models::BEMBlock;
+	use crate::parse;
+	use crate::path::BEMPath;
+
+	#[test]
+	fn test_from_classes() {
+		let blocks = super::from_classes([
+			"media-player",
+			"media-player--dark",
+			"media-player__button",
+			"media-player__button--fast-forward",
+			"media-player__button--rewind",
+			"media-player__timeline",
+		]);
+
+		assert_eq!(blocks, vec![parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap()]);
+	}
+
+	#[test]
+	fn test_from_classes_without_base_classes() {
+		let blocks = super::from_classes(["media-player__button--rewind", "media-player--dark"]);
+
+		assert_eq!(blocks, vec![parse("media-player[dark]\nbutton[rewind]").unwrap()]);
+	}
+
+	#[test]
+	fn test_from_classes_multiple_blocks_and_repetition() {
+		let blocks = super::from_classes(["card", "button--primary", "card__title", "button--primary", "card__title"]);
+
+		assert_eq!(blocks, vec![parse("card\ntitle").unwrap(), parse("button[primary]").unwrap()]);
+	}
+
+	#[test]
+	fn test_from_classes_with_counts() {
+		let blocks = super::from_classes_with_counts([
+			"card",
+			"card--active",
+			"Button",
+			"card__title",
+			"card--active",
+			"button--primary",
+			"card__title--large",
+			"card--active",
+			"card__title",
+			"button--primary",
+			"button--ghost",
+		]);
+		let card = BEMPath::new("card");
+		let button = BEMPath::new("button");
+
+		assert_eq!(blocks.iter().map(|(block, _)| block.clone()).collect::<Vec<_>>(), vec![
+			parse("card[active]\ntitle[large]").unwrap(),
+			parse("button[primary,ghost]").unwrap(),
+		]);
+		assert_eq!(blocks[0].1.counts, BTreeMap::from([
+			(card.clone(), 1),
+			(card.clone().with_modifier("active"), 3),
+			(card.clone().with_element("title"), 2),
+			(card.clone().with_element("title").with_modifier("large"), 1),
+		]));
+		assert_eq!(blocks[1].1.get(&button), 0);
+		assert_eq!(blocks[1].1.most_used_modifiers(), vec![
+			(&button.clone().with_modifier("primary"), 2),
+			(&button.clone().with_modifier("ghost"), 1),
+		]);
+	}
+
+	#[test]
+	fn test_usage_counts_serialization() {
+		let blocks = super::from_classes_with_counts(["card__title", "card--active", "card__title"]);
+		let json = serde_json::to_string(&blocks).unwrap();
+
+		assert_eq!(
+			json,
+			"[[{\"name\":\"card\",\"modifiers\":[\"active\"],\"elements\":[{\"name\":\"title\",\"modifiers\":[]}]},{\"card--active\":1,\"card__title\":2}]]"
+		);
+		assert_eq!(serde_json::from_str::<Vec<(BEMBlock, UsageCounts)>>(&json).unwrap(), blocks);
+	}
+
+	#[test]
+	fn test_from_classes_ignores_non_bem_classes() {
+		let blocks = super::from_classes(["Button", "a__b__c", "is-active", "", "x y"]);
+
+		assert_eq!(blocks, vec![parse("is-active").unwrap()]);
+	}
+}
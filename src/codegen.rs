@@ -0,0 +1,904 @@
+//! This module turns parsed BEM structures into stylesheet skeletons. Every generator
+//! emits one empty rule per class the block can produce, so the output can be used as
+//! the starting point of a component stylesheet.
+//!
+//! Classes are escaped with [`css_escape`] wherever they appear in a selector, so blocks whose
+//! names are not valid CSS identifiers still produce a valid stylesheet.
+
+use std::slice;
+use crate::attributes::AttributeScheme;
+use crate::ident::{ css_escape, identifier, identifiers, numbered_identifiers, scss_identifier, Case, IdentifierError };
+use crate::models::{ BEMBlock, BEMElement, BEMModifier };
+use crate::naming::BemConfig;
+
+/// Generates a stylesheet skeleton that uses native CSS nesting.
+///
+/// The block rule is the outermost rule. Block modifiers are nested inside it as compound
+/// selectors (`&.media-player--dark`), and elements are nested as descendant selectors
+/// (`& .media-player__button`) that in turn nest their own modifiers. Full class names are
+/// always spelled out, because the `&__element` concatenation shorthand known from SCSS is
+/// not valid in the CSS Nesting specification.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate CSS for.
+///
+/// # Returns
+///
+/// * `String` - The nested CSS, which browsers supporting CSS Nesting accept without preprocessing.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_css_nested };
+///
+/// let bem_block = parse("media-player[dark]\nbutton").unwrap();
+/// let css = to_css_nested(&bem_block);
+///
+/// assert!(css.contains("&.media-player--dark { }"));
+/// assert!(css.contains("& .media-player__button { }"));
+/// ```
+pub fn to_css_nested(bem_block: &BEMBlock) -> String {
+	to_css_nested_with_config(bem_block, &BemConfig::default())
+}
+
+/// Generates a stylesheet skeleton like [`to_css_nested`], with the classes composed with the
+/// separators of `config`.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate CSS for.
+/// * `config`: &BemConfig - The separators the classes are composed with.
+///
+/// # Returns
+///
+/// * `String` - The nested CSS.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_css_nested_with_config, BemConfig };
+///
+/// let bem_block = parse("media-player[dark]\nbutton").unwrap();
+/// let config = BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+/// let css = to_css_nested_with_config(&bem_block, &config);
+///
+/// assert!(css.contains("&.media-player_dark { }"));
+/// assert!(css.contains("& .media-player-button { }"));
+/// ```
+pub fn to_css_nested_with_config(bem_block: &BEMBlock, config: &BemConfig) -> String {
+	nested_stylesheet(bem_block, config, |class, modifier| format!(".{}", css_escape(&config.modifier_class(class, modifier))))
+}
+
+/// Generates a stylesheet skeleton like [`to_css_nested`], but with modifiers written as data
+/// attributes instead of modifier classes.
+///
+/// Modifier rules are nested as attribute selectors on the block or element they belong to,
+/// e.g. `&[data-rewind]` or `&[data-state~="rewind"]` depending on the scheme. Block and element
+/// rules are the same as with modifier classes.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate CSS for.
+/// * `scheme`: &AttributeScheme - How modifiers are written as attributes.
+/// * `config`: &BemConfig - The separators the element classes are composed with.
+///
+/// # Returns
+///
+/// * `String` - The nested CSS.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_css_nested_with_attributes, AttributeScheme, BemConfig };
+///
+/// let bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+/// let css = to_css_nested_with_attributes(&bem_block, &AttributeScheme::Boolean, &BemConfig::default());
+///
+/// assert!(css.contains("&[data-dark] { }"));
+/// assert!(css.contains("& .media-player__button {\n\t\t&[data-rewind] { }"));
+/// ```
+pub fn to_css_nested_with_attributes(bem_block: &BEMBlock, scheme: &AttributeScheme, config: &BemConfig) -> String {
+	nested_stylesheet(bem_block, config, |_, modifier| scheme.selector(modifier))
+}
+
+/// Renders the nested stylesheet of a block, with `modifier_selector` returning the selector
+/// compounded with a block or element class to select one of its modifiers.
+fn nested_stylesheet<F>(bem_block: &BEMBlock, config: &BemConfig, modifier_selector: F) -> String where F: Fn(&str, &str) -> String {
+	let mut rules = Vec::new();
+
+	for modifier in &bem_block.modifiers {
+		rules.push(empty_rule(&format!("&{}", modifier_selector(&bem_block.name, &modifier.class_name())), 1));
+	}
+
+	for element in &bem_block.elements {
+		rules.push(nested_element_rule(&bem_block.name, element, config, &modifier_selector));
+	}
+
+	rule(&format!(".{}", css_escape(&bem_block.name)), &rules, 0)
+}
+
+fn nested_element_rule<F>(block_name: &str, element: &BEMElement, config: &BemConfig, modifier_selector: &F) -> String where F: Fn(&str, &str) -> String {
+	let class = config.element_class(block_name, &element.name);
+	let rules: Vec<String> = element.modifiers
+		.iter()
+		.map(|modifier| empty_rule(&format!("&{}", modifier_selector(&class, &modifier.class_name())), 2))
+		.collect();
+
+	rule(&format!("& .{}", css_escape(&class)), &rules, 1)
+}
+
+/// Generates an SCSS stylesheet skeleton that nests modifiers with the `&` suffix syntax.
+///
+/// The block rule is the outermost rule. Block modifiers are nested inside it as `&--dark`, and
+/// elements are nested as descendant rules (`.media-player__button`) that in turn nest their own
+/// modifiers as `&--rewind`. Entities without modifiers get an empty rule. Classes are composed
+/// with the separators of `config`.
+///
+/// A modifier class that does not start with the escaped class of its entity, as with a block
+/// named `-` whose escape `\-` is not kept within `-_dark`, cannot be written as a suffix. It
+/// gets a top-level rule of its own after the block rule instead, e.g. `.-_dark { }`.
+///
+/// # Arguments
+///
+/// * `block`: &BEMBlock - A reference to the `BEMBlock` to generate SCSS for.
+/// * `config`: &BemConfig - The separators the classes are composed with.
+///
+/// # Returns
+///
+/// * `String` - The nested SCSS.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_scss, BemConfig };
+///
+/// let bem_block = parse("media-player[dark]\nbutton[rewind]\ntimeline").unwrap();
+/// let scss = to_scss(&bem_block, &BemConfig::default());
+///
+/// assert!(scss.starts_with(".media-player {\n\t&--dark { }\n"));
+/// assert!(scss.contains("\t.media-player__button {\n\t\t&--rewind { }\n\t}\n"));
+/// assert!(scss.contains("\t.media-player__timeline { }\n"));
+/// ```
+pub fn to_scss(block: &BEMBlock, config: &BemConfig) -> String {
+	let mut top_level = Vec::new();
+	let mut nested = |base_class: &str, modifiers: &[BEMModifier], depth: usize| -> Vec<String> {
+		let mut rules = Vec::new();
+
+		for modifier in modifiers {
+			match modifier_suffix(base_class, &modifier.class_name(), config) {
+				Ok(suffix) => rules.push(empty_rule(&suffix, depth)),
+				Err(class) => top_level.push(empty_rule(&format!(".{}", class), 0)),
+			}
+		}
+
+		rules
+	};
+	let mut rules = nested(&block.name, &block.modifiers, 1);
+
+	for element in &block.elements {
+		let class = config.element_class(&block.name, &element.name);
+		let modifiers = nested(&class, &element.modifiers, 2);
+
+		rules.push(rule(&format!(".{}", css_escape(&class)), &modifiers, 1));
+	}
+
+	std::iter::once(rule(&format!(".{}", css_escape(&block.name)), &rules, 0))
+		.chain(top_level)
+		.collect::<Vec<String>>()
+		.join("\n")
+}
+
+/// Returns the selector of a modifier nested in the rule of `base_class`, e.g. `&--dark`,
+/// escaped like the full modifier class, or the escaped modifier class if it does not start with
+/// the escaped `base_class`.
+fn modifier_suffix(base_class: &str, modifier: &str, config: &BemConfig) -> Result<String, String> {
+	let class = css_escape(&config.modifier_class(base_class, modifier)).into_owned();
+
+	match class.strip_prefix(&*css_escape(base_class)) {
+		Some(suffix) => Ok(format!("&{}", suffix)),
+		None => Err(class),
+	}
+}
+
+/// Generates one SCSS mixin per block and element, with a boolean argument per modifier.
+///
+/// Each mixin is named after the class of its entity and contains an `@if` block per modifier,
+/// so a component's styles can be included with the modifiers toggled on as needed, e.g.
+/// `@include media-player($dark: true);`. Entities without modifiers get a mixin without
+/// arguments. Argument names are sanitized into SCSS identifiers, and mixins and their arguments
+/// follow the order of the block.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate mixins for.
+/// * `config`: &BemConfig - The separators the classes are composed with.
+///
+/// # Returns
+///
+/// * `Result<String, IdentifierError>` - The mixins, or an error if two modifiers of the same
+///   entity map to the same argument name (Sass treats `-` and `_` as the same character).
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_scss_mixins, BemConfig };
+///
+/// let bem_block = parse("media-player[dark]").unwrap();
+/// let scss = to_scss_mixins(&bem_block, &BemConfig::default()).unwrap();
+///
+/// assert_eq!(scss, "@mixin media-player($dark: false) {\n\t@if $dark { }\n}\n");
+/// ```
+pub fn to_scss_mixins(bem_block: &BEMBlock, config: &BemConfig) -> Result<String, IdentifierError> {
+	let mut mixins = vec![mixin(&bem_block.name, &bem_block.modifiers, config)?];
+
+	for element in &bem_block.elements {
+		mixins.push(mixin(&config.element_class(&bem_block.name, &element.name), &element.modifiers, config)?);
+	}
+
+	Ok(mixins.join("\n"))
+}
+
+fn mixin(class: &str, modifiers: &[BEMModifier], config: &BemConfig) -> Result<String, IdentifierError> {
+	let sources = modifiers.iter().map(|modifier| (config.modifier_class(class, &modifier.class_name()), modifier.class_name().into_owned()));
+	let arguments: Vec<String> = identifiers(sources, scss_identifier)?
+		.into_iter()
+		.map(|(_, ident)| ident)
+		.collect();
+
+	if arguments.is_empty() {
+		return Ok(empty_rule(&format!("@mixin {}", css_escape(class)), 0));
+	}
+
+	let parameters: Vec<String> = arguments
+		.iter()
+		.map(|argument| format!("${}: false", argument))
+		.collect();
+	let conditions: Vec<String> = arguments
+		.iter()
+		.map(|argument| empty_rule(&format!("@if ${}", argument), 1))
+		.collect();
+
+	Ok(rule(&format!("@mixin {}({})", css_escape(class), parameters.join(", ")), &conditions, 0))
+}
+
+/// Generates an SCSS placeholder selector for every class a block can produce.
+///
+/// Placeholders (`%media-player__button`) are only emitted when they are extended, so they let
+/// other rules share a component's styles with `@extend %media-player__button;`. They follow
+/// the order of [`NamingScheme::class_names`](crate::NamingScheme::class_names).
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate placeholders for.
+/// * `config`: &BemConfig - The separators the classes are composed with.
+///
+/// # Returns
+///
+/// * `String` - The placeholder selectors, each with an empty body.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_scss_placeholders, BemConfig };
+///
+/// let bem_block = parse("media-player\nbutton").unwrap();
+///
+/// assert_eq!(to_scss_placeholders(&bem_block, &BemConfig::default()), "%media-player { }\n\n%media-player__button { }\n");
+/// ```
+pub fn to_scss_placeholders(bem_block: &BEMBlock, config: &BemConfig) -> String {
+	let placeholders: Vec<String> = config
+		.class_names(bem_block)
+		.iter()
+		.map(|class| empty_rule(&format!("%{}", css_escape(class)), 0))
+		.collect();
+
+	placeholders.join("\n")
+}
+
+/// Generates a flat stylesheet skeleton: one empty rule per class of the block, in the order of
+/// [`NamingScheme::class_names`](crate::NamingScheme::class_names), with the classes composed
+/// with the separators of `config`.
+///
+/// Unlike [`to_css_nested`], every rule stands on its own, so the output works without CSS
+/// Nesting and can be split up or reordered freely.
+///
+/// # Arguments
+///
+/// * `block`: &BEMBlock - A reference to the `BEMBlock` to generate CSS for.
+/// * `config`: &BemConfig - The separators the classes are composed with.
+///
+/// # Returns
+///
+/// * `String` - The CSS, with rules separated by blank lines.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ generate_css_skeleton, parse, BemConfig };
+///
+/// let bem_block = parse("media-player[dark]\nbutton").unwrap();
+///
+/// assert_eq!(
+///     generate_css_skeleton(&bem_block, &BemConfig::default()),
+///     ".media-player { }\n\n.media-player--dark { }\n\n.media-player__button { }\n"
+/// );
+/// ```
+pub fn generate_css_skeleton(block: &BEMBlock, config: &BemConfig) -> String {
+	let rules: Vec<String> = config
+		.class_names(block)
+		.iter()
+		.map(|class| empty_rule(&format!(".{}", css_escape(class)), 0))
+		.collect();
+
+	rules.join("\n")
+}
+
+/// Generates a CSS Modules stylesheet, e.g. `media-player.module.css`, with one empty rule per
+/// class of the block, in the order of [`NamingScheme::class_names`](crate::NamingScheme::class_names).
+///
+/// CSS Modules export every class as a property of the imported object, so classes are
+/// camelCased into JavaScript identifiers, e.g. `.mediaPlayerButtonRewind` for
+/// `media-player__button--rewind`, and read as `styles.mediaPlayerButtonRewind`. Identifiers
+/// starting with a digit get a leading underscore, and reserved words a trailing one. A class
+/// whose identifier is already taken by an earlier class gets it numbered instead, e.g.
+/// `.mediaPlayerButtonRewind_2` for `media-player__button-rewind`, so the number cannot run
+/// into digits ending the name. The declarations of the
+/// exported object are written by [`to_css_modules_dts`].
+///
+/// # Arguments
+///
+/// * `block`: &BEMBlock - A reference to the `BEMBlock` to generate CSS for.
+/// * `config`: &BemConfig - The separators the classes are composed with before they are camelCased.
+///
+/// # Returns
+///
+/// * `String` - The CSS, with rules separated by blank lines.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_css_modules, BemConfig };
+///
+/// let bem_block = parse("media-player[dark]\nbutton").unwrap();
+///
+/// assert_eq!(
+///     to_css_modules(&bem_block, &BemConfig::default()),
+///     ".mediaPlayer { }\n\n.mediaPlayerDark { }\n\n.mediaPlayerButton { }\n"
+/// );
+/// ```
+pub fn to_css_modules(block: &BEMBlock, config: &BemConfig) -> String {
+	css_modules_stylesheet(slice::from_ref(block), config)
+}
+
+/// Generates the TypeScript declarations of a CSS Modules stylesheet holding the rules of
+/// [`to_css_modules`] for the given blocks, e.g. `media-player.module.css.d.ts`, so that
+/// components only use classes that exist. Identifiers taken by several classes of the blocks
+/// are numbered like in [`to_css_modules`].
+///
+/// # Arguments
+///
+/// * `blocks`: &[BEMBlock] - The blocks of the stylesheet.
+/// * `config`: &BemConfig - The separators the classes are composed with before they are camelCased.
+///
+/// # Returns
+///
+/// * `String` - The declarations.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_css_modules_dts, BemConfig };
+///
+/// let bem_block = parse("media-player[dark]").unwrap();
+///
+/// assert_eq!(
+///     to_css_modules_dts(&[bem_block], &BemConfig::default()),
+///     "declare const styles: {\n\treadonly mediaPlayer: string;\n\treadonly mediaPlayerDark: string;\n};\n\nexport default styles;\n"
+/// );
+/// ```
+pub fn to_css_modules_dts(blocks: &[BEMBlock], config: &BemConfig) -> String {
+	let properties: String = css_modules_identifiers(blocks, config)
+		.iter()
+		.map(|(_, ident)| format!("\treadonly {}: string;\n", ident))
+		.collect();
+
+	format!("declare const styles: {{\n{}}};\n\nexport default styles;\n", properties)
+}
+
+/// Renders the CSS Modules stylesheet of the blocks, with the identifiers of
+/// [`css_modules_identifiers`].
+pub(crate) fn css_modules_stylesheet(blocks: &[BEMBlock], config: &BemConfig) -> String {
+	let rules: Vec<String> = css_modules_identifiers(blocks, config)
+		.iter()
+		.map(|(_, ident)| empty_rule(&format!(".{}", css_escape(ident)), 0))
+		.collect();
+
+	rules.join("\n")
+}
+
+/// Returns the classes of the blocks with their CSS Modules identifier, in order and each class
+/// once. A class whose identifier is taken by an earlier class gets the first free numbered one,
+/// the number joined with an underscore.
+fn css_modules_identifiers(blocks: &[BEMBlock], config: &BemConfig) -> Vec<(String, String)> {
+	let classes = blocks
+		.iter()
+		.flat_map(|block| config.class_names(block))
+		.map(|class| {
+			let ident = identifier(&class, Case::Camel);
+
+			(class, ident)
+		});
+
+	numbered_identifiers(classes)
+}
+
+/// How [`to_css_split`] names and fills its files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitOptions {
+	/// The extension of every file name, without the dot.
+	pub extension: String,
+	/// Whether the file of a block modifier also overrides the modifiers of elements, like
+	/// `.media-player--dark .media-player__button--rewind`, and not only the elements themselves.
+	pub element_modifiers: bool,
+	/// The separators the classes are composed with.
+	pub naming: BemConfig,
+}
+
+impl Default for SplitOptions {
+	fn default() -> Self {
+		SplitOptions { extension: "css".to_string(), element_modifiers: true, naming: BemConfig::default() }
+	}
+}
+
+/// Generates a flat stylesheet skeleton split into one file per block modifier, so themes like
+/// `dark` can be shipped as separate stylesheets loaded on demand.
+///
+/// The first file is the base file, named after the block, with the rules of the block, its
+/// elements, and their modifiers. It is followed by one file per block modifier, in the order of
+/// the block, named after the modifier class and holding the rule of the modifier and the rules of
+/// the elements scoped inside it, e.g. `.media-player--dark .media-player__button { }`. A block
+/// without modifiers only produces the base file.
+///
+/// File names keep letters, digits, `-`, and `_`, and replace every other character with `_`.
+/// Names that end up the same get a numeric suffix, e.g. `media-player--dark_mobile-2.css`, so
+/// no file overwrites another.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate CSS for.
+/// * `options`: &SplitOptions - The extension of the files, whether element modifiers are scoped too, and the separators of the classes.
+///
+/// # Returns
+///
+/// * `Vec<(String, String)>` - The file names and their CSS, base file first.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_css_split, SplitOptions };
+///
+/// let bem_block = parse("media-player[dark]\nbutton").unwrap();
+/// let files = to_css_split(&bem_block, &SplitOptions::default());
+///
+/// assert_eq!(files[0], ("media-player.css".to_string(), ".media-player { }\n\n.media-player__button { }\n".to_string()));
+/// assert_eq!(files[1].0, "media-player--dark.css");
+/// assert!(files[1].1.contains(".media-player--dark .media-player__button { }"));
+/// ```
+pub fn to_css_split(bem_block: &BEMBlock, options: &SplitOptions) -> Vec<(String, String)> {
+	let mut names: Vec<String> = Vec::new();
+	let mut file_name = |stem: &str| {
+		let stem: String = stem
+			.chars()
+			.map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+			.collect();
+		let mut name = format!("{}.{}", stem, options.extension);
+		let mut suffix = 2;
+
+		while names.contains(&name) {
+			name = format!("{}-{}.{}", stem, suffix, options.extension);
+			suffix += 1;
+		}
+
+		names.push(name.clone());
+		name
+	};
+	let config = &options.naming;
+	let selectors = |scope: &str, element_modifiers: bool| {
+		let mut selectors = Vec::new();
+
+		for element in &bem_block.elements {
+			let class = config.element_class(&bem_block.name, &element.name);
+
+			selectors.push(format!("{}.{}", scope, css_escape(&class)));
+
+			if element_modifiers {
+				for modifier in &element.modifiers {
+					selectors.push(format!("{}.{}", scope, css_escape(&config.modifier_class(&class, &modifier.class_name()))));
+				}
+			}
+		}
+
+		selectors
+	};
+	let stylesheet = |first: String, rest: Vec<String>| {
+		let rules: Vec<String> = std::iter::once(first).chain(rest).map(|selector| empty_rule(&selector, 0)).collect();
+
+		rules.join("\n")
+	};
+
+	let mut files = vec![(file_name(&bem_block.name), stylesheet(format!(".{}", css_escape(&bem_block.name)), selectors("", true)))];
+
+	for modifier in &bem_block.modifiers {
+		let class = format!(".{}", css_escape(&config.modifier_class(&bem_block.name, &modifier.class_name())));
+		let css = stylesheet(class.clone(), selectors(&format!("{} ", class), options.element_modifiers));
+
+		files.push((file_name(&config.modifier_class(&bem_block.name, &modifier.class_name())), css));
+	}
+
+	files
+}
+
+/// Renders a rule with the given selector, separating nested rules with blank lines.
+/// Rules without nested rules collapse into an empty `{ }` body.
+fn rule(selector: &str, nested: &[String], depth: usize) -> String {
+	if nested.is_empty() {
+		return empty_rule(selector, depth);
+	}
+
+	let indent = "\t".repeat(depth);
+
+	format!("{}{} {{\n{}{}}}\n", indent, selector, nested.join("\n"), indent)
+}
+
+fn empty_rule(selector: &str, depth: usize) -> String {
+	format!("{}{} {{ }}\n", "\t".repeat(depth), selector)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::attributes::AttributeScheme;
+	use crate::parse;
+
+	fn assert_balanced_braces(css: &str) {
+		let mut depth = 0;
+
+		for c in css.chars() {
+			match c {
+				'{' => {
+					depth += 1;
+				}
+				'}' => {
+					assert!(depth > 0, "unbalanced closing brace in:\n{}", css);
+					depth -= 1;
+				}
+				_ => {}
+			}
+		}
+
+		assert_eq!(depth, 0, "unclosed braces in:\n{}", css);
+	}
+
+	fn assert_valid_nesting(css: &str) {
+		assert_balanced_braces(css);
+		assert!(!css.contains("&__"), "invalid `&__` shorthand in:\n{}", css);
+		assert!(!css.contains("&--"), "invalid `&--` shorthand in:\n{}", css);
+	}
+
+	#[test]
+	fn test_to_css_nested() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let css = super::to_css_nested(&bem_block);
+
+		assert_valid_nesting(&css);
+
+		insta::assert_snapshot!(css);
+	}
+
+	#[test]
+	fn test_to_css_nested_with_boolean_attributes() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let css = super::to_css_nested_with_attributes(&bem_block, &AttributeScheme::Boolean, &crate::BemConfig::default());
+
+		assert_valid_nesting(&css);
+
+		insta::assert_snapshot!(css);
+	}
+
+	#[test]
+	fn test_to_css_nested_with_key_value_attributes() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let css = super::to_css_nested_with_attributes(&bem_block, &AttributeScheme::KeyValue("data-state".to_string()), &crate::BemConfig::default());
+
+		assert_valid_nesting(&css);
+
+		insta::assert_snapshot!(css);
+	}
+
+	#[test]
+	fn test_to_css_split() {
+		let mut bem_block = parse("media-player[dark]\nbutton[rewind]\ntimeline").unwrap();
+		bem_block.modifiers.extend(["dark@mobile".into(), "dark#mobile".into()]);
+
+		let files = super::to_css_split(&bem_block, &super::SplitOptions::default());
+		let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+
+		assert_eq!(names, vec!["media-player.css", "media-player--dark.css", "media-player--dark_mobile.css", "media-player--dark_mobile-2.css"]);
+		assert_eq!(files[0].1, ".media-player { }\n\n.media-player__button { }\n\n.media-player__button--rewind { }\n\n.media-player__timeline { }\n");
+		assert_eq!(
+			files[1].1,
+			".media-player--dark { }\n\n.media-player--dark .media-player__button { }\n\n.media-player--dark .media-player__button--rewind { }\n\n.media-player--dark .media-player__timeline { }\n"
+		);
+		assert!(files[2].1.starts_with(".media-player--dark\\@mobile { }\n\n.media-player--dark\\@mobile .media-player__button { }"));
+
+		for (_, css) in &files {
+			assert_balanced_braces(css);
+		}
+	}
+
+	#[test]
+	fn test_to_css_split_without_element_modifiers() {
+		let bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+		let options = super::SplitOptions { extension: "scss".to_string(), element_modifiers: false, ..Default::default() };
+		let files = super::to_css_split(&bem_block, &options);
+
+		assert_eq!(files[1], ("media-player--dark.scss".to_string(), ".media-player--dark { }\n\n.media-player--dark .media-player__button { }\n".to_string()));
+	}
+
+	#[test]
+	fn test_to_css_split_with_config() {
+		let bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+		let naming = crate::BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+		let options = super::SplitOptions { naming, ..Default::default() };
+		let files = super::to_css_split(&bem_block, &options);
+
+		assert_eq!(files[0].1, ".media-player { }\n\n.media-player-button { }\n\n.media-player-button_rewind { }\n");
+		assert_eq!(files[1].0, "media-player_dark.css");
+		assert!(files[1].1.contains(".media-player_dark .media-player-button_rewind { }"));
+	}
+
+	#[test]
+	fn test_to_css_split_block_without_modifiers() {
+		let bem_block = parse("play-list\nitem[playing]").unwrap();
+		let files = super::to_css_split(&bem_block, &super::SplitOptions::default());
+
+		assert_eq!(files, vec![("play-list.css".to_string(), ".play-list { }\n\n.play-list__item { }\n\n.play-list__item--playing { }\n".to_string())]);
+	}
+
+	#[test]
+	fn test_to_css_nested_with_config() {
+		let bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+		let config = crate::BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+		let css = super::to_css_nested_with_config(&bem_block, &config);
+
+		assert_valid_nesting(&css);
+
+		assert_eq!(css, ".media-player {\n\t&.media-player_dark { }\n\n\t& .media-player-button {\n\t\t&.media-player-button_rewind { }\n\t}\n}\n");
+		assert_eq!(super::to_css_nested(&bem_block), super::to_css_nested_with_config(&bem_block, &crate::BemConfig::default()));
+	}
+
+	#[test]
+	fn test_to_css_nested_with_attributes_and_config() {
+		let bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+		let config = crate::BemConfig { element_separator: "_".to_string(), modifier_separator: "-".to_string() };
+		let css = super::to_css_nested_with_attributes(&bem_block, &AttributeScheme::Boolean, &config);
+
+		assert_valid_nesting(&css);
+
+		assert_eq!(css, ".media-player {\n\t&[data-dark] { }\n\n\t& .media-player_button {\n\t\t&[data-rewind] { }\n\t}\n}\n");
+	}
+
+	#[test]
+	fn test_to_css_nested_block_only() {
+		let bem_block = parse("foo").unwrap();
+		let css = super::to_css_nested(&bem_block);
+
+		assert_valid_nesting(&css);
+
+		assert_eq!(css, ".foo { }\n");
+	}
+
+	#[test]
+	fn test_to_css_nested_elements_without_modifiers() {
+		let bem_block = parse("foo\nbar\nbaz").unwrap();
+		let css = super::to_css_nested(&bem_block);
+
+		assert_valid_nesting(&css);
+
+		assert_eq!(css, ".foo {\n\t& .foo__bar { }\n\n\t& .foo__baz { }\n}\n");
+	}
+
+	#[test]
+	fn test_to_css_nested_escapes_classes() {
+		let mut bem_block = parse("foo\nbar").unwrap();
+		bem_block.name = "1st".to_string();
+		bem_block.modifiers.push("hover:dark".into());
+		bem_block.elements.get_mut("bar").unwrap().modifiers.push("dark mode".into());
+
+		let css = super::to_css_nested(&bem_block);
+
+		assert_valid_nesting(&css);
+
+		assert_eq!(
+			css,
+			".\\31 st {\n\t&.\\31 st--hover\\:dark { }\n\n\t& .\\31 st__bar {\n\t\t&.\\31 st__bar--dark\\ mode { }\n\t}\n}\n"
+		);
+		assert_eq!(super::to_scss_placeholders(&bem_block, &crate::BemConfig::default()).lines().next(), Some("%\\31 st { }"));
+		assert!(super::to_scss_mixins(&bem_block, &crate::BemConfig::default()).unwrap().starts_with("@mixin \\31 st("));
+	}
+
+	#[test]
+	fn test_to_scss_mixins() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let scss = super::to_scss_mixins(&bem_block, &crate::BemConfig::default()).unwrap();
+
+		assert_balanced_braces(&scss);
+		assert_eq!(scss.matches("@if ").count(), 3);
+		assert_eq!(scss.matches("@mixin ").count(), 3);
+
+		insta::assert_snapshot!(scss);
+	}
+
+	#[test]
+	fn test_to_scss_mixins_with_config() {
+		let bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+		let config = crate::BemConfig { element_separator: "_".to_string(), modifier_separator: "-".to_string() };
+		let scss = super::to_scss_mixins(&bem_block, &config).unwrap();
+
+		assert_eq!(scss, "@mixin media-player($dark: false) {\n\t@if $dark { }\n}\n\n@mixin media-player_button($rewind: false) {\n\t@if $rewind { }\n}\n");
+	}
+
+	#[test]
+	fn test_to_scss_mixins_sanitizes_arguments() {
+		let mut bem_block = parse("foo\nbar\nbar").unwrap();
+		bem_block.modifiers.push("2x:large".into());
+
+		let scss = super::to_scss_mixins(&bem_block, &crate::BemConfig::default()).unwrap();
+
+		assert_eq!(scss, "@mixin foo($x2x-large: false) {\n\t@if $x2x-large { }\n}\n\n@mixin foo__bar { }\n");
+	}
+
+	#[test]
+	fn test_to_scss_mixins_argument_collision() {
+		let mut bem_block = parse("foo[fast-forward]").unwrap();
+		bem_block.modifiers.push("fast_forward".into());
+
+		let error = super::to_scss_mixins(&bem_block, &crate::BemConfig::default()).unwrap_err();
+
+		assert_eq!(error.identifier, "fast-forward");
+		assert_eq!(error.sources, vec!["foo--fast-forward".to_string(), "foo--fast_forward".to_string()]);
+	}
+
+	#[test]
+	fn test_to_scss_placeholders() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let scss = super::to_scss_placeholders(&bem_block, &crate::BemConfig::default());
+
+		assert_balanced_braces(&scss);
+		assert_eq!(scss.matches('%').count(), 6);
+
+		insta::assert_snapshot!(scss);
+	}
+
+	#[test]
+	fn test_to_scss_placeholders_with_config() {
+		let bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+		let config = crate::BemConfig { element_separator: "_".to_string(), modifier_separator: "-".to_string() };
+
+		assert_eq!(
+			super::to_scss_placeholders(&bem_block, &config),
+			"%media-player { }\n\n%media-player-dark { }\n\n%media-player_button { }\n\n%media-player_button-rewind { }\n"
+		);
+	}
+
+	#[test]
+	fn test_to_scss() {
+		let bem_block = parse("media-player[dark,size=lg]\nbutton[fast-forward,rewind]\ntimeline\nvolume[muted]").unwrap();
+		let scss = super::to_scss(&bem_block, &crate::BemConfig::default());
+
+		assert_balanced_braces(&scss);
+		assert_eq!(scss.matches("&--").count(), 5);
+
+		insta::assert_snapshot!(scss);
+	}
+
+	#[test]
+	fn test_to_scss_without_modifiers() {
+		let config = crate::BemConfig::default();
+
+		assert_eq!(super::to_scss(&parse("media-player").unwrap(), &config), ".media-player { }\n");
+		assert_eq!(
+			super::to_scss(&parse("media-player\nbutton\ntimeline").unwrap(), &config),
+			".media-player {\n\t.media-player__button { }\n\n\t.media-player__timeline { }\n}\n"
+		);
+	}
+
+	#[test]
+	fn test_to_scss_with_config_and_escapes() {
+		let config = crate::BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+		let bem_block = crate::BEMBlock::new("media-player")
+			.with_modifier("dark@mobile")
+			.with_element(crate::BEMElement::new("button").with_modifier("rewind"));
+		let scss = super::to_scss(&bem_block, &config);
+
+		assert!(scss.contains("\t&_dark\\@mobile { }\n"));
+		assert!(scss.contains("\t.media-player-button {\n\t\t&_rewind { }\n\t}\n"));
+		assert_eq!(super::modifier_suffix("-", "dark", &config), Err("-_dark".to_string()));
+	}
+
+	#[test]
+	fn test_to_scss_modifier_without_suffix() {
+		let config = crate::BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+		let bem_block = crate::BEMBlock::new("-")
+			.with_modifier("dark@mobile")
+			.with_modifier("compact")
+			.with_element(crate::BEMElement::new("button").with_modifier("rewind"));
+		let scss = super::to_scss(&bem_block, &config);
+
+		assert_balanced_braces(&scss);
+
+		assert_eq!(scss, ".\\- {\n\t.--button {\n\t\t&_rewind { }\n\t}\n}\n\n.-_dark\\@mobile { }\n\n.-_compact { }\n");
+	}
+
+	#[test]
+	fn test_to_css_modules() {
+		let bem_block = parse("media-player[dark,size=lg]\nbutton[fast-forward,rewind]\ntimeline\nvolume[muted]").unwrap();
+		let config = crate::BemConfig::default();
+		let css = super::to_css_modules(&bem_block, &config);
+		let dts = super::to_css_modules_dts(&[bem_block, parse("play-list\nitem").unwrap()], &config);
+
+		assert_balanced_braces(&css);
+		assert_eq!(css.matches(" { }").count(), 9);
+		assert!(!css.contains('-'));
+
+		insta::assert_snapshot!(css);
+		insta::assert_snapshot!(dts);
+	}
+
+	#[test]
+	fn test_to_css_modules_identifiers() {
+		let config = crate::BemConfig::default();
+		let bem_block = crate::BEMBlock::new("media-player")
+			.with_modifier("2x")
+			.with_element(crate::BEMElement::new("new"));
+		let css = super::to_css_modules(&bem_block, &config);
+		let collision = parse("media-player\nbutton[rewind]\nbutton-rewind\nbutton-rewind2").unwrap();
+
+		assert_eq!(css, ".mediaPlayer { }\n\n.mediaPlayer2x { }\n\n.mediaPlayerNew { }\n");
+		assert_eq!(
+			super::to_css_modules(&collision, &config),
+			".mediaPlayer { }\n\n.mediaPlayerButton { }\n\n.mediaPlayerButtonRewind { }\n\n.mediaPlayerButtonRewind_2 { }\n\n.mediaPlayerButtonRewind2 { }\n"
+		);
+		assert!(super::to_css_modules_dts(&[parse("a-b").unwrap(), parse("a\nb").unwrap()], &config).contains("\treadonly aB: string;\n\treadonly a: string;\n\treadonly aB_2: string;\n"));
+	}
+
+	#[test]
+	fn test_to_css_modules_identifiers_ending_in_digits() {
+		let config = crate::BemConfig::default();
+		let collision = parse("media-player\nbutton[rewind-2]\nbutton-rewind-2\nbutton-rewind-22").unwrap();
+
+		assert_eq!(
+			super::to_css_modules(&collision, &config),
+			".mediaPlayer { }\n\n.mediaPlayerButton { }\n\n.mediaPlayerButtonRewind2 { }\n\n.mediaPlayerButtonRewind2_2 { }\n\n.mediaPlayerButtonRewind22 { }\n"
+		);
+	}
+
+	#[test]
+	fn test_generate_css_skeleton() {
+		let bem_block = parse("media-player[dark,size=lg]\nbutton[fast-forward,rewind]\ntimeline\nvolume[muted]").unwrap();
+		let css = super::generate_css_skeleton(&bem_block, &crate::BemConfig::default());
+
+		assert_balanced_braces(&css);
+		assert_eq!(css.matches(" { }").count(), 9);
+
+		insta::assert_snapshot!(css);
+	}
+
+	#[test]
+	fn test_generate_css_skeleton_with_config() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let config = crate::BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+
+		insta::assert_snapshot!(super::generate_css_skeleton(&bem_block, &config));
+	}
+}
@@ -0,0 +1,847 @@
+//! This module runs the subcommands of the `bem` command-line tool, like `bem lint` and
+//! `bem diff`, reaching files and streams only through a [`PipelineIo`], see [`crate::pipeline`].
+//!
+//! Every subcommand takes its flags as an options struct, writes its output to stdout, and
+//! returns the output too, so embedding tools can use it without capturing stdout.
+
+use std::io;
+use std::path::{ Path, PathBuf };
+use regex::Regex;
+use crate::changelog::{ changelog, ChangelogOptions };
+use crate::classes::from_classes;
+use crate::emit::EmitContext;
+use crate::error::Error;
+use crate::files::{ bem_files, parse_source };
+use crate::filter::Glob;
+use crate::generate::{ generate, GenerateConfig };
+use crate::impact::semver_document_impact;
+use crate::js::scan_js;
+use crate::lint::{ lint_css_verbose, CssLintConfig };
+use crate::models::{ BEMBlock, BEMDocument };
+use crate::naming::NamingScheme;
+use crate::parser::{ parse, parse_block_line, parse_element, GRAMMAR_VERSION };
+use crate::patch::apply_merge_patch;
+use crate::pipeline::{ emit_to_bytes, PipelineIo };
+use crate::schema::{ schema_dump_with, SchemaLocation };
+use crate::search::{ find, SearchScope };
+use crate::usage::{ usage_report, ScanSource, SourceKind };
+
+/// The format the subcommands that print blocks default to.
+const BEM_FORMAT: &str = "bem";
+
+/// The flags of `bem audit`, see [`run_audit`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditOptions {
+	/// The BEM files, or directories of `.bem` files, of the design system.
+	pub files: Vec<PathBuf>,
+	/// Markup files to count class uses in, as paths or globs.
+	pub html: Vec<String>,
+	/// Stylesheets to count class uses in, as paths or globs.
+	pub css: Vec<String>,
+	/// JS/TS files to count class uses in, as paths or globs.
+	pub js: Vec<String>,
+	/// The file to write the uses of every entity to, as JSON if it ends in `.json` and CSV
+	/// otherwise.
+	pub usage_report: Option<PathBuf>,
+}
+
+/// The flags of `bem diff`, see [`run_diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffOptions {
+	/// The published version, a BEM file or a directory of `.bem` files.
+	pub old: PathBuf,
+	/// The version about to be published.
+	pub new: PathBuf,
+	/// Whether to print the semver impact of the changes before the changes themselves.
+	pub impact: bool,
+	/// Whether to print Markdown release notes instead of the list of changes.
+	pub changelog: bool,
+}
+
+/// The flags of `bem doctor`, see [`run_doctor`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DoctorOptions {
+	/// The BEM file to diagnose, or `None` to only report the environment.
+	pub input: Option<PathBuf>,
+	/// Whether to print the diagnosis as JSON.
+	pub json: bool,
+}
+
+/// The flags of `bem generate`, see [`run_generate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerateOptions {
+	/// The seed of the generator.
+	pub seed: u64,
+	/// The numbers of elements and modifiers.
+	pub config: GenerateConfig,
+	/// The id of the emitter of the output, `bem` by default.
+	pub format: String,
+}
+
+impl Default for GenerateOptions {
+	fn default() -> Self {
+		GenerateOptions { seed: 0, config: GenerateConfig::default(), format: BEM_FORMAT.to_string() }
+	}
+}
+
+/// The flags of `bem grep`, see [`run_grep`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GrepOptions {
+	/// The regex names are matched against, anywhere in the name unless anchored.
+	pub pattern: String,
+	/// The BEM files, or directories of `.bem` files, to search.
+	pub files: Vec<PathBuf>,
+	/// Which names to search.
+	pub scope: SearchScope,
+	/// Whether to print the matches as a JSON array, with their capture groups.
+	pub json: bool,
+}
+
+/// The flags of `bem lint`, see [`run_lint`], and of the lint stage of
+/// [`run`](crate::pipeline::run), see [`RunOptions::lint`](crate::pipeline::RunOptions::lint).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintOptions {
+	/// The stylesheets to lint.
+	pub css: Vec<PathBuf>,
+	/// The rules to lint with.
+	pub config: CssLintConfig,
+	/// Whether to also report, and fail on, `bem-lint-disable` comments that suppress nothing.
+	pub report_unused_suppressions: bool,
+}
+
+/// The flags of `bem patch`, see [`run_patch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchOptions {
+	/// The JSON merge patch to apply.
+	pub merge: PathBuf,
+	/// The BEM file to patch.
+	pub input: PathBuf,
+	/// The id of the emitter of the output, `bem` by default.
+	pub format: String,
+}
+
+impl Default for PatchOptions {
+	fn default() -> Self {
+		PatchOptions { merge: PathBuf::new(), input: PathBuf::new(), format: BEM_FORMAT.to_string() }
+	}
+}
+
+/// The flags of `bem scan`, see [`run_scan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+	/// The JS/TS files to scan, as paths or globs like `src/**/*.tsx` where `*` also matches `/`.
+	pub js: Vec<String>,
+	/// The id of the emitter of the output, `bem` by default.
+	pub format: String,
+}
+
+impl Default for ScanOptions {
+	fn default() -> Self {
+		ScanOptions { js: Vec::new(), format: BEM_FORMAT.to_string() }
+	}
+}
+
+/// The flags of `bem schema-dump`, see [`run_schema_dump`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDumpOptions {
+	/// The BEM files, or directories of `.bem` files, to dump.
+	pub files: Vec<PathBuf>,
+	/// Whether to print the versioned JSON structure of [`SchemaDump`](crate::SchemaDump)
+	/// instead of one class per line.
+	pub json: bool,
+}
+
+/// Writes `output` to stdout and returns it.
+fn print(io: &mut dyn PipelineIo, output: String) -> Result<String, Error> {
+	io.write_stdout(output.as_bytes())?;
+
+	Ok(output)
+}
+
+/// Writes `output` to stdout and returns it as text.
+fn print_bytes(io: &mut dyn PipelineIo, output: Vec<u8>) -> Result<String, Error> {
+	io.write_stdout(&output)?;
+
+	Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+/// Returns an [`Error::Io`] of `kind`, for the errors of the subcommands themselves.
+fn io_error(kind: io::ErrorKind, message: String) -> Error {
+	Error::Io(io::Error::new(kind, message))
+}
+
+/// Returns the `.bem` files of a directory in file name order, or the path itself if it is not a
+/// directory.
+fn bem_paths(io: &mut dyn PipelineIo, path: &Path) -> Result<Vec<PathBuf>, Error> {
+	if !io.is_dir(path) {
+		return Ok(vec![path.to_path_buf()]);
+	}
+
+	let entries = io.read_dir(path).map_err(|error| Error::from(error).in_file(path))?;
+
+	Ok(bem_files(entries, |entry| io.is_dir(entry)))
+}
+
+/// Parses a `.bem` file, or every `.bem` file of a directory, keeping the path each block was
+/// read from.
+fn read_files(io: &mut dyn PipelineIo, path: &Path) -> Result<Vec<(PathBuf, BEMBlock)>, Error> {
+	bem_paths(io, path)?
+		.into_iter()
+		.map(|path| {
+			let bem_input = io.read_to_string(&path).map_err(|error| Error::from(error).in_file(&path))?;
+			let bem_block = parse_source(&path, &bem_input)?;
+
+			Ok((path, bem_block))
+		})
+		.collect()
+}
+
+/// Parses a `.bem` file, or every `.bem` file of a directory in file name order.
+fn read_blocks(io: &mut dyn PipelineIo, path: &Path) -> Result<Vec<BEMBlock>, Error> {
+	Ok(read_files(io, path)?.into_iter().map(|(_, bem_block)| bem_block).collect())
+}
+
+/// Returns the files matching `pattern`, in path order. The search starts from the directories of
+/// the pattern before its first wildcard, and `**/` also matches no directory at all.
+fn glob_paths(io: &mut dyn PipelineIo, pattern: &str) -> Result<Vec<PathBuf>, Error> {
+	if !pattern.contains(['*', '?']) {
+		return Ok(vec![pattern.into()]);
+	}
+
+	let prefix = pattern[..pattern.find(['*', '?']).unwrap_or(pattern.len())].rsplit_once('/').map_or("", |(root, _)| root);
+	let globs = [Glob::new(pattern), Glob::new(&pattern.replace("**/", ""))];
+	let mut directories = vec![PathBuf::from(if prefix.is_empty() { "." } else { prefix })];
+	let mut paths = Vec::new();
+
+	while let Some(directory) = directories.pop() {
+		for path in io.read_dir(&directory)? {
+			if io.is_dir(&path) {
+				directories.push(path);
+				continue;
+			}
+
+			let name = path.to_string_lossy().replace('\\', "/");
+			let name = if prefix.is_empty() { name.trim_start_matches("./").to_string() } else { name };
+
+			if globs.iter().any(|glob| glob.matches(&name)) {
+				paths.push(path);
+			}
+		}
+	}
+
+	paths.sort();
+	Ok(paths)
+}
+
+/// Prints the findings of the design audit of every block against the others, and writes the
+/// usage report of the sources if asked to.
+pub fn run_audit(options: &AuditOptions, io: &mut dyn PipelineIo) -> Result<String, Error> {
+	let mut blocks = Vec::new();
+
+	for file in &options.files {
+		blocks.extend(read_blocks(io, file)?);
+	}
+
+	let mut output = String::new();
+
+	for (position, bem_block) in blocks.iter().enumerate() {
+		let peers: Vec<BEMBlock> = blocks
+			.iter()
+			.enumerate()
+			.filter(|(peer, _)| *peer != position)
+			.map(|(_, peer)| peer.clone())
+			.collect();
+		let audit = bem_block.to_bem_design_audit(&peers);
+
+		for modifier in &audit.inconsistent_modifier_names {
+			output.push_str(&format!("{}: modifier `{}` is spelled differently by other blocks\n", bem_block.name, modifier));
+		}
+		for element in &audit.duplicate_element_names {
+			output.push_str(&format!("{}: element `{}` is also the name of a block\n", bem_block.name, element));
+		}
+		for violation in &audit.naming_convention_violations {
+			output.push_str(&format!("{}: {}\n", bem_block.name, violation));
+		}
+	}
+
+	if let Some(path) = &options.usage_report {
+		let mut sources = Vec::new();
+
+		for (patterns, kind) in [(&options.html, SourceKind::Html), (&options.css, SourceKind::Css), (&options.js, SourceKind::Js)] {
+			for pattern in patterns {
+				for source_path in glob_paths(io, pattern)? {
+					let source = io.read_to_string(&source_path)?;
+
+					sources.push(match kind {
+						SourceKind::Html => ScanSource::html(&source),
+						SourceKind::Css => ScanSource::css(&source),
+						SourceKind::Js => ScanSource::js(&source)
+							.map_err(|e| io_error(io::ErrorKind::InvalidData, format!("{}: {}", source_path.display(), e)))?,
+					});
+				}
+			}
+		}
+
+		let report = usage_report(&blocks, &sources);
+		let contents = match path.extension().is_some_and(|extension| extension == "json") {
+			true => serde_json::to_string_pretty(&report).map_err(Error::Serialize)? + "\n",
+			false => report.to_csv(),
+		};
+
+		io.write(path, contents.as_bytes())?;
+	}
+
+	print(io, output)
+}
+
+/// Prints the changes between two versions, with their semver impact if asked to, or Markdown
+/// release notes.
+pub fn run_diff(options: &DiffOptions, io: &mut dyn PipelineIo) -> Result<String, Error> {
+	let old = read_blocks(io, &options.old)?;
+	let new = read_blocks(io, &options.new)?;
+
+	if options.changelog {
+		return print(io, changelog(&old, &new, &ChangelogOptions::default()));
+	}
+
+	let report = semver_document_impact(&old, &new);
+	let mut output = String::new();
+
+	if options.impact {
+		output.push_str(&format!("Impact: {}\n", report.level));
+	}
+
+	for change in &report.changes {
+		output.push_str(&format!("- {}\n", change));
+	}
+
+	if !options.impact && report.changes.is_empty() {
+		output.push_str("No structural changes\n");
+	}
+
+	print(io, output)
+}
+
+/// Returns the optional features the crate was built with, in the order of `Cargo.toml`.
+pub fn features() -> Vec<&'static str> {
+	[("yaml", cfg!(feature = "yaml")), ("toml", cfg!(feature = "toml")), ("miette", cfg!(feature = "miette")), ("tokio", cfg!(feature = "tokio"))]
+		.into_iter()
+		.filter(|(_, enabled)| *enabled)
+		.map(|(feature, _)| feature)
+		.collect()
+}
+
+/// Returns the optional features the crate was built with as a list for people, `none` if
+/// there are none.
+fn feature_list() -> String {
+	match features().as_slice() {
+		[] => "none".to_string(),
+		features => features.join(", "),
+	}
+}
+
+/// What `bem doctor` found out about an input file.
+#[derive(Debug, Default, PartialEq)]
+struct Diagnosis {
+	/// `utf-8`, `utf-16le`, `utf-16be`, or `unknown` if the bytes are none of them.
+	encoding: &'static str,
+	bom: bool,
+	/// `lf`, `crlf`, `cr`, `mixed`, or `none` for a single line.
+	line_endings: &'static str,
+	clean_lines: usize,
+	/// The lines that fail to parse, numbered from 1, with the parse error.
+	failing_lines: Vec<(usize, String)>,
+	suggestions: Vec<String>,
+}
+
+/// Detects the encoding of `bytes` from its byte order mark, or from the zero bytes UTF-16 puts
+/// in ASCII text. Returns the encoding and whether there is a byte order mark.
+fn detect_encoding(bytes: &[u8]) -> (&'static str, bool) {
+	match bytes {
+		[0xEF, 0xBB, 0xBF, ..] => ("utf-8", true),
+		[0xFF, 0xFE, ..] => ("utf-16le", true),
+		[0xFE, 0xFF, ..] => ("utf-16be", true),
+		[first, 0, ..] if *first != 0 => ("utf-16le", false),
+		[0, second, ..] if *second != 0 => ("utf-16be", false),
+		_ if std::str::from_utf8(bytes).is_ok() => ("utf-8", false),
+		_ => ("unknown", false),
+	}
+}
+
+/// Decodes `bytes` in `encoding`, without the byte order mark.
+fn decode_text(bytes: &[u8], encoding: &str, bom: bool) -> Option<String> {
+	let bytes = match (bom, encoding) {
+		(true, "utf-8") => &bytes[3..],
+		(true, _) => &bytes[2..],
+		(false, _) => bytes,
+	};
+	let units = |to_u16: fn([u8; 2]) -> u16| bytes
+		.chunks(2)
+		.map(|pair| to_u16([pair[0], *pair.get(1).unwrap_or(&0)]))
+		.collect::<Vec<u16>>();
+
+	match encoding {
+		"utf-8" => String::from_utf8(bytes.to_vec()).ok(),
+		"utf-16le" => String::from_utf16(&units(u16::from_le_bytes)).ok(),
+		"utf-16be" => String::from_utf16(&units(u16::from_be_bytes)).ok(),
+		_ => None,
+	}
+}
+
+/// Names the line ending style of `text`.
+fn line_endings(text: &str) -> &'static str {
+	let crlf = text.matches("\r\n").count();
+	let lf = text.matches('\n').count() - crlf;
+	let cr = text.matches('\r').count() - crlf;
+
+	match (lf > 0, crlf > 0, cr > 0) {
+		(false, false, false) => "none",
+		(true, false, false) => "lf",
+		(false, true, false) => "crlf",
+		(false, false, true) => "cr",
+		_ => "mixed",
+	}
+}
+
+/// Suggests how to fix a line that fails to parse.
+fn line_suggestion(number: usize, line: &str, message: &str) -> String {
+	if line.contains('(') || line.contains(')') {
+		format!("line {} uses parentheses; write modifiers in square brackets, like `button[dark]`", number)
+	} else if line.chars().any(|c| c.is_ascii_uppercase()) {
+		format!("line {} has uppercase letters; names are lowercase", number)
+	} else if line.contains('_') {
+		format!("line {} has underscores; separate words with `-`", number)
+	} else if line.trim() != line {
+		format!("line {} has leading or trailing spaces; remove them", number)
+	} else {
+		format!("line {} does not parse: {}", number, message)
+	}
+}
+
+/// Diagnoses the contents of a BEM file. Lines are checked one at a time by a lenient parse,
+/// which takes the first line as the block and the others as elements, skips blank lines, and
+/// accepts `#!bem` and `@set` lines, so a modifier list spanning several lines is reported as
+/// failing unless the whole file parses.
+fn diagnose(bytes: &[u8]) -> Diagnosis {
+	let (encoding, bom) = detect_encoding(bytes);
+	let mut diagnosis = Diagnosis { encoding, bom, line_endings: "none", ..Diagnosis::default() };
+	let Some(text) = decode_text(bytes, encoding, bom) else {
+		diagnosis.suggestions.push(match encoding {
+			"unknown" => "file is neither UTF-8 nor UTF-16; convert it to UTF-8".to_string(),
+			encoding => format!("file is not valid {}; convert it to UTF-8", encoding.to_uppercase()),
+		});
+		return diagnosis;
+	};
+
+	diagnosis.line_endings = line_endings(&text);
+
+	match (encoding, bom) {
+		("utf-8", false) => {}
+		("utf-8", true) => diagnosis.suggestions.push("file starts with a byte order mark; save it as UTF-8 without BOM".to_string()),
+		_ => diagnosis.suggestions.push("file is UTF-16; convert to UTF-8".to_string()),
+	}
+
+	if diagnosis.line_endings == "mixed" || diagnosis.line_endings == "cr" {
+		diagnosis.suggestions.push(format!("file uses {} line endings; convert them to LF or CRLF", diagnosis.line_endings));
+	}
+
+	let parses = parse(&text).is_ok();
+	let mut seen_block = false;
+
+	for (index, line) in text.replace("\r\n", "\n").replace('\r', "\n").split('\n').enumerate() {
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		let directive = line.starts_with("#!bem") || line.starts_with("@set") || line.trim_start().starts_with("//");
+		let result = if parses || directive {
+			Ok(())
+		} else if seen_block {
+			parse_element(line).map(|_| ())
+		} else {
+			parse_block_line(line).map(|_| ())
+		};
+
+		seen_block |= !directive;
+
+		match result {
+			Ok(()) => diagnosis.clean_lines += 1,
+			Err(error) => {
+				diagnosis.suggestions.push(line_suggestion(index + 1, line, &error.message));
+				diagnosis.failing_lines.push((index + 1, error.message));
+			}
+		}
+	}
+
+	diagnosis
+}
+
+/// Prints the versions of the tool, and with an input the diagnosis of the file, as text or JSON.
+pub fn run_doctor(options: &DoctorOptions, io: &mut dyn PipelineIo) -> Result<String, Error> {
+	let diagnosis = match options.input.as_deref() {
+		Some(path) => Some((path, diagnose(&io.read(path)?))),
+		None => None,
+	};
+	let output = if options.json {
+		let input = diagnosis.as_ref().map(|(path, diagnosis)| serde_json::json!({
+			"path": path,
+			"encoding": diagnosis.encoding,
+			"bom": diagnosis.bom,
+			"line_endings": diagnosis.line_endings,
+			"lines": { "clean": diagnosis.clean_lines, "failing": diagnosis.failing_lines.len() },
+			"failures": diagnosis.failing_lines
+				.iter()
+				.map(|(line, message)| serde_json::json!({ "line": line, "message": message }))
+				.collect::<Vec<_>>(),
+			"suggestions": diagnosis.suggestions,
+		}));
+
+		serde_json::to_string_pretty(&serde_json::json!({
+			"version": env!("CARGO_PKG_VERSION"),
+			"grammar_version": GRAMMAR_VERSION,
+			"features": features(),
+			"input": input,
+		})).map_err(Error::Serialize)? + "\n"
+	} else {
+		let mut output = format!("bem {} (grammar version {})\n", env!("CARGO_PKG_VERSION"), GRAMMAR_VERSION);
+
+		output.push_str(&format!("features: {}\n", feature_list()));
+
+		if let Some((path, diagnosis)) = &diagnosis {
+			output.push_str(&format!("input: {}\n", path.display()));
+			output.push_str(&format!("encoding: {}{}\n", diagnosis.encoding, if diagnosis.bom { " with BOM" } else { "" }));
+			output.push_str(&format!("line endings: {}\n", diagnosis.line_endings));
+			output.push_str(&format!("lines: {} clean, {} failing\n", diagnosis.clean_lines, diagnosis.failing_lines.len()));
+
+			for suggestion in &diagnosis.suggestions {
+				output.push_str(&format!("- {}\n", suggestion));
+			}
+		}
+
+		output
+	};
+
+	print(io, output)
+}
+
+/// Prints a random block, always the same for a given seed, in the requested format.
+pub fn run_generate(options: &GenerateOptions, io: &mut dyn PipelineIo) -> Result<String, Error> {
+	let bem_block = generate(options.seed, &options.config);
+	let output = emit_to_bytes(&options.format, &bem_block.into(), &EmitContext::default())?;
+
+	print_bytes(io, output)
+}
+
+/// Prints the blocks, elements, and modifiers whose names match the pattern, one per line or as
+/// JSON.
+pub fn run_grep(options: &GrepOptions, io: &mut dyn PipelineIo) -> Result<String, Error> {
+	let pattern = Regex::new(&options.pattern)
+		.map_err(|e| io_error(io::ErrorKind::InvalidInput, format!("invalid pattern `{}`: {}", options.pattern, e)))?;
+	let mut lines = Vec::new();
+	let mut matches = Vec::new();
+
+	for file in &options.files {
+		for (path, bem_block) in read_files(io, file)? {
+			for found in find(std::slice::from_ref(&bem_block), &pattern, options.scope) {
+				lines.push(format!("{}:{}:{}\n", path.display(), found.path, found.name));
+				matches.push(serde_json::json!({
+					"file": path.display().to_string(),
+					"path": found.path.to_string(),
+					"name": found.name,
+					"captures": found.captures,
+				}));
+			}
+		}
+	}
+
+	let output = match options.json {
+		true => serde_json::to_string_pretty(&matches).map_err(Error::Serialize)? + "\n",
+		false => lines.concat(),
+	};
+
+	print(io, output)
+}
+
+/// The findings of linting the stylesheets of [`LintOptions`].
+pub(crate) struct LintReport {
+	/// One line per finding, and per unused suppression if reported, prefixed with the stylesheet.
+	pub(crate) output: String,
+	violations: usize,
+	unused_suppressions: usize,
+}
+
+impl LintReport {
+	/// Fails if there are findings, or unused suppressions.
+	pub(crate) fn result(&self) -> Result<(), Error> {
+		let message = match (self.violations, self.unused_suppressions) {
+			(0, 0) => return Ok(()),
+			(violations, 0) => format!("found {} BEM violation(s)", violations),
+			(violations, unused) => format!("found {} BEM violation(s) and {} unused suppression(s)", violations, unused),
+		};
+
+		Err(io_error(io::ErrorKind::InvalidData, message))
+	}
+}
+
+/// Lints the stylesheets of `options`.
+pub(crate) fn lint_stylesheets(options: &LintOptions, io: &mut dyn PipelineIo) -> Result<LintReport, Error> {
+	let mut report = LintReport { output: String::new(), violations: 0, unused_suppressions: 0 };
+
+	for path in &options.css {
+		let css = io.read_to_string(path).map_err(|error| Error::from(error).in_file(path))?;
+		let outcome = lint_css_verbose(&css, &options.config);
+
+		for finding in &outcome.findings {
+			report.output.push_str(&format!("{}:{}\n", path.display(), finding));
+			report.violations += 1;
+		}
+
+		if options.report_unused_suppressions {
+			for suppression in outcome.unused_suppressions() {
+				report.output.push_str(&format!("{}:{}\n", path.display(), suppression));
+				report.unused_suppressions += 1;
+			}
+		}
+	}
+
+	Ok(report)
+}
+
+/// Prints one line per finding, prefixed with the stylesheet, and fails if there are any.
+///
+/// # Examples
+///
+/// ```
+/// use bem::pipeline::{ run_lint, LintOptions, MemoryIo };
+///
+/// let mut io = MemoryIo::default();
+/// io.files.insert("styles.css".into(), b".card__title__text { }".to_vec());
+///
+/// let options = LintOptions { css: vec!["styles.css".into()], ..LintOptions::default() };
+/// let error = run_lint(&options, &mut io).unwrap_err();
+///
+/// assert_eq!(std::io::Error::from(error).to_string(), "found 1 BEM violation(s)");
+/// assert!(String::from_utf8(io.stdout).unwrap().starts_with("styles.css:1:"));
+/// ```
+pub fn run_lint(options: &LintOptions, io: &mut dyn PipelineIo) -> Result<String, Error> {
+	let report = lint_stylesheets(options, io)?;
+
+	io.write_stdout(report.output.as_bytes())?;
+	report.result()?;
+
+	Ok(report.output)
+}
+
+/// Applies a JSON merge patch to a BEM file and prints the patched block in the requested format.
+pub fn run_patch(options: &PatchOptions, io: &mut dyn PipelineIo) -> Result<String, Error> {
+	let patch: serde_json::Value = serde_json::from_str(&io.read_to_string(&options.merge)?)
+		.map_err(|e| io_error(io::ErrorKind::InvalidData, format!("{}: {}", options.merge.display(), e)))?;
+	let bem_input = io.read_to_string(&options.input).map_err(|error| Error::from(error).in_file(&options.input))?;
+	let mut bem_block = parse_source(&options.input, &bem_input)?;
+
+	apply_merge_patch(&mut bem_block, &patch)?;
+
+	let output = emit_to_bytes(&options.format, &bem_block.into(), &EmitContext::default())?;
+
+	print_bytes(io, output)
+}
+
+/// Scans the files matching the globs, prints their warnings to stderr, and emits the blocks
+/// found across all of them, merged by name.
+pub fn run_scan(options: &ScanOptions, io: &mut dyn PipelineIo) -> Result<String, Error> {
+	let naming = NamingScheme::default();
+	let mut classes = Vec::new();
+
+	for pattern in &options.js {
+		for path in glob_paths(io, pattern)? {
+			let scan = scan_js(&io.read_to_string(&path)?)
+				.map_err(|e| io_error(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))?;
+
+			for warning in &scan.warnings {
+				io.write_stderr(&format!("{}: {}\n", path.display(), warning))?;
+			}
+
+			classes.extend(scan.blocks.iter().flat_map(|bem_block| naming.class_names(bem_block)));
+		}
+	}
+
+	let document = BEMDocument::from(from_classes(classes));
+	let output = emit_to_bytes(&options.format, &document, &EmitContext::default())?;
+
+	print_bytes(io, output)
+}
+
+/// Prints every class of the files with its location, or the schema dump as JSON.
+pub fn run_schema_dump(options: &SchemaDumpOptions, io: &mut dyn PipelineIo) -> Result<String, Error> {
+	let mut docs = Vec::new();
+
+	for file in &options.files {
+		for (path, bem_block) in read_files(io, file)? {
+			docs.push((path, BEMDocument::from(bem_block)));
+		}
+	}
+
+	let dump = schema_dump_with(&docs, &NamingScheme::default(), |path| io.read_to_string(path).ok());
+	let output = if options.json {
+		serde_json::to_string_pretty(&dump).map_err(Error::Serialize)? + "\n"
+	} else {
+		let mut output = String::new();
+		let mut line = |class: &str, location: &SchemaLocation| {
+			let line = location.line.map(|line| line.to_string()).unwrap_or_default();
+
+			output.push_str(&format!("{}:{}: {}\n", location.file.display(), line, class));
+		};
+
+		for bem_block in &dump.blocks {
+			line(&bem_block.class, &bem_block.location);
+
+			for modifier in &bem_block.modifiers {
+				line(&modifier.class, &modifier.location);
+			}
+
+			for element in &bem_block.elements {
+				line(&element.class, &element.location);
+
+				for modifier in &element.modifiers {
+					line(&modifier.class, &modifier.location);
+				}
+			}
+		}
+
+		output
+	};
+
+	print(io, output)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::PathBuf;
+	use super::{
+		diagnose, feature_list, features, run_audit, run_diff, run_doctor, run_grep, run_lint, run_scan, run_schema_dump,
+		AuditOptions, DiffOptions, DoctorOptions, GrepOptions, LintOptions, ScanOptions, SchemaDumpOptions,
+	};
+	use crate::pipeline::MemoryIo;
+
+	fn io(files: &[(&str, &str)]) -> MemoryIo {
+		MemoryIo {
+			files: files.iter().map(|(path, content)| (PathBuf::from(path), content.as_bytes().to_vec())).collect(),
+			..MemoryIo::default()
+		}
+	}
+
+	fn stdout(io: &MemoryIo) -> String {
+		String::from_utf8(io.stdout.clone()).unwrap()
+	}
+
+	#[test]
+	fn test_features() {
+		assert_eq!(features().contains(&"yaml"), cfg!(feature = "yaml"));
+		assert_eq!(features().contains(&"toml"), cfg!(feature = "toml"));
+		assert_eq!(features().contains(&"miette"), cfg!(feature = "miette"));
+		assert_eq!(features().contains(&"tokio"), cfg!(feature = "tokio"));
+		assert_eq!(feature_list() == "none", features().is_empty());
+	}
+
+	#[test]
+	fn test_diagnose_crlf_and_bom() {
+		let diagnosis = diagnose(b"\xEF\xBB\xBFmedia-player[dark]\r\nbutton\r\n");
+
+		assert_eq!((diagnosis.encoding, diagnosis.bom, diagnosis.line_endings), ("utf-8", true, "crlf"));
+		assert_eq!(diagnosis.clean_lines, 2);
+		assert!(diagnosis.failing_lines.is_empty());
+		assert_eq!(diagnosis.suggestions, vec!["file starts with a byte order mark; save it as UTF-8 without BOM"]);
+
+		let utf16: Vec<u8> = "media-player\nbutton".encode_utf16().flat_map(u16::to_le_bytes).collect();
+		let diagnosis = diagnose(&utf16);
+
+		assert_eq!((diagnosis.encoding, diagnosis.bom, diagnosis.clean_lines), ("utf-16le", false, 2));
+		assert_eq!(diagnosis.suggestions, vec!["file is UTF-16; convert to UTF-8"]);
+	}
+
+	#[test]
+	fn test_diagnose_skips_comment_lines() {
+		let diagnosis = diagnose(b"// media player\nmedia-player[dark]\n  // controls\nbutton // the play button\ntimeline(slow)\n");
+
+		assert_eq!(diagnosis.clean_lines, 4);
+		assert_eq!(diagnosis.failing_lines.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![5]);
+	}
+
+	#[test]
+	fn test_run_doctor_reads_through_the_io() {
+		let mut io = io(&[("media-player.bem", "media-player[dark]\r\nbutton(rewind)\r\n")]);
+		let options = DoctorOptions { input: Some("media-player.bem".into()), json: false };
+		let output = run_doctor(&options, &mut io).unwrap();
+
+		assert!(output.ends_with("input: media-player.bem\nencoding: utf-8\nline endings: crlf\nlines: 1 clean, 1 failing\n- line 2 uses parentheses; write modifiers in square brackets, like `button[dark]`\n"));
+		assert!(output.contains(&format!("features: {}\n", feature_list())));
+		assert_eq!(stdout(&io), output);
+	}
+
+	#[test]
+	fn test_run_grep_reads_directories() {
+		let mut io = io(&[("design/media-player.bem", "media-player[fast-forward]\nfast-button"), ("design/play-list.bem", "play-list"), ("design/notes.txt", "fast")]);
+		let options = GrepOptions { pattern: "^fast-".to_string(), files: vec!["design".into()], ..GrepOptions::default() };
+
+		assert_eq!(run_grep(&options, &mut io).unwrap(), "design/media-player.bem:media-player--fast-forward:fast-forward\ndesign/media-player.bem:media-player__fast-button:fast-button\n");
+	}
+
+	#[test]
+	fn test_run_diff_reads_directories() {
+		let mut io = io(&[("old/media-player.bem", "media-player[dark]\nbutton"), ("new/media-player.bem", "media-player[dark]")]);
+		let options = DiffOptions { old: "old".into(), new: "new".into(), impact: true, changelog: false };
+		let output = run_diff(&options, &mut io).unwrap();
+
+		assert!(output.starts_with("Impact: major\n- "));
+		assert_eq!(stdout(&io), output);
+
+		let error = run_diff(&DiffOptions { old: "missing".into(), ..options }, &mut io).unwrap_err();
+
+		assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::NotFound);
+	}
+
+	#[test]
+	fn test_run_schema_dump_finds_the_lines_of_the_source() {
+		let mut io = io(&[("design/media-player.bem", "media-player[dark]\n// controls\nbutton")]);
+		let options = SchemaDumpOptions { files: vec!["design".into()], json: false };
+
+		assert_eq!(run_schema_dump(&options, &mut io).unwrap(), concat!(
+			"design/media-player.bem:1: media-player\n",
+			"design/media-player.bem:1: media-player--dark\n",
+			"design/media-player.bem:3: media-player__button\n",
+		));
+	}
+
+	#[test]
+	fn test_run_lint_and_audit_through_the_io() {
+		let mut io = io(&[("design/card.bem", "card\ntitle"), ("src/card.css", ".card__title { }\n.card__title__text { }"), ("src/page.html", "<div class=\"card\"></div>")]);
+		let lint = LintOptions { css: vec!["src/card.css".into()], ..LintOptions::default() };
+
+		assert_eq!(std::io::Error::from(run_lint(&lint, &mut io).unwrap_err()).to_string(), "found 1 BEM violation(s)");
+		assert!(stdout(&io).starts_with("src/card.css:2: "));
+
+		let audit = AuditOptions {
+			files: vec!["design".into()],
+			html: vec!["src/*.html".to_string()],
+			css: vec!["src/**/*.css".to_string()],
+			usage_report: Some("usage.json".into()),
+			..AuditOptions::default()
+		};
+
+		run_audit(&audit, &mut io).unwrap();
+
+		let report: serde_json::Value = serde_json::from_slice(&io.files[&PathBuf::from("usage.json")]).unwrap();
+
+		assert_eq!(report["rows"][0]["path"], "card");
+		assert_eq!(report["rows"][0]["html"], 1);
+	}
+
+	#[test]
+	fn test_run_scan_warns_on_stderr() {
+		let mut io = io(&[("src/card.tsx", "const a = 'card card--dark';\nconst b = `card__${part}`;")]);
+		let output = run_scan(&ScanOptions { js: vec!["src/*.tsx".to_string()], ..ScanOptions::default() }, &mut io).unwrap();
+
+		assert_eq!(output, "card[dark]\n");
+		assert!(io.stderr.starts_with("src/card.tsx: "));
+	}
+}
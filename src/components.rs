@@ -0,0 +1,1125 @@
+//! This module generates UI component skeletons from BEM blocks. Every modifier becomes a
+//! boolean prop that toggles the matching modifier class, and every element becomes a child
+//! node carrying its element class.
+
+use serde_json::{ json, Map, Value };
+use crate::abbreviation::AbbreviationMap;
+use crate::attributes::AttributeScheme;
+use crate::budget::{ BudgetError, ClassBudget };
+use crate::builder::ClassBuilder;
+use crate::ident::{ abbreviated_identifier, convert_case, identifier, identifiers, numbered_identifiers, Case, IdentifierError };
+use crate::models::{ BEMBlock, BEMModifier };
+use crate::naming::{ BemConfig, NamingScheme };
+
+/// A boolean prop toggling one modifier class.
+struct ModifierProp {
+	/// The modifier class the prop toggles, e.g. `media-player__button--rewind`.
+	class: String,
+	/// The modifier, e.g. `rewind`.
+	modifier: String,
+	/// The prop name, e.g. `buttonRewind`.
+	ident: String,
+}
+
+/// The props of a component, split by the entity they belong to.
+struct ComponentProps {
+	block: Vec<ModifierProp>,
+	elements: Vec<Vec<ModifierProp>>,
+}
+
+impl ComponentProps {
+	/// Derives one prop per modifier. Block modifiers are named after the modifier (`dark`),
+	/// element modifiers after the element and the modifier (`buttonRewind`), with their segments
+	/// abbreviated by `abbreviations`. The classes are composed with the separators of `scheme`.
+	fn from_block(bem_block: &BEMBlock, abbreviations: &AbbreviationMap, scheme: &NamingScheme) -> Result<ComponentProps, IdentifierError> {
+		let idents = identifiers(ComponentProps::sources(bem_block, scheme), |name| abbreviated_identifier(name, Case::Camel, abbreviations))?;
+
+		Ok(ComponentProps::with_idents(bem_block, scheme, idents))
+	}
+
+	/// Derives the props of [`from_block`](ComponentProps::from_block) with standard BEM classes
+	/// and without abbreviations, numbering a prop name taken by an earlier modifier instead of
+	/// failing, e.g. `buttonRewind_2`.
+	fn numbered(bem_block: &BEMBlock) -> ComponentProps {
+		let scheme = NamingScheme::default();
+		let pairs = ComponentProps::sources(bem_block, &scheme)
+			.into_iter()
+			.map(|(class, name)| {
+				let ident = identifier(&name, Case::Camel);
+
+				(class, ident)
+			});
+
+		ComponentProps::with_idents(bem_block, &scheme, numbered_identifiers(pairs))
+	}
+
+	/// Returns a `(class, name)` pair per modifier, `name` being the source of its prop name.
+	fn sources(bem_block: &BEMBlock, scheme: &NamingScheme) -> Vec<(String, String)> {
+		let mut sources = Vec::new();
+
+		for modifier in &bem_block.modifiers {
+			sources.push((scheme.modifier_class(&bem_block.name, &modifier.class_name()), modifier.class_name().into_owned()));
+		}
+		for element in &bem_block.elements {
+			for modifier in &element.modifiers {
+				sources.push((
+					scheme.modifier_class(&scheme.element_class(&bem_block.name, &element.name), &modifier.class_name()),
+					format!("{} {}", element.name, modifier.class_name()),
+				));
+			}
+		}
+
+		sources
+	}
+
+	/// Builds the props of the block from the `(class, prop name)` pairs of its modifiers.
+	fn with_idents(bem_block: &BEMBlock, scheme: &NamingScheme, idents: Vec<(String, String)>) -> ComponentProps {
+		let prop = |class: String, modifier: &str| {
+			let ident = idents
+				.iter()
+				.find(|(label, _)| *label == class)
+				.map(|(_, ident)| ident.clone())
+				.expect("every modifier class has an identifier");
+
+			ModifierProp { class, modifier: modifier.to_string(), ident }
+		};
+
+		let mut block = Vec::new();
+
+		for modifier in &bem_block.modifiers {
+			let class = scheme.modifier_class(&bem_block.name, &modifier.class_name());
+
+			if !block.iter().any(|existing: &ModifierProp| existing.class == class) {
+				block.push(prop(class, &modifier.class_name()));
+			}
+		}
+
+		let mut elements = Vec::new();
+
+		for element in &bem_block.elements {
+			let mut element_props = Vec::new();
+
+			for modifier in &element.modifiers {
+				let class = scheme.modifier_class(&scheme.element_class(&bem_block.name, &element.name), &modifier.class_name());
+
+				if !element_props.iter().any(|existing: &ModifierProp| existing.class == class) {
+					element_props.push(prop(class, &modifier.class_name()));
+				}
+			}
+
+			elements.push(element_props);
+		}
+
+		ComponentProps { block, elements }
+	}
+
+	fn all(&self) -> impl Iterator<Item = &ModifierProp> {
+		self.block.iter().chain(self.elements.iter().flatten())
+	}
+}
+
+/// Generates a Svelte component skeleton for a `BEMBlock`.
+///
+/// The script block declares one `export let` boolean prop per modifier, defaulting to `false`.
+/// The markup renders the block and its elements as `div`s carrying their BEM classes, with a
+/// `class:` directive per modifier, followed by an empty style block.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate the component for.
+///
+/// # Returns
+///
+/// * `Result<String, IdentifierError>` - The component source, or an error if two modifiers map to the same prop name.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_svelte };
+///
+/// let bem_block = parse("media-player[dark]\nbutton[fast-forward]").unwrap();
+/// let svelte = to_svelte(&bem_block).unwrap();
+///
+/// assert!(svelte.contains("export let buttonFastForward = false;"));
+/// assert!(svelte.contains("class:media-player--dark={dark}"));
+/// ```
+pub fn to_svelte(bem_block: &BEMBlock) -> Result<String, IdentifierError> {
+	let props = ComponentProps::from_block(bem_block, &AbbreviationMap::default(), &NamingScheme::default())?;
+	let mut output = String::from("<script>\n");
+
+	for prop in props.all() {
+		output.push_str(&format!("\texport let {} = false;\n", prop.ident));
+	}
+
+	output.push_str("</script>\n\n");
+	output.push_str(&format!("<div {}>", svelte_classes(&bem_block.name, &props.block)));
+
+	if !bem_block.elements.is_empty() {
+		output.push('\n');
+
+		for (element, element_props) in bem_block.elements.iter().zip(&props.elements) {
+			let class = format!("{}__{}", bem_block.name, element.name);
+
+			output.push_str(&format!("\t<div {}></div>\n", svelte_classes(&class, element_props)));
+		}
+	}
+
+	output.push_str("</div>\n\n<style>\n</style>\n");
+
+	Ok(output)
+}
+
+fn svelte_classes(class: &str, props: &[ModifierProp]) -> String {
+	let mut attributes = format!("class=\"{}\"", class);
+
+	for prop in props {
+		attributes.push_str(&format!(" class:{}={{{}}}", prop.class, prop.ident));
+	}
+
+	attributes
+}
+
+/// Generates an Astro component skeleton for a `BEMBlock`.
+///
+/// The frontmatter declares a `Props` interface with one optional boolean per modifier and
+/// destructures them from `Astro.props` with `false` defaults. The markup renders the block and
+/// its elements as `div`s whose classes are composed with `class:list`.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate the component for.
+///
+/// # Returns
+///
+/// * `Result<String, IdentifierError>` - The component source, or an error if two modifiers map to the same prop name.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_astro };
+///
+/// let bem_block = parse("media-player[dark]\ntimeline").unwrap();
+/// let astro = to_astro(&bem_block).unwrap();
+///
+/// assert!(astro.contains("dark?: boolean;"));
+/// assert!(astro.contains("class:list={[\"media-player\", { \"media-player--dark\": dark }]}"));
+/// ```
+pub fn to_astro(bem_block: &BEMBlock) -> Result<String, IdentifierError> {
+	let props = ComponentProps::from_block(bem_block, &AbbreviationMap::default(), &NamingScheme::default())?;
+	let mut output = String::from("---\n");
+	let idents: Vec<&str> = props
+		.all()
+		.map(|prop| prop.ident.as_str())
+		.collect();
+
+	if idents.is_empty() {
+		output.push_str("interface Props {}\n");
+	} else {
+		output.push_str("interface Props {\n");
+
+		for ident in &idents {
+			output.push_str(&format!("\t{}?: boolean;\n", ident));
+		}
+
+		output.push_str("}\n\n");
+
+		let defaults: Vec<String> = idents
+			.iter()
+			.map(|ident| format!("{} = false", ident))
+			.collect();
+
+		output.push_str(&format!("const {{ {} }} = Astro.props;\n", defaults.join(", ")));
+	}
+
+	output.push_str("---\n\n");
+	output.push_str(&format!("<div {}>", astro_classes(&bem_block.name, &props.block)));
+
+	if !bem_block.elements.is_empty() {
+		output.push('\n');
+
+		for (element, element_props) in bem_block.elements.iter().zip(&props.elements) {
+			let class = format!("{}__{}", bem_block.name, element.name);
+
+			output.push_str(&format!("\t<div {}></div>\n", astro_classes(&class, element_props)));
+		}
+	}
+
+	output.push_str("</div>\n");
+
+	Ok(output)
+}
+
+fn astro_classes(class: &str, props: &[ModifierProp]) -> String {
+	if props.is_empty() {
+		return format!("class=\"{}\"", class);
+	}
+
+	let toggles: Vec<String> = props
+		.iter()
+		.map(|prop| format!("\"{}\": {}", prop.class, prop.ident))
+		.collect();
+
+	format!("class:list={{[\"{}\", {{ {} }}]}}", class, toggles.join(", "))
+}
+
+impl BEMBlock {
+	/// Generates a React component pair following the container/presentational pattern.
+	///
+	/// The presentational component (`MediaPlayer.tsx`) declares one optional boolean prop per
+	/// modifier and only renders: the block and its elements become `div`s whose `className`
+	/// toggles the modifier classes from the props. The container component
+	/// (`MediaPlayerContainer.tsx`) imports it and renders it with the modifier values provided by
+	/// a `MediaPlayerContext`, letting its own props override them.
+	///
+	/// Props are named like the props of [`to_jsx_with_attributes`]. A prop name taken by an
+	/// earlier modifier is numbered instead, e.g. `buttonRewind_2` for the `rewind` modifier of a
+	/// `button` element after the block modifier `button-rewind`.
+	///
+	/// # Returns
+	///
+	/// * `(String, String)` - The presentational and container components.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let bem_block = parse("media-player[dark]").unwrap();
+	/// let (presentational, container) = bem_block.to_bem_container_component();
+	///
+	/// assert!(presentational.contains("props.dark && \"media-player--dark\""));
+	/// assert!(container.contains("import { MediaPlayer, MediaPlayerProps } from \"./MediaPlayer\";"));
+	/// ```
+	pub fn to_bem_container_component(&self) -> (String, String) {
+		let component = convert_case(&self.name, Case::Pascal);
+		let presentational = react_component(
+			self,
+			&ComponentProps::numbered(self),
+			&AbbreviationMap::default(),
+			&NamingScheme::default(),
+			react_class_name
+		);
+		let container = format!(
+			"import React, {{ createContext, useContext }} from \"react\";\n\
+			import {{ {component}, {component}Props }} from \"./{component}\";\n\
+			\n\
+			export const {component}Context = createContext<{component}Props>({{}});\n\
+			\n\
+			export function {component}Container(props: {component}Props) {{\n\
+			\tconst state = useContext({component}Context);\n\
+			\n\
+			\treturn <{component} {{...state}} {{...props}} />;\n\
+			}}\n",
+			component = component
+		);
+
+		(presentational, container)
+	}
+
+	/// Generates a React context sharing the modifier state of the block.
+	///
+	/// The context value holds one boolean per modifier, named like the props of the component
+	/// generated by [`to_bem_container_component`](BEMBlock::to_bem_container_component), and a
+	/// `toggle` function per modifier (`toggleDark`). `MediaPlayerProvider` keeps the state,
+	/// every modifier starting out `false`, and the `useMediaPlayerContext` hook reads it, failing
+	/// when used outside of the provider. Fields are numbered like the props of the component.
+	///
+	/// # Returns
+	///
+	/// * `String` - The module source.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let provider = parse("media-player[dark]").unwrap().to_bem_context_provider();
+	///
+	/// assert!(provider.contains("export function MediaPlayerProvider("));
+	/// assert!(provider.contains("toggleDark: () => void;"));
+	/// ```
+	pub fn to_bem_context_provider(&self) -> String {
+		let props = ComponentProps::numbered(self);
+		let component = convert_case(&self.name, Case::Pascal);
+		let mut fields = String::new();
+		let mut toggles = String::new();
+		let mut states = String::new();
+		let mut values = String::new();
+
+		for prop in props.all() {
+			let capitalized = capitalize(&prop.ident);
+
+			fields.push_str(&format!("\t{}: boolean;\n", prop.ident));
+			toggles.push_str(&format!("\ttoggle{}: () => void;\n", capitalized));
+			states.push_str(&format!("\tconst [{}, set{}] = useState(false);\n", prop.ident, capitalized));
+			values.push_str(&format!("\t\t{},\n", prop.ident));
+		}
+		for prop in props.all() {
+			let capitalized = capitalize(&prop.ident);
+
+			values.push_str(&format!("\t\ttoggle{}: () => set{}((value) => !value),\n", capitalized, capitalized));
+		}
+
+		let mut output = String::from("import React, { createContext, ReactNode, useContext, useState } from \"react\";\n\n");
+
+		if fields.is_empty() {
+			output.push_str(&format!("export interface {}ContextValue {{}}\n\n", component));
+		} else {
+			output.push_str(&format!("export interface {}ContextValue {{\n{}{}}}\n\n", component, fields, toggles));
+		}
+
+		output.push_str(&format!(
+			"const {component}Context = createContext<{component}ContextValue | undefined>(undefined);\n\
+			\n\
+			export function {component}Provider({{ children }}: {{ children?: ReactNode }}) {{\n",
+			component = component
+		));
+
+		if values.is_empty() {
+			output.push_str(&format!("\tconst value: {}ContextValue = {{}};\n", component));
+		} else {
+			output.push_str(&format!("{}\n\tconst value: {}ContextValue = {{\n{}\t}};\n", states, component, values));
+		}
+
+		output.push_str(&format!(
+			"\n\
+			\treturn <{component}Context.Provider value={{value}}>{{children}}</{component}Context.Provider>;\n\
+			}}\n\
+			\n\
+			export function use{component}Context(): {component}ContextValue {{\n\
+			\tconst context = useContext({component}Context);\n\
+			\n\
+			\tif (context === undefined) {{\n\
+			\t\tthrow new Error(\"use{component}Context must be used within a {component}Provider\");\n\
+			\t}}\n\
+			\n\
+			\treturn context;\n\
+			}}\n",
+			component = component
+		));
+
+		output
+	}
+
+	/// Generates a React error boundary class component for the block's component.
+	///
+	/// The boundary accepts the modifier props of the component generated by
+	/// [`to_bem_container_component`](BEMBlock::to_bem_container_component) next to its
+	/// `children`. When a child throws, `componentDidCatch` logs the error labelled with the block
+	/// name, along with the modifier classes active at the time, and the boundary renders nothing.
+	/// Props are numbered like the props of the component.
+	///
+	/// # Returns
+	///
+	/// * `String` - The component source.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let boundary = parse("media-player[dark]").unwrap().to_bem_error_boundary_config();
+	///
+	/// assert!(boundary.contains("export class MediaPlayerErrorBoundary extends Component"));
+	/// assert!(boundary.contains("console.error(\"[media-player]\""));
+	/// ```
+	pub fn to_bem_error_boundary_config(&self) -> String {
+		let props = ComponentProps::numbered(self);
+		let component = convert_case(&self.name, Case::Pascal);
+		let active_modifiers = if props.all().next().is_none() {
+			"const activeModifiers: string[] = [];".to_string()
+		} else {
+			let toggles: Vec<String> = props
+				.all()
+				.map(|prop| format!("\t\t\tthis.props.{} && \"{}\",\n", prop.ident, prop.class))
+				.collect();
+
+			format!("const activeModifiers = [\n{}\t\t].filter(Boolean);", toggles.concat())
+		};
+
+		format!(
+			"import React, {{ Component, ErrorInfo, ReactNode }} from \"react\";\n\
+			import {{ {component}Props }} from \"./{component}\";\n\
+			\n\
+			interface {component}ErrorBoundaryProps extends {component}Props {{\n\
+			\tchildren?: ReactNode;\n\
+			}}\n\
+			\n\
+			interface {component}ErrorBoundaryState {{\n\
+			\thasError: boolean;\n\
+			}}\n\
+			\n\
+			export class {component}ErrorBoundary extends Component<{component}ErrorBoundaryProps, {component}ErrorBoundaryState> {{\n\
+			\tstate: {component}ErrorBoundaryState = {{ hasError: false }};\n\
+			\n\
+			\tstatic getDerivedStateFromError(): {component}ErrorBoundaryState {{\n\
+			\t\treturn {{ hasError: true }};\n\
+			\t}}\n\
+			\n\
+			\tcomponentDidCatch(error: Error, errorInfo: ErrorInfo) {{\n\
+			\t\t{active_modifiers}\n\
+			\n\
+			\t\tconsole.error(\"[{block}]\", {{ activeModifiers }}, error, errorInfo);\n\
+			\t}}\n\
+			\n\
+			\trender() {{\n\
+			\t\tif (this.state.hasError) {{\n\
+			\t\t\treturn null;\n\
+			\t\t}}\n\
+			\n\
+			\t\treturn this.props.children;\n\
+			\t}}\n\
+			}}\n",
+			component = component,
+			active_modifiers = active_modifiers,
+			block = self.name
+		)
+	}
+
+	/// Generates a component manifest in the format of `react-docgen`, describing the component
+	/// generated by [`to_bem_container_component`](BEMBlock::to_bem_container_component).
+	///
+	/// Every modifier is described as an optional `bool` prop defaulting to `false`, and listed in
+	/// `defaultProps` with that default. Props are numbered like the props of the component.
+	///
+	/// # Returns
+	///
+	/// * `Value` - The manifest.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let manifest = parse("media-player[dark]").unwrap().to_bem_docgen_manifest();
+	///
+	/// assert_eq!(manifest["displayName"], "MediaPlayer");
+	/// assert_eq!(manifest["props"]["dark"]["type"]["name"], "bool");
+	/// assert_eq!(manifest["defaultProps"]["dark"], false);
+	/// ```
+	pub fn to_bem_docgen_manifest(&self) -> Value {
+		let props = ComponentProps::numbered(self);
+		let mut prop_types = Map::new();
+		let mut default_props = Map::new();
+
+		for prop in props.all() {
+			prop_types.insert(prop.ident.clone(), json!({
+				"type": { "name": "bool" },
+				"required": false,
+				"description": format!("Toggles the `{}` modifier class.", prop.class),
+				"defaultValue": { "value": "false", "computed": false },
+			}));
+			default_props.insert(prop.ident.clone(), Value::Bool(false));
+		}
+
+		json!({
+			"displayName": convert_case(&self.name, Case::Pascal),
+			"description": format!("Renders the `{}` block.", self.name),
+			"methods": [],
+			"props": prop_types,
+			"defaultProps": default_props,
+		})
+	}
+
+	/// Generates the barrel `index.ts` of the block's component directory.
+	///
+	/// The index re-exports the components and props type generated by
+	/// [`to_bem_container_component`](BEMBlock::to_bem_container_component) (`./MediaPlayer`,
+	/// `./MediaPlayerContainer`), the CSS module of the block (`./media-player.module.scss`) as
+	/// `mediaPlayerStyles`, and the class selector constants (`./media-player.selectors`) as
+	/// `mediaPlayerSelectors`. Component files are named after the block in PascalCase, style and
+	/// selector files after the block class.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let index = parse("media-player").unwrap().to_bem_component_index();
+	///
+	/// assert!(index.contains("export { MediaPlayer } from \"./MediaPlayer\";"));
+	/// assert!(index.contains("from \"./media-player.module.scss\";"));
+	/// ```
+	pub fn to_bem_component_index(&self) -> String {
+		let component = convert_case(&self.name, Case::Pascal);
+		let ident = identifier(&self.name, Case::Camel);
+
+		format!(
+			"export {{ {component} }} from \"./{component}\";\n\
+			export type {{ {component}Props }} from \"./{component}\";\n\
+			export {{ {component}Container, {component}Context }} from \"./{component}Container\";\n\
+			export {{ default as {ident}Styles }} from \"./{class}.module.scss\";\n\
+			export {{ {ident}Selectors }} from \"./{class}.selectors\";\n",
+			component = component,
+			ident = ident,
+			class = self.name
+		)
+	}
+}
+
+/// Uppercases the first character of an identifier, e.g. `buttonRewind` into `ButtonRewind`.
+fn capitalize(ident: &str) -> String {
+	let mut chars = ident.chars();
+
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
+
+/// Renders a React function component with an optional boolean prop per prop of `props`,
+/// rendering the block and its elements as `div`s whose attributes are written by `attributes`.
+/// The name of the component is abbreviated by `abbreviations`.
+fn react_component<F>(bem_block: &BEMBlock, props: &ComponentProps, abbreviations: &AbbreviationMap, scheme: &NamingScheme, attributes: F) -> String
+	where F: Fn(&str, &[ModifierProp]) -> String
+{
+	let component = convert_case(&abbreviations.abbreviate(&bem_block.name), Case::Pascal);
+	let mut output = String::from("import React from \"react\";\n\n");
+
+	if props.all().next().is_none() {
+		output.push_str(&format!("export interface {}Props {{}}\n\n", component));
+	} else {
+		output.push_str(&format!("export interface {}Props {{\n", component));
+
+		for prop in props.all() {
+			output.push_str(&format!("\t{}?: boolean;\n", prop.ident));
+		}
+
+		output.push_str("}\n\n");
+	}
+
+	output.push_str(&format!("export function {}(props: {}Props) {{\n\treturn (\n", component, component));
+
+	if bem_block.elements.is_empty() {
+		output.push_str(&format!("\t\t<div {} />\n", attributes(&bem_block.name, &props.block)));
+	} else {
+		output.push_str(&format!("\t\t<div {}>\n", attributes(&bem_block.name, &props.block)));
+
+		for (element, element_props) in bem_block.elements.iter().zip(&props.elements) {
+			let class = scheme.element_class(&bem_block.name, &element.name);
+
+			output.push_str(&format!("\t\t\t<div {} />\n", attributes(&class, element_props)));
+		}
+
+		output.push_str("\t\t</div>\n");
+	}
+
+	output.push_str("\t);\n}\n");
+
+	output
+}
+
+/// Generates a React component skeleton whose modifiers are data attributes instead of
+/// modifier classes.
+///
+/// The component is the presentational component of
+/// [`to_bem_container_component`](BEMBlock::to_bem_container_component), with the same props.
+/// The block and its elements keep their classes, and each modifier prop sets the attribute of
+/// its modifier, e.g. `data-rewind={props.buttonRewind || undefined}` with boolean attributes, so
+/// the component matches the selectors of [`to_css_nested_with_attributes`](crate::to_css_nested_with_attributes).
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate the component for.
+/// * `scheme`: &AttributeScheme - How modifiers are written as attributes.
+///
+/// # Returns
+///
+/// * `Result<String, IdentifierError>` - The component source, or an error if two modifiers map to the same prop name.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_jsx_with_attributes, AttributeScheme };
+///
+/// let bem_block = parse("media-player[dark]").unwrap();
+/// let jsx = to_jsx_with_attributes(&bem_block, &AttributeScheme::KeyValue("data-state".to_string())).unwrap();
+///
+/// assert!(jsx.contains("<div className=\"media-player\" data-state={[props.dark && \"dark\"].filter(Boolean).join(\" \") || undefined} />"));
+/// ```
+pub fn to_jsx_with_attributes(bem_block: &BEMBlock, scheme: &AttributeScheme) -> Result<String, IdentifierError> {
+	to_jsx_with_abbreviations(bem_block, scheme, &AbbreviationMap::default(), &BemConfig::default())
+}
+
+/// Generates the React component of [`to_jsx_with_attributes`], with the segments of the names
+/// of the component and its props abbreviated, e.g. `NavDrawer` and `toggleBtnActive` with
+/// `navigation → nav` and `button → btn`, and the element classes composed with the separators
+/// of `config`. Classes and attributes keep the names as written.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate the component for.
+/// * `scheme`: &AttributeScheme - How modifiers are written as attributes.
+/// * `abbreviations`: &AbbreviationMap - The abbreviations of name segments.
+/// * `config`: &BemConfig - The separators the classes are composed with.
+///
+/// # Returns
+///
+/// * `Result<String, IdentifierError>` - The component source, or an error if two modifiers map
+///   to the same prop name once abbreviated.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_jsx_with_abbreviations, AbbreviationMap, AttributeScheme, BemConfig };
+///
+/// let bem_block = parse("navigation-drawer\ntoggle-button[active]").unwrap();
+/// let abbreviations = AbbreviationMap::from_json("{\"navigation\": \"nav\", \"button\": \"btn\"}").unwrap();
+/// let jsx = to_jsx_with_abbreviations(&bem_block, &AttributeScheme::Boolean, &abbreviations, &BemConfig::default()).unwrap();
+///
+/// assert!(jsx.contains("export function NavDrawer(props: NavDrawerProps)"));
+/// assert!(jsx.contains("<div className=\"navigation-drawer__toggle-button\" data-active={props.toggleBtnActive || undefined} />"));
+/// ```
+pub fn to_jsx_with_abbreviations(bem_block: &BEMBlock, scheme: &AttributeScheme, abbreviations: &AbbreviationMap, config: &BemConfig) -> Result<String, IdentifierError> {
+	let props = ComponentProps::from_block(bem_block, abbreviations, config)?;
+
+	Ok(react_component(bem_block, &props, abbreviations, config, |class, props| {
+		let mut attributes = format!("className=\"{}\"", class);
+
+		match scheme {
+			AttributeScheme::Boolean => {
+				for prop in props {
+					attributes.push_str(&format!(" data-{}={{props.{} || undefined}}", prop.modifier, prop.ident));
+				}
+			}
+			AttributeScheme::KeyValue(_) if props.is_empty() => {}
+			AttributeScheme::KeyValue(attribute) => {
+				let toggles: Vec<String> = props
+					.iter()
+					.map(|prop| format!("props.{} && \"{}\"", prop.ident, prop.modifier))
+					.collect();
+
+				attributes.push_str(&format!(
+					" {}={{[{}].filter(Boolean).join(\" \") || undefined}}",
+					attribute,
+					toggles.join(", ")
+				));
+			}
+		}
+
+		attributes
+	}))
+}
+
+/// Generates a static HTML skeleton of a block.
+///
+/// The block and its elements are rendered as `div`s carrying their classes. Every modifier is
+/// applied, so the skeleton shows every class the block uses: remove the modifiers that do not
+/// apply to the markup being written.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate the markup for.
+///
+/// # Returns
+///
+/// * `String` - The HTML.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_html };
+///
+/// let bem_block = parse("media-player[dark]\ntimeline").unwrap();
+///
+/// assert_eq!(
+///     to_html(&bem_block),
+///     "<div class=\"media-player media-player--dark\">\n\t<div class=\"media-player__timeline\"></div>\n</div>\n"
+/// );
+/// ```
+pub fn to_html(bem_block: &BEMBlock) -> String {
+	html(bem_block, None, None).expect("markup without a budget cannot exceed it")
+}
+
+/// Generates a static HTML skeleton like [`to_html`], within the limits of `budget`: classes
+/// that are too long are abbreviated or rejected, and so are `class` attributes that are still
+/// too long. Abbreviations are unique within the block, see [`ClassBudget`].
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate the markup for.
+/// * `budget`: &ClassBudget - The length limits of classes and `class` attributes.
+///
+/// # Returns
+///
+/// * `Result<String, BudgetError>` - The HTML, or the first class or attribute exceeding the
+///   budget.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_html_with_budget, BudgetError, ClassBudget };
+///
+/// let bem_block = parse("media-player[dark]").unwrap();
+/// let budget = ClassBudget { max_class_len: 50, max_attr_len: 20, abbreviate: true, length_mode: Default::default() };
+///
+/// assert!(matches!(to_html_with_budget(&bem_block, &budget), Err(BudgetError::AttributeTooLong { len: 31, .. })));
+/// ```
+pub fn to_html_with_budget(bem_block: &BEMBlock, budget: &ClassBudget) -> Result<String, BudgetError> {
+	html(bem_block, None, Some(budget))
+}
+
+/// Generates a static HTML skeleton like [`to_html`], but with modifiers written as data
+/// attributes instead of modifier classes, e.g. `<div class="media-player" data-dark>`.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate the markup for.
+/// * `scheme`: &AttributeScheme - How modifiers are written as attributes.
+///
+/// # Returns
+///
+/// * `String` - The HTML.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_html_with_attributes, AttributeScheme };
+///
+/// let bem_block = parse("media-player[dark]").unwrap();
+///
+/// assert_eq!(to_html_with_attributes(&bem_block, &AttributeScheme::Boolean), "<div class=\"media-player\" data-dark></div>\n");
+/// ```
+pub fn to_html_with_attributes(bem_block: &BEMBlock, scheme: &AttributeScheme) -> String {
+	html(bem_block, Some(scheme), None).expect("markup without a budget cannot exceed it")
+}
+
+fn html(bem_block: &BEMBlock, scheme: Option<&AttributeScheme>, budget: Option<&ClassBudget>) -> Result<String, BudgetError> {
+	let class_map = match budget {
+		Some(budget) => Some(budget.class_map(NamingScheme::default().class_names(bem_block))?),
+		None => None,
+	};
+	let tag = |element: Option<&str>, modifiers: &[BEMModifier]| -> Result<String, BudgetError> {
+		let mut builder = ClassBuilder::new(&bem_block.name);
+
+		if let Some(element) = element {
+			builder = builder.element(element);
+		}
+		for modifier in modifiers {
+			builder = builder.modifier(&modifier.class_name());
+		}
+		if let Some(scheme) = scheme {
+			builder = builder.attribute_scheme(scheme.clone());
+		}
+
+		let classes = match &class_map {
+			Some(class_map) => builder.classes().iter().map(|class| class_map[class].clone()).collect(),
+			None => builder.classes(),
+		}.join(" ");
+
+		if let Some(budget) = budget {
+			budget.check_attribute(&classes)?;
+		}
+
+		let mut attributes = format!("class=\"{}\"", html_escape(&classes));
+
+		for (name, value) in builder.attributes() {
+			if value.is_empty() {
+				attributes.push_str(&format!(" {}", name));
+			} else {
+				attributes.push_str(&format!(" {}=\"{}\"", name, html_escape(&value)));
+			}
+		}
+
+		Ok(format!("<div {}>", attributes))
+	};
+
+	if bem_block.elements.is_empty() {
+		return Ok(format!("{}</div>\n", tag(None, &bem_block.modifiers)?));
+	}
+
+	let mut output = format!("{}\n", tag(None, &bem_block.modifiers)?);
+
+	for element in &bem_block.elements {
+		output.push_str(&format!("\t{}</div>\n", tag(Some(&element.name), &element.modifiers)?));
+	}
+
+	output.push_str("</div>\n");
+	Ok(output)
+}
+
+/// Escapes the characters that cannot appear in a double-quoted HTML attribute value.
+fn html_escape(text: &str) -> String {
+	text.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;")
+}
+
+fn react_class_name(class: &str, props: &[ModifierProp]) -> String {
+	if props.is_empty() {
+		return format!("className=\"{}\"", class);
+	}
+
+	let toggles: Vec<String> = props
+		.iter()
+		.map(|prop| format!("props.{} && \"{}\"", prop.ident, prop.class))
+		.collect();
+
+	format!("className={{[\"{}\", {}].filter(Boolean).join(\" \")}}", class, toggles.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::attributes::AttributeScheme;
+	use crate::budget::{ BudgetError, ClassBudget };
+	use crate::models::BEMBlock;
+	use crate::parse;
+
+	fn media_player() -> BEMBlock {
+		parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap()
+	}
+
+	fn modifiers_only() -> BEMBlock {
+		parse("toggle[on,off,disabled]").unwrap()
+	}
+
+	fn colliding() -> BEMBlock {
+		parse("media-player[button-rewind]\nbutton[rewind]").unwrap()
+	}
+
+	#[test]
+	fn test_to_svelte() {
+		insta::assert_snapshot!(super::to_svelte(&media_player()).unwrap());
+	}
+
+	#[test]
+	fn test_to_svelte_modifiers_only() {
+		insta::assert_snapshot!(super::to_svelte(&modifiers_only()).unwrap());
+	}
+
+	#[test]
+	fn test_to_astro() {
+		insta::assert_snapshot!(super::to_astro(&media_player()).unwrap());
+	}
+
+	#[test]
+	fn test_to_astro_modifiers_only() {
+		insta::assert_snapshot!(super::to_astro(&modifiers_only()).unwrap());
+	}
+
+	#[test]
+	fn test_to_astro_without_modifiers() {
+		let astro = super::to_astro(&parse("foo\nbar").unwrap()).unwrap();
+
+		assert_eq!(
+			astro,
+			"---\ninterface Props {}\n---\n\n<div class=\"foo\">\n\t<div class=\"foo__bar\"></div>\n</div>\n"
+		);
+	}
+
+	#[test]
+	fn test_to_jsx_with_boolean_attributes() {
+		insta::assert_snapshot!(super::to_jsx_with_attributes(&media_player(), &AttributeScheme::Boolean).unwrap());
+	}
+
+	#[test]
+	fn test_to_jsx_with_key_value_attributes() {
+		let scheme = AttributeScheme::KeyValue("data-state".to_string());
+
+		insta::assert_snapshot!(super::to_jsx_with_attributes(&media_player(), &scheme).unwrap());
+	}
+
+	#[test]
+	fn test_to_html() {
+		insta::assert_snapshot!(super::to_html(&media_player()));
+	}
+
+	#[test]
+	fn test_to_html_with_boolean_attributes() {
+		insta::assert_snapshot!(super::to_html_with_attributes(&media_player(), &AttributeScheme::Boolean));
+	}
+
+	#[test]
+	fn test_to_html_with_key_value_attributes() {
+		let scheme = AttributeScheme::KeyValue("data-state".to_string());
+
+		insta::assert_snapshot!(super::to_html_with_attributes(&media_player(), &scheme));
+	}
+
+	#[test]
+	fn test_to_html_with_budget() {
+		let bem_block = parse("media-player[dark]\nfast-forward-button[pressed]\ntimeline").unwrap();
+		let budget = ClassBudget { max_class_len: 20, max_attr_len: 41, abbreviate: true, length_mode: Default::default() };
+		let html = super::to_html_with_budget(&bem_block, &budget).unwrap();
+		let attributes: Vec<&str> = html
+			.split("class=\"")
+			.skip(1)
+			.map(|rest| rest.split('"').next().unwrap())
+			.collect();
+
+		assert_eq!(attributes.len(), 3);
+		assert!(attributes.iter().all(|value| value.len() <= 41));
+		assert!(attributes.iter().flat_map(|value| value.split(' ')).all(|class| class.len() <= 20));
+		assert_eq!(super::to_html_with_budget(&bem_block, &budget).unwrap(), html);
+		assert_eq!(
+			super::to_html_with_budget(&bem_block, &ClassBudget { max_attr_len: 40, ..budget.clone() }),
+			Err(BudgetError::AttributeTooLong { value: attributes[1].to_string(), len: 41, max: 40, mode: Default::default() })
+		);
+		assert!(matches!(
+			super::to_html_with_budget(&bem_block, &ClassBudget { abbreviate: false, ..budget }),
+			Err(BudgetError::ClassTooLong { len: 33, .. })
+		));
+	}
+
+	#[test]
+	fn test_to_html_escapes_attributes() {
+		let mut bem_block = parse("foo").unwrap();
+		bem_block.modifiers.push("a\"b".into());
+
+		assert_eq!(
+			super::to_html_with_attributes(&bem_block, &AttributeScheme::KeyValue("data-state".to_string())),
+			"<div class=\"foo\" data-state=\"a&quot;b\"></div>\n"
+		);
+	}
+
+	#[test]
+	fn test_to_bem_container_component() {
+		let (presentational, container) = media_player().to_bem_container_component();
+
+		assert!(presentational.contains("export function MediaPlayer(props: MediaPlayerProps)"));
+		assert!(presentational.contains("props.buttonRewind && \"media-player__button--rewind\""));
+		assert!(container.contains("import { MediaPlayer, MediaPlayerProps } from \"./MediaPlayer\";"));
+		assert!(container.contains("<MediaPlayer {...state} {...props} />"));
+
+		insta::assert_snapshot!("to_bem_container_component_presentational", presentational);
+		insta::assert_snapshot!("to_bem_container_component_container", container);
+	}
+
+	#[test]
+	fn test_to_bem_container_component_without_modifiers() {
+		let (presentational, _) = parse("foo").unwrap().to_bem_container_component();
+
+		assert_eq!(
+			presentational,
+			"import React from \"react\";\n\nexport interface FooProps {}\n\nexport function Foo(props: FooProps) {\n\treturn (\n\t\t<div className=\"foo\" />\n\t);\n}\n"
+		);
+	}
+
+	#[test]
+	fn test_to_bem_context_provider() {
+		let provider = media_player().to_bem_context_provider();
+
+		assert!(provider.contains("createContext<MediaPlayerContextValue | undefined>"));
+		assert!(provider.contains("export function useMediaPlayerContext()"));
+		assert!(provider.contains("export function MediaPlayerProvider("));
+
+		for state in ["dark", "buttonFastForward", "buttonRewind"] {
+			assert!(provider.contains(&format!("\t{}: boolean;\n", state)));
+		}
+
+		insta::assert_snapshot!(provider);
+	}
+
+	#[test]
+	fn test_to_bem_context_provider_without_modifiers() {
+		let provider = parse("foo").unwrap().to_bem_context_provider();
+
+		assert!(provider.contains("export interface FooContextValue {}\n"));
+		assert!(provider.contains("\tconst value: FooContextValue = {};\n"));
+		assert!(!provider.contains("useState("));
+	}
+
+	#[test]
+	fn test_to_bem_error_boundary_config() {
+		let boundary = media_player().to_bem_error_boundary_config();
+
+		assert!(boundary.contains("export class MediaPlayerErrorBoundary extends Component<"));
+		assert!(boundary.contains("componentDidCatch(error: Error, errorInfo: ErrorInfo)"));
+		assert!(boundary.contains("console.error(\"[media-player]\", { activeModifiers }, error, errorInfo);"));
+		assert!(boundary.contains("\trender() {\n"));
+
+		insta::assert_snapshot!(boundary);
+	}
+
+	#[test]
+	fn test_to_bem_error_boundary_config_without_modifiers() {
+		let boundary = parse("foo\nbar").unwrap().to_bem_error_boundary_config();
+
+		assert!(boundary.contains("const activeModifiers: string[] = [];"));
+	}
+
+	#[test]
+	fn test_to_bem_docgen_manifest() {
+		let manifest = media_player().to_bem_docgen_manifest();
+		let props = manifest["props"].as_object().unwrap();
+
+		assert_eq!(props.len(), 3);
+		assert_eq!(manifest["defaultProps"].as_object().unwrap().len(), 3);
+
+		for prop in ["dark", "buttonFastForward", "buttonRewind"] {
+			assert_eq!(props[prop]["type"], serde_json::json!({ "name": "bool" }));
+			assert_eq!(manifest["defaultProps"][prop], false);
+		}
+
+		insta::assert_snapshot!(serde_json::to_string_pretty(&manifest).unwrap());
+	}
+
+	#[test]
+	fn test_to_bem_component_index() {
+		let index = media_player().to_bem_component_index();
+
+		assert!(index.lines().all(|line| line.starts_with("export ") && line.contains(" from ")));
+		assert!(index.contains("\"./MediaPlayer\""));
+		assert!(index.contains("\"./MediaPlayerContainer\""));
+		assert!(index.contains("\"./media-player.module.scss\""));
+		assert!(index.contains("\"./media-player.selectors\""));
+
+		insta::assert_snapshot!(index);
+	}
+
+	#[test]
+	fn test_to_jsx_with_abbreviations() {
+		let abbreviations = crate::AbbreviationMap::from_json("{\"button\": \"btn\", \"navigation\": \"nav\"}").unwrap();
+		let bem_block = parse("navigation-drawer[collapsed]\ntoggle-button[active]\nbuttons[active]").unwrap();
+		let jsx = super::to_jsx_with_abbreviations(&bem_block, &AttributeScheme::Boolean, &abbreviations, &crate::BemConfig::default()).unwrap();
+
+		assert!(jsx.contains("export interface NavDrawerProps {\n\tcollapsed?: boolean;\n\ttoggleBtnActive?: boolean;\n\tbuttonsActive?: boolean;\n}"));
+		assert!(jsx.contains("<div className=\"navigation-drawer__toggle-button\" data-active={props.toggleBtnActive || undefined} />"));
+
+		let colliding = parse("navigation-drawer\ntoggle-btn[active]\ntoggle-button[active]").unwrap();
+		let error = super::to_jsx_with_abbreviations(&colliding, &AttributeScheme::Boolean, &abbreviations, &crate::BemConfig::default()).unwrap_err();
+
+		assert_eq!(error.identifier, "toggleBtnActive");
+		assert_eq!(error.sources, vec!["navigation-drawer__toggle-btn--active", "navigation-drawer__toggle-button--active"]);
+		assert!(super::to_jsx_with_attributes(&colliding, &AttributeScheme::Boolean).is_ok());
+
+		let config = crate::BemConfig { element_separator: "_".to_string(), modifier_separator: "-".to_string() };
+		let jsx = super::to_jsx_with_abbreviations(&bem_block, &AttributeScheme::Boolean, &abbreviations, &config).unwrap();
+
+		assert!(jsx.contains("<div className=\"navigation-drawer_toggle-button\" data-active={props.toggleBtnActive || undefined} />"));
+	}
+
+	#[test]
+	fn test_prop_name_collision() {
+		let svelte_error = super::to_svelte(&colliding()).unwrap_err();
+		let astro_error = super::to_astro(&colliding()).unwrap_err();
+
+		assert_eq!(svelte_error.identifier, "buttonRewind");
+		assert_eq!(svelte_error.sources, vec![
+			"media-player--button-rewind".to_string(),
+			"media-player__button--rewind".to_string()
+		]);
+		assert_eq!(svelte_error, astro_error);
+	}
+
+	#[test]
+	fn test_prop_name_collision_is_numbered() {
+		let (presentational, _) = colliding().to_bem_container_component();
+
+		assert!(presentational.contains("\tbuttonRewind?: boolean;\n\tbuttonRewind_2?: boolean;\n"));
+		assert!(presentational.contains("props.buttonRewind && \"media-player--button-rewind\""));
+		assert!(presentational.contains("props.buttonRewind_2 && \"media-player__button--rewind\""));
+
+		let provider = colliding().to_bem_context_provider();
+
+		assert!(provider.contains("\tbuttonRewind: boolean;\n\tbuttonRewind_2: boolean;\n"));
+		assert!(provider.contains("\ttoggleButtonRewind_2: () => void;\n"));
+
+		let boundary = colliding().to_bem_error_boundary_config();
+
+		assert!(boundary.contains("\t\t\tthis.props.buttonRewind_2 && \"media-player__button--rewind\",\n"));
+
+		let manifest = colliding().to_bem_docgen_manifest();
+
+		assert_eq!(manifest["props"]["buttonRewind_2"]["description"], "Toggles the `media-player__button--rewind` modifier class.");
+		assert_eq!(manifest["defaultProps"].as_object().unwrap().len(), 2);
+	}
+}
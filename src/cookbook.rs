@@ -0,0 +1,126 @@
+//! Recipes for the workflows that combine several functions of this crate. Every recipe is a
+//! complete program, compiled and run as a doctest by `cargo test`, so none of them can fall
+//! behind the API.
+//!
+//! The [`examples`](https://github.com/vortex-design/bem/tree/main/examples) directory holds
+//! two larger programs that run against the fixtures of the repository:
+//!
+//! - `batch_convert`: converts every `.bem` file of a directory with [`pipeline::run`](crate::pipeline::run).
+//!   `cargo run --example batch_convert -- fixtures/examples/design out/ scss`
+//! - `audit_report`: reports naming violations and inconsistencies across a design system.
+//!   `cargo run --example audit_report -- fixtures/examples/design 16`
+//!
+//! # Parse and validate a document
+//!
+//! [`parse_verbose`](crate::parse_verbose) keeps the warnings a plain [`parse`](crate::parse)
+//! drops, and [`validate_with_options`](crate::validate_with_options) checks the names that
+//! parsed against the conventions of a team.
+//!
+//! ```
+//! use bem::{ parse_verbose, validate_with_options, ValidationOptions };
+//!
+//! let outcome = parse_verbose("media-player[dark,dark]\nfast-forward-button\ntimeline").unwrap();
+//! let options = ValidationOptions { max_name_length: Some(16), ..ValidationOptions::default() };
+//! let errors = validate_with_options(&outcome.block, &options);
+//!
+//! assert_eq!(outcome.warnings[0].code, "duplicate-modifier");
+//! assert_eq!(errors.len(), 1);
+//! assert!(errors[0].to_string().contains("fast-forward-button"));
+//! ```
+//!
+//! # Lint the stylesheets of a block
+//!
+//! [`lint_css`](crate::lint_css) finds selectors that break the structure of BEM, like an
+//! element of an element, in hand-written stylesheets as well as in generated ones.
+//!
+//! ```
+//! use bem::{ lint_css, parse, to_css_nested, CssLintConfig, CssLintRule };
+//!
+//! let bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+//! let config = CssLintConfig::default();
+//!
+//! assert!(lint_css(&to_css_nested(&bem_block), &config).is_empty());
+//!
+//! let findings = lint_css(".media-player__button__icon { }", &config);
+//!
+//! assert_eq!(findings[0].rule, CssLintRule::ElementOfElement);
+//! ```
+//!
+//! # Rename an element and review the impact
+//!
+//! Renaming through [`Elements::get_mut`](crate::Elements::get_mut) keeps the index of the
+//! elements up to date, and [`semver_impact`](crate::semver_impact) tells whether the rename
+//! breaks the consumers of the old classes.
+//!
+//! ```
+//! use bem::{ parse, semver_impact, to_bem, ImpactLevel };
+//!
+//! let old = parse("media-player[dark]\nbutton[rewind]\ntimeline").unwrap();
+//! let mut new = old.clone();
+//!
+//! new.elements.get_mut("button").unwrap().name = "control".to_string();
+//!
+//! assert_eq!(to_bem(&new), "media-player[dark]\ncontrol[rewind]\ntimeline");
+//! assert!(new.elements.contains("control") && !new.elements.contains("button"));
+//! assert_eq!(semver_impact(&old, &new), ImpactLevel::Major);
+//! ```
+//!
+//! # Emit SCSS and TypeScript for a design system
+//!
+//! The same blocks feed the stylesheets and the typed theme keys of the components, so both
+//! always agree on the classes.
+//!
+//! ```
+//! use bem::{ parse, to_scss_mixins, to_theme_keys_ts, BemConfig };
+//!
+//! let blocks = vec![parse("media-player[dark]\nbutton").unwrap(), parse("play-list\nitem[playing]").unwrap()];
+//! let scss: Vec<String> = blocks.iter().map(|bem_block| to_scss_mixins(bem_block, &BemConfig::default()).unwrap()).collect();
+//! let ts = to_theme_keys_ts(&blocks).unwrap();
+//!
+//! assert!(scss[0].starts_with("@mixin media-player($dark: false) {"));
+//! assert!(scss[1].contains("@mixin play-list__item($playing: false) {"));
+//! assert!(ts.contains("mediaPlayer"));
+//! assert!(ts.contains("playList"));
+//! ```
+//!
+//! # Diff two versions for a release
+//!
+//! [`semver_document_impact`](crate::semver_document_impact) classifies every change between two
+//! versions of a design system, and [`changelog`](crate::changelog) turns them into release notes.
+//!
+//! ```
+//! use bem::{ changelog, parse, semver_document_impact, ChangelogOptions, ImpactLevel };
+//!
+//! let old = vec![parse("media-player[dark]\nbutton").unwrap()];
+//! let new = vec![parse("media-player[dark,compact]\nbutton").unwrap(), parse("play-list").unwrap()];
+//! let report = semver_document_impact(&old, &new);
+//! let options = ChangelogOptions { title: Some("2.1.0".to_string()), ..ChangelogOptions::default() };
+//! let notes = changelog(&old, &new, &options);
+//!
+//! assert_eq!(report.level, ImpactLevel::Minor);
+//! assert!(notes.starts_with("## 2.1.0"));
+//! assert!(notes.contains("play-list"));
+//! assert!(notes.contains("media-player--compact"));
+//! ```
+//!
+//! # Run the command-line pipeline in memory
+//!
+//! [`pipeline::run`](crate::pipeline::run) is what the `bem` binary runs, so tools can convert
+//! files exactly like it without spawning it.
+//!
+//! ```
+//! use bem::pipeline::{ run, MemoryIo, RunOptions };
+//!
+//! let mut io = MemoryIo::default();
+//! io.files.insert("design/media-player.bem".into(), b"media-player[dark]\nbutton".to_vec());
+//! io.files.insert("design/play-list.bem".into(), b"play-list\nitem".to_vec());
+//!
+//! let options = RunOptions { input: Some("design".into()), format: "classes".to_string(), ..RunOptions::default() };
+//! let report = run(&options, &mut io).unwrap();
+//!
+//! assert_eq!(report.blocks, 2);
+//! assert_eq!(
+//!     String::from_utf8(io.stdout).unwrap(),
+//!     "media-player\nmedia-player--dark\nmedia-player__button\nplay-list\nplay-list__item\n"
+//! );
+//! ```
@@ -0,0 +1,394 @@
+//! This module scans stylesheets for the classes their selectors use. It is not a CSS parser:
+//! it only finds the selectors of style rules, including rules inside at-rules like `@media`
+//! and rules nested with CSS nesting, and splits them into compound selectors and classes.
+//! Comments and strings are skipped, declarations are ignored. Comments can be scanned on their
+//! own, for the directives of tools.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A complex selector of a style rule, like `.media-player .media-player__button:hover`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Selector {
+	/// The selector as written, with nested selectors resolved against their parent rule.
+	pub text: String,
+	/// The line of the rule, starting at 1.
+	pub line: usize,
+	/// The classes of each compound selector, in order. Compound selectors are separated by
+	/// combinators, and classes inside functional pseudo-classes like `:not()` are not included
+	/// since they do not describe the selected element.
+	pub compounds: Vec<Vec<String>>,
+}
+
+/// A comment of a stylesheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Comment {
+	/// The text between `/*` and `*/`, trimmed.
+	pub text: String,
+	/// The line the comment starts on, starting at 1.
+	pub line: usize,
+	/// The line the comment ends on.
+	pub end_line: usize,
+	/// Whether only whitespace precedes the comment on its line.
+	pub own_line: bool,
+}
+
+/// What a `{` opened, so the matching `}` knows what it closes.
+enum Scope {
+	/// A style rule, with its resolved selectors.
+	Rule(Vec<String>),
+	/// An at-rule like `@media`, whose rules belong to the enclosing scope.
+	AtRule,
+}
+
+/// Returns every selector of every style rule of `css`, in source order.
+pub(crate) fn scan_selectors(css: &str) -> Vec<Selector> {
+	let mut selectors = Vec::new();
+	let mut scopes: Vec<Scope> = Vec::new();
+	let mut prelude = String::new();
+	let mut prelude_line = 1;
+	let mut line = 1;
+	let mut chars = css.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		match c {
+			'/' if chars.peek() == Some(&'*') => {
+				chars.next();
+
+				let mut previous = ' ';
+
+				for c in chars.by_ref() {
+					if c == '\n' {
+						line += 1;
+					}
+					if previous == '*' && c == '/' {
+						break;
+					}
+					previous = c;
+				}
+			}
+			'"' | '\'' => {
+				prelude.push(c);
+
+				while let Some(s) = chars.next() {
+					prelude.push(s);
+
+					match s {
+						'\\' => prelude.extend(chars.next()),
+						'\n' => {
+							line += 1;
+							break;
+						}
+						s if s == c => break,
+						_ => {}
+					}
+				}
+			}
+			'{' => {
+				let text = prelude.trim();
+
+				if text.starts_with('@') {
+					scopes.push(Scope::AtRule);
+				} else {
+					let parents = scopes.iter().rev().find_map(|scope| match scope {
+						Scope::Rule(selectors) => Some(selectors.as_slice()),
+						Scope::AtRule => None,
+					});
+					let resolved = resolve(text, parents);
+
+					for text in &resolved {
+						selectors.push(Selector {
+							text: text.clone(),
+							line: prelude_line,
+							compounds: compounds(text),
+						});
+					}
+
+					scopes.push(Scope::Rule(resolved));
+				}
+
+				prelude.clear();
+			}
+			'}' => {
+				scopes.pop();
+				prelude.clear();
+			}
+			';' => prelude.clear(),
+			c => {
+				if prelude.trim().is_empty() && !c.is_whitespace() {
+					prelude_line = line;
+				}
+				if c == '\n' {
+					line += 1;
+				}
+
+				prelude.push(c);
+			}
+		}
+	}
+
+	selectors
+}
+
+/// Returns every comment of `css`, in source order. Comment delimiters inside strings are
+/// skipped, and an unterminated comment runs to the end of the stylesheet.
+pub(crate) fn scan_comments(css: &str) -> Vec<Comment> {
+	let mut comments = Vec::new();
+	let mut line = 1;
+	let mut own_line = true;
+	let mut chars = css.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		match c {
+			'/' if chars.peek() == Some(&'*') => {
+				chars.next();
+
+				let start = line;
+				let mut text = String::new();
+
+				while let Some(c) = chars.next() {
+					if c == '*' && chars.peek() == Some(&'/') {
+						chars.next();
+						break;
+					}
+					if c == '\n' {
+						line += 1;
+					}
+					text.push(c);
+				}
+
+				comments.push(Comment { text: text.trim().to_string(), line: start, end_line: line, own_line });
+				own_line = false;
+			}
+			'"' | '\'' => {
+				own_line = false;
+
+				while let Some(s) = chars.next() {
+					match s {
+						'\\' => {
+							chars.next();
+						}
+						'\n' => {
+							line += 1;
+							own_line = true;
+							break;
+						}
+						s if s == c => break,
+						_ => {}
+					}
+				}
+			}
+			'\n' => {
+				line += 1;
+				own_line = true;
+			}
+			c if c.is_whitespace() => {}
+			_ => own_line = false,
+		}
+	}
+
+	comments
+}
+
+/// Splits a selector list on its top-level commas, and resolves each selector against the
+/// selectors of its parent rule: `&` stands for the parent, and selectors without `&` are
+/// descendants of it.
+fn resolve(selector_list: &str, parents: Option<&[String]>) -> Vec<String> {
+	let selectors = split_top_level(selector_list, |c| c == ',');
+
+	let Some(parents) = parents else {
+		return selectors.iter().map(|selector| selector.trim().to_string()).collect();
+	};
+
+	let mut resolved = Vec::new();
+
+	for parent in parents {
+		for selector in &selectors {
+			let selector = selector.trim();
+
+			if selector.contains('&') {
+				resolved.push(selector.replace('&', parent));
+			} else {
+				resolved.push(format!("{} {}", parent, selector));
+			}
+		}
+	}
+
+	resolved
+}
+
+/// Splits `text` on every character matching `separator` that is not inside parentheses,
+/// brackets, or a string.
+fn split_top_level(text: &str, separator: impl Fn(char) -> bool) -> Vec<String> {
+	let mut parts = vec![String::new()];
+	let mut depth = 0;
+	let mut quote = None;
+	let mut chars = text.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		if c == '\\' {
+			let part = parts.last_mut().expect("parts is never empty");
+
+			part.push(c);
+			unescape(&mut chars, |c| part.push(c));
+			continue;
+		}
+
+		if let Some(q) = quote {
+			if c == q {
+				quote = None;
+			}
+		} else {
+			match c {
+				'"' | '\'' => quote = Some(c),
+				'(' | '[' => depth += 1,
+				')' | ']' => depth -= 1,
+				c if depth == 0 && separator(c) => {
+					parts.push(String::new());
+					continue;
+				}
+				_ => {}
+			}
+		}
+
+		parts.last_mut().expect("parts is never empty").push(c);
+	}
+
+	parts
+}
+
+/// Returns the classes of each compound selector of a complex selector.
+fn compounds(selector: &str) -> Vec<Vec<String>> {
+	split_top_level(selector, |c| c.is_whitespace() || c == '>' || c == '+' || c == '~')
+		.iter()
+		.filter(|compound| !compound.is_empty())
+		.map(|compound| classes(compound))
+		.collect()
+}
+
+/// Consumes the escape sequence following a backslash, passing the raw characters to `raw`
+/// and returning the character it stands for. Hexadecimal escapes take up to six digits and
+/// an optional whitespace, e.g. `\\31 ` for `1`.
+fn unescape(chars: &mut Peekable<Chars<'_>>, mut raw: impl FnMut(char)) -> Option<char> {
+	let mut hex = String::new();
+
+	while hex.len() < 6 {
+		match chars.peek() {
+			Some(&c) if c.is_ascii_hexdigit() => {
+				hex.push(c);
+				raw(c);
+				chars.next();
+			}
+			_ => break,
+		}
+	}
+
+	if hex.is_empty() {
+		let c = chars.next()?;
+		raw(c);
+
+		return Some(c);
+	}
+
+	if let Some(&c) = chars.peek().filter(|c| c.is_whitespace()) {
+		raw(c);
+		chars.next();
+	}
+
+	let code = u32::from_str_radix(&hex, 16).expect("only hexadecimal digits are collected");
+
+	Some(char::from_u32(code).filter(|&c| c != '\0').unwrap_or('\u{fffd}'))
+}
+
+/// Returns the classes of a compound selector, unescaped, skipping the contents of
+/// parentheses and brackets.
+fn classes(compound: &str) -> Vec<String> {
+	let mut classes = Vec::new();
+	let mut chars = compound.chars().peekable();
+	let mut depth = 0;
+
+	while let Some(c) = chars.next() {
+		match c {
+			'\\' => {
+				unescape(&mut chars, |_| ());
+			}
+			'(' | '[' => depth += 1,
+			')' | ']' => depth -= 1,
+			'.' if depth == 0 => {
+				let mut class = String::new();
+
+				while let Some(&c) = chars.peek() {
+					if c == '\\' {
+						chars.next();
+						class.extend(unescape(&mut chars, |_| ()));
+					} else if c.is_alphanumeric() || c == '-' || c == '_' || !c.is_ascii() {
+						class.push(c);
+						chars.next();
+					} else {
+						break;
+					}
+				}
+
+				if !class.is_empty() {
+					classes.push(class);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	classes
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ scan_comments, scan_selectors, Comment };
+
+	fn texts(css: &str) -> Vec<String> {
+		scan_selectors(css).into_iter().map(|selector| selector.text).collect()
+	}
+
+	#[test]
+	fn test_scan_selectors() {
+		let css = "/* .commented { } */\n.a, .b > .c:not(.d) {\n\tcontent: \"}\";\n}\n\n@media (min-width: 10px) {\n\t.e[title='.f'] { }\n}\n";
+		let selectors = scan_selectors(css);
+
+		assert_eq!(texts(css), vec![".a", ".b > .c:not(.d)", ".e[title='.f']"]);
+		assert_eq!(selectors[1].compounds, vec![vec!["b".to_string()], vec!["c".to_string()]]);
+		assert_eq!(selectors.iter().map(|selector| selector.line).collect::<Vec<_>>(), vec![2, 2, 7]);
+		assert_eq!(selectors[2].compounds, vec![vec!["e".to_string()]]);
+	}
+
+	#[test]
+	fn test_scan_comments() {
+		let css = "/* a */\n.b { content: \"/* c */\"; } /* d */\n\t/* e\n f */ .g { }\n/* h";
+		let comment = |text: &str, line, end_line, own_line| Comment { text: text.to_string(), line, end_line, own_line };
+
+		assert_eq!(scan_comments(css), vec![
+			comment("a", 1, 1, true),
+			comment("d", 2, 2, false),
+			comment("e\n f", 3, 4, true),
+			comment("h", 5, 5, true),
+		]);
+	}
+
+	#[test]
+	fn test_scan_selectors_resolves_nesting() {
+		let css = crate::to_css_nested(&crate::parse("media-player[dark]\nbutton[rewind]").unwrap());
+
+		assert_eq!(texts(&css), vec![
+			".media-player",
+			".media-player.media-player--dark",
+			".media-player .media-player__button",
+			".media-player .media-player__button.media-player__button--rewind",
+		]);
+		assert_eq!(texts(".a, .b { .c, &.d { } }"), vec![".a", ".b", ".a .c", ".a.d", ".b .c", ".b.d"]);
+	}
+
+	#[test]
+	fn test_scan_selectors_unescapes_classes() {
+		let css = ".media-player--dark\\@mobile.\\31 st { }";
+
+		assert_eq!(scan_selectors(css)[0].compounds, vec![vec!["media-player--dark@mobile".to_string(), "1st".to_string()]]);
+	}
+}
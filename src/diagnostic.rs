@@ -0,0 +1,66 @@
+//! This module defines the diagnostics reported alongside a successful parse, for input that is
+//! accepted but probably not what its author meant, see [`parse_verbose`](crate::parse_verbose)
+//! and [`scan_js`](crate::scan_js).
+
+use std::fmt;
+use std::ops::Range;
+
+/// How much a [`Diagnostic`] matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+	/// Information about how the input was read, e.g. an expanded modifier set.
+	Note,
+	/// Input that was accepted, but is likely a mistake or changed while reading it.
+	Warning,
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Severity::Note => write!(f, "note"),
+			Severity::Warning => write!(f, "warning"),
+		}
+	}
+}
+
+/// A note or warning about a document that parsed successfully.
+///
+/// The codes are stable, so tools can filter on them:
+///
+/// - `bom`: The input started with a byte order mark, which was ignored.
+/// - `duplicate-element`: An element was listed again and merged into its first occurrence, see
+///   [`Elements`](crate::Elements).
+/// - `duplicate-modifier`: A modifier list holds the same modifier more than once.
+/// - `alias-expanded`: A modifier set alias was replaced by the modifiers of the set.
+/// - `unresolved-template`: A template literal scanned by [`scan_js`](crate::scan_js) has
+///   interpolation, so its classes are unknown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	/// The stable code of the diagnostic, e.g. `duplicate-element`.
+	pub code: &'static str,
+	pub message: String,
+	/// The byte range of the input the diagnostic points at.
+	pub span: Range<usize>,
+	/// The line of the start of `span`, starting at 1.
+	pub line: usize,
+	/// The column of the start of `span`, in characters, starting at 1.
+	pub column: usize,
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}[{}]: line {}, column {}: {}", self.severity, self.code, self.line, self.column, self.message)
+	}
+}
+
+impl Diagnostic {
+	/// Returns a diagnostic pointing at the byte range `span` of `input`.
+	pub(crate) fn at(input: &str, severity: Severity, code: &'static str, span: Range<usize>, message: String) -> Diagnostic {
+		let (line, column) = pest::Position::new(input, span.start)
+			.expect("spans lie within the input")
+			.line_col();
+
+		Diagnostic { severity, code, message, span, line, column }
+	}
+}
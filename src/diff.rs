@@ -0,0 +1,277 @@
+//! This module compares two versions of BEM structures. The diffs it computes are the basis of
+//! the release tooling, such as the semver impact classification.
+
+use std::fmt;
+use crate::models::{ BEMBlock, BEMElement, BEMModifier };
+
+/// The structural changes between two versions of a block, see [`BEMBlock::diff`].
+///
+/// Displayed like a unified diff, with removals before additions: `-modifier dark`,
+/// `+element volume[muted]`, and the changed elements as a context line, e.g. ` element button`,
+/// followed by their indented modifier changes. An empty diff displays as an empty string.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BEMDiff {
+	/// The modifiers of the block only the new version has.
+	pub added_modifiers: Vec<BEMModifier>,
+	/// The modifiers of the block only the old version has.
+	pub removed_modifiers: Vec<BEMModifier>,
+	/// The elements only the new version has.
+	pub added_elements: Vec<BEMElement>,
+	/// The names of the elements only the old version has.
+	pub removed_elements: Vec<String>,
+	/// The elements of both versions whose modifiers changed.
+	pub changed_elements: Vec<BEMElementDiff>,
+}
+
+impl BEMDiff {
+	/// Returns whether the versions have the same structure.
+	pub fn is_empty(&self) -> bool {
+		self.added_modifiers.is_empty()
+			&& self.removed_modifiers.is_empty()
+			&& self.added_elements.is_empty()
+			&& self.removed_elements.is_empty()
+			&& self.changed_elements.is_empty()
+	}
+}
+
+impl fmt::Display for BEMDiff {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut lines: Vec<String> = Vec::new();
+
+		modifier_lines(&mut lines, "", &self.removed_modifiers, &self.added_modifiers);
+		lines.extend(self.removed_elements.iter().map(|name| format!("-element {}", name)));
+		lines.extend(self.added_elements.iter().map(|element| format!("+element {}", element)));
+
+		for element in &self.changed_elements {
+			lines.push(format!(" element {}", element.name));
+			modifier_lines(&mut lines, "  ", &element.removed_modifiers, &element.added_modifiers);
+		}
+
+		write!(f, "{}", lines.join("\n"))
+	}
+}
+
+/// Adds the lines of removed and then added modifiers, indented by `indent`.
+fn modifier_lines(lines: &mut Vec<String>, indent: &str, removed: &[BEMModifier], added: &[BEMModifier]) {
+	let line = |sign: char, modifier: &BEMModifier| format!("{}{}modifier {}", sign, indent, modifier);
+
+	lines.extend(removed.iter().map(|modifier| line('-', modifier)));
+	lines.extend(added.iter().map(|modifier| line('+', modifier)));
+}
+
+/// The modifier changes of an element present in both versions of a block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BEMElementDiff {
+	/// The name of the element.
+	pub name: String,
+	/// The modifiers only the new version of the element has.
+	pub added_modifiers: Vec<BEMModifier>,
+	/// The modifiers only the old version of the element has.
+	pub removed_modifiers: Vec<BEMModifier>,
+}
+
+impl BEMBlock {
+	/// Compares this block, the old version, with `other`, the new version. Names are compared
+	/// exactly, so a renamed modifier or element shows up as removed and added, and the order of
+	/// modifiers and elements is ignored. The names of the blocks are not compared.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let old = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+	/// let new = parse("media-player[light]\nbutton[rewind,play]\nvolume[muted]").unwrap();
+	/// let diff = old.diff(&new);
+	///
+	/// assert_eq!(diff.added_modifiers, vec!["light"]);
+	/// assert_eq!(diff.to_string(), [
+	///     "-modifier dark",
+	///     "+modifier light",
+	///     "-element timeline",
+	///     "+element volume[muted]",
+	///     " element button",
+	///     "-  modifier fast-forward",
+	///     "+  modifier play",
+	/// ].join("\n"));
+	/// assert!(old.diff(&old).is_empty());
+	/// ```
+	pub fn diff(&self, other: &BEMBlock) -> BEMDiff {
+		diff_blocks(self, other)
+	}
+}
+
+/// The changes between two versions of a set of blocks, matched by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DocumentDiff {
+	pub(crate) added_blocks: Vec<BEMBlock>,
+	pub(crate) removed_blocks: Vec<BEMBlock>,
+	/// The blocks present in both versions whose structure changed, with their diffs.
+	pub(crate) changed_blocks: Vec<(String, BEMDiff)>,
+}
+
+/// Compares two versions of a block. Names are compared exactly, so a renamed modifier or element
+/// shows up as removed and added. Repeated elements are compared as one element carrying the
+/// modifiers of every occurrence, and the order of modifiers and elements is ignored.
+pub(crate) fn diff_blocks(old: &BEMBlock, new: &BEMBlock) -> BEMDiff {
+	let old_elements = merged_elements(old);
+	let new_elements = merged_elements(new);
+	let mut diff = BEMDiff {
+		added_modifiers: missing_from(&new.modifiers, &old.modifiers),
+		removed_modifiers: missing_from(&old.modifiers, &new.modifiers),
+		..BEMDiff::default()
+	};
+
+	for element in &old_elements {
+		match new_elements.iter().find(|new_element| new_element.name == element.name) {
+			Some(new_element) => {
+				let added_modifiers = missing_from(&new_element.modifiers, &element.modifiers);
+				let removed_modifiers = missing_from(&element.modifiers, &new_element.modifiers);
+
+				if !added_modifiers.is_empty() || !removed_modifiers.is_empty() {
+					diff.changed_elements.push(BEMElementDiff {
+						name: element.name.clone(),
+						added_modifiers,
+						removed_modifiers,
+					});
+				}
+			}
+			None => diff.removed_elements.push(element.name.clone()),
+		}
+	}
+
+	for element in new_elements {
+		if !old_elements.iter().any(|old_element| old_element.name == element.name) {
+			diff.added_elements.push(element);
+		}
+	}
+
+	diff
+}
+
+/// Compares two versions of a set of blocks, matching blocks by name. Blocks missing from one
+/// side are reported whole. When several blocks share a name, only the first one is compared.
+pub(crate) fn diff_documents(old: &[BEMBlock], new: &[BEMBlock]) -> DocumentDiff {
+	let mut diff = DocumentDiff::default();
+
+	for (index, block) in old.iter().enumerate() {
+		if old[..index].iter().any(|previous| previous.name == block.name) {
+			continue;
+		}
+
+		match new.iter().find(|new_block| new_block.name == block.name) {
+			Some(new_block) => {
+				let block_diff = diff_blocks(block, new_block);
+
+				if !block_diff.is_empty() {
+					diff.changed_blocks.push((block.name.clone(), block_diff));
+				}
+			}
+			None => diff.removed_blocks.push(block.clone()),
+		}
+	}
+
+	for (index, block) in new.iter().enumerate() {
+		let repeated = new[..index].iter().any(|previous| previous.name == block.name);
+
+		if !repeated && !old.iter().any(|old_block| old_block.name == block.name) {
+			diff.added_blocks.push(block.clone());
+		}
+	}
+
+	diff
+}
+
+/// Returns the elements of a block with repeated elements merged into their first occurrence.
+fn merged_elements(bem_block: &BEMBlock) -> Vec<BEMElement> {
+	let mut elements: Vec<BEMElement> = Vec::new();
+
+	for element in &bem_block.elements {
+		match elements.iter_mut().find(|merged| merged.name == element.name) {
+			Some(merged) => {
+				for modifier in &element.modifiers {
+					if !merged.modifiers.contains(modifier) {
+						merged.modifiers.push(modifier.clone());
+					}
+				}
+			}
+			None => elements.push(element.clone()),
+		}
+	}
+
+	elements
+}
+
+/// Returns the modifiers of `modifiers` that are not in `other`, without repetitions.
+fn missing_from(modifiers: &[BEMModifier], other: &[BEMModifier]) -> Vec<BEMModifier> {
+	let mut missing: Vec<BEMModifier> = Vec::new();
+
+	for modifier in modifiers {
+		if !other.contains(modifier) && !missing.contains(modifier) {
+			missing.push(modifier.clone());
+		}
+	}
+
+	missing
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ diff_blocks, diff_documents, BEMElementDiff };
+	use crate::models::BEMElement;
+	use crate::parse;
+
+	#[test]
+	fn test_diff_blocks() {
+		let old = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let new = parse("media-player[light]\nbutton[rewind,play]\nvolume[muted]").unwrap();
+		let diff = diff_blocks(&old, &new);
+
+		assert_eq!(diff.added_modifiers, vec!["light"]);
+		assert_eq!(diff.removed_modifiers, vec!["dark"]);
+		assert_eq!(diff.added_elements, vec![BEMElement {
+			name: "volume".to_string(),
+			modifiers: vec!["muted".into()],
+		}]);
+		assert_eq!(diff.removed_elements, vec!["timeline"]);
+		assert_eq!(diff.changed_elements, vec![BEMElementDiff {
+			name: "button".to_string(),
+			added_modifiers: vec!["play".into()],
+			removed_modifiers: vec!["fast-forward".into()],
+		}]);
+	}
+
+	#[test]
+	fn test_diff_blocks_ignores_order_and_repetitions() {
+		let old = parse("media-player[dark,light]\nbutton[rewind]\nbutton[play]\ntimeline").unwrap();
+		let new = parse("media-player[light,dark,dark]\ntimeline\nbutton[play,rewind]").unwrap();
+
+		assert!(diff_blocks(&old, &new).is_empty());
+	}
+
+	#[test]
+	fn test_diff_display() {
+		let old = parse("media-player[size=sm]\nbutton[rewind]\nvolume").unwrap();
+		let new = parse("media-player[size=lg]\nbutton[color=red]\nvolume").unwrap();
+
+		assert_eq!(
+			old.diff(&new).to_string(),
+			"-modifier size=sm\n+modifier size=lg\n element button\n-  modifier rewind\n+  modifier color=red"
+		);
+		assert_eq!(old.diff(&old).to_string(), "");
+		assert_eq!(old.diff(&new), diff_blocks(&old, &new));
+	}
+
+	#[test]
+	fn test_diff_documents() {
+		let old = vec![parse("media-player[dark]").unwrap(), parse("play-list").unwrap()];
+		let new = vec![parse("media-player").unwrap(), parse("volume-slider").unwrap()];
+		let diff = diff_documents(&old, &new);
+
+		assert_eq!(diff.added_blocks, vec![parse("volume-slider").unwrap()]);
+		assert_eq!(diff.removed_blocks, vec![parse("play-list").unwrap()]);
+		assert_eq!(diff.changed_blocks.len(), 1);
+		assert_eq!(diff.changed_blocks[0].0, "media-player");
+		assert_eq!(diff.changed_blocks[0].1.removed_modifiers, vec!["dark"]);
+	}
+}
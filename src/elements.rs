@@ -0,0 +1,591 @@
+//! This module stores the elements of a block in the order they were written, indexed by name,
+//! so looking an element up does not scan the block and two elements can never share a name.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{ Deref, DerefMut };
+use std::slice;
+use std::sync::OnceLock;
+use serde::{ Deserialize, Deserializer, Serialize, Serializer };
+use crate::models::BEMElement;
+
+/// The elements of a [`BEMBlock`](crate::BEMBlock), in insertion order and unique by name.
+///
+/// Elements dereference to a slice, so they are read like a `Vec<BEMElement>`, and serialize as
+/// a JSON array in insertion order. Names are indexed, so [`get`](Elements::get) and
+/// [`contains`](Elements::contains) take constant time.
+///
+/// Elements are changed in place through guards, [`get_mut`](Elements::get_mut) for one element
+/// and [`as_mut_slice`](Elements::as_mut_slice) for all of them, which update the index when they
+/// are dropped, so it always matches the names. [`iter_mut`](Elements::iter_mut) hands out plain
+/// references instead, which may outlive any guard, so every read after it goes through a view
+/// rebuilt from the elements, with renamed elements merged, and the next change keeps that view.
+///
+/// # Duplicate policy
+///
+/// Inserting an element under a name that is already present merges it into the existing
+/// element: the modifiers the existing element does not have yet are appended to its own, and
+/// the element keeps its first position. Parsing `.bem` sources, deserializing JSON, collecting
+/// an iterator, and renaming through [`get_mut`](Elements::get_mut),
+/// [`as_mut_slice`](Elements::as_mut_slice) or [`iter_mut`](Elements::iter_mut) all follow this
+/// policy, so `button[rewind]` and
+/// `button[fast-forward]` on two lines describe `button[rewind,fast-forward]`.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ BEMElement, Elements };
+///
+/// let element = |name: &str, modifiers: &[&str]| BEMElement {
+///     name: name.to_string(),
+///     modifiers: modifiers.iter().map(|modifier| (*modifier).into()).collect(),
+/// };
+/// let elements: Elements = vec![element("button", &["rewind"]), element("timeline", &[]), element("button", &["fast-forward"])].into();
+///
+/// assert_eq!(elements.len(), 2);
+/// assert_eq!(elements[0].modifiers, vec!["rewind", "fast-forward"]);
+/// assert!(elements.contains("timeline"));
+/// ```
+#[derive(Clone, Default)]
+pub struct Elements {
+	items: Vec<BEMElement>,
+	/// The index, and the elements merged by name if renames made `items` repeat a name. Empty
+	/// after [`iter_mut`](Elements::iter_mut), and built again by the next read.
+	view: OnceLock<View>,
+}
+
+/// The elements of [`Elements`] as every read sees them.
+#[derive(Clone, Default)]
+struct View {
+	/// The position of every name.
+	index: HashMap<String, usize>,
+	/// The elements with the ones sharing a name merged, or `None` if no two share a name.
+	merged: Option<Vec<BEMElement>>,
+}
+
+impl View {
+	/// Indexes `items`, merging the elements that share a name by the
+	/// [duplicate policy](Elements#duplicate-policy).
+	fn of(items: &[BEMElement]) -> View {
+		let mut view = View { index: HashMap::with_capacity(items.len()), merged: None };
+
+		for (position, element) in items.iter().enumerate() {
+			match view.index.get(&element.name) {
+				Some(&first) => {
+					let existing = &mut view.merged.get_or_insert_with(|| items[..position].to_vec())[first];
+
+					for modifier in &element.modifiers {
+						if !existing.modifiers.contains(modifier) {
+							existing.modifiers.push(modifier.clone());
+						}
+					}
+				}
+				None => {
+					let merged = view.merged.as_mut().map(|merged| {
+						merged.push(element.clone());
+						merged.len() - 1
+					});
+
+					view.index.insert(element.name.clone(), merged.unwrap_or(position));
+				}
+			}
+		}
+
+		view
+	}
+}
+
+impl Elements {
+	/// Returns an empty list of elements.
+	pub fn new() -> Elements {
+		Elements::default()
+	}
+
+	/// Appends an element, or merges it into the element of the same name, see the
+	/// [duplicate policy](Elements#duplicate-policy). Returns whether the name was new.
+	pub fn insert(&mut self, element: BEMElement) -> bool {
+		match self.index_mut().get(&element.name).copied() {
+			Some(position) => {
+				let existing = &mut self.items[position];
+
+				for modifier in element.modifiers {
+					if !existing.modifiers.contains(&modifier) {
+						existing.modifiers.push(modifier);
+					}
+				}
+
+				false
+			}
+			None => {
+				let position = self.items.len();
+
+				self.index_mut().insert(element.name.clone(), position);
+				self.items.push(element);
+
+				true
+			}
+		}
+	}
+
+	/// Returns the element named exactly `name`.
+	pub fn get(&self, name: &str) -> Option<&BEMElement> {
+		self.index().get(name).map(|&position| &self[position])
+	}
+
+	/// Returns the element named exactly `name` for modification. If the element is renamed,
+	/// the index is updated once the returned guard is dropped, merging the element into another
+	/// one of the new name if there is one.
+	pub fn get_mut(&mut self, name: &str) -> Option<ElementMut<'_>> {
+		let position = *self.index_mut().get(name)?;
+
+		Some(ElementMut { elements: self, position })
+	}
+
+	/// Returns whether an element is named exactly `name`.
+	pub fn contains(&self, name: &str) -> bool {
+		self.index().contains_key(name)
+	}
+
+	/// Returns the elements as a mutable slice, in insertion order, behind a guard. Once the
+	/// guard is dropped, the index is rebuilt, and renamed elements are merged into the first
+	/// element of the same name, see the [duplicate policy](Elements#duplicate-policy).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let mut block = parse("media-player\nbutton[rewind]\ntimeline").unwrap();
+	///
+	/// for element in block.elements.as_mut_slice().iter_mut() {
+	///     element.name = format!("player-{}", element.name);
+	/// }
+	///
+	/// assert!(block.elements.contains("player-button"));
+	/// assert!(!block.elements.contains("button"));
+	/// ```
+	pub fn as_mut_slice(&mut self) -> ElementsMut<'_> {
+		self.index_mut();
+
+		ElementsMut { elements: self }
+	}
+
+	/// Returns an iterator over the elements for modification, in insertion order. As the
+	/// references may rename elements after the iterator is gone, the next read merges renamed
+	/// elements into the first element of the same name, see the
+	/// [duplicate policy](Elements#duplicate-policy).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let mut block = parse("media-player\nbutton[rewind]\ntimeline").unwrap();
+	///
+	/// for element in block.elements.iter_mut() {
+	///     element.name = format!("player-{}", element.name);
+	/// }
+	///
+	/// assert!(block.elements.contains("player-button"));
+	/// assert!(!block.elements.contains("button"));
+	///
+	/// for element in block.elements.iter_mut() {
+	///     element.name = "player".to_string();
+	/// }
+	///
+	/// assert_eq!(block.elements.len(), 1);
+	/// ```
+	pub fn iter_mut(&mut self) -> slice::IterMut<'_, BEMElement> {
+		self.index_mut();
+		self.view.take();
+		self.items.iter_mut()
+	}
+
+	/// Removes the element named exactly `name` and returns it, keeping the order of the others.
+	pub fn remove(&mut self, name: &str) -> Option<BEMElement> {
+		let position = self.index_mut().remove(name)?;
+		let element = self.items.remove(position);
+
+		self.reindex_from(position);
+
+		Some(element)
+	}
+
+	/// Keeps only the elements for which `keep` returns `true`, in their order.
+	pub fn retain(&mut self, keep: impl FnMut(&BEMElement) -> bool) {
+		self.index_mut().clear();
+		self.items.retain(keep);
+		self.reindex_from(0);
+	}
+
+	/// Sorts the elements with a comparator, keeping the order of equal elements.
+	pub fn sort_by(&mut self, compare: impl FnMut(&BEMElement, &BEMElement) -> std::cmp::Ordering) {
+		self.index_mut();
+		self.items.sort_by(compare);
+		self.reindex_from(0);
+	}
+
+	/// Returns the elements as a vector, in insertion order, with renamed elements merged.
+	pub fn into_vec(mut self) -> Vec<BEMElement> {
+		self.index_mut();
+		self.items
+	}
+
+	/// Returns the view every read goes through, building it after [`iter_mut`](Elements::iter_mut).
+	fn view(&self) -> &View {
+		self.view.get_or_init(|| View::of(&self.items))
+	}
+
+	/// Returns the index of the names.
+	fn index(&self) -> &HashMap<String, usize> {
+		&self.view().index
+	}
+
+	/// Returns the index for modification, first keeping the elements merged since
+	/// [`iter_mut`](Elements::iter_mut).
+	fn index_mut(&mut self) -> &mut HashMap<String, usize> {
+		self.view();
+
+		let view = self.view.get_mut().expect("the view is built");
+
+		if let Some(merged) = view.merged.take() {
+			self.items = merged;
+		}
+
+		&mut view.index
+	}
+
+	/// Updates the positions of the elements from `start` on, if the index is built.
+	fn reindex_from(&mut self, start: usize) {
+		if let Some(View { index, .. }) = self.view.get_mut() {
+			for (position, element) in self.items.iter().enumerate().skip(start) {
+				index.insert(element.name.clone(), position);
+			}
+		}
+	}
+}
+
+/// A mutable reference to an element of [`Elements`], returned by [`Elements::get_mut`].
+pub struct ElementMut<'a> {
+	elements: &'a mut Elements,
+	position: usize,
+}
+
+impl Deref for ElementMut<'_> {
+	type Target = BEMElement;
+
+	fn deref(&self) -> &BEMElement {
+		&self.elements.items[self.position]
+	}
+}
+
+impl DerefMut for ElementMut<'_> {
+	fn deref_mut(&mut self) -> &mut BEMElement {
+		&mut self.elements.items[self.position]
+	}
+}
+
+impl Drop for ElementMut<'_> {
+	fn drop(&mut self) {
+		let elements = &mut *self.elements;
+		let name = elements.items[self.position].name.clone();
+		let index = elements.index_mut();
+
+		if index.get(&name) == Some(&self.position) {
+			return;
+		}
+
+		index.retain(|_, position| *position != self.position);
+
+		match index.entry(name) {
+			Entry::Occupied(_) => {
+				let element = elements.items.remove(self.position);
+
+				elements.reindex_from(self.position);
+				elements.insert(element);
+			}
+			Entry::Vacant(entry) => {
+				entry.insert(self.position);
+			}
+		}
+	}
+}
+
+/// The elements of [`Elements`] as a mutable slice, returned by [`Elements::as_mut_slice`].
+pub struct ElementsMut<'a> {
+	elements: &'a mut Elements,
+}
+
+impl Deref for ElementsMut<'_> {
+	type Target = [BEMElement];
+
+	fn deref(&self) -> &[BEMElement] {
+		&self.elements.items
+	}
+}
+
+impl DerefMut for ElementsMut<'_> {
+	fn deref_mut(&mut self) -> &mut [BEMElement] {
+		&mut self.elements.items
+	}
+}
+
+impl Drop for ElementsMut<'_> {
+	fn drop(&mut self) {
+		let elements = &mut *self.elements;
+		let index = elements.index();
+		let unchanged = elements.items.len() == index.len() && elements.items
+			.iter()
+			.enumerate()
+			.all(|(position, element)| index.get(&element.name) == Some(&position));
+
+		if !unchanged {
+			elements.view.take();
+			elements.index_mut();
+		}
+	}
+}
+
+impl Deref for Elements {
+	type Target = [BEMElement];
+
+	fn deref(&self) -> &[BEMElement] {
+		self.view().merged.as_deref().unwrap_or(&self.items)
+	}
+}
+
+impl fmt::Debug for Elements {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_list().entries(self.iter()).finish()
+	}
+}
+
+impl PartialEq for Elements {
+	fn eq(&self, other: &Self) -> bool {
+		**self == **other
+	}
+}
+
+impl Eq for Elements {}
+
+impl From<Vec<BEMElement>> for Elements {
+	fn from(elements: Vec<BEMElement>) -> Self {
+		elements.into_iter().collect()
+	}
+}
+
+impl FromIterator<BEMElement> for Elements {
+	fn from_iter<I: IntoIterator<Item = BEMElement>>(iter: I) -> Self {
+		let mut elements = Elements::new();
+
+		elements.extend(iter);
+		elements
+	}
+}
+
+impl Extend<BEMElement> for Elements {
+	fn extend<I: IntoIterator<Item = BEMElement>>(&mut self, iter: I) {
+		for element in iter {
+			self.insert(element);
+		}
+	}
+}
+
+impl IntoIterator for Elements {
+	type Item = BEMElement;
+	type IntoIter = std::vec::IntoIter<BEMElement>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.into_vec().into_iter()
+	}
+}
+
+impl<'a> IntoIterator for &'a Elements {
+	type Item = &'a BEMElement;
+	type IntoIter = std::slice::Iter<'a, BEMElement>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<'a> IntoIterator for &'a mut Elements {
+	type Item = &'a mut BEMElement;
+	type IntoIter = slice::IterMut<'a, BEMElement>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter_mut()
+	}
+}
+
+impl Serialize for Elements {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		(**self).serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for Elements {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Ok(Vec::<BEMElement>::deserialize(deserializer)?.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Elements;
+	use crate::models::BEMElement;
+	use crate::{ from_json, parse, to_json };
+
+	fn element(name: &str, modifiers: &[&str]) -> BEMElement {
+		BEMElement {
+			name: name.to_string(),
+			modifiers: modifiers.iter().map(|modifier| (*modifier).into()).collect(),
+		}
+	}
+
+	#[test]
+	fn test_insert_merges_duplicates() {
+		let mut elements = Elements::new();
+
+		assert!(elements.insert(element("button", &["rewind"])));
+		assert!(elements.insert(element("timeline", &[])));
+		assert!(!elements.insert(element("button", &["fast-forward", "rewind"])));
+		assert_eq!(elements.into_vec(), vec![element("button", &["rewind", "fast-forward"]), element("timeline", &[])]);
+	}
+
+	#[test]
+	fn test_lookup_and_removal() {
+		let mut elements: Elements = vec![element("a", &[]), element("b", &["x"]), element("c", &[])].into();
+
+		assert_eq!(elements.get("b"), Some(&element("b", &["x"])));
+		assert_eq!(elements.remove("a"), Some(element("a", &[])));
+		assert_eq!(elements.get("c"), Some(&element("c", &[])));
+		assert!(!elements.contains("a"));
+		assert_eq!(elements.remove("a"), None);
+	}
+
+	#[test]
+	fn test_get_mut_reindexes_renamed_elements() {
+		let mut elements: Elements = vec![element("a", &["x"]), element("b", &["y"]), element("c", &[])].into();
+
+		elements.get_mut("a").unwrap().name = "d".to_string();
+
+		assert!(elements.contains("d") && !elements.contains("a"));
+		assert_eq!(elements[0].name, "d");
+
+		elements.get_mut("b").unwrap().name = "c".to_string();
+
+		assert_eq!(elements.to_vec(), vec![element("d", &["x"]), element("c", &["y"])]);
+		assert_eq!(elements.get("c"), Some(&element("c", &["y"])));
+		assert_eq!(elements.get("d"), Some(&element("d", &["x"])));
+
+		elements.get_mut("c").unwrap().modifiers.push("z".into());
+
+		assert_eq!(elements.get("c").unwrap().modifiers, vec!["y", "z"]);
+	}
+
+	#[test]
+	fn test_as_mut_slice_reindexes_renamed_elements() {
+		let mut elements: Elements = vec![element("a", &["x"]), element("b", &["y"]), element("c", &[])].into();
+
+		for element in elements.as_mut_slice().iter_mut() {
+			element.name = element.name.replace('a', "c");
+		}
+
+		assert_eq!(elements.to_vec(), vec![element("c", &["x"]), element("b", &["y"])]);
+		assert_eq!(elements.get("c"), Some(&element("c", &["x"])));
+		assert_eq!(elements.get("b"), Some(&element("b", &["y"])));
+		assert!(!elements.contains("a"));
+
+		elements.as_mut_slice()[1].modifiers.push("z".into());
+
+		assert_eq!(elements.get("b"), Some(&element("b", &["y", "z"])));
+	}
+
+	#[test]
+	fn test_as_mut_slice_merges_before_reads() {
+		let mut bem_block = parse("foo\na[x]\nb[y]\nc").unwrap();
+
+		bem_block.elements.as_mut_slice().iter_mut().for_each(|element| element.name = "d".to_string());
+
+		assert_eq!(bem_block.elements.len(), 1);
+		assert_eq!(bem_block.elements.to_vec(), vec![element("d", &["x", "y"])]);
+		assert_eq!(to_json(&bem_block).unwrap(), "{\"name\":\"foo\",\"modifiers\":[],\"elements\":[{\"name\":\"d\",\"modifiers\":[\"x\",\"y\"]}]}");
+		assert_eq!(parse(&bem_block.to_string()).unwrap(), bem_block);
+	}
+
+	#[test]
+	fn test_iter_mut_marks_the_index_stale() {
+		let mut elements: Elements = vec![element("a", &["x"]), element("b", &["y"]), element("c", &[])].into();
+
+		for element in &mut elements {
+			element.name = element.name.replace('a', "d");
+		}
+
+		assert_eq!(elements.get("d"), Some(&element("d", &["x"])));
+		assert!(!elements.contains("a"));
+
+		for element in elements.iter_mut() {
+			element.name = element.name.replace('d', "c");
+		}
+
+		assert_eq!(elements.get("c"), Some(&element("c", &["x"])));
+		assert!(elements.insert(element("e", &[])));
+		assert_eq!(elements.to_vec(), vec![element("c", &["x"]), element("b", &["y"]), element("e", &[])]);
+		assert_eq!(elements.get("e"), Some(&element("e", &[])));
+	}
+
+	#[test]
+	fn test_renames_through_a_mutable_block_are_merged() {
+		let mut bem_block = parse("media-player\nbutton[rewind]\ntimeline").unwrap();
+
+		for element in &mut bem_block {
+			element.name = "x".to_string();
+		}
+
+		assert_eq!(to_json(&bem_block).unwrap(), "{\"name\":\"media-player\",\"modifiers\":[],\"elements\":[{\"name\":\"x\",\"modifiers\":[\"rewind\"]}]}");
+		assert_eq!(bem_block.elements.len(), 1);
+		assert_eq!(bem_block, parse("media-player\nx[rewind]").unwrap());
+	}
+
+	#[test]
+	fn test_serialization_keeps_insertion_order() {
+		let bem_block = parse("foo\nzeta\nalpha[x]\nmid").unwrap();
+		let json = to_json(&bem_block).unwrap();
+
+		assert_eq!(
+			json,
+			"{\"name\":\"foo\",\"modifiers\":[],\"elements\":[{\"name\":\"zeta\",\"modifiers\":[]},{\"name\":\"alpha\",\"modifiers\":[\"x\"]},{\"name\":\"mid\",\"modifiers\":[]}]}"
+		);
+		assert_eq!(from_json(&json).unwrap(), bem_block);
+	}
+
+	#[test]
+	fn test_duplicate_policy_in_parse_from_json_and_mutable_iteration() {
+		let merged = parse("foo\nbutton[rewind]").unwrap();
+		let mut expected = merged.clone();
+
+		expected.elements.get_mut("button").unwrap().modifiers.push("fast-forward".into());
+
+		assert_eq!(parse("foo\nbutton[rewind]\nbutton[fast-forward,rewind]").unwrap(), expected);
+		assert_eq!(
+			from_json("{\"name\":\"foo\",\"modifiers\":[],\"elements\":[{\"name\":\"button\",\"modifiers\":[\"rewind\"]},{\"name\":\"button\",\"modifiers\":[\"fast-forward\"]}]}").unwrap(),
+			expected
+		);
+		let mut renamed = parse("foo\nbutton[rewind]\nbutton-alt[fast-forward,rewind]").unwrap();
+
+		for element in &mut renamed {
+			element.name = "button".to_string();
+		}
+
+		assert_eq!(renamed, expected);
+		assert_eq!(renamed.elements.get("button"), Some(&expected.elements[0]));
+
+		let mut renamed: Elements = vec![element("button", &["rewind"]), element("button-alt", &["fast-forward"])].into();
+
+		renamed.iter_mut().for_each(|element| element.name = "button".to_string());
+
+		assert_eq!(renamed.into_vec(), expected.elements.to_vec());
+	}
+}
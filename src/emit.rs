@@ -0,0 +1,842 @@
+//! This module defines the output formats of the CLI as [`Emitter`]s, so formats can be added
+//! without changing the CLI itself.
+//!
+//! Every format is identified by the id passed to `--format`. An [`EmitterRegistry`] maps ids to
+//! emitters: [`EmitterRegistry::new`] holds the built-in formats, and tools embedding the CLI
+//! logic can [`register`](EmitterRegistry::register) their own emitters on top of them, or
+//! replace a built-in one by registering an emitter with the same id.
+//!
+//! # Example
+//!
+//! ```
+//! use bem::{ parse, BEMDocument, EmitContext, EmitError, Emitter, EmitterRegistry };
+//!
+//! struct BlockNames;
+//!
+//! impl Emitter for BlockNames {
+//!     fn id(&self) -> &str {
+//!         "block-names"
+//!     }
+//!
+//!     fn emit(&self, doc: &BEMDocument, _opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+//!         let names: Vec<&str> = doc.blocks.iter().map(|block| block.name.as_str()).collect();
+//!
+//!         Ok(names.join("\n").into_bytes())
+//!     }
+//! }
+//!
+//! let mut registry = EmitterRegistry::new();
+//! registry.register(BlockNames);
+//!
+//! let document = BEMDocument::from(parse("media-player").unwrap());
+//! let output = registry.emit("block-names", &document, &EmitContext::default()).unwrap();
+//!
+//! assert_eq!(output, b"media-player");
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+use crate::abbreviation::AbbreviationMap;
+use crate::attributes::AttributeScheme;
+use crate::budget::{ BudgetError, ClassBudget };
+use crate::codegen::{ css_modules_stylesheet, generate_css_skeleton, to_css_nested_with_config, to_scss, to_scss_placeholders };
+use crate::error::Error;
+use crate::filter::GenerateFilter;
+use crate::ident::{ check_css_names, CssNameError, CssNameMode, IdentifierError };
+use crate::ids::to_json_with_ids;
+use crate::models::{ BEMBlock, BEMDocument };
+use crate::naming::NamingScheme;
+use crate::order::EmitOrder;
+use crate::tooling::{ to_purgecss_safelist, SafelistMode };
+use crate::{ to_bem, to_json, to_json_jcs, to_json_pretty, to_jsx_with_abbreviations, to_theme_keys_ts_with_abbreviations };
+
+/// Options shared by every emitter. Emitters ignore the options that do not apply to them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmitContext {
+	/// Whether the `safelist` format lists exact classes or patterns.
+	pub safelist_mode: SafelistMode,
+	/// Whether the `css`, `css-flat`, `scss`, `scss-placeholders` and `classes` formats escape classes that are not valid CSS
+	/// identifiers or fail on them.
+	pub css_name_mode: CssNameMode,
+	/// The length limits of the classes listed by the `classes` format, if any. Abbreviations
+	/// are unique within the document, see [`ClassBudget`].
+	pub class_budget: Option<ClassBudget>,
+	/// The elements, and optionally modifiers, the `css`, `css-flat`, `css-modules`, `scss`,
+	/// `scss-placeholders`, `classes` and `safelist` formats emit. Keeps everything by default.
+	pub filter: GenerateFilter,
+	/// The order of elements and modifiers in every format. Source order by default.
+	pub order: EmitOrder,
+	/// Whether the `json` format is indented, see [`to_json_pretty`], and the `bem` format
+	/// writes modifier lists one modifier per line, see
+	/// [`BEMBlock::to_bem_string_pretty`](crate::BEMBlock::to_bem_string_pretty).
+	pub pretty: bool,
+	/// The separators every format writing classes composes them with. Standard BEM by default.
+	pub naming: NamingScheme,
+	/// The abbreviations of name segments the `theme-ts`, `jsx` and `react-native` formats apply
+	/// to their identifiers before casing. Classes are never abbreviated. None by default.
+	pub abbreviations: AbbreviationMap,
+}
+
+/// An error raised while emitting a document.
+#[derive(Debug)]
+pub enum EmitError {
+	/// No emitter is registered with the requested id.
+	UnknownFormat(String),
+	/// The document could not be serialized to JSON.
+	Serialize(serde_json::Error),
+	/// Two BEM names of the document map to the same identifier in the output language.
+	Identifier(IdentifierError),
+	/// A class of the document is not a valid CSS identifier, see [`CssNameMode::Reject`].
+	CssName(CssNameError),
+	/// A class of the document exceeds [`EmitContext::class_budget`].
+	Budget(BudgetError),
+	/// Any other error, for emitters defined outside of this crate.
+	Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for EmitError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			EmitError::UnknownFormat(id) => write!(f, "unknown format `{}`", id),
+			EmitError::Serialize(error) => write!(f, "{}", error),
+			EmitError::Identifier(error) => write!(f, "{}", error),
+			EmitError::CssName(error) => write!(f, "{}", error),
+			EmitError::Budget(error) => write!(f, "{}", error),
+			EmitError::Other(error) => write!(f, "{}", error),
+		}
+	}
+}
+
+impl std::error::Error for EmitError {}
+
+impl From<serde_json::Error> for EmitError {
+	fn from(error: serde_json::Error) -> Self {
+		EmitError::Serialize(error)
+	}
+}
+
+impl From<Error> for EmitError {
+	fn from(error: Error) -> Self {
+		match error {
+			Error::Serialize(error) => EmitError::Serialize(error),
+			error => EmitError::Other(Box::new(error)),
+		}
+	}
+}
+
+impl From<IdentifierError> for EmitError {
+	fn from(error: IdentifierError) -> Self {
+		EmitError::Identifier(error)
+	}
+}
+
+impl From<CssNameError> for EmitError {
+	fn from(error: CssNameError) -> Self {
+		EmitError::CssName(error)
+	}
+}
+
+impl From<BudgetError> for EmitError {
+	fn from(error: BudgetError) -> Self {
+		EmitError::Budget(error)
+	}
+}
+
+/// An output format, turning a [`BEMDocument`] into the bytes written by the CLI.
+///
+/// Emitters must be `Send + Sync`, so a registry can be shared between threads.
+pub trait Emitter: Send + Sync {
+	/// Returns the id selecting this emitter, e.g. `json` for `--format json`.
+	fn id(&self) -> &str;
+
+	/// Renders `doc` in the format of this emitter.
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError>;
+}
+
+/// The emitters available to the CLI, looked up by id.
+pub struct EmitterRegistry {
+	emitters: Vec<Box<dyn Emitter>>,
+}
+
+impl EmitterRegistry {
+	/// Returns a registry holding the built-in formats: `json`, `json-with-ids`, `jcs`,
+	/// `safelist`, `css`, `css-flat`, `css-modules`, `scss`, `scss-placeholders`, `classes`, `bem`,
+	/// `theme-ts`, `jsx` and `react-native`, and `yaml` with the `yaml` feature.
+	pub fn new() -> EmitterRegistry {
+		let mut registry = EmitterRegistry::empty();
+
+		registry.register(JsonEmitter);
+		registry.register(JsonWithIdsEmitter);
+		registry.register(JcsEmitter);
+		registry.register(SafelistEmitter);
+		registry.register(CssEmitter);
+		registry.register(CssFlatEmitter);
+		registry.register(CssModulesEmitter);
+		registry.register(ScssEmitter);
+		registry.register(ScssPlaceholdersEmitter);
+		registry.register(ClassesEmitter);
+		registry.register(BemEmitter);
+		registry.register(ThemeTsEmitter);
+		registry.register(JsxEmitter);
+		registry.register(ReactNativeEmitter);
+		#[cfg(feature = "yaml")]
+		registry.register(YamlEmitter);
+
+		registry
+	}
+
+	/// Returns a registry without any emitter.
+	pub fn empty() -> EmitterRegistry {
+		EmitterRegistry { emitters: Vec::new() }
+	}
+
+	/// Adds an emitter to the registry. An emitter already registered with the same id is
+	/// replaced, keeping its position in [`ids`](EmitterRegistry::ids), and returned.
+	pub fn register<E: Emitter + 'static>(&mut self, emitter: E) -> Option<Box<dyn Emitter>> {
+		let emitter: Box<dyn Emitter> = Box::new(emitter);
+
+		match self.emitters.iter().position(|existing| existing.id() == emitter.id()) {
+			Some(index) => Some(std::mem::replace(&mut self.emitters[index], emitter)),
+			None => {
+				self.emitters.push(emitter);
+				None
+			}
+		}
+	}
+
+	/// Returns the emitter registered with `id`, if any.
+	pub fn get(&self, id: &str) -> Option<&dyn Emitter> {
+		self.emitters
+			.iter()
+			.find(|emitter| emitter.id() == id)
+			.map(|emitter| emitter.as_ref())
+	}
+
+	/// Returns the ids of the registered emitters, in registration order.
+	pub fn ids(&self) -> Vec<&str> {
+		self.emitters
+			.iter()
+			.map(|emitter| emitter.id())
+			.collect()
+	}
+
+	/// Renders `doc` with the emitter registered with `id`.
+	///
+	/// # Arguments
+	///
+	/// * `id`: &str - The id of the emitter, as passed to `--format`.
+	/// * `doc`: &BEMDocument - The document to render.
+	/// * `opts`: &EmitContext - The options of the emitter.
+	///
+	/// # Returns
+	///
+	/// * `Result<Vec<u8>, EmitError>` - The rendered document, or [`EmitError::UnknownFormat`]
+	///   if no emitter is registered with `id`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::{ parse, BEMDocument, EmitContext, EmitterRegistry };
+	///
+	/// let document = BEMDocument::from(parse("media-player[dark]").unwrap());
+	/// let output = EmitterRegistry::new().emit("classes", &document, &EmitContext::default()).unwrap();
+	///
+	/// assert_eq!(output, b"media-player\nmedia-player--dark\n");
+	/// ```
+	pub fn emit(&self, id: &str, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		self.get(id)
+			.ok_or_else(|| EmitError::UnknownFormat(id.to_string()))?
+			.emit(doc, opts)
+	}
+}
+
+impl Default for EmitterRegistry {
+	fn default() -> Self {
+		EmitterRegistry::new()
+	}
+}
+
+/// Renders every block as JSON. A document with a single block is rendered as that block,
+/// otherwise the blocks are rendered as an array.
+fn json_blocks<F>(doc: &BEMDocument, to_json: F) -> Result<Vec<u8>, EmitError>
+	where F: Fn(&BEMBlock) -> Result<String, Error>
+{
+	let blocks = doc.blocks
+		.iter()
+		.map(to_json)
+		.collect::<Result<Vec<String>, _>>()?;
+
+	match blocks.as_slice() {
+		[block] => Ok(block.clone().into_bytes()),
+		_ => Ok(format!("[{}]", blocks.join(",")).into_bytes()),
+	}
+}
+
+/// Renders every block with `to_text`, separating blocks with a blank line.
+fn text_blocks<F>(doc: &BEMDocument, to_text: F) -> Vec<u8> where F: Fn(&BEMBlock) -> String {
+	let blocks: Vec<String> = doc.blocks.iter().map(to_text).collect();
+
+	blocks.join("\n").into_bytes()
+}
+
+/// The JSON representation of the blocks, see [`to_json`].
+struct JsonEmitter;
+
+impl Emitter for JsonEmitter {
+	fn id(&self) -> &str {
+		"json"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, false);
+
+		match (opts.pretty, doc.blocks.as_slice()) {
+			(false, _) => json_blocks(&doc, to_json),
+			(true, [block]) => Ok(to_json_pretty(block)?.into_bytes()),
+			(true, blocks) => serde_json::to_vec_pretty(blocks).map_err(EmitError::Serialize),
+		}
+	}
+}
+
+/// The JSON representation with a stable `id` on every entity, see [`to_json_with_ids`].
+struct JsonWithIdsEmitter;
+
+impl Emitter for JsonWithIdsEmitter {
+	fn id(&self) -> &str {
+		"json-with-ids"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		json_blocks(&prepared(doc, opts, false), to_json_with_ids)
+	}
+}
+
+/// Canonical JSON (RFC 8785), see [`to_json_jcs`].
+struct JcsEmitter;
+
+impl Emitter for JcsEmitter {
+	fn id(&self) -> &str {
+		"jcs"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		json_blocks(&prepared(doc, opts, false), to_json_jcs)
+	}
+}
+
+/// A PurgeCSS safelist of the classes of all blocks, see [`to_purgecss_safelist`].
+struct SafelistEmitter;
+
+impl Emitter for SafelistEmitter {
+	fn id(&self) -> &str {
+		"safelist"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, true);
+
+		Ok(to_purgecss_safelist(&doc.blocks, opts.safelist_mode, &opts.naming).into_bytes())
+	}
+}
+
+/// A stylesheet skeleton using native CSS nesting, see [`to_css_nested_with_config`].
+struct CssEmitter;
+
+impl Emitter for CssEmitter {
+	fn id(&self) -> &str {
+		"css"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, true);
+
+		check_classes(&doc, opts)?;
+
+		Ok(text_blocks(&doc, |bem_block| to_css_nested_with_config(bem_block, &opts.naming)))
+	}
+}
+
+/// Flat CSS rules for every class of the blocks, see [`generate_css_skeleton`].
+struct CssFlatEmitter;
+
+impl Emitter for CssFlatEmitter {
+	fn id(&self) -> &str {
+		"css-flat"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, true);
+
+		check_classes(&doc, opts)?;
+
+		Ok(text_blocks(&doc, |bem_block| generate_css_skeleton(bem_block, &opts.naming)))
+	}
+}
+
+/// A CSS Modules stylesheet with camelCased classes, see [`to_css_modules`](crate::to_css_modules). Identifiers are
+/// numbered across all blocks, since they share one exported object, like
+/// [`to_css_modules_dts`](crate::to_css_modules_dts) does.
+struct CssModulesEmitter;
+
+impl Emitter for CssModulesEmitter {
+	fn id(&self) -> &str {
+		"css-modules"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, true);
+
+		Ok(css_modules_stylesheet(&doc.blocks, &opts.naming).into_bytes())
+	}
+}
+
+/// The YAML representation of the blocks, see [`to_yaml`](crate::to_yaml). Several blocks are
+/// written as a sequence.
+#[cfg(feature = "yaml")]
+struct YamlEmitter;
+
+#[cfg(feature = "yaml")]
+impl Emitter for YamlEmitter {
+	fn id(&self) -> &str {
+		"yaml"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, false);
+		let yaml = match doc.blocks.as_slice() {
+			[block] => crate::to_yaml(block),
+			blocks => serde_yaml::to_string(blocks).map_err(Error::Yaml),
+		};
+
+		Ok(yaml?.into_bytes())
+	}
+}
+
+/// SCSS rules nesting modifiers as `&--modifier`, see [`to_scss`].
+struct ScssEmitter;
+
+impl Emitter for ScssEmitter {
+	fn id(&self) -> &str {
+		"scss"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, true);
+
+		check_classes(&doc, opts)?;
+
+		Ok(text_blocks(&doc, |bem_block| to_scss(bem_block, &opts.naming)))
+	}
+}
+
+/// SCSS placeholder selectors, see [`to_scss_placeholders`].
+struct ScssPlaceholdersEmitter;
+
+impl Emitter for ScssPlaceholdersEmitter {
+	fn id(&self) -> &str {
+		"scss-placeholders"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, true);
+
+		check_classes(&doc, opts)?;
+
+		Ok(text_blocks(&doc, |bem_block| to_scss_placeholders(bem_block, &opts.naming)))
+	}
+}
+
+/// Every class of the blocks, one per line.
+struct ClassesEmitter;
+
+impl Emitter for ClassesEmitter {
+	fn id(&self) -> &str {
+		"classes"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, true);
+
+		check_classes(&doc, opts)?;
+
+		let mut classes: Vec<String> = doc.blocks
+			.iter()
+			.flat_map(|bem_block| opts.naming.class_names(bem_block))
+			.collect();
+
+		if let Some(budget) = &opts.class_budget {
+			classes = budget.apply(&classes)?;
+		}
+
+		let classes: String = classes.iter().map(|class| format!("{}\n", class)).collect();
+
+		Ok(classes.into_bytes())
+	}
+}
+
+/// The TypeScript declarations of a theme object keyed by the blocks, see
+/// [`to_theme_keys_ts_with_abbreviations`].
+struct ThemeTsEmitter;
+
+impl Emitter for ThemeTsEmitter {
+	fn id(&self) -> &str {
+		"theme-ts"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let doc = prepared(doc, opts, false);
+
+		Ok(to_theme_keys_ts_with_abbreviations(&doc.blocks, &opts.abbreviations)?.into_bytes())
+	}
+}
+
+/// A React component per block, with one boolean attribute per modifier, see
+/// [`to_jsx_with_abbreviations`].
+struct JsxEmitter;
+
+impl Emitter for JsxEmitter {
+	fn id(&self) -> &str {
+		"jsx"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let components = prepared(doc, opts, false).blocks
+			.iter()
+			.map(|bem_block| to_jsx_with_abbreviations(bem_block, &AttributeScheme::Boolean, &opts.abbreviations, &opts.naming))
+			.collect::<Result<Vec<String>, _>>()?;
+
+		Ok(components.join("\n").into_bytes())
+	}
+}
+
+/// A React Native module per block, see
+/// [`BEMBlock::to_bem_native_module_with_abbreviations`].
+struct ReactNativeEmitter;
+
+impl Emitter for ReactNativeEmitter {
+	fn id(&self) -> &str {
+		"react-native"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let modules: Vec<String> = prepared(doc, opts, false).blocks
+			.iter()
+			.map(|bem_block| bem_block.to_bem_native_module_with_abbreviations(&opts.abbreviations))
+			.collect();
+
+		Ok(modules.join("\n").into_bytes())
+	}
+}
+
+/// Applies [`EmitContext::order`], and with `filter` [`EmitContext::filter`], to `doc`, without
+/// copying it if neither changes anything.
+fn prepared<'a>(doc: &'a BEMDocument, opts: &EmitContext, filter: bool) -> Cow<'a, BEMDocument> {
+	let filter = filter && !opts.filter.is_empty();
+
+	if !filter && opts.order == EmitOrder::SourceOrder {
+		return Cow::Borrowed(doc);
+	}
+
+	let blocks: Vec<BEMBlock> = doc.blocks
+		.iter()
+		.map(|bem_block| match filter {
+			true => opts.order.apply(&opts.filter.apply(bem_block)),
+			false => opts.order.apply(bem_block),
+		})
+		.collect();
+
+	Cow::Owned(blocks.into())
+}
+
+/// Fails on the first class that is not a valid CSS identifier if `opts` asks for it.
+fn check_classes(doc: &BEMDocument, opts: &EmitContext) -> Result<(), CssNameError> {
+	if opts.css_name_mode == CssNameMode::Reject {
+		for bem_block in &doc.blocks {
+			check_css_names(bem_block)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// The `.bem` syntax, see [`to_bem`]. Blocks are separated by a blank
+/// line, so the output reads back with [`parse_all`](crate::parse_all).
+struct BemEmitter;
+
+impl Emitter for BemEmitter {
+	fn id(&self) -> &str {
+		"bem"
+	}
+
+	fn emit(&self, doc: &BEMDocument, opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+		let blocks: Vec<String> = prepared(doc, opts, false).blocks
+			.iter()
+			.map(|bem_block| match opts.pretty {
+				true => format!("{}\n", bem_block.to_bem_string_pretty()),
+				false => format!("{}\n", to_bem(bem_block)),
+			})
+			.collect();
+
+		Ok(blocks.join("\n").into_bytes())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ EmitContext, EmitError, Emitter, EmitterRegistry };
+	use crate::abbreviation::AbbreviationMap;
+	use crate::attributes::AttributeScheme;
+	use crate::budget::{ BudgetError, ClassBudget };
+	use crate::ident::CssNameMode;
+	use crate::models::BEMDocument;
+	use crate::tooling::SafelistMode;
+	use crate::{ generate_css_skeleton, parse, to_css_modules, to_css_modules_dts, to_css_nested, to_css_nested_with_config, to_json, to_json_jcs, to_json_pretty, to_json_with_ids, to_jsx_with_attributes, to_purgecss_safelist, to_scss, to_scss_placeholders, to_theme_keys_ts, BemConfig };
+
+	const MEDIA_PLAYER: &str = "media-player[dark]\nbutton[fast-forward,rewind]\ntimeline";
+
+	struct Uppercase;
+
+	impl Emitter for Uppercase {
+		fn id(&self) -> &str {
+			"uppercase"
+		}
+
+		fn emit(&self, doc: &BEMDocument, _opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+			let names: Vec<String> = doc.blocks
+				.iter()
+				.map(|bem_block| bem_block.name.to_uppercase())
+				.collect();
+
+			Ok(names.join("\n").into_bytes())
+		}
+	}
+
+	fn emit(registry: &EmitterRegistry, id: &str, doc: &BEMDocument, opts: &EmitContext) -> String {
+		String::from_utf8(registry.emit(id, doc, opts).unwrap()).unwrap()
+	}
+
+	#[test]
+	fn test_builtins_match_generators() {
+		let bem_block = parse(MEDIA_PLAYER).unwrap();
+		let document = BEMDocument::from(bem_block.clone());
+		let registry = EmitterRegistry::new();
+		let context = EmitContext::default();
+		let patterns = EmitContext { safelist_mode: SafelistMode::Pattern, ..EmitContext::default() };
+
+		assert_eq!(
+			registry.ids()[..14],
+			["json", "json-with-ids", "jcs", "safelist", "css", "css-flat", "css-modules", "scss", "scss-placeholders", "classes", "bem", "theme-ts", "jsx", "react-native"]
+		);
+		assert_eq!(emit(&registry, "json", &document, &context), to_json(&bem_block).unwrap());
+		assert_eq!(emit(&registry, "json-with-ids", &document, &context), to_json_with_ids(&bem_block).unwrap());
+		assert_eq!(emit(&registry, "jcs", &document, &context), to_json_jcs(&bem_block).unwrap());
+		assert_eq!(
+			emit(&registry, "safelist", &document, &context),
+			to_purgecss_safelist(&document.blocks, SafelistMode::Exact, &BemConfig::default())
+		);
+		assert_eq!(
+			emit(&registry, "safelist", &document, &patterns),
+			to_purgecss_safelist(&document.blocks, SafelistMode::Pattern, &BemConfig::default())
+		);
+		assert_eq!(emit(&registry, "css", &document, &context), to_css_nested(&bem_block));
+		assert_eq!(emit(&registry, "css-flat", &document, &context), generate_css_skeleton(&bem_block, &BemConfig::default()));
+		assert_eq!(emit(&registry, "css-modules", &document, &context), to_css_modules(&bem_block, &BemConfig::default()));
+		assert_eq!(emit(&registry, "scss", &document, &context), to_scss(&bem_block, &BemConfig::default()));
+		assert_eq!(emit(&registry, "scss-placeholders", &document, &context), to_scss_placeholders(&bem_block, &BemConfig::default()));
+		assert_eq!(parse(&emit(&registry, "bem", &document, &context)).unwrap(), bem_block);
+		assert_eq!(emit(&registry, "theme-ts", &document, &context), to_theme_keys_ts(std::slice::from_ref(&bem_block)).unwrap());
+		assert_eq!(emit(&registry, "jsx", &document, &context), to_jsx_with_attributes(&bem_block, &AttributeScheme::Boolean).unwrap());
+		assert_eq!(emit(&registry, "react-native", &document, &context), bem_block.to_bem_native_module());
+	}
+
+	#[test]
+	fn test_abbreviations() {
+		let document = BEMDocument::from(parse("navigation-drawer
+toggle-button[active]").unwrap());
+		let registry = EmitterRegistry::new();
+		let context = EmitContext {
+			abbreviations: AbbreviationMap::from_json("{\"navigation\": \"nav\", \"button\": \"btn\"}").unwrap(),
+			..EmitContext::default()
+		};
+
+		assert!(emit(&registry, "theme-ts", &document, &context).contains("\tnavDrawer: {\n\t\ttoggleBtn: {\n"));
+		assert!(emit(&registry, "jsx", &document, &context).contains("data-active={props.toggleBtnActive || undefined}"));
+		assert!(emit(&registry, "react-native", &document, &context).contains("\ttoggleBtn: {},\n\ttoggleBtnActive: {},\n"));
+		assert!(emit(&registry, "classes", &document, &context).contains("navigation-drawer__toggle-button\n"));
+
+		let colliding = BEMDocument::from(parse("navigation-drawer\ntoggle-btn[active]\ntoggle-button[active]").unwrap());
+
+		assert!(matches!(registry.emit("jsx", &colliding, &context), Err(EmitError::Identifier(error)) if error.identifier == "toggleBtnActive"));
+		assert!(emit(&registry, "react-native", &colliding, &context).contains("\ttoggleBtnActive: {},\n\ttoggleBtn_2: {},\n\ttoggleBtnActive_2: {},\n"));
+	}
+
+	#[test]
+	fn test_css_uses_naming() {
+		let bem_block = parse("media-player[dark]\nbutton").unwrap();
+		let document = BEMDocument::from(vec![bem_block.clone()]);
+		let naming = BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+		let context = EmitContext { naming: naming.clone(), ..EmitContext::default() };
+
+		assert_eq!(emit(&EmitterRegistry::new(), "css", &document, &context), to_css_nested_with_config(&bem_block, &naming));
+		assert_eq!(
+			emit(&EmitterRegistry::new(), "safelist", &document, &context),
+			to_purgecss_safelist(&document.blocks, SafelistMode::Exact, &naming)
+		);
+		assert_eq!(emit(&EmitterRegistry::new(), "scss", &document, &context), to_scss(&bem_block, &naming));
+		assert_eq!(emit(&EmitterRegistry::new(), "scss-placeholders", &document, &context), to_scss_placeholders(&bem_block, &naming));
+		assert!(emit(&EmitterRegistry::new(), "jsx", &document, &context).contains("className=\"media-player-button\""));
+	}
+
+	#[test]
+	fn test_builtins_with_several_blocks() {
+		let document = BEMDocument::from(vec![parse("media-player[dark]").unwrap(), parse("play-list\nitem").unwrap()]);
+		let registry = EmitterRegistry::new();
+		let context = EmitContext::default();
+
+		assert_eq!(
+			emit(&registry, "jcs", &document, &context),
+			"[{\"elements\":[],\"modifiers\":[\"dark\"],\"name\":\"media-player\"},\
+			{\"elements\":[{\"modifiers\":[],\"name\":\"item\"}],\"modifiers\":[],\"name\":\"play-list\"}]"
+		);
+		assert_eq!(
+			emit(&registry, "classes", &document, &context),
+			"media-player\nmedia-player--dark\nplay-list\nplay-list__item\n"
+		);
+		assert_eq!(emit(&registry, "scss-placeholders", &document, &context), "%media-player { }\n\n%media-player--dark { }\n\n%play-list { }\n\n%play-list__item { }\n");
+		assert_eq!(emit(&registry, "bem", &document, &context), "media-player[dark]\n\nplay-list\nitem\n");
+		assert_eq!(
+			emit(&registry, "css-modules", &document, &context),
+			".mediaPlayer { }\n\n.mediaPlayerDark { }\n\n.playList { }\n\n.playListItem { }\n"
+		);
+	}
+
+	#[test]
+	fn test_css_modules_collisions_across_blocks() {
+		let document = BEMDocument::from(vec![parse("play-list-item").unwrap(), parse("play-list\nitem").unwrap()]);
+		let css = emit(&EmitterRegistry::new(), "css-modules", &document, &EmitContext::default());
+
+		assert_eq!(css, ".playListItem { }\n\n.playList { }\n\n.playListItem_2 { }\n");
+		assert!(to_css_modules_dts(&document.blocks, &BemConfig::default()).contains("\treadonly playListItem_2: string;\n"));
+	}
+
+	#[cfg(feature = "yaml")]
+	#[test]
+	fn test_yaml() {
+		let registry = EmitterRegistry::new();
+		let bem_block = parse("media-player[dark]").unwrap();
+		let document = BEMDocument::from(vec![bem_block.clone(), parse("play-list\nitem").unwrap()]);
+
+		assert_eq!(registry.ids().last(), Some(&"yaml"));
+		assert_eq!(emit(&registry, "yaml", &BEMDocument::from(bem_block.clone()), &EmitContext::default()), crate::to_yaml(&bem_block).unwrap());
+		assert_eq!(
+			emit(&registry, "yaml", &document, &EmitContext::default()),
+			"- name: media-player\n  modifiers:\n  - dark\n  elements: []\n- name: play-list\n  modifiers: []\n  elements:\n  - name: item\n    modifiers: []\n"
+		);
+	}
+
+	#[test]
+	fn test_pretty_bem() {
+		let registry = EmitterRegistry::new();
+		let pretty = EmitContext { pretty: true, ..EmitContext::default() };
+		let document = BEMDocument::from(vec![parse("media-player[dark,compact]\nbutton").unwrap(), parse("play-list").unwrap()]);
+
+		assert_eq!(emit(&registry, "bem", &document, &pretty), "media-player[\n\tdark,\n\tcompact,\n]\nbutton\n\nplay-list\n");
+		assert_eq!(crate::parse_all(&emit(&registry, "bem", &document, &pretty)).unwrap(), document.blocks);
+	}
+
+	#[test]
+	fn test_pretty_json() {
+		let bem_block = parse("media-player[dark]").unwrap();
+		let pretty = EmitContext { pretty: true, ..EmitContext::default() };
+		let registry = EmitterRegistry::new();
+
+		assert_eq!(emit(&registry, "json", &BEMDocument::from(bem_block.clone()), &pretty), to_json_pretty(&bem_block).unwrap());
+		assert_eq!(
+			emit(&registry, "json", &BEMDocument::from(vec![bem_block.clone(), parse("play-list").unwrap()]), &pretty),
+			"[\n  {\n    \"name\": \"media-player\",\n    \"modifiers\": [\n      \"dark\"\n    ],\n    \"elements\": []\n  },\n  {\n    \"name\": \"play-list\",\n    \"modifiers\": [],\n    \"elements\": []\n  }\n]"
+		);
+		assert_eq!(emit(&registry, "jcs", &BEMDocument::from(bem_block.clone()), &pretty), to_json_jcs(&bem_block).unwrap());
+	}
+
+	#[test]
+	fn test_css_name_mode() {
+		let mut bem_block = parse("media-player[dark]").unwrap();
+		bem_block.modifiers.push("dark@mobile".into());
+
+		let document = BEMDocument::from(bem_block);
+		let registry = EmitterRegistry::new();
+		let reject = EmitContext { css_name_mode: CssNameMode::Reject, ..EmitContext::default() };
+
+		assert!(emit(&registry, "css", &document, &EmitContext::default()).contains("&.media-player--dark\\@mobile { }"));
+
+		for id in ["css", "scss", "scss-placeholders", "classes"] {
+			let error = registry.emit(id, &document, &reject).unwrap_err();
+
+			assert!(matches!(error, EmitError::CssName(ref error) if error.class == "media-player--dark@mobile"), "{}", id);
+		}
+
+		assert!(registry.emit("json", &document, &reject).is_ok());
+	}
+
+	#[test]
+	fn test_class_budget() {
+		let document = BEMDocument::from(vec![parse("media-player[dark]").unwrap(), parse("play-list\nitem").unwrap()]);
+		let registry = EmitterRegistry::new();
+		let budget = ClassBudget { max_class_len: 15, max_attr_len: 255, abbreviate: false, length_mode: Default::default() };
+		let strict = EmitContext { class_budget: Some(budget.clone()), ..EmitContext::default() };
+		let abbreviating = EmitContext { class_budget: Some(ClassBudget { abbreviate: true, ..budget }), ..EmitContext::default() };
+		let classes = emit(&registry, "classes", &document, &abbreviating);
+		let classes: Vec<&str> = classes.lines().collect();
+
+		assert!(matches!(
+			registry.emit("classes", &document, &strict),
+			Err(EmitError::Budget(BudgetError::ClassTooLong { ref class, .. })) if class == "media-player--dark"
+		));
+		assert_eq!(classes[0], "media-player");
+		assert!(classes[1].starts_with("media-") && classes[1].len() <= 15);
+		assert_eq!(classes[2..], ["play-list", "play-list__item"]);
+	}
+
+	#[test]
+	fn test_register() {
+		let document = BEMDocument::from(vec![parse("media-player").unwrap(), parse("play-list").unwrap()]);
+		let mut registry = EmitterRegistry::new();
+
+		assert!(registry.register(Uppercase).is_none());
+		assert_eq!(registry.ids().last(), Some(&"uppercase"));
+		assert_eq!(emit(&registry, "uppercase", &document, &EmitContext::default()), "MEDIA-PLAYER\nPLAY-LIST");
+	}
+
+	#[test]
+	fn test_register_replaces_emitter_with_same_id() {
+		struct Json;
+
+		impl Emitter for Json {
+			fn id(&self) -> &str {
+				"json"
+			}
+
+			fn emit(&self, _doc: &BEMDocument, _opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+				Ok(b"{}".to_vec())
+			}
+		}
+
+		let document = BEMDocument::from(parse("media-player").unwrap());
+		let mut registry = EmitterRegistry::new();
+		let replaced = registry.register(Json).unwrap();
+
+		assert_eq!(replaced.id(), "json");
+		assert_eq!(registry.ids().first(), Some(&"json"));
+		assert_eq!(registry.ids().len(), EmitterRegistry::new().ids().len());
+		assert_eq!(emit(&registry, "json", &document, &EmitContext::default()), "{}");
+	}
+
+	#[test]
+	fn test_unknown_format() {
+		let document = BEMDocument::from(parse("media-player").unwrap());
+		let error = EmitterRegistry::empty().emit("json", &document, &EmitContext::default()).unwrap_err();
+
+		assert!(matches!(error, EmitError::UnknownFormat(ref id) if id == "json"));
+		assert_eq!(error.to_string(), "unknown format `json`");
+	}
+}
@@ -0,0 +1,407 @@
+//! This module defines the error types returned by the fallible operations of this crate.
+//!
+//! Public functions return [`Error`], which wraps the more specific errors of the individual
+//! operations. With the `miette` feature, [`Error`] also implements `miette::Diagnostic`, with
+//! a `bem::` code per kind of error and a label on the span of parse errors.
+
+use std::fmt;
+use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
+use pest::RuleType;
+use pest::error::{ InputLocation, LineColLocation };
+use crate::validator::ValidationError;
+
+/// An error raised by any fallible operation of this crate.
+///
+/// # Example
+///
+/// ```
+/// fn block_name(json: &str) -> Result<String, bem::Error> {
+///     let bem_block = bem::from_json(json)?;
+///     let reparsed = bem::parse(&bem_block.name)?;
+///
+///     Ok(reparsed.name)
+/// }
+///
+/// # fn main() -> Result<(), bem::Error> {
+/// assert_eq!(block_name("{\"name\":\"media-player\",\"modifiers\":[],\"elements\":[]}")?, "media-player");
+///
+/// let error = block_name("{}").unwrap_err();
+///
+/// assert!(error.to_string().starts_with("invalid JSON: missing field `name`"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum Error {
+	/// The input does not follow the `.bem` grammar, or does not describe a BEM block.
+	Parse(BEMParseError),
+	/// A block could not be serialized.
+	Serialize(serde_json::Error),
+	/// A block could not be deserialized.
+	Deserialize(serde_json::Error),
+	/// A block could not be written or read as YAML.
+	#[cfg(feature = "yaml")]
+	Yaml(serde_yaml::Error),
+	/// A block could not be written as TOML.
+	#[cfg(feature = "toml")]
+	TomlSerialize(toml::ser::Error),
+	/// A block could not be read from TOML.
+	#[cfg(feature = "toml")]
+	TomlDeserialize(toml::de::Error),
+	/// Reading or writing failed.
+	Io(io::Error),
+	/// A block breaks the BEM naming conventions. Holds every violation, in document order.
+	Validation(Vec<ValidationError>),
+	/// The document declares a grammar version, with a `#!bem <version>` directive, that is newer
+	/// than the parser supports, or zero.
+	UnsupportedGrammarVersion {
+		/// The declared version.
+		version: u32,
+		/// The newest version supported, see [`GRAMMAR_VERSION`](crate::GRAMMAR_VERSION).
+		supported: u32,
+	},
+	/// Any of the other errors, raised while reading or writing the file at `path`.
+	File {
+		/// The file being read or written.
+		path: PathBuf,
+		/// The error itself.
+		error: Box<Error>,
+	},
+}
+
+/// Another name for [`Error`], for code that imports it next to the errors of other crates.
+pub type BemError = Error;
+
+/// A `Result` whose error is this crate's [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Parse(error) => write!(f, "parse error at {}", error),
+			Error::Serialize(error) => write!(f, "serialization failed: {}", error),
+			Error::Deserialize(error) => write!(f, "invalid JSON: {}", error),
+			#[cfg(feature = "yaml")]
+			Error::Yaml(error) => write!(f, "invalid YAML: {}", error),
+			#[cfg(feature = "toml")]
+			Error::TomlSerialize(error) => write!(f, "TOML serialization failed: {}", error),
+			#[cfg(feature = "toml")]
+			Error::TomlDeserialize(error) => write!(f, "invalid TOML: {}", error),
+			Error::Io(error) => write!(f, "I/O error: {}", error),
+			Error::Validation(errors) => match errors.as_slice() {
+				[] => write!(f, "invalid block"),
+				[error] => write!(f, "invalid block: {}", error),
+				[error, others @ ..] => write!(f, "invalid block: {} (and {} more)", error, others.len()),
+			},
+			Error::UnsupportedGrammarVersion { version, supported } => write!(
+				f,
+				"unsupported grammar version {}, expected 1 to {}",
+				version,
+				supported
+			),
+			Error::File { path, error } => write!(f, "{}: {}", path.display(), error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Parse(error) => Some(error),
+			Error::Serialize(error) | Error::Deserialize(error) => Some(error),
+			#[cfg(feature = "yaml")]
+			Error::Yaml(error) => Some(error),
+			#[cfg(feature = "toml")]
+			Error::TomlSerialize(error) => Some(error),
+			#[cfg(feature = "toml")]
+			Error::TomlDeserialize(error) => Some(error),
+			Error::Io(error) => Some(error),
+			Error::Validation(errors) => errors.first().map(|error| error as &(dyn std::error::Error + 'static)),
+			Error::UnsupportedGrammarVersion { .. } => None,
+			Error::File { error, .. } => Some(error.as_ref()),
+		}
+	}
+}
+
+impl Error {
+	/// Attaches the path of the file being read or written to an error.
+	pub(crate) fn in_file(self, path: impl Into<PathBuf>) -> Error {
+		Error::File { path: path.into(), error: Box::new(self) }
+	}
+
+	/// Returns a stable, machine-readable code for the kind of error, e.g. `parse` or `io`, for
+	/// reports and tooling that should not depend on error messages. File errors return the code
+	/// of the error they hold.
+	///
+	/// # Example
+	///
+	/// ```
+	/// assert_eq!(bem::parse("media-player(dark)").unwrap_err().code(), "parse");
+	/// ```
+	pub fn code(&self) -> &'static str {
+		match self {
+			Error::Parse(_) => "parse",
+			Error::Serialize(_) => "serialize",
+			Error::Deserialize(_) => "deserialize",
+			#[cfg(feature = "yaml")]
+			Error::Yaml(_) => "yaml",
+			#[cfg(feature = "toml")]
+			Error::TomlSerialize(_) | Error::TomlDeserialize(_) => "toml",
+			Error::Io(_) => "io",
+			Error::Validation(_) => "validation",
+			Error::UnsupportedGrammarVersion { .. } => "unsupported-grammar-version",
+			Error::File { error, .. } => error.code(),
+		}
+	}
+
+	/// Returns the kind of I/O error this error converts to, see `From<Error> for io::Error`.
+	fn io_kind(&self) -> io::ErrorKind {
+		match self {
+			Error::Io(error) => error.kind(),
+			Error::Parse(_) | Error::Validation(_) | Error::UnsupportedGrammarVersion { .. } => io::ErrorKind::InvalidInput,
+			Error::Serialize(_) | Error::Deserialize(_) => io::ErrorKind::InvalidData,
+			#[cfg(feature = "yaml")]
+			Error::Yaml(_) => io::ErrorKind::InvalidData,
+			#[cfg(feature = "toml")]
+			Error::TomlSerialize(_) | Error::TomlDeserialize(_) => io::ErrorKind::InvalidData,
+			Error::File { error, .. } => error.io_kind(),
+		}
+	}
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for Error {
+	fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+		Some(Box::new(format!("bem::{}", Error::code(self))))
+	}
+
+	fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+		match self {
+			Error::Parse(error) => error.labels(),
+			Error::File { error, .. } => error.labels(),
+			_ => None,
+		}
+	}
+
+	fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn miette::Diagnostic> + 'a>> {
+		match self {
+			Error::Validation(errors) => Some(Box::new(errors.iter().map(|error| error as &dyn miette::Diagnostic))),
+			Error::File { error, .. } => error.related(),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for ValidationError {
+	fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+		Some(Box::new("bem::validation"))
+	}
+}
+
+impl From<BEMParseError> for Error {
+	fn from(error: BEMParseError) -> Self {
+		Error::Parse(error)
+	}
+}
+
+impl<R: RuleType> From<pest::error::Error<R>> for Error {
+	fn from(error: pest::error::Error<R>) -> Self {
+		Error::Parse(error.into())
+	}
+}
+
+impl From<io::Error> for Error {
+	fn from(error: io::Error) -> Self {
+		Error::Io(error)
+	}
+}
+
+impl From<Vec<ValidationError>> for Error {
+	fn from(errors: Vec<ValidationError>) -> Self {
+		Error::Validation(errors)
+	}
+}
+
+impl From<Error> for io::Error {
+	fn from(error: Error) -> Self {
+		match error {
+			Error::Io(error) => error,
+			error => io::Error::new(error.io_kind(), error),
+		}
+	}
+}
+
+/// An error raised when the input does not follow the `.bem` grammar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BEMParseError {
+	/// What the parser expected, e.g. `expected EOI, modifiers, or element`.
+	pub message: String,
+	/// The byte range of the input the error points at. Empty when it points between two characters.
+	pub span: Range<usize>,
+	/// The line of the start of `span`, starting at 1.
+	pub line: usize,
+	/// The column of the start of `span`, in characters, starting at 1.
+	pub column: usize,
+}
+
+impl fmt::Display for BEMParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+	}
+}
+
+impl std::error::Error for BEMParseError {}
+
+/// Labels the span of the input the error points at. The input itself is not kept, so attach it
+/// with `miette::Report::with_source_code` to render the label.
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for BEMParseError {
+	fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+		Some(Box::new("bem::parse"))
+	}
+
+	fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+		let label = miette::LabeledSpan::new_with_span(Some(self.message.clone()), self.span.clone());
+
+		Some(Box::new(std::iter::once(label)))
+	}
+}
+
+impl BEMParseError {
+	/// Returns an error pointing at the byte range `span` of `input`.
+	pub(crate) fn at(input: &str, span: Range<usize>, message: String) -> BEMParseError {
+		let (line, column) = pest::Position::new(input, span.start)
+			.expect("spans lie within the input")
+			.line_col();
+
+		BEMParseError { message, span, line, column }
+	}
+}
+
+impl<R: RuleType> From<pest::error::Error<R>> for BEMParseError {
+	fn from(error: pest::error::Error<R>) -> Self {
+		let span = match error.location {
+			InputLocation::Pos(position) => position..position,
+			InputLocation::Span((start, end)) => start..end,
+		};
+		let (line, column) = match error.line_col {
+			LineColLocation::Pos(position) => position,
+			LineColLocation::Span(start, _) => start,
+		};
+
+		BEMParseError {
+			message: error.variant.message().into_owned(),
+			span,
+			line,
+			column,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::error::Error as _;
+	use std::io;
+	use super::{ BEMParseError, Error };
+	use crate::models::BEMBlock;
+	use crate::{ from_json, parse, validate };
+
+	#[test]
+	fn test_parse_error() {
+		let error = parse("foo\nbar(baz,qux)").unwrap_err();
+		let source = error.source().unwrap().downcast_ref::<BEMParseError>().unwrap();
+
+		assert_eq!(error.to_string(), "parse error at line 2, column 4: expected EOI, modifiers, element, or comment");
+		assert_eq!((source.line, source.column, source.span.clone()), (2, 4, 7..7));
+		assert!(source.source().is_none());
+	}
+
+	#[test]
+	fn test_deserialize_error() {
+		let error = from_json("{\"name\":\"media-player\"}").unwrap_err();
+
+		assert!(matches!(error, Error::Deserialize(_)));
+		assert_eq!(error.to_string(), "invalid JSON: missing field `modifiers` at line 1 column 23");
+		assert!(error.source().unwrap().is::<serde_json::Error>());
+	}
+
+	#[test]
+	fn test_io_error() {
+		let error = Error::from(io::Error::new(io::ErrorKind::NotFound, "media-player.bem"));
+
+		assert_eq!(error.to_string(), "I/O error: media-player.bem");
+		assert_eq!(error.source().unwrap().downcast_ref::<io::Error>().unwrap().kind(), io::ErrorKind::NotFound);
+		assert_eq!(io::Error::from(error).kind(), io::ErrorKind::NotFound);
+	}
+
+	#[test]
+	fn test_file_error() {
+		let error = parse("media-player(dark)").unwrap_err().in_file("design/media-player.bem");
+
+		assert_eq!(
+			error.to_string(),
+			"design/media-player.bem: parse error at line 1, column 13: expected EOI, modifiers, element, or comment"
+		);
+		assert!(matches!(error.source().unwrap().downcast_ref::<Error>(), Some(Error::Parse(_))));
+		assert_eq!(error.code(), "parse");
+		assert_eq!(io::Error::from(error).kind(), io::ErrorKind::InvalidInput);
+
+		let error = Error::from(io::Error::from(io::ErrorKind::NotFound)).in_file("missing.bem");
+
+		assert_eq!(io::Error::from(error).kind(), io::ErrorKind::NotFound);
+	}
+
+	#[test]
+	fn test_validation_error() {
+		let bem_block = BEMBlock::new("Media--Player");
+		let error = Error::from(validate(&bem_block));
+
+		assert_eq!(
+			error.to_string(),
+			"invalid block: Media--Player: \"Media--Player\" contains uppercase letters (and 1 more)"
+		);
+		assert_eq!(error.source().unwrap().to_string(), "Media--Player: \"Media--Player\" contains uppercase letters");
+		assert_eq!(io::Error::from(error).kind(), io::ErrorKind::InvalidInput);
+	}
+
+	#[cfg(feature = "miette")]
+	#[test]
+	fn test_diagnostic() {
+		use miette::Diagnostic;
+
+		let error = parse("foo\nbar(baz,qux)").unwrap_err().in_file("foo.bem");
+		let labels: Vec<miette::LabeledSpan> = error.labels().unwrap().collect();
+
+		assert_eq!(Diagnostic::code(&error).unwrap().to_string(), "bem::parse");
+		assert_eq!(labels.len(), 1);
+		assert_eq!((labels[0].offset(), labels[0].len()), (7, 0));
+		assert_eq!(labels[0].label(), Some("expected EOI, modifiers, element, or comment"));
+
+		let error = Error::from(validate(&BEMBlock::new("Media--Player")));
+		let related: Vec<String> = error.related().unwrap().map(|error| error.to_string()).collect();
+
+		assert_eq!(Diagnostic::code(&error).unwrap().to_string(), "bem::validation");
+		assert!(error.labels().is_none());
+		assert_eq!(related.len(), 2);
+		assert_eq!(related[0], "Media--Player: \"Media--Player\" contains uppercase letters");
+
+		let report = miette::Report::new(parse("media-player(dark)").unwrap_err()).with_source_code("media-player(dark)");
+
+		assert!(format!("{:?}", report).contains("bem::parse"));
+	}
+
+	#[test]
+	fn test_question_mark_composition() {
+		fn round_trip(input: &str) -> Result<BEMBlock, Error> {
+			let json = crate::to_json(&parse(input)?)?;
+
+			from_json(&json)
+		}
+
+		assert_eq!(round_trip("media-player[dark]").unwrap(), parse("media-player[dark]").unwrap());
+		assert!(matches!(round_trip("media-player(dark)"), Err(Error::Parse(_))));
+	}
+}
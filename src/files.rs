@@ -0,0 +1,178 @@
+//! This module reads and writes BEM files. The helpers only add file access around the
+//! in-memory API ([`parse`](crate::parse), [`to_json`](crate::to_json)), and attach the path of
+//! the file to every error they return.
+
+use std::fs;
+use std::io;
+use std::path::{ Path, PathBuf };
+use crate::error::{ Error, Result };
+use crate::models::{ BEMBlock, BEMDocument };
+use crate::{ parse, to_json };
+
+/// Reads and parses a `.bem` file.
+///
+/// # Arguments
+///
+/// * `path`: AsRef<Path> - The file to parse.
+///
+/// # Returns
+///
+/// * `Result<BEMBlock>` - The parsed block, or an [`Error::File`] holding the path and the read
+///   or parse error.
+///
+/// # Examples
+///
+/// ```
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("media-player.bem");
+/// std::fs::write(&path, "media-player[dark]").unwrap();
+///
+/// assert_eq!(bem::parse_file(&path).unwrap(), bem::parse("media-player[dark]").unwrap());
+/// assert!(bem::parse_file(dir.path().join("missing.bem")).unwrap_err().to_string().contains("missing.bem"));
+/// ```
+pub fn parse_file(path: impl AsRef<Path>) -> Result<BEMBlock> {
+	let path = path.as_ref();
+	let bem_input = fs::read_to_string(path).map_err(|error| Error::from(error).in_file(path))?;
+
+	parse_source(path, &bem_input)
+}
+
+/// Parses every `.bem` file of a directory into a document, in file name order. Other files
+/// and subdirectories are ignored.
+///
+/// # Arguments
+///
+/// * `path`: AsRef<Path> - The directory to parse.
+///
+/// # Returns
+///
+/// * `Result<BEMDocument>` - One block per file, or an [`Error::File`] for the directory or the
+///   first file that could not be read or parsed.
+///
+/// # Examples
+///
+/// ```
+/// let dir = tempfile::tempdir().unwrap();
+/// std::fs::write(dir.path().join("play-list.bem"), "play-list").unwrap();
+/// std::fs::write(dir.path().join("media-player.bem"), "media-player").unwrap();
+///
+/// let document = bem::parse_dir(dir.path()).unwrap();
+///
+/// assert_eq!(document.blocks[0].name, "media-player");
+/// assert_eq!(document.blocks[1].name, "play-list");
+/// ```
+pub fn parse_dir(path: impl AsRef<Path>) -> Result<BEMDocument> {
+	let path = path.as_ref();
+	let entries = fs::read_dir(path)
+		.and_then(|entries| entries.map(|entry| entry.map(|entry| entry.path())).collect::<io::Result<Vec<PathBuf>>>())
+		.map_err(|error| Error::from(error).in_file(path))?;
+
+	bem_files(entries, |entry| entry.is_dir())
+		.iter()
+		.map(parse_file)
+		.collect::<Result<Vec<BEMBlock>>>()
+		.map(BEMDocument::from)
+}
+
+/// Writes a block to a file as JSON, see [`to_json`].
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - The block to write.
+/// * `path`: AsRef<Path> - The file to create or overwrite.
+///
+/// # Returns
+///
+/// * `Result<()>` - Nothing, or an [`Error::File`] holding the path and the serialization or
+///   write error.
+///
+/// # Examples
+///
+/// ```
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("media-player.json");
+/// let bem_block = bem::parse("media-player").unwrap();
+///
+/// bem::write_json(&bem_block, &path).unwrap();
+///
+/// assert_eq!(bem::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap(), bem_block);
+/// ```
+pub fn write_json(bem_block: &BEMBlock, path: impl AsRef<Path>) -> Result<()> {
+	let path = path.as_ref();
+	let json = json_source(bem_block, path)?;
+
+	fs::write(path, json).map_err(|error| Error::from(error).in_file(path))
+}
+
+/// Parses the contents of the file at `path`, attaching the path to errors.
+pub(crate) fn parse_source(path: &Path, bem_input: &str) -> Result<BEMBlock> {
+	parse(bem_input).map_err(|error| error.in_file(path))
+}
+
+/// Serializes the block written to the file at `path`, attaching the path to errors.
+pub(crate) fn json_source(bem_block: &BEMBlock, path: &Path) -> Result<String> {
+	to_json(bem_block).map_err(|error| error.in_file(path))
+}
+
+/// Keeps the `.bem` files of a directory listing, sorted by path, with `is_dir` telling the
+/// directories apart.
+pub(crate) fn bem_files<F>(entries: Vec<PathBuf>, mut is_dir: F) -> Vec<PathBuf> where F: FnMut(&Path) -> bool {
+	let mut paths: Vec<PathBuf> = entries
+		.into_iter()
+		.filter(|path| path.extension().is_some_and(|extension| extension == "bem") && !is_dir(path))
+		.collect();
+
+	paths.sort();
+	paths
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use tempfile::tempdir;
+	use super::{ parse_dir, parse_file, write_json };
+	use crate::error::Error;
+	use crate::{ from_json, parse };
+
+	#[test]
+	fn test_parse_file_errors() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("media-player.bem");
+		fs::write(&path, "media-player(dark)").unwrap();
+
+		let error = parse_file(&path).unwrap_err();
+
+		assert!(matches!(error, Error::File { ref path, ref error } if path.ends_with("media-player.bem") && matches!(**error, Error::Parse(_))));
+
+		let error = parse_file(dir.path().join("missing.bem")).unwrap_err();
+
+		assert!(matches!(error, Error::File { ref error, .. } if matches!(**error, Error::Io(_))));
+		assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::NotFound);
+	}
+
+	#[test]
+	fn test_parse_dir() {
+		let dir = tempdir().unwrap();
+		fs::write(dir.path().join("play-list.bem"), "play-list\nitem").unwrap();
+		fs::write(dir.path().join("media-player.bem"), "media-player[dark]").unwrap();
+		fs::write(dir.path().join("notes.txt"), "media-player(dark)").unwrap();
+		fs::create_dir(dir.path().join("nested.bem")).unwrap();
+
+		let document = parse_dir(dir.path()).unwrap();
+
+		assert_eq!(document.blocks, vec![parse("media-player[dark]").unwrap(), parse("play-list\nitem").unwrap()]);
+		assert!(matches!(parse_dir(dir.path().join("missing")), Err(Error::File { .. })));
+	}
+
+	#[test]
+	fn test_write_json() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("media-player.json");
+		let bem_block = parse("media-player[dark]\nbutton").unwrap();
+
+		write_json(&bem_block, &path).unwrap();
+
+		assert_eq!(from_json(&fs::read_to_string(&path).unwrap()).unwrap(), bem_block);
+		assert!(write_json(&bem_block, dir.path().join("missing/media-player.json")).is_err());
+	}
+}
@@ -0,0 +1,256 @@
+//! This module selects the elements, and optionally the modifiers, that generators emit, e.g. to
+//! leave experimental elements out of the stylesheet of a large block.
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{ Deserialize, Deserializer, Serialize, Serializer };
+use crate::models::{ BEMBlock, BEMDocument, BEMElement, BEMModifier };
+
+/// A shell-style wildcard pattern matched against whole names: `*` matches any sequence of
+/// characters, `?` matches a single character, and every other character matches itself.
+///
+/// # Example
+///
+/// ```
+/// use bem::Glob;
+///
+/// let glob = Glob::new("x-*");
+///
+/// assert!(glob.matches("x-carousel"));
+/// assert!(!glob.matches("button"));
+/// assert!(Glob::new("b?tton").matches("button"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glob {
+	pattern: String,
+}
+
+impl Glob {
+	/// Returns the glob of `pattern`. Every string is a valid pattern.
+	pub fn new(pattern: &str) -> Glob {
+		Glob { pattern: pattern.to_string() }
+	}
+
+	/// Returns whether the whole of `name` matches the pattern.
+	pub fn matches(&self, name: &str) -> bool {
+		let pattern: Vec<char> = self.pattern.chars().collect();
+		let name: Vec<char> = name.chars().collect();
+		let (mut p, mut n) = (0, 0);
+		// The position of the last `*` in the pattern, and of the name when it was reached, to
+		// backtrack to when the rest of the pattern fails to match.
+		let mut star = None;
+
+		while n < name.len() {
+			match pattern.get(p) {
+				Some('*') => {
+					star = Some((p, n));
+					p += 1;
+				}
+				Some(&c) if c == '?' || c == name[n] => {
+					p += 1;
+					n += 1;
+				}
+				_ => match star {
+					Some((star_p, star_n)) => {
+						star = Some((star_p, star_n + 1));
+						p = star_p + 1;
+						n = star_n + 1;
+					}
+					None => return false,
+				},
+			}
+		}
+
+		pattern[p..].iter().all(|&c| c == '*')
+	}
+}
+
+impl fmt::Display for Glob {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.pattern)
+	}
+}
+
+impl FromStr for Glob {
+	type Err = std::convert::Infallible;
+
+	fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+		Ok(Glob::new(pattern))
+	}
+}
+
+impl From<&str> for Glob {
+	fn from(pattern: &str) -> Self {
+		Glob::new(pattern)
+	}
+}
+
+impl Serialize for Glob {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.pattern.serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for Glob {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Ok(Glob { pattern: String::deserialize(deserializer)? })
+	}
+}
+
+/// Selects the elements generators emit by name.
+///
+/// A name is kept if `include` is empty or one of its globs matches, and none of the globs of
+/// `exclude` matches: exclude wins over include. With `modifiers`, the modifiers of the block
+/// and of the kept elements are filtered by the same rules. Blocks are always kept, so filtering
+/// out every element leaves the block-only output rather than an error.
+///
+/// Generators that take a [`BEMBlock`] run on the result of [`apply`](GenerateFilter::apply),
+/// and the `css`, `scss`, `classes` and `safelist` formats apply [`EmitContext::filter`](crate::EmitContext::filter).
+///
+/// # Example
+///
+/// ```
+/// use bem::{ parse, to_css_nested, GenerateFilter };
+///
+/// let bem_block = parse("media-player\nbutton\nbutton-group\nx-carousel").unwrap();
+/// let filter = GenerateFilter { include: vec!["button*".into()], exclude: vec!["*-group".into()], modifiers: false };
+/// let css = to_css_nested(&filter.apply(&bem_block));
+///
+/// assert!(css.contains(".media-player__button { }"));
+/// assert!(!css.contains("button-group") && !css.contains("x-carousel"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerateFilter {
+	/// The globs one of which a name must match, or every name if empty.
+	pub include: Vec<Glob>,
+	/// The globs no name may match.
+	pub exclude: Vec<Glob>,
+	/// Whether modifiers are filtered too, or only elements.
+	pub modifiers: bool,
+}
+
+impl GenerateFilter {
+	/// Returns whether the filter keeps every name, i.e. has no glob.
+	pub fn is_empty(&self) -> bool {
+		self.include.is_empty() && self.exclude.is_empty()
+	}
+
+	/// Returns whether `name` is kept by the filter.
+	pub fn keeps(&self, name: &str) -> bool {
+		(self.include.is_empty() || self.include.iter().any(|glob| glob.matches(name)))
+			&& !self.exclude.iter().any(|glob| glob.matches(name))
+	}
+
+	/// Returns a copy of `bem_block` without the elements, and with `modifiers` the modifiers,
+	/// that the filter does not keep.
+	pub fn apply(&self, bem_block: &BEMBlock) -> BEMBlock {
+		let modifiers = |modifiers: &[BEMModifier]| -> Vec<BEMModifier> {
+			modifiers
+				.iter()
+				.filter(|modifier| !self.modifiers || self.keeps(&modifier.class_name()))
+				.cloned()
+				.collect()
+		};
+
+		BEMBlock {
+			name: bem_block.name.clone(),
+			modifiers: modifiers(&bem_block.modifiers),
+			elements: bem_block.elements
+				.iter()
+				.filter(|element| self.keeps(&element.name))
+				.map(|element| BEMElement { name: element.name.clone(), modifiers: modifiers(&element.modifiers) })
+				.collect(),
+		}
+	}
+
+	/// Applies the filter to every block of `doc`, see [`apply`](GenerateFilter::apply).
+	pub fn apply_document(&self, doc: &BEMDocument) -> BEMDocument {
+		doc.blocks.iter().map(|bem_block| self.apply(bem_block)).collect::<Vec<BEMBlock>>().into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ GenerateFilter, Glob };
+	use crate::emit::{ EmitContext, EmitterRegistry };
+	use crate::models::BEMDocument;
+	use crate::{ parse, to_scss_placeholders, to_theme_keys_ts, BemConfig };
+
+	const MEDIA_PLAYER: &str = "media-player[dark,x-compact]\nbutton[x-loud]\nbutton-group\nx-carousel\ntimeline";
+
+	fn filter(include: &[&str], exclude: &[&str]) -> GenerateFilter {
+		GenerateFilter {
+			include: include.iter().map(|&glob| glob.into()).collect(),
+			exclude: exclude.iter().map(|&glob| glob.into()).collect(),
+			modifiers: false,
+		}
+	}
+
+	fn element_names(filter: &GenerateFilter) -> Vec<String> {
+		filter.apply(&parse(MEDIA_PLAYER).unwrap()).elements.iter().map(|element| element.name.clone()).collect()
+	}
+
+	#[test]
+	fn test_glob_matches() {
+		assert!(Glob::new("*").matches(""));
+		assert!(Glob::new("a*b*c").matches("aXbYbc"));
+		assert!(!Glob::new("a*b*c").matches("aXbYbcd"));
+		assert!(Glob::new("??-*").matches("ab-"));
+		assert!(!Glob::new("button").matches("buttons"));
+		assert!(Glob::new("écran-?").matches("écran-é"));
+	}
+
+	#[test]
+	fn test_include_only() {
+		assert_eq!(element_names(&filter(&["button*"], &[])), vec!["button", "button-group"]);
+		assert_eq!(element_names(&filter(&["timeline", "x-*"], &[])), vec!["x-carousel", "timeline"]);
+	}
+
+	#[test]
+	fn test_exclude_only() {
+		assert_eq!(element_names(&filter(&[], &["x-*"])), vec!["button", "button-group", "timeline"]);
+		assert_eq!(element_names(&GenerateFilter::default()).len(), 4);
+	}
+
+	#[test]
+	fn test_exclude_wins_over_include() {
+		assert_eq!(element_names(&filter(&["button*", "x-*"], &["x-*", "*-group"])), vec!["button"]);
+
+		let modifiers = GenerateFilter { modifiers: true, ..filter(&[], &["x-*"]) };
+		let bem_block = modifiers.apply(&parse(MEDIA_PLAYER).unwrap());
+
+		assert_eq!(bem_block.modifiers, vec!["dark"]);
+		assert!(bem_block.get_element("button").unwrap().modifiers.is_empty());
+	}
+
+	#[test]
+	fn test_empty_result_emits_the_block() {
+		let filter = filter(&["none"], &[]);
+		let bem_block = filter.apply(&parse(MEDIA_PLAYER).unwrap());
+		let document = BEMDocument::from(parse(MEDIA_PLAYER).unwrap());
+		let context = EmitContext { filter: filter.clone(), ..EmitContext::default() };
+		let classes = EmitterRegistry::new().emit("classes", &document, &context).unwrap();
+
+		assert_eq!(String::from_utf8(classes).unwrap(), "media-player\nmedia-player--dark\nmedia-player--x-compact\n");
+		assert_eq!(to_scss_placeholders(&bem_block, &BemConfig::default()), to_scss_placeholders(&parse("media-player[dark,x-compact]").unwrap(), &BemConfig::default()));
+		assert_eq!(to_theme_keys_ts(std::slice::from_ref(&bem_block)).unwrap(), to_theme_keys_ts(&[parse("media-player[dark,x-compact]").unwrap()]).unwrap());
+	}
+
+	#[test]
+	fn test_emitters_apply_the_filter() {
+		let document = BEMDocument::from(parse(MEDIA_PLAYER).unwrap());
+		let context = EmitContext { filter: filter(&[], &["x-*", "button-*"]), ..EmitContext::default() };
+		let expected = BEMDocument::from(parse("media-player[dark,x-compact]\nbutton[x-loud]\ntimeline").unwrap());
+
+		for format in ["css", "scss", "scss-placeholders", "classes", "safelist"] {
+			let registry = EmitterRegistry::new();
+
+			assert_eq!(
+				registry.emit(format, &document, &context).unwrap(),
+				registry.emit(format, &expected, &EmitContext::default()).unwrap(),
+				"{}",
+				format
+			);
+		}
+	}
+}
@@ -0,0 +1,189 @@
+//! This module defines serialization formats for single blocks, so code converting blocks to and
+//! from text can be written once for every format, including formats defined by other crates.
+
+use crate::error::Error;
+use crate::models::BEMBlock;
+use crate::{ from_json, to_json };
+
+/// A text format `BEMBlock`s can be written to and read from.
+///
+/// Implementors are usually unit structs used as type parameters of [`encode`] and [`decode`].
+///
+/// # Example
+///
+/// ```
+/// use bem::{ decode, encode, BEMBlock, BemFormat };
+///
+/// /// Writes a block as its name only, dropping its modifiers and elements.
+/// struct Name;
+///
+/// impl BemFormat for Name {
+///     type Error = std::convert::Infallible;
+///
+///     fn serialize(block: &BEMBlock) -> Result<String, Self::Error> {
+///         Ok(block.name.clone())
+///     }
+///
+///     fn deserialize(s: &str) -> Result<BEMBlock, Self::Error> {
+///         Ok(BEMBlock::new(s))
+///     }
+/// }
+///
+/// let block = decode::<Name>("media-player").unwrap();
+///
+/// assert_eq!(encode::<Name>(&block).unwrap(), "media-player");
+/// ```
+pub trait BemFormat {
+	/// The error raised when a block cannot be written or read.
+	type Error;
+
+	/// Writes `block` in this format.
+	fn serialize(block: &BEMBlock) -> Result<String, Self::Error>;
+
+	/// Reads a block written in this format.
+	fn deserialize(s: &str) -> Result<BEMBlock, Self::Error>;
+}
+
+/// The JSON representation of blocks, as written by [`to_json`] and read by [`from_json`].
+pub struct Json;
+
+impl BemFormat for Json {
+	type Error = Error;
+
+	fn serialize(block: &BEMBlock) -> Result<String, Self::Error> {
+		to_json(block)
+	}
+
+	fn deserialize(s: &str) -> Result<BEMBlock, Self::Error> {
+		from_json(s)
+	}
+}
+
+/// Writes a block in the format `F`.
+///
+/// # Arguments
+///
+/// * `block`: &BEMBlock - A reference to the `BEMBlock` to be written.
+///
+/// # Returns
+///
+/// * `Result<String, F::Error>` - A result containing the block in the format `F` or an error.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ encode, parse, Json };
+///
+/// let json = encode::<Json>(&parse("media-player").unwrap()).unwrap();
+///
+/// assert_eq!(json, "{\"name\":\"media-player\",\"modifiers\":[],\"elements\":[]}");
+/// ```
+pub fn encode<F: BemFormat>(block: &BEMBlock) -> Result<String, F::Error> {
+	F::serialize(block)
+}
+
+/// Reads a block written in the format `F`.
+///
+/// # Arguments
+///
+/// * `s`: &str - The block in the format `F`.
+///
+/// # Returns
+///
+/// * `Result<BEMBlock, F::Error>` - A result containing the `BEMBlock` or an error.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ decode, Json };
+///
+/// let block = decode::<Json>("{\"name\":\"media-player\",\"modifiers\":[],\"elements\":[]}").unwrap();
+///
+/// assert_eq!(block.name, "media-player");
+/// ```
+pub fn decode<F: BemFormat>(s: &str) -> Result<BEMBlock, F::Error> {
+	F::deserialize(s)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ decode, encode, BemFormat, Json };
+	use crate::models::{ BEMBlock, BEMElement, BEMModifier };
+	use crate::{ parse, to_json };
+
+	const MEDIA_PLAYER: &str = "media-player[dark]\nbutton[fast-forward,rewind]\ntimeline";
+
+	/// Writes blocks as `name:modifiers` lines, the block first and then its elements.
+	struct Lines;
+
+	impl BemFormat for Lines {
+		type Error = String;
+
+		fn serialize(block: &BEMBlock) -> Result<String, Self::Error> {
+			let modifiers = |modifiers: &[BEMModifier]| modifiers.iter().map(BEMModifier::to_string).collect::<Vec<String>>().join(",");
+			let mut lines = vec![format!("{}:{}", block.name, modifiers(&block.modifiers))];
+
+			for element in &block.elements {
+				lines.push(format!("{}:{}", element.name, modifiers(&element.modifiers)));
+			}
+
+			Ok(lines.join("\n"))
+		}
+
+		fn deserialize(s: &str) -> Result<BEMBlock, Self::Error> {
+			let mut entities = s.lines().map(|line| {
+				let (name, modifiers) = line.split_once(':').ok_or(format!("missing `:` in `{}`", line))?;
+				let modifiers = modifiers
+					.split(',')
+					.filter(|modifier| !modifier.is_empty())
+					.map(BEMModifier::from)
+					.collect();
+
+				Ok::<_, String>(BEMElement { name: name.to_string(), modifiers })
+			});
+			let block = entities.next().ok_or("empty input")??;
+
+			Ok(BEMBlock {
+				name: block.name,
+				modifiers: block.modifiers,
+				elements: entities.collect::<Result<_, _>>()?,
+			})
+		}
+	}
+
+	/// Converts a block written in `A` to the format `B`, as code written against the trait would.
+	fn convert<A: BemFormat, B: BemFormat>(s: &str) -> Result<String, String>
+		where A::Error: ToString, B::Error: ToString
+	{
+		let block = decode::<A>(s).map_err(|e| e.to_string())?;
+
+		encode::<B>(&block).map_err(|e| e.to_string())
+	}
+
+	#[test]
+	fn test_json_round_trip() {
+		let bem_block = parse(MEDIA_PLAYER).unwrap();
+		let json = encode::<Json>(&bem_block).unwrap();
+
+		assert_eq!(json, to_json(&bem_block).unwrap());
+		assert_eq!(decode::<Json>(&json).unwrap(), bem_block);
+	}
+
+	#[test]
+	fn test_custom_format_round_trip() {
+		let bem_block = parse(MEDIA_PLAYER).unwrap();
+		let lines = encode::<Lines>(&bem_block).unwrap();
+
+		assert_eq!(lines, "media-player:dark\nbutton:fast-forward,rewind\ntimeline:");
+		assert_eq!(decode::<Lines>(&lines).unwrap(), bem_block);
+		assert_eq!(decode::<Lines>("media-player").unwrap_err(), "missing `:` in `media-player`");
+	}
+
+	#[test]
+	fn test_convert_between_formats() {
+		let json = to_json(&parse(MEDIA_PLAYER).unwrap()).unwrap();
+		let lines = convert::<Json, Lines>(&json).unwrap();
+
+		assert_eq!(convert::<Lines, Json>(&lines).unwrap(), json);
+	}
+}
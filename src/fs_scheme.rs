@@ -0,0 +1,104 @@
+//! This module lays out the files of a BEM block following the nested file structure scheme,
+//! where every element and modifier gets its own directory inside the block directory.
+
+use crate::models::BEMBlock;
+use crate::naming::NamingScheme;
+
+impl BEMBlock {
+	/// Returns the paths of the files implementing the block in one technology (`css`, `js`, ...),
+	/// following the nested file structure scheme.
+	///
+	/// Every file is named after its class and lives in the directory of its entity: the block
+	/// directory for the block, an `__element` directory inside it for each element, and a
+	/// `--modifier` directory inside the block or element directory for each modifier, e.g.
+	/// `media-player/__button/--rewind/media-player__button--rewind.css`. Paths are relative,
+	/// use `/` as separator, and follow the order of [`NamingScheme::class_names`].
+	///
+	/// # Arguments
+	///
+	/// * `extension`: &str - The file extension of the technology, without the leading dot.
+	///
+	/// # Returns
+	///
+	/// * `Vec<String>` - One path per class of the block.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let bem_block = parse("media-player[dark]\nbutton").unwrap();
+	///
+	/// assert_eq!(bem_block.to_bem_fs_scheme("css"), vec![
+	///     "media-player/media-player.css",
+	///     "media-player/--dark/media-player--dark.css",
+	///     "media-player/__button/media-player__button.css",
+	/// ]);
+	/// ```
+	pub fn to_bem_fs_scheme(&self, extension: &str) -> Vec<String> {
+		let scheme = NamingScheme::default();
+		let mut paths = Vec::new();
+		let mut push = |directory: &str, class: &str| {
+			let path = format!("{}/{}.{}", directory, class, extension);
+
+			if !paths.contains(&path) {
+				paths.push(path);
+			}
+		};
+
+		push(&self.name, &self.name);
+
+		for modifier in &self.modifiers {
+			let directory = format!("{}/{}{}", self.name, scheme.modifier_separator, modifier.class_name());
+
+			push(&directory, &scheme.modifier_class(&self.name, &modifier.class_name()));
+		}
+
+		for element in &self.elements {
+			let class = scheme.element_class(&self.name, &element.name);
+			let directory = format!("{}/{}{}", self.name, scheme.element_separator, element.name);
+
+			push(&directory, &class);
+
+			for modifier in &element.modifiers {
+				let modifier_directory = format!("{}/{}{}", directory, scheme.modifier_separator, modifier.class_name());
+
+				push(&modifier_directory, &scheme.modifier_class(&class, &modifier.class_name()));
+			}
+		}
+
+		paths
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::naming::NamingScheme;
+	use crate::parse;
+
+	#[test]
+	fn test_to_bem_fs_scheme() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+
+		assert_eq!(bem_block.to_bem_fs_scheme("scss"), vec![
+			"media-player/media-player.scss",
+			"media-player/--dark/media-player--dark.scss",
+			"media-player/__button/media-player__button.scss",
+			"media-player/__button/--fast-forward/media-player__button--fast-forward.scss",
+			"media-player/__button/--rewind/media-player__button--rewind.scss",
+			"media-player/__timeline/media-player__timeline.scss",
+		]);
+	}
+
+	#[test]
+	fn test_to_bem_fs_scheme_names_files_after_classes() {
+		let bem_block = parse("foo[bar]\nbaz[qux]\nbaz").unwrap();
+		let file_names: Vec<String> = bem_block
+			.to_bem_fs_scheme("css")
+			.iter()
+			.map(|path| path.rsplit('/').next().unwrap().trim_end_matches(".css").to_string())
+			.collect();
+
+		assert_eq!(file_names, NamingScheme::default().class_names(&bem_block));
+	}
+}
@@ -0,0 +1,254 @@
+//! This module generates random but plausible BEM blocks, for demos, benchmarks, and test
+//! fixtures. Generation is deterministic: the same seed and configuration always produce the
+//! same blocks.
+
+use std::ops::Range;
+use crate::models::{ BEMBlock, BEMDocument, BEMElement, BEMModifier };
+
+/// Words combined into block and element names.
+const NOUNS: &[&str] = &[
+	"avatar", "badge", "banner", "button", "card", "carousel", "chip", "dialog", "drawer", "field",
+	"footer", "header", "icon", "image", "input", "item", "label", "link", "list", "media",
+	"menu", "modal", "nav", "panel", "player", "progress", "search", "slider", "tab", "tile",
+	"timeline", "title", "toast", "toolbar", "tooltip", "track",
+];
+
+/// Words used as modifiers.
+const ADJECTIVES: &[&str] = &[
+	"active", "compact", "dark", "disabled", "expanded", "fast-forward", "focused", "ghost",
+	"hidden", "inline", "large", "light", "loading", "muted", "open", "primary", "rewind",
+	"rounded", "secondary", "selected", "small", "sticky", "vertical", "wide",
+];
+
+/// Configures the shape of the blocks produced by [`generate`] and [`generate_document`].
+///
+/// Counts are drawn uniformly from half-open ranges, so `1..4` yields 1, 2 or 3. An empty range
+/// always yields its start. Counts larger than the number of distinct names available are capped.
+///
+/// # Example
+///
+/// ```
+/// use bem::GenerateConfig;
+///
+/// let config = GenerateConfig { elements: 5..10, ..GenerateConfig::default() };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerateConfig {
+	/// The number of blocks of a document.
+	pub blocks: Range<usize>,
+	/// The number of elements of a block.
+	pub elements: Range<usize>,
+	/// The number of modifiers of a block or an element.
+	pub modifiers: Range<usize>,
+}
+
+impl Default for GenerateConfig {
+	fn default() -> Self {
+		GenerateConfig {
+			blocks: 1..4,
+			elements: 1..6,
+			modifiers: 0..3,
+		}
+	}
+}
+
+/// A SplitMix64 generator, which is small, fast, and good enough for fixtures.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+
+	/// Returns a number in `range`, or its start if it is empty.
+	fn in_range(&mut self, range: &Range<usize>) -> usize {
+		if range.is_empty() {
+			return range.start;
+		}
+
+		range.start + (self.next() % (range.end - range.start) as u64) as usize
+	}
+
+	/// Returns up to `count` distinct names drawn from `candidates`, in random order.
+	fn distinct(&mut self, candidates: Vec<String>, count: usize) -> Vec<String> {
+		let mut candidates = candidates;
+		let count = count.min(candidates.len());
+
+		for i in 0..count {
+			let j = self.in_range(&(i..candidates.len()));
+			candidates.swap(i, j);
+		}
+
+		candidates.truncate(count);
+		candidates
+	}
+}
+
+/// Returns every name made of one noun, or of two distinct nouns joined by a dash.
+fn compound_names() -> Vec<String> {
+	let mut names: Vec<String> = NOUNS.iter().map(|noun| noun.to_string()).collect();
+
+	for first in NOUNS {
+		for second in NOUNS.iter().filter(|second| *second != first) {
+			names.push(format!("{}-{}", first, second));
+		}
+	}
+
+	names
+}
+
+fn random_modifiers(rng: &mut Rng, config: &GenerateConfig) -> Vec<BEMModifier> {
+	let count = rng.in_range(&config.modifiers);
+
+	rng.distinct(ADJECTIVES.iter().map(|adjective| adjective.to_string()).collect(), count)
+		.into_iter()
+		.map(BEMModifier::new)
+		.collect()
+}
+
+fn block(rng: &mut Rng, name: String, config: &GenerateConfig) -> BEMBlock {
+	let modifiers = random_modifiers(rng, config);
+	let count = rng.in_range(&config.elements);
+	let elements = rng
+		.distinct(NOUNS.iter().map(|noun| noun.to_string()).collect(), count)
+		.into_iter()
+		.map(|name| BEMElement { name, modifiers: random_modifiers(rng, config) })
+		.collect();
+
+	BEMBlock { name, modifiers, elements }
+}
+
+/// Generates a random block.
+///
+/// The block name is one or two nouns from a built-in word list, like `media-player`. Element
+/// names are single nouns and modifiers are adjectives, all distinct within their block or
+/// element. Every generated name follows the `.bem` grammar, so the block can be written out
+/// and parsed back.
+///
+/// # Arguments
+///
+/// * `seed`: u64 - The seed of the generator. The same seed always produces the same block.
+/// * `config`: &GenerateConfig - The ranges of element and modifier counts.
+///
+/// # Returns
+///
+/// * `BEMBlock` - The generated block.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ generate, GenerateConfig };
+///
+/// let config = GenerateConfig { elements: 5..10, ..GenerateConfig::default() };
+/// let bem_block = generate(42, &config);
+///
+/// assert!((5..10).contains(&bem_block.elements.len()));
+/// assert_eq!(bem_block, generate(42, &config));
+/// ```
+pub fn generate(seed: u64, config: &GenerateConfig) -> BEMBlock {
+	let mut rng = Rng(seed);
+	let name = rng.distinct(compound_names(), 1).remove(0);
+
+	block(&mut rng, name, config)
+}
+
+/// Generates a document of random blocks with distinct names, see [`generate`].
+///
+/// # Arguments
+///
+/// * `seed`: u64 - The seed of the generator. The same seed always produces the same document.
+/// * `config`: &GenerateConfig - The ranges of block, element, and modifier counts.
+///
+/// # Returns
+///
+/// * `BEMDocument` - The generated document.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ generate_document, GenerateConfig };
+///
+/// let config = GenerateConfig { blocks: 3..4, ..GenerateConfig::default() };
+///
+/// assert_eq!(generate_document(7, &config).blocks.len(), 3);
+/// ```
+pub fn generate_document(seed: u64, config: &GenerateConfig) -> BEMDocument {
+	let mut rng = Rng(seed);
+	let count = rng.in_range(&config.blocks);
+	let names = rng.distinct(compound_names(), count);
+
+	BEMDocument {
+		blocks: names
+			.into_iter()
+			.map(|name| block(&mut rng, name, config))
+			.collect(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ generate, generate_document, GenerateConfig };
+	use crate::to_bem;
+	use crate::{ parse, validate };
+
+	#[test]
+	fn test_generate() {
+		insta::assert_snapshot!(to_bem(&generate(42, &GenerateConfig::default())));
+	}
+
+	#[test]
+	fn test_generate_reparses() {
+		let config = GenerateConfig { elements: 0..12, modifiers: 0..6, ..GenerateConfig::default() };
+
+		for seed in 0..1000 {
+			let bem_block = generate(seed, &config);
+
+			assert_eq!(parse(&to_bem(&bem_block)).unwrap(), bem_block, "seed {}", seed);
+			assert!(validate(&bem_block).is_empty(), "seed {}", seed);
+		}
+	}
+
+	#[test]
+	fn test_generate_respects_ranges() {
+		let config = GenerateConfig { blocks: 2..5, elements: 5..10, modifiers: 1..2 };
+
+		for seed in 0..100 {
+			let document = generate_document(seed, &config);
+			let mut names: Vec<&str> = document.blocks.iter().map(|block| block.name.as_str()).collect();
+
+			assert!((2..5).contains(&document.blocks.len()), "seed {}", seed);
+
+			for bem_block in &document.blocks {
+				assert!((5..10).contains(&bem_block.elements.len()), "seed {}", seed);
+				assert_eq!(bem_block.modifiers.len(), 1, "seed {}", seed);
+			}
+
+			names.sort();
+			names.dedup();
+
+			assert_eq!(names.len(), document.blocks.len(), "seed {}", seed);
+		}
+	}
+
+	#[test]
+	fn test_generate_caps_counts() {
+		let config = GenerateConfig { elements: 1000..1001, modifiers: 5..5, ..GenerateConfig::default() };
+		let bem_block = generate(1, &config);
+
+		assert_eq!(bem_block.elements.len(), super::NOUNS.len());
+		assert_eq!(bem_block.modifiers.len(), 5);
+	}
+
+	#[test]
+	fn test_generate_is_deterministic() {
+		let config = GenerateConfig::default();
+
+		assert_eq!(generate_document(42, &config), generate_document(42, &config));
+		assert_ne!(generate(42, &config), generate(43, &config));
+	}
+}
@@ -0,0 +1,116 @@
+//! This module derives internationalization keys from BEM blocks, so translation catalogs can be
+//! scaffolded from the same structure as the markup and styles.
+
+use std::collections::HashMap;
+use crate::models::BEMBlock;
+
+impl BEMBlock {
+	/// Returns the i18n keys for the labels a component might render.
+	///
+	/// The block gets a `{block}.label` key, and every element a `{block}.{element}.label` key.
+	/// Keys are listed in document order, and repeated elements only produce one key.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let bem_block = parse("media-player\nbutton").unwrap();
+	///
+	/// assert_eq!(bem_block.to_bem_i18n_keys(), vec!["media-player.label", "media-player.button.label"]);
+	/// ```
+	pub fn to_bem_i18n_keys(&self) -> Vec<String> {
+		let mut keys = vec![format!("{}.label", self.name)];
+
+		for element in &self.elements {
+			let key = format!("{}.{}.label", self.name, element.name);
+
+			if !keys.contains(&key) {
+				keys.push(key);
+			}
+		}
+
+		keys
+	}
+
+	/// Builds a translation catalog skeleton for the given locales.
+	///
+	/// The outer map is keyed by locale, and every locale maps each key of
+	/// [`to_bem_i18n_keys`](BEMBlock::to_bem_i18n_keys) to an empty string, ready to be filled
+	/// in by translators.
+	///
+	/// # Arguments
+	///
+	/// * `locales`: &[&str] - The locales to scaffold, e.g. `["en", "fr"]`.
+	///
+	/// # Returns
+	///
+	/// * `HashMap<String, HashMap<String, String>>` - The placeholder translations per locale.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let bem_block = parse("media-player\nbutton").unwrap();
+	/// let map = bem_block.to_bem_localization_map(&["en", "fr"]);
+	///
+	/// assert_eq!(map["fr"]["media-player.button.label"], "");
+	/// ```
+	pub fn to_bem_localization_map(&self, locales: &[&str]) -> HashMap<String, HashMap<String, String>> {
+		let keys = self.to_bem_i18n_keys();
+
+		locales
+			.iter()
+			.map(|locale| {
+				let translations = keys
+					.iter()
+					.map(|key| (key.clone(), String::new()))
+					.collect();
+
+				(locale.to_string(), translations)
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+	use crate::parse;
+
+	#[test]
+	fn test_to_bem_i18n_keys() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline\nbutton").unwrap();
+
+		assert_eq!(bem_block.to_bem_i18n_keys(), vec![
+			"media-player.label",
+			"media-player.button.label",
+			"media-player.timeline.label"
+		]);
+	}
+
+	#[test]
+	fn test_to_bem_localization_map() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let map = bem_block.to_bem_localization_map(&["en", "fr"]);
+		let expected_keys: HashSet<String> = bem_block.to_bem_i18n_keys().into_iter().collect();
+
+		assert_eq!(map.len(), 2);
+
+		for locale in ["en", "fr"] {
+			let translations = &map[locale];
+			let keys: HashSet<String> = translations.keys().cloned().collect();
+
+			assert_eq!(keys, expected_keys);
+			assert!(translations.values().all(String::is_empty));
+		}
+	}
+
+	#[test]
+	fn test_to_bem_localization_map_without_locales() {
+		let bem_block = parse("media-player").unwrap();
+
+		assert!(bem_block.to_bem_localization_map(&[]).is_empty());
+	}
+}
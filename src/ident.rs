@@ -0,0 +1,483 @@
+//! This module converts BEM names into identifiers for generated source code. Every generator
+//! that emits identifiers (props, object keys, type names) goes through it, so casing rules and
+//! collision detection behave the same across output formats.
+//!
+//! Class names written into selectors go through [`css_escape`] instead, since they have to
+//! stay the exact classes of the markup.
+
+use std::borrow::Cow;
+use std::fmt;
+use crate::abbreviation::AbbreviationMap;
+use crate::models::BEMBlock;
+use crate::naming::NamingScheme;
+
+/// The casing applied to generated identifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Case {
+	/// `fastForward`
+	Camel,
+	/// `FastForward`
+	Pascal,
+	/// `fast_forward`
+	Snake,
+}
+
+/// Returned when two distinct BEM names map to the same generated identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentifierError {
+	/// The identifier that would have been generated more than once.
+	pub identifier: String,
+	/// The BEM names that map to `identifier`, in the order they were encountered.
+	pub sources: Vec<String>,
+}
+
+impl fmt::Display for IdentifierError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "identifier `{}` would be generated for each of `{}`", self.identifier, self.sources.join("`, `"))
+	}
+}
+
+impl std::error::Error for IdentifierError {}
+
+/// How stylesheet generators handle classes that are not valid CSS identifiers, like
+/// `media-player--dark@mobile` or a class starting with a digit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CssNameMode {
+	/// Escape such classes in selectors with [`css_escape`], so `.media-player--dark\@mobile`
+	/// still matches `class="media-player--dark@mobile"`.
+	#[default]
+	Escape,
+	/// Fail with a [`CssNameError`] instead.
+	Reject,
+}
+
+/// Returned when a class is not a valid CSS identifier and [`CssNameMode::Reject`] is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CssNameError {
+	/// The offending class.
+	pub class: String,
+}
+
+impl fmt::Display for CssNameError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "`{}` is not a valid CSS identifier", self.class)
+	}
+}
+
+impl std::error::Error for CssNameError {}
+
+const RESERVED_WORDS: &[&str] = &[
+	"await",
+	"break",
+	"case",
+	"catch",
+	"class",
+	"const",
+	"continue",
+	"debugger",
+	"default",
+	"delete",
+	"do",
+	"else",
+	"enum",
+	"export",
+	"extends",
+	"false",
+	"finally",
+	"for",
+	"function",
+	"if",
+	"implements",
+	"import",
+	"in",
+	"instanceof",
+	"interface",
+	"let",
+	"new",
+	"null",
+	"package",
+	"private",
+	"protected",
+	"public",
+	"return",
+	"static",
+	"super",
+	"switch",
+	"this",
+	"throw",
+	"true",
+	"try",
+	"typeof",
+	"var",
+	"void",
+	"while",
+	"with",
+	"yield",
+];
+
+/// Converts a name to the given case. Every character that is not alphanumeric separates
+/// words, so `fast-forward` becomes `fastForward`, `FastForward`, or `fast_forward`. Camel and
+/// Pascal case keep the rest of each word as written, snake case lowercases it.
+pub(crate) fn convert_case(name: &str, case: Case) -> String {
+	let mut converted = String::new();
+	let words = name.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty());
+
+	for (index, word) in words.enumerate() {
+		let mut chars = word.chars();
+		let first = chars.next().expect("empty words are filtered out");
+
+		match case {
+			Case::Camel if index == 0 => {
+				converted.extend(first.to_lowercase());
+				converted.push_str(chars.as_str());
+			}
+			Case::Camel | Case::Pascal => {
+				converted.extend(first.to_uppercase());
+				converted.push_str(chars.as_str());
+			}
+			Case::Snake => {
+				if index > 0 {
+					converted.push('_');
+				}
+				converted.push_str(&word.to_lowercase());
+			}
+		}
+	}
+
+	converted
+}
+
+/// Converts a name into a valid JavaScript identifier in the given case, see [`convert_case`].
+/// Identifiers starting with a digit are prefixed with an underscore, and reserved words get a
+/// trailing underscore.
+pub(crate) fn identifier(name: &str, case: Case) -> String {
+	let mut ident = convert_case(name, case);
+
+	if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+		ident.insert(0, '_');
+	}
+	if RESERVED_WORDS.contains(&ident.as_str()) {
+		ident.push('_');
+	}
+
+	ident
+}
+
+/// Like [`identifier`], with the segments of `name` abbreviated first, see [`AbbreviationMap`].
+pub(crate) fn abbreviated_identifier(name: &str, case: Case, abbreviations: &AbbreviationMap) -> String {
+	identifier(&abbreviations.abbreviate(name), case)
+}
+
+/// Converts a name into a valid SCSS identifier, e.g. for `$variables` or mixin names.
+///
+/// Sass treats `-` and `_` as the same character in identifiers, so underscores and every other
+/// character that is not alphanumeric are written as dashes. Identifiers starting with a digit
+/// are prefixed with an `x`, since Sass identifiers cannot start with one.
+pub(crate) fn scss_identifier(name: &str) -> String {
+	let mut ident: String = name
+		.chars()
+		.map(|c| if c.is_alphanumeric() { c } else { '-' })
+		.collect();
+
+	if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+		ident.insert(0, 'x');
+	}
+
+	ident
+}
+
+/// Escapes a name for use as an identifier in a CSS selector, like `CSS.escape()` in browsers.
+///
+/// Characters that cannot appear in an identifier are escaped with a backslash, and digits at
+/// the start of the name (or after a leading `-`) as well as control characters are written as
+/// hexadecimal escapes. Valid identifiers are returned unchanged, without allocating.
+///
+/// # Arguments
+///
+/// * `name`: &str - The class or name to escape.
+///
+/// # Returns
+///
+/// * `Cow<str>` - The escaped name, borrowed from `name` if nothing had to be escaped.
+///
+/// # Examples
+///
+/// ```
+/// use bem::css_escape;
+///
+/// assert_eq!(css_escape("media-player--dark@mobile"), "media-player--dark\\@mobile");
+/// assert_eq!(css_escape("2x"), "\\32 x");
+/// assert_eq!(css_escape("media-player"), "media-player");
+/// ```
+pub fn css_escape(name: &str) -> Cow<'_, str> {
+	let starts_with_dash = name.starts_with('-');
+	let escape = |index: usize, c: char| -> Option<String> {
+		match c {
+			'\0' => Some('\u{fffd}'.to_string()),
+			'\u{1}'..='\u{1f}' | '\u{7f}' => Some(format!("\\{:x} ", c as u32)),
+			'0'..='9' if index == 0 || (index == 1 && starts_with_dash) => Some(format!("\\{:x} ", c as u32)),
+			'-' if name.len() == 1 => Some("\\-".to_string()),
+			'-' | '_' | '\u{80}'.. => None,
+			c if c.is_ascii_alphanumeric() => None,
+			c => Some(format!("\\{}", c)),
+		}
+	};
+
+	if name.chars().enumerate().all(|(index, c)| escape(index, c).is_none()) {
+		return Cow::Borrowed(name);
+	}
+
+	Cow::Owned(
+		name.chars()
+			.enumerate()
+			.map(|(index, c)| escape(index, c).unwrap_or_else(|| c.to_string()))
+			.collect()
+	)
+}
+
+/// Checks that every class of a block is a valid CSS identifier, returning an error for the
+/// first one that is not, in the order of [`NamingScheme::class_names`].
+///
+/// Stylesheet generators escape such classes, so this is for callers who would rather fail, like
+/// the emitters do with [`CssNameMode::Reject`].
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ check_css_names, parse, BEMBlock };
+///
+/// let bem_block = parse("media-player[dark]").unwrap();
+/// let responsive = BEMBlock { modifiers: vec!["dark@mobile".into()], ..bem_block.clone() };
+///
+/// assert!(check_css_names(&bem_block).is_ok());
+/// assert_eq!(check_css_names(&responsive).unwrap_err().class, "media-player--dark@mobile");
+/// ```
+pub fn check_css_names(bem_block: &BEMBlock) -> Result<(), CssNameError> {
+	for class in NamingScheme::default().class_names(bem_block) {
+		if class.is_empty() || matches!(css_escape(&class), Cow::Owned(_)) {
+			return Err(CssNameError { class });
+		}
+	}
+
+	Ok(())
+}
+
+/// Converts the name of every source with `convert` (usually [`identifier`] or
+/// [`scss_identifier`]), failing if two distinct sources end up with the same identifier.
+/// Sources are `(label, name)` pairs: `name` is converted, while `label` identifies the source
+/// in errors (usually the BEM class it belongs to).
+///
+/// Returns `(label, identifier)` pairs, see [`unique_identifiers`].
+pub(crate) fn identifiers<I, F>(sources: I, convert: F) -> Result<Vec<(String, String)>, IdentifierError>
+	where I: IntoIterator<Item = (String, String)>, F: Fn(&str) -> String
+{
+	unique_identifiers(sources.into_iter().map(|(label, name)| (label, convert(&name))))
+}
+
+/// Checks already generated `(label, identifier)` pairs for collisions, failing if two distinct
+/// labels have the same identifier. Repeated labels are only kept once.
+///
+/// Returns the pairs in the order the labels were first encountered.
+pub(crate) fn unique_identifiers<I>(pairs: I) -> Result<Vec<(String, String)>, IdentifierError>
+	where I: IntoIterator<Item = (String, String)>
+{
+	let mut unique: Vec<(String, String)> = Vec::new();
+
+	for (label, ident) in pairs {
+		if unique.iter().any(|(existing, _)| *existing == label) {
+			continue;
+		}
+
+		let colliding: Vec<String> = unique
+			.iter()
+			.filter(|(_, existing)| *existing == ident)
+			.map(|(existing, _)| existing.clone())
+			.collect();
+
+		if !colliding.is_empty() {
+			let mut sources = colliding;
+			sources.push(label);
+
+			return Err(IdentifierError { identifier: ident, sources });
+		}
+
+		unique.push((label, ident));
+	}
+
+	Ok(unique)
+}
+
+/// Deduplicates already generated `(label, identifier)` pairs like [`unique_identifiers`], but
+/// instead of failing, a label whose identifier is taken by an earlier label gets the first free
+/// numbered one, the number joined with an underscore, e.g. `buttonRewind_2`.
+///
+/// Returns the pairs in the order the labels were first encountered.
+pub(crate) fn numbered_identifiers<I>(pairs: I) -> Vec<(String, String)>
+	where I: IntoIterator<Item = (String, String)>
+{
+	let mut numbered: Vec<(String, String)> = Vec::new();
+
+	for (label, base) in pairs {
+		if numbered.iter().any(|(existing, _)| *existing == label) {
+			continue;
+		}
+
+		let mut ident = base.clone();
+		let mut suffix = 2;
+
+		while numbered.iter().any(|(_, existing)| *existing == ident) {
+			ident = format!("{}_{}", base, suffix);
+			suffix += 1;
+		}
+
+		numbered.push((label, ident));
+	}
+
+	numbered
+}
+
+#[cfg(test)]
+mod tests {
+	use std::borrow::Cow;
+	use super::{ abbreviated_identifier, check_css_names, css_escape, identifier, identifiers, numbered_identifiers, scss_identifier, Case, IdentifierError };
+	use crate::abbreviation::AbbreviationMap;
+	use crate::parse;
+
+	fn camel(name: &str) -> String {
+		identifier(name, Case::Camel)
+	}
+
+	#[test]
+	fn test_identifier_casing() {
+		assert_eq!(identifier("fast-forward", Case::Camel), "fastForward");
+		assert_eq!(identifier("fast-forward", Case::Pascal), "FastForward");
+		assert_eq!(identifier("Fast-Forward", Case::Snake), "fast_forward");
+		assert_eq!(identifier("media-player button", Case::Camel), "mediaPlayerButton");
+	}
+
+	#[test]
+	fn test_abbreviated_identifier_casing() {
+		let abbreviations = AbbreviationMap::from_json("{\"navigation\": \"nav\", \"button\": \"BTN\", \"new\": \"2\"}").unwrap();
+
+		assert_eq!(abbreviated_identifier("navigation-drawer-button", Case::Camel, &abbreviations), "navDrawerBTN");
+		assert_eq!(abbreviated_identifier("navigation-drawer-button", Case::Pascal, &abbreviations), "NavDrawerBTN");
+		assert_eq!(abbreviated_identifier("navigation-drawer-button", Case::Snake, &abbreviations), "nav_drawer_btn");
+		assert_eq!(abbreviated_identifier("new", Case::Camel, &abbreviations), "_2");
+		assert_eq!(abbreviated_identifier("navigational", Case::Camel, &abbreviations), "navigational");
+	}
+
+	#[test]
+	fn test_identifier_sanitization() {
+		assert_eq!(identifier("2x", Case::Camel), "_2x");
+		assert_eq!(identifier("default", Case::Camel), "default_");
+		assert_eq!(identifier("--", Case::Camel), "_");
+	}
+
+	#[test]
+	fn test_scss_identifier() {
+		assert_eq!(scss_identifier("fast-forward"), "fast-forward");
+		assert_eq!(scss_identifier("fast_forward"), "fast-forward");
+		assert_eq!(scss_identifier("2x"), "x2x");
+		assert_eq!(scss_identifier("a:b c"), "a-b-c");
+	}
+
+	#[test]
+	fn test_identifiers_scss_collision() {
+		let result = identifiers(sources(&["fast-forward", "fast_forward"]), scss_identifier);
+
+		assert_eq!(result.unwrap_err().identifier, "fast-forward");
+	}
+
+	fn sources(names: &[&str]) -> Vec<(String, String)> {
+		names
+			.iter()
+			.map(|name| (name.to_string(), name.to_string()))
+			.collect()
+	}
+
+	#[test]
+	fn test_identifiers_skips_repeated_sources() {
+		let result = identifiers(sources(&["dark", "light", "dark"]), camel);
+
+		assert_eq!(result, Ok(vec![
+			("dark".to_string(), "dark".to_string()),
+			("light".to_string(), "light".to_string())
+		]));
+	}
+
+	#[test]
+	fn test_identifiers_collision() {
+		let result = identifiers(sources(&["fast-forward", "rewind", "fast_forward"]), camel);
+		let error = IdentifierError {
+			identifier: "fastForward".to_string(),
+			sources: vec!["fast-forward".to_string(), "fast_forward".to_string()],
+		};
+
+		assert_eq!(result, Err(error.clone()));
+		assert_eq!(
+			error.to_string(),
+			"identifier `fastForward` would be generated for each of `fast-forward`, `fast_forward`"
+		);
+	}
+
+	#[test]
+	fn test_numbered_identifiers() {
+		let pairs = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+			pairs
+				.iter()
+				.map(|(label, ident)| (label.to_string(), ident.to_string()))
+				.collect()
+		};
+		let result = numbered_identifiers(pairs(&[
+			("fast-forward", "fastForward"),
+			("fast_forward", "fastForward"),
+			("fast-forward", "fastForward"),
+			("fast-forward-2", "fastForward_2"),
+		]));
+
+		assert_eq!(result, pairs(&[
+			("fast-forward", "fastForward"),
+			("fast_forward", "fastForward_2"),
+			("fast-forward-2", "fastForward_2_2"),
+		]));
+	}
+
+	#[test]
+	fn test_css_escape() {
+		assert_eq!(css_escape("1st"), "\\31 st");
+		assert_eq!(css_escape("-1st"), "-\\31 st");
+		assert_eq!(css_escape("dark mode"), "dark\\ mode");
+		assert_eq!(css_escape("hover:dark"), "hover\\:dark");
+		assert_eq!(css_escape("-"), "\\-");
+		assert_eq!(css_escape("a\u{7}b\0"), "a\\7 b\u{fffd}");
+	}
+
+	#[test]
+	fn test_css_escape_borrows_valid_names() {
+		for name in ["media-player", "media-player__button--fast-forward", "_private", "--custom", "écran", "a1", ""] {
+			assert!(matches!(css_escape(name), Cow::Borrowed(_)), "{}", name);
+		}
+	}
+
+	#[test]
+	fn test_check_css_names() {
+		let mut bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+
+		assert_eq!(check_css_names(&bem_block), Ok(()));
+
+		bem_block.elements.get_mut("button").unwrap().modifiers.push("2x".into());
+
+		assert_eq!(check_css_names(&bem_block), Ok(()));
+
+		bem_block.elements.get_mut("button").unwrap().modifiers.push("hover:dark".into());
+
+		assert_eq!(
+			check_css_names(&bem_block).unwrap_err().to_string(),
+			"`media-player__button--hover:dark` is not a valid CSS identifier"
+		);
+
+		bem_block.name = "1st-player".to_string();
+
+		assert_eq!(check_css_names(&bem_block).unwrap_err().class, "1st-player");
+	}
+}
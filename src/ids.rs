@@ -0,0 +1,177 @@
+//! This module derives stable identifiers for BEM entities, so records about them (in a database,
+//! an analytics pipeline, ...) survive re-exports and reordering.
+
+use serde::Serialize;
+use sha2::{ Digest, Sha256 };
+use crate::error::Error;
+use crate::models::{ BEMBlock, BEMModifier };
+use crate::path::BEMPath;
+
+/// The number of hex characters kept from the digest of a path.
+const ID_LENGTH: usize = 12;
+
+/// Returns the identifier of the entity `path` points to within `bem_block`.
+///
+/// The identifier is the first 12 hex characters of the SHA-256 digest of the normalized path
+/// (see [`BEMPath`]). It only depends on the names along the path, so it changes when the entity
+/// or one of its parents is renamed, but not when siblings are added, removed or reordered.
+///
+/// # Arguments
+///
+/// * `path`: &BEMPath - The path of the entity, which must exist in `bem_block`.
+/// * `bem_block`: &BEMBlock - The block the entity belongs to.
+///
+/// # Returns
+///
+/// * `String` - The identifier of the entity.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ entity_id, parse, BEMPath };
+///
+/// let bem_block = parse("media-player\nbutton").unwrap();
+/// let id = entity_id(&BEMPath::new("media-player").with_element("button"), &bem_block);
+///
+/// assert_eq!(id.len(), 12);
+/// ```
+pub fn entity_id(path: &BEMPath, bem_block: &BEMBlock) -> String {
+	debug_assert!(path.exists_in(bem_block), "`{}` does not exist in block `{}`", path, bem_block.name);
+
+	Sha256::digest(path.to_string().as_bytes())
+		.iter()
+		.map(|byte| format!("{:02x}", byte))
+		.collect::<String>()[..ID_LENGTH]
+		.to_string()
+}
+
+#[derive(Serialize)]
+struct IdentifiedModifier {
+	id: String,
+	name: String,
+}
+
+#[derive(Serialize)]
+struct IdentifiedElement {
+	id: String,
+	name: String,
+	modifiers: Vec<IdentifiedModifier>,
+}
+
+#[derive(Serialize)]
+struct IdentifiedBlock {
+	id: String,
+	name: String,
+	modifiers: Vec<IdentifiedModifier>,
+	elements: Vec<IdentifiedElement>,
+}
+
+/// Converts a `BEMBlock` into a JSON string where every entity carries its [`entity_id`].
+///
+/// The block and its elements get an `"id"` field, and modifiers are written as objects with an
+/// `"id"` and a `"name"` instead of plain strings. Otherwise the output has the same shape as
+/// [`to_json`](crate::to_json).
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to be converted to JSON.
+///
+/// # Returns
+///
+/// * `Result<String, Error>` - A result containing the JSON string or an [`Error::Serialize`].
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_json_with_ids };
+///
+/// let json = to_json_with_ids(&parse("media-player[dark]").unwrap()).unwrap();
+///
+/// assert!(json.contains("\"modifiers\":[{\"id\":"));
+/// ```
+pub fn to_json_with_ids(bem_block: &BEMBlock) -> Result<String, Error> {
+	let block_path = BEMPath::new(&bem_block.name);
+	let modifiers = |path: &BEMPath, modifiers: &[BEMModifier]| -> Vec<IdentifiedModifier> {
+		modifiers
+			.iter()
+			.map(|modifier| IdentifiedModifier {
+				id: entity_id(&path.clone().with_modifier(&modifier.class_name()), bem_block),
+				name: modifier.to_string(),
+			})
+			.collect()
+	};
+
+	let identified = IdentifiedBlock {
+		id: entity_id(&block_path, bem_block),
+		name: bem_block.name.clone(),
+		modifiers: modifiers(&block_path, &bem_block.modifiers),
+		elements: bem_block.elements
+			.iter()
+			.map(|element| {
+				let element_path = block_path.clone().with_element(&element.name);
+
+				IdentifiedElement {
+					id: entity_id(&element_path, bem_block),
+					name: element.name.clone(),
+					modifiers: modifiers(&element_path, &element.modifiers),
+				}
+			})
+			.collect(),
+	};
+
+	serde_json::to_string(&identified).map_err(Error::Serialize)
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::Value;
+	use super::{ entity_id, to_json_with_ids };
+	use crate::parse;
+	use crate::path::BEMPath;
+
+	const MEDIA_PLAYER: &str = "media-player[dark]\nbutton[fast-forward,rewind]\ntimeline";
+
+	#[test]
+	fn test_entity_id() {
+		let bem_block = parse(MEDIA_PLAYER).unwrap();
+		let block = BEMPath::new("media-player");
+
+		assert_eq!(entity_id(&block, &bem_block), "cf6f3680dc69");
+		assert_eq!(entity_id(&block.clone().with_modifier("dark"), &bem_block), "fd5331b3f3c2");
+		assert_eq!(entity_id(&block.clone().with_element("button"), &bem_block), "603c6e9869d8");
+		assert_eq!(entity_id(&block.with_element("button").with_modifier("rewind"), &bem_block), "ee7e99b28f14");
+	}
+
+	#[test]
+	fn test_to_json_with_ids() {
+		insta::assert_snapshot!(to_json_with_ids(&parse(MEDIA_PLAYER).unwrap()).unwrap());
+	}
+
+	#[test]
+	fn test_ids_ignore_sibling_order() {
+		let original: Value = serde_json::from_str(&to_json_with_ids(&parse(MEDIA_PLAYER).unwrap()).unwrap()).unwrap();
+		let reordered: Value = serde_json::from_str(
+			&to_json_with_ids(&parse("media-player[dark]\ntimeline\nbutton[rewind,fast-forward]").unwrap()).unwrap()
+		).unwrap();
+
+		assert_eq!(original["id"], reordered["id"]);
+		assert_eq!(original["elements"][0]["id"], reordered["elements"][1]["id"]);
+		assert_eq!(original["elements"][0]["modifiers"][0]["id"], reordered["elements"][1]["modifiers"][1]["id"]);
+	}
+
+	#[test]
+	fn test_ids_change_on_rename() {
+		let original: Value = serde_json::from_str(&to_json_with_ids(&parse(MEDIA_PLAYER).unwrap()).unwrap()).unwrap();
+		let renamed: Value = serde_json::from_str(
+			&to_json_with_ids(&parse("media-player[dark]\nbutton[fast-forward,rewind]\nprogress").unwrap()).unwrap()
+		).unwrap();
+		let renamed_block: Value = serde_json::from_str(
+			&to_json_with_ids(&parse("video-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap()).unwrap()
+		).unwrap();
+
+		assert_eq!(original["elements"][0]["id"], renamed["elements"][0]["id"]);
+		assert_ne!(original["elements"][1]["id"], renamed["elements"][1]["id"]);
+		assert_ne!(original["id"], renamed_block["id"]);
+		assert_ne!(original["modifiers"][0]["id"], renamed_block["modifiers"][0]["id"]);
+	}
+}
@@ -0,0 +1,261 @@
+//! This module classifies the changes between two versions of BEM blocks by their semver impact,
+//! so design system releases can be versioned from their structure.
+
+use std::fmt;
+use std::slice;
+use crate::diff::{ diff_documents, BEMDiff };
+use crate::models::BEMBlock;
+
+/// The semver level a structural change calls for. Levels are ordered by severity, so the
+/// impact of several changes is the maximum of their levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ImpactLevel {
+	/// No structural change.
+	Patch,
+	/// A block, element or modifier was added.
+	Minor,
+	/// A block, element or modifier was removed (or renamed).
+	Major,
+}
+
+impl fmt::Display for ImpactLevel {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ImpactLevel::Patch => write!(f, "patch"),
+			ImpactLevel::Minor => write!(f, "minor"),
+			ImpactLevel::Major => write!(f, "major"),
+		}
+	}
+}
+
+/// A structural change along with the impact it has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpactChange {
+	pub level: ImpactLevel,
+	/// What changed, e.g. ``removed element `media-player__button` ``.
+	pub description: String,
+}
+
+impl fmt::Display for ImpactChange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} ({})", self.description, self.level)
+	}
+}
+
+/// The overall impact of the changes between two versions, along with the changes justifying it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpactReport {
+	pub level: ImpactLevel,
+	/// Every structural change: removed blocks first, then added blocks, then the changes within
+	/// blocks present in both versions.
+	pub changes: Vec<ImpactChange>,
+}
+
+/// Classifies the changes between two versions of a block.
+///
+/// Removing a modifier or element is a major change, adding one is a minor change, and anything
+/// else (such as reordering) is a patch. Names are compared exactly, so renaming counts as
+/// removing and adding, which is major. A block whose own name changed is removed as a whole.
+///
+/// # Arguments
+///
+/// * `old`: &BEMBlock - The published version of the block.
+/// * `new`: &BEMBlock - The version about to be published.
+///
+/// # Returns
+///
+/// * `ImpactLevel` - The highest impact among the changes.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, semver_impact, ImpactLevel };
+///
+/// let old = parse("media-player\nbutton").unwrap();
+/// let new = parse("media-player[dark]\nbutton").unwrap();
+///
+/// assert_eq!(semver_impact(&old, &new), ImpactLevel::Minor);
+/// ```
+pub fn semver_impact(old: &BEMBlock, new: &BEMBlock) -> ImpactLevel {
+	semver_document_impact(slice::from_ref(old), slice::from_ref(new)).level
+}
+
+/// Classifies the changes between two versions of a set of blocks, matched by name.
+///
+/// Blocks present in both versions are compared like in [`semver_impact`]. A removed block is a
+/// major change and an added block is a minor change.
+///
+/// # Arguments
+///
+/// * `old`: &[BEMBlock] - The published version of the blocks.
+/// * `new`: &[BEMBlock] - The version about to be published.
+///
+/// # Returns
+///
+/// * `ImpactReport` - The highest impact among the changes, and the changes themselves.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, semver_document_impact, ImpactLevel };
+///
+/// let old = vec![parse("media-player").unwrap(), parse("play-list").unwrap()];
+/// let new = vec![parse("media-player").unwrap()];
+/// let report = semver_document_impact(&old, &new);
+///
+/// assert_eq!(report.level, ImpactLevel::Major);
+/// assert_eq!(report.changes[0].description, "removed block `play-list`");
+/// ```
+pub fn semver_document_impact(old: &[BEMBlock], new: &[BEMBlock]) -> ImpactReport {
+	let diff = diff_documents(old, new);
+	let mut changes = Vec::new();
+
+	for block in &diff.removed_blocks {
+		changes.push(change(ImpactLevel::Major, "removed block", &block.name));
+	}
+	for block in &diff.added_blocks {
+		changes.push(change(ImpactLevel::Minor, "added block", &block.name));
+	}
+	for (block_name, block_diff) in &diff.changed_blocks {
+		block_changes(block_name, block_diff, &mut changes);
+	}
+
+	ImpactReport {
+		level: changes
+			.iter()
+			.map(|change| change.level)
+			.max()
+			.unwrap_or(ImpactLevel::Patch),
+		changes,
+	}
+}
+
+fn block_changes(block_name: &str, diff: &BEMDiff, changes: &mut Vec<ImpactChange>) {
+	for modifier in &diff.removed_modifiers {
+		changes.push(change(ImpactLevel::Major, "removed modifier", &format!("{}--{}", block_name, modifier.class_name())));
+	}
+	for modifier in &diff.added_modifiers {
+		changes.push(change(ImpactLevel::Minor, "added modifier", &format!("{}--{}", block_name, modifier.class_name())));
+	}
+	for element in &diff.removed_elements {
+		changes.push(change(ImpactLevel::Major, "removed element", &format!("{}__{}", block_name, element)));
+	}
+	for element in &diff.added_elements {
+		changes.push(change(ImpactLevel::Minor, "added element", &format!("{}__{}", block_name, element.name)));
+	}
+	for element in &diff.changed_elements {
+		let class = format!("{}__{}", block_name, element.name);
+
+		for modifier in &element.removed_modifiers {
+			changes.push(change(ImpactLevel::Major, "removed modifier", &format!("{}--{}", class, modifier.class_name())));
+		}
+		for modifier in &element.added_modifiers {
+			changes.push(change(ImpactLevel::Minor, "added modifier", &format!("{}--{}", class, modifier.class_name())));
+		}
+	}
+}
+
+fn change(level: ImpactLevel, action: &str, class: &str) -> ImpactChange {
+	ImpactChange { level, description: format!("{} `{}`", action, class) }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ semver_document_impact, semver_impact, ImpactLevel };
+	use crate::parse;
+
+	const MEDIA_PLAYER: &str = "media-player[dark]\nbutton[fast-forward,rewind]\ntimeline";
+
+	#[test]
+	fn test_semver_impact_identical() {
+		let bem_block = parse(MEDIA_PLAYER).unwrap();
+
+		assert_eq!(semver_impact(&bem_block, &bem_block.clone()), ImpactLevel::Patch);
+	}
+
+	#[test]
+	fn test_semver_impact_reordered() {
+		let old = parse(MEDIA_PLAYER).unwrap();
+		let new = parse("media-player[dark]\ntimeline\nbutton[rewind,fast-forward]").unwrap();
+
+		assert_eq!(semver_impact(&old, &new), ImpactLevel::Patch);
+	}
+
+	#[test]
+	fn test_semver_impact_added() {
+		let old = parse(MEDIA_PLAYER).unwrap();
+
+		for new in [
+			"media-player[dark,light]\nbutton[fast-forward,rewind]\ntimeline",
+			"media-player[dark]\nbutton[fast-forward,rewind,play]\ntimeline",
+			"media-player[dark]\nbutton[fast-forward,rewind]\ntimeline\nvolume",
+		] {
+			assert_eq!(semver_impact(&old, &parse(new).unwrap()), ImpactLevel::Minor, "{}", new);
+		}
+	}
+
+	#[test]
+	fn test_semver_impact_removed() {
+		let old = parse(MEDIA_PLAYER).unwrap();
+
+		for new in [
+			"media-player\nbutton[fast-forward,rewind]\ntimeline",
+			"media-player[dark]\nbutton[rewind]\ntimeline",
+			"media-player[dark]\nbutton[fast-forward,rewind]",
+		] {
+			assert_eq!(semver_impact(&old, &parse(new).unwrap()), ImpactLevel::Major, "{}", new);
+		}
+	}
+
+	#[test]
+	fn test_semver_impact_renamed() {
+		let old = parse(MEDIA_PLAYER).unwrap();
+
+		for new in [
+			"media-player[darker]\nbutton[fast-forward,rewind]\ntimeline",
+			"media-player[dark]\nbutton[fast-forward,rewind]\nprogress",
+			"video-player[dark]\nbutton[fast-forward,rewind]\ntimeline",
+		] {
+			assert_eq!(semver_impact(&old, &parse(new).unwrap()), ImpactLevel::Major, "{}", new);
+		}
+	}
+
+	#[test]
+	fn test_semver_document_impact() {
+		let old = vec![parse(MEDIA_PLAYER).unwrap(), parse("play-list[compact]").unwrap()];
+		let new = vec![
+			parse("media-player[dark]\nbutton[rewind,play]\ntimeline").unwrap(),
+			parse("volume-slider").unwrap(),
+		];
+		let report = semver_document_impact(&old, &new);
+		let changes: Vec<String> = report.changes
+			.iter()
+			.map(|change| change.to_string())
+			.collect();
+
+		assert_eq!(report.level, ImpactLevel::Major);
+		assert_eq!(changes, vec![
+			"removed block `play-list` (major)",
+			"added block `volume-slider` (minor)",
+			"removed modifier `media-player__button--fast-forward` (major)",
+			"added modifier `media-player__button--play` (minor)",
+		]);
+	}
+
+	#[test]
+	fn test_semver_document_impact_added_block() {
+		let old = vec![parse(MEDIA_PLAYER).unwrap()];
+		let new = vec![parse(MEDIA_PLAYER).unwrap(), parse("play-list").unwrap()];
+
+		assert_eq!(semver_document_impact(&old, &new).level, ImpactLevel::Minor);
+	}
+
+	#[test]
+	fn test_semver_document_impact_identical() {
+		let blocks = vec![parse(MEDIA_PLAYER).unwrap(), parse("play-list").unwrap()];
+		let report = semver_document_impact(&blocks, &blocks);
+
+		assert_eq!(report.level, ImpactLevel::Patch);
+		assert!(report.changes.is_empty());
+	}
+}
@@ -0,0 +1,468 @@
+//! This module extracts BEM class names from JavaScript and TypeScript source code. It is not
+//! a JavaScript parser: it only understands enough of the lexical structure (string literals,
+//! comments, and parentheses) to find class name strings reliably.
+
+use std::ops::Range;
+use crate::classes::from_classes;
+use crate::diagnostic::{ Diagnostic, Severity };
+use crate::error::{ BEMParseError, Error };
+use crate::models::BEMBlock;
+
+/// The functions whose string arguments are treated as class names.
+const CLASS_NAME_FUNCTIONS: &[&str] = &["clsx", "classnames", "classNames", "cx"];
+
+/// A string literal found in source code.
+#[derive(Debug, PartialEq)]
+pub(crate) struct StringLiteral {
+	/// The byte range of the literal in the source, including its quotes.
+	pub span: Range<usize>,
+	/// The unescaped contents of the literal.
+	pub value: String,
+	/// Whether the literal is a template literal containing `${...}` interpolation, in which
+	/// case `value` is the raw text between the backticks.
+	pub interpolated: bool,
+	/// Whether the literal is closed before the end of the source.
+	pub terminated: bool,
+}
+
+/// Returns every string literal in `source`, in order. Comments are skipped.
+pub(crate) fn string_literals(source: &str) -> Vec<StringLiteral> {
+	let mut literals = Vec::new();
+	let mut index = 0;
+
+	while let Some(c) = source[index..].chars().next() {
+		match c {
+			'"' | '\'' | '`' => {
+				let (literal, end) = read_string_literal(source, index);
+				literals.push(literal);
+				index = end;
+			}
+			'/' if source[index..].starts_with("//") => {
+				index = source[index..].find('\n').map_or(source.len(), |offset| index + offset);
+			}
+			'/' if source[index..].starts_with("/*") => {
+				index = source[index + 2..].find("*/").map_or(source.len(), |offset| index + 2 + offset + 2);
+			}
+			_ => {
+				index += c.len_utf8();
+			}
+		}
+	}
+
+	literals
+}
+
+/// Reads the string literal starting at `start` (which must be a quote character) and returns
+/// it together with the byte offset just past its closing quote.
+fn read_string_literal(source: &str, start: usize) -> (StringLiteral, usize) {
+	let mut chars = source[start..].char_indices();
+	let (_, quote) = chars.next().expect("a string literal starts with a quote");
+	let mut value = String::new();
+	let mut interpolated = false;
+	let literal = |value, interpolated, terminated, end| (StringLiteral { span: start..end, value, interpolated, terminated }, end);
+
+	while let Some((offset, c)) = chars.next() {
+		match c {
+			'\\' => {
+				if let Some((_, escaped)) = chars.next() {
+					value.push(match escaped {
+						'n' => '\n',
+						't' => '\t',
+						'r' => '\r',
+						other => other,
+					});
+				}
+			}
+			'$' if quote == '`' && source[start + offset..].starts_with("${") => {
+				interpolated = true;
+				value.push(c);
+			}
+			c if c == quote => {
+				return literal(value, interpolated, true, start + offset + c.len_utf8());
+			}
+			c => value.push(c),
+		}
+	}
+
+	literal(value, interpolated, false, source.len())
+}
+
+/// Returns the argument text of every `clsx`, `classnames`, `classNames`, or `cx` call in
+/// `source`, without the surrounding parentheses.
+pub(crate) fn class_name_calls(source: &str) -> Vec<&str> {
+	let mut calls = Vec::new();
+
+	for function in CLASS_NAME_FUNCTIONS {
+		for (start, _) in source.match_indices(function) {
+			let preceded_by_identifier = source[..start]
+				.chars()
+				.next_back()
+				.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$' || c == '.');
+			let rest = source[start + function.len()..].trim_start();
+
+			if preceded_by_identifier || !rest.starts_with('(') {
+				continue;
+			}
+
+			let arguments_start = source.len() - rest.len() + 1;
+
+			if let Some(arguments_end) = closing_parenthesis(source, arguments_start) {
+				calls.push((arguments_start, &source[arguments_start..arguments_end]));
+			}
+		}
+	}
+
+	calls.sort_by_key(|(start, _)| *start);
+	calls
+		.into_iter()
+		.map(|(_, arguments)| arguments)
+		.collect()
+}
+
+/// Returns the string literals of a call's arguments that denote class names: bare arguments
+/// and object keys. Literals compared against something (`mode === "rewind"`) are values, not
+/// class names, and are left out.
+pub(crate) fn class_name_literals(arguments: &str) -> Vec<StringLiteral> {
+	string_literals(arguments)
+		.into_iter()
+		.filter(|literal| !is_compared(arguments, &literal.span))
+		.collect()
+}
+
+/// Returns whether the literal at `span` of `source` is compared against something, like
+/// `"rewind"` in `mode === "rewind"`, or assigned.
+fn is_compared(source: &str, span: &Range<usize>) -> bool {
+	let before = source[..span.start].trim_end();
+	let after = source[span.end..].trim_start();
+
+	is_comparison(source, span) || before.ends_with('=') || (after.starts_with('=') && !after.starts_with("=>"))
+}
+
+/// Returns whether the literal at `span` of `source` is an operand of `==`, `===`, `!=`, or `!==`.
+fn is_comparison(source: &str, span: &Range<usize>) -> bool {
+	let before = source[..span.start].trim_end();
+	let after = source[span.end..].trim_start();
+
+	before.ends_with("==") || before.ends_with("!=") || after.starts_with("==") || after.starts_with("!=")
+}
+
+/// Returns whether the literal at `span` of `source` is a member of a TypeScript union type,
+/// like `"small"` in `type Size = "small" | "large"`.
+fn is_union_member(source: &str, span: &Range<usize>) -> bool {
+	let before = source[..span.start].trim_end();
+	let after = source[span.end..].trim_start();
+
+	(before.ends_with('|') && !before.ends_with("||")) || (after.starts_with('|') && !after.starts_with("||"))
+}
+
+/// Returns whether the literal at `span` of `source` names a module, like `"react"` in
+/// `import React from "react"` or `require("react")`.
+fn is_module_specifier(source: &str, span: &Range<usize>) -> bool {
+	let before = source[..span.start].trim_end();
+
+	["from", "import", "require(", "import("]
+		.iter()
+		.any(|keyword| before.ends_with(keyword))
+}
+
+/// Finds the parenthesis closing the one just before `start`, skipping string literals.
+fn closing_parenthesis(source: &str, start: usize) -> Option<usize> {
+	let mut depth = 1;
+	let mut index = start;
+
+	while let Some(c) = source[index..].chars().next() {
+		match c {
+			'"' | '\'' | '`' => {
+				index = read_string_literal(source, index).1;
+				continue;
+			}
+			'(' => {
+				depth += 1;
+			}
+			')' => {
+				depth -= 1;
+
+				if depth == 0 {
+					return Some(index);
+				}
+			}
+			_ => {}
+		}
+
+		index += c.len_utf8();
+	}
+
+	None
+}
+
+impl BEMBlock {
+	/// Infers a BEM block from the `clsx` or `classnames` calls of a React component.
+	///
+	/// Every string literal passed to a `clsx`, `classnames`, `classNames`, or `cx` call is
+	/// treated as a whitespace-separated list of class names, including string keys of object
+	/// arguments such as `{ "media-player--dark": props.dark }`. Literals that are compared
+	/// against something, like `"rewind"` in `props.mode === "rewind"`, are skipped. The BEM
+	/// classes among them are grouped like [`from_classes`](crate::from_classes) does. Unquoted
+	/// object keys and template literals with interpolation cannot be resolved and are ignored.
+	///
+	/// # Arguments
+	///
+	/// * `source`: &str - The JavaScript or TypeScript source to inspect.
+	///
+	/// # Returns
+	///
+	/// * `Result<BEMBlock, Error>` - The inferred block, or an [`Error::Parse`] spanning the whole
+	///   source if the calls do not describe exactly one BEM block.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::BEMBlock;
+	///
+	/// let source = r#"<div className={clsx("media-player", { "media-player--dark": props.dark })} />"#;
+	/// let bem_block = BEMBlock::from_bem_react_classnames(source).unwrap();
+	///
+	/// assert_eq!(bem_block.name, "media-player");
+	/// assert_eq!(bem_block.modifiers, vec!["dark".to_string()]);
+	/// ```
+	pub fn from_bem_react_classnames(source: &str) -> Result<BEMBlock, Error> {
+		let calls = class_name_calls(source);
+
+		if calls.is_empty() {
+			return Err(inference_error(source, "no `clsx` or `classnames` call found".to_string()));
+		}
+
+		let classes: Vec<String> = calls
+			.into_iter()
+			.flat_map(class_name_literals)
+			.filter(|literal| !literal.interpolated)
+			.flat_map(|literal| {
+				literal.value
+					.split_whitespace()
+					.map(str::to_string)
+					.collect::<Vec<_>>()
+			})
+			.collect();
+		let mut blocks = from_classes(classes);
+
+		match blocks.len() {
+			0 => Err(inference_error(source, "no BEM class names found in `clsx` or `classnames` calls".to_string())),
+			1 => Ok(blocks.remove(0)),
+			_ => {
+				let names: Vec<String> = blocks
+					.into_iter()
+					.map(|block| block.name)
+					.collect();
+
+				Err(inference_error(source, format!("class names belong to more than one block: {}", names.join(", "))))
+			}
+		}
+	}
+}
+
+/// The blocks found in JavaScript or TypeScript source by [`scan_js`], and what could not be
+/// resolved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsScan {
+	/// The reconstructed blocks, in the order their first class appears in the source.
+	pub blocks: Vec<BEMBlock>,
+	/// An `unresolved-template` warning for every template literal with interpolation, whose
+	/// classes cannot be known without running the code.
+	pub warnings: Vec<Diagnostic>,
+}
+
+/// Finds the BEM classes written in the string literals of JavaScript or TypeScript source, to
+/// bootstrap `.bem` files from existing components.
+///
+/// Every string literal, whether single-quoted, double-quoted, or a template literal without
+/// interpolation, is split on whitespace, and the tokens that are BEM classes are grouped into
+/// blocks like [`from_classes`](crate::from_classes) does. This covers `className` attributes as
+/// well as `clsx` or `classnames` calls. Literals in comments, module specifiers like
+/// `from "react"`, members of union types, and literals compared against something, like
+/// `"rewind"` in `mode === "rewind"`, are skipped. Any other literal that happens to be a valid
+/// block name is reported as a block, so review the result before saving it.
+///
+/// # Arguments
+///
+/// * `source`: &str - The JavaScript or TypeScript source to scan.
+///
+/// # Returns
+///
+/// * `Result<JsScan, Error>` - The blocks and warnings, or an [`Error::Parse`] pointing at a string
+///   literal that is never closed.
+///
+/// # Examples
+///
+/// ```
+/// use bem::scan_js;
+///
+/// let source = r#"<div className={clsx("media-player media-player--dark", `media-player--${size}`)} />"#;
+/// let scan = scan_js(source).unwrap();
+///
+/// assert_eq!(scan.blocks, vec![bem::parse("media-player[dark]").unwrap()]);
+/// assert_eq!(scan.warnings[0].code, "unresolved-template");
+/// ```
+pub fn scan_js(source: &str) -> Result<JsScan, Error> {
+	let (classes, warnings) = js_classes(source)?;
+
+	Ok(JsScan { blocks: from_classes(classes), warnings })
+}
+
+/// Returns the tokens of the string literals [`scan_js`] considers, in source order and with
+/// repetitions, and its warnings.
+pub(crate) fn js_classes(source: &str) -> Result<(Vec<String>, Vec<Diagnostic>), Error> {
+	let mut classes = Vec::new();
+	let mut warnings = Vec::new();
+
+	for literal in string_literals(source) {
+		if !literal.terminated {
+			return Err(Error::Parse(BEMParseError::at(source, literal.span, "unterminated string literal".to_string())));
+		}
+		if is_comparison(source, &literal.span) || is_union_member(source, &literal.span) || is_module_specifier(source, &literal.span) {
+			continue;
+		}
+		if literal.interpolated {
+			warnings.push(Diagnostic::at(
+				source,
+				Severity::Warning,
+				"unresolved-template",
+				literal.span,
+				"template literal with interpolation cannot be resolved".to_string(),
+			));
+			continue;
+		}
+
+		classes.extend(literal.value.split_whitespace().map(str::to_string));
+	}
+
+	Ok((classes, warnings))
+}
+
+/// Reports that `source` as a whole does not describe a BEM block.
+fn inference_error(source: &str, message: String) -> Error {
+	Error::Parse(BEMParseError {
+		message,
+		span: 0..source.len(),
+		line: 1,
+		column: 1,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ class_name_calls, class_name_literals, string_literals, StringLiteral };
+	use crate::models::BEMBlock;
+	use crate::parse;
+
+	const MEDIA_PLAYER_COMPONENT: &str =
+		r#"
+import clsx from "clsx";
+
+// The root class is always present, `dark` is optional.
+export function MediaPlayer(props: MediaPlayerProps) {
+	return (
+		<div className={clsx("media-player", { "media-player--dark": props.dark })}>
+			<button
+				className={clsx('media-player__button', {
+					'media-player__button--fast-forward': props.mode === "fast-forward",
+					'media-player__button--rewind': props.mode === "rewind",
+				})}
+			/>
+			<div className={clsx(`media-player__timeline`, props.className)} />
+		</div>
+	);
+}
+"#;
+
+	#[test]
+	fn test_string_literals() {
+		let literals = string_literals(r#"a("x y", 'it\'s', `t`, `${u}`) // "comment" "#);
+
+		assert_eq!(literals, vec![
+			StringLiteral { span: 2..7, value: "x y".to_string(), interpolated: false, terminated: true },
+			StringLiteral { span: 9..16, value: "it's".to_string(), interpolated: false, terminated: true },
+			StringLiteral { span: 18..21, value: "t".to_string(), interpolated: false, terminated: true },
+			StringLiteral { span: 23..29, value: "${u}".to_string(), interpolated: true, terminated: true }
+		]);
+		assert!(!string_literals("a(\"x)")[0].terminated);
+	}
+
+	#[test]
+	fn test_class_name_literals_skips_compared_values() {
+		let values: Vec<String> = class_name_literals(r#""a", { "b": mode === "c", "d": "e" != mode }"#)
+			.into_iter()
+			.map(|literal| literal.value)
+			.collect();
+
+		assert_eq!(values, vec!["a".to_string(), "b".to_string(), "d".to_string()]);
+	}
+
+	#[test]
+	fn test_class_name_calls() {
+		let calls = class_name_calls(r#"clsx("a", f(")"), "b"); classnames ("c"); myclsx("d"); utils.cx("e")"#);
+
+		assert_eq!(calls, vec![r#""a", f(")"), "b""#, r#""c""#]);
+	}
+
+	#[test]
+	fn test_from_bem_react_classnames() {
+		let result = BEMBlock::from_bem_react_classnames(MEDIA_PLAYER_COMPONENT);
+
+		assert_eq!(result.unwrap(), parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap());
+	}
+
+	#[test]
+	fn test_from_bem_react_classnames_non_bem_call() {
+		let result = BEMBlock::from_bem_react_classnames(
+			r#"<button className={clsx("Button", "Button-primary", { active: isActive })} />"#
+		);
+
+		assert_eq!(
+			result.unwrap_err().to_string(),
+			"parse error at line 1, column 1: no BEM class names found in `clsx` or `classnames` calls"
+		);
+	}
+
+	#[test]
+	fn test_from_bem_react_classnames_multiple_blocks() {
+		let result = BEMBlock::from_bem_react_classnames(r#"clsx("btn", "btn-primary")"#);
+
+		assert_eq!(
+			result.unwrap_err().to_string(),
+			"parse error at line 1, column 1: class names belong to more than one block: btn, btn-primary"
+		);
+	}
+
+	#[test]
+	fn test_from_bem_react_classnames_without_calls() {
+		let result = BEMBlock::from_bem_react_classnames(r#"<div className="media-player" />"#);
+
+		assert_eq!(
+			result.unwrap_err().to_string(),
+			"parse error at line 1, column 1: no `clsx` or `classnames` call found"
+		);
+	}
+
+	#[test]
+	fn test_scan_js() {
+		let source = std::fs::read_to_string("fixtures/js/MediaPlayer.tsx").unwrap();
+		let scan = super::scan_js(&source).unwrap();
+		let warning = &scan.warnings[0];
+
+		assert_eq!(scan.blocks, vec![
+			parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline[live]\nvolume[muted]").unwrap(),
+			parse("play-list[compact]").unwrap(),
+		]);
+		assert_eq!(scan.warnings.len(), 1);
+		assert_eq!((warning.line, warning.column), (19, 20));
+		assert_eq!(warning.to_string(), "warning[unresolved-template]: line 19, column 20: template literal with interpolation cannot be resolved");
+	}
+
+	#[test]
+	fn test_scan_js_unterminated_literal() {
+		let error = super::scan_js("const a = \"media-player;\nconst b = 'c';").unwrap_err();
+
+		assert_eq!(error.to_string(), "parse error at line 1, column 11: unterminated string literal");
+		assert_eq!(super::scan_js("").unwrap(), super::JsScan::default());
+	}
+}
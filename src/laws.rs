@@ -0,0 +1,163 @@
+//! Property tests for the round-trip laws documented on [`parse`], [`to_bem`], [`to_json`], and
+//! [`from_json`]. Blocks and their `.bem` sources are generated by proptest strategies following
+//! the grammar, so failing cases are shrunk to a minimal block or source.
+
+use proptest::prelude::*;
+use crate::models::{ BEMBlock, BEMElement, BEMModifier };
+use crate::{ from_json, parse, to_bem, to_json };
+
+/// The number of cases checked by each law.
+const CASES: u32 = 512;
+
+/// Generates names following the grammar, `[a-z]("-"?[a-z0-9])*`.
+fn name() -> impl Strategy<Value = String> {
+	"[a-z](-?[a-z0-9]){0,7}"
+}
+
+/// Generates modifiers, a quarter of them with a value.
+fn modifiers() -> impl Strategy<Value = Vec<BEMModifier>> {
+	prop::collection::vec((name(), prop::option::weighted(0.25, name())), 0..4).prop_map(|modifiers| modifiers
+		.into_iter()
+		.map(|(name, value)| match value {
+			Some(value) => BEMModifier::new(name).with_value(value),
+			None => BEMModifier::new(name),
+		})
+		.collect())
+}
+
+/// Generates blocks, with possibly repeated names and modifiers.
+fn block() -> impl Strategy<Value = BEMBlock> {
+	(name(), modifiers(), prop::collection::vec((name(), modifiers()), 0..5)).prop_map(|(name, modifiers, elements)| BEMBlock {
+		name,
+		modifiers,
+		elements: elements
+			.into_iter()
+			.map(|(name, modifiers)| BEMElement { name, modifiers })
+			.collect(),
+	})
+}
+
+/// Generates whitespace allowed inside a modifier list.
+fn whitespace(newline: &'static str) -> impl Strategy<Value = String> {
+	prop::collection::vec(prop_oneof![Just(" "), Just("\t"), Just(newline)], 0..3).prop_map(|whitespace| whitespace.concat())
+}
+
+/// Writes a modifier list with random whitespace, trailing commas, and empty brackets.
+fn modifier_list(modifiers: Vec<BEMModifier>, newline: &'static str) -> BoxedStrategy<String> {
+	if modifiers.is_empty() {
+		return prop_oneof![
+			1 => whitespace(newline).prop_map(|whitespace| format!("[{}]", whitespace)),
+			2 => Just(String::new()),
+		].boxed();
+	}
+
+	let count = modifiers.len();
+
+	(
+		whitespace(newline),
+		prop::collection::vec((whitespace(newline), whitespace(newline)), count),
+		prop::option::of(whitespace(newline)),
+	)
+		.prop_map(move |(opening, separators, trailing_comma)| {
+			let mut list = format!("[{}", opening);
+
+			for (index, (modifier, (before, after))) in modifiers.iter().zip(separators).enumerate() {
+				if index > 0 {
+					list.push(',');
+					list.push_str(&before);
+				}
+				list.push_str(&modifier.to_string());
+				list.push_str(&after);
+			}
+
+			if let Some(whitespace) = trailing_comma {
+				list.push(',');
+				list.push_str(&whitespace);
+			}
+
+			list.push(']');
+			list
+		})
+		.boxed()
+}
+
+/// Generates blocks with a random but valid formatting of their `.bem` source, with an optional
+/// modifier set standing for the modifiers of the block if none of them has a value.
+fn block_with_source() -> impl Strategy<Value = (BEMBlock, String)> {
+	(block(), prop_oneof![Just("\n"), Just("\r\n")]).prop_flat_map(|(bem_block, newline)| {
+		let element_lists: Vec<BoxedStrategy<String>> = bem_block.elements
+			.iter()
+			.map(|element| modifier_list(element.modifiers.clone(), newline))
+			.collect();
+
+		(
+			modifier_list(bem_block.modifiers.clone(), newline),
+			element_lists,
+			prop::bool::weighted(1.0 / 3.0),
+			1..3usize,
+			0..3usize,
+			Just(bem_block),
+		)
+			.prop_map(move |(mut block_modifiers, element_lists, modifier_set, set_newlines, trailing_newlines, bem_block)| {
+				let mut source = String::new();
+
+				if modifier_set && !bem_block.modifiers.is_empty() && bem_block.modifiers.iter().all(|modifier| modifier.value.is_none()) {
+					let names: Vec<&str> = bem_block.modifiers.iter().map(|modifier| modifier.name.as_str()).collect();
+
+					source.push_str(&format!("@set  state = {}{}", names.join(" ,"), newline.repeat(set_newlines)));
+					block_modifiers = "[@state]".to_string();
+				}
+
+				source.push_str(&bem_block.name);
+				source.push_str(&block_modifiers);
+
+				for (element, modifier_list) in bem_block.elements.iter().zip(element_lists) {
+					source.push_str(newline);
+					source.push_str(&element.name);
+					source.push_str(&modifier_list);
+				}
+
+				source.push_str(&newline.repeat(trailing_newlines));
+				(bem_block, source)
+			})
+	})
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(CASES))]
+
+	#[test]
+	fn test_parse_inverts_to_bem(bem_block in block()) {
+		let bem_input = to_bem(&bem_block);
+
+		prop_assert_eq!(parse(&bem_input).ok(), Some(bem_block), "{:?}", bem_input);
+	}
+
+	#[test]
+	fn test_parse_inverts_display(bem_block in block()) {
+		let bem_input = bem_block.to_string();
+		let lines: Vec<&str> = bem_input.lines().skip(1).collect();
+
+		prop_assert_eq!(lines, bem_block.elements.iter().map(ToString::to_string).collect::<Vec<_>>());
+		prop_assert_eq!(parse(&bem_input).ok(), Some(bem_block), "{:?}", bem_input);
+	}
+
+	#[test]
+	fn test_from_json_inverts_to_json(bem_block in block()) {
+		let json = to_json(&bem_block).unwrap();
+
+		prop_assert_eq!(from_json(&json).unwrap(), bem_block);
+	}
+
+	#[test]
+	fn test_parse_ignores_formatting((bem_block, bem_input) in block_with_source()) {
+		prop_assert_eq!(parse(&bem_input).ok(), Some(bem_block), "{:?}", bem_input);
+	}
+
+	#[test]
+	fn test_to_bem_is_a_normal_form((_, bem_input) in block_with_source()) {
+		let normal = to_bem(&parse(&bem_input).unwrap());
+
+		prop_assert_eq!(to_bem(&parse(&normal).unwrap()), normal, "{:?}", bem_input);
+	}
+}
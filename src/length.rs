@@ -0,0 +1,144 @@
+//! This module measures names for length rules, in bytes, characters, or grapheme clusters, and
+//! shortens long names for display without cutting a character or grapheme cluster in half.
+
+use std::borrow::Cow;
+use serde::{ Deserialize, Serialize };
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How the length of a name is counted by length rules, such as
+/// [`ValidationOptions::max_name_length`](crate::ValidationOptions::max_name_length) and
+/// [`ClassBudget`](crate::ClassBudget).
+///
+/// Grapheme clusters are what readers perceive as one character: `é` written as `e` and a
+/// combining accent, a flag made of two regional indicators, or a Hangul syllable written as
+/// several jamo, count once. Clusters are the extended grapheme clusters of UAX #29.
+///
+/// # Example
+///
+/// ```
+/// use bem::LengthMode;
+///
+/// let name = "cafe\u{301}";
+///
+/// assert_eq!(LengthMode::Bytes.len(name), 6);
+/// assert_eq!(LengthMode::Chars.len(name), 5);
+/// assert_eq!(LengthMode::Graphemes.len(name), 4);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LengthMode {
+	/// UTF-8 bytes, for targets storing names in fixed-size buffers.
+	Bytes,
+	/// Unicode scalar values.
+	#[default]
+	Chars,
+	/// Grapheme clusters, as perceived by readers.
+	Graphemes,
+}
+
+impl LengthMode {
+	/// Returns the length of `text` counted in this mode.
+	pub fn len(&self, text: &str) -> usize {
+		match self {
+			LengthMode::Bytes => text.len(),
+			LengthMode::Chars => text.chars().count(),
+			LengthMode::Graphemes => text.graphemes(true).count(),
+		}
+	}
+
+	/// Returns the unit of this mode, as used in messages.
+	pub fn unit(&self) -> &'static str {
+		match self {
+			LengthMode::Bytes => "bytes",
+			LengthMode::Chars => "characters",
+			LengthMode::Graphemes => "grapheme clusters",
+		}
+	}
+
+	/// Returns the longest start of `text`, in whole grapheme clusters, at most `max` long in
+	/// this mode.
+	pub(crate) fn prefix<'a>(&self, text: &'a str, max: usize) -> &'a str {
+		let mut end = 0;
+		let mut len = 0;
+
+		for grapheme in text.graphemes(true) {
+			len += self.len(grapheme);
+
+			if len > max {
+				break;
+			}
+
+			end += grapheme.len();
+		}
+
+		&text[..end]
+	}
+}
+
+/// The number of grapheme clusters of a name shown in messages before it is abbreviated.
+pub(crate) const DISPLAY_LEN: usize = 48;
+
+/// Shortens `text` to its first `max` grapheme clusters followed by `…`, for messages. Text that
+/// is short enough is returned as is.
+pub(crate) fn abbreviate_for_display(text: &str, max: usize) -> Cow<'_, str> {
+	match text.graphemes(true).nth(max) {
+		Some(_) => Cow::Owned(format!("{}…", LengthMode::Graphemes.prefix(text, max))),
+		None => Cow::Borrowed(text),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ abbreviate_for_display, LengthMode };
+
+	/// `e` with a combining acute accent and a combining grave accent.
+	const STACKED: &str = "e\u{301}\u{300}";
+	/// A woman technologist with a skin tone modifier: woman, modifier, ZWJ, laptop.
+	const TECHNOLOGIST: &str = "\u{1F469}\u{1F3FD}\u{200D}\u{1F4BB}";
+	/// The flag of France, two regional indicators.
+	const FLAG: &str = "\u{1F1EB}\u{1F1F7}";
+	/// The Hangul syllable `한` written as three conjoining jamo.
+	const HANGUL: &str = "\u{1112}\u{1161}\u{11AB}";
+	/// The Devanagari syllable `कि`, a consonant and a dependent vowel sign.
+	const DEVANAGARI: &str = "\u{0915}\u{093F}";
+
+	#[test]
+	fn test_graphemes() {
+		let text = format!("a{}{}{}{}\r\nb\u{2764}\u{FE0F}{}{}", STACKED, TECHNOLOGIST, FLAG, FLAG, HANGUL, DEVANAGARI);
+
+		assert_eq!(LengthMode::Graphemes.len(&text), 10);
+		assert_eq!(LengthMode::Graphemes.prefix(&text, 9), text.strip_suffix(DEVANAGARI).unwrap());
+		assert_eq!(LengthMode::Graphemes.prefix(HANGUL, 0), "");
+		assert_eq!(LengthMode::Graphemes.len(HANGUL), 1);
+		assert_eq!(LengthMode::Graphemes.len(""), 0);
+	}
+
+	#[test]
+	fn test_len_in_every_mode() {
+		let name = format!("btn-{}{}", STACKED, TECHNOLOGIST);
+
+		assert_eq!(LengthMode::Bytes.len(&name), 4 + 5 + 15);
+		assert_eq!(LengthMode::Chars.len(&name), 4 + 3 + 4);
+		assert_eq!(LengthMode::Graphemes.len(&name), 4 + 1 + 1);
+		assert_eq!(LengthMode::default(), LengthMode::Chars);
+	}
+
+	#[test]
+	fn test_prefix_keeps_whole_clusters() {
+		let name = format!("ab{}{}c", STACKED, TECHNOLOGIST);
+
+		assert_eq!(LengthMode::Chars.prefix(&name, 4), "ab");
+		assert_eq!(LengthMode::Chars.prefix(&name, 5), format!("ab{}", STACKED));
+		assert_eq!(LengthMode::Bytes.prefix(&name, 8), format!("ab{}", STACKED));
+		assert_eq!(LengthMode::Graphemes.prefix(&name, 4), format!("ab{}{}", STACKED, TECHNOLOGIST));
+	}
+
+	#[test]
+	fn test_abbreviate_for_display() {
+		let name = format!("{}{}{}", FLAG, STACKED, TECHNOLOGIST);
+
+		assert_eq!(abbreviate_for_display(&name, 3), name);
+		assert_eq!(abbreviate_for_display(&name, 2), format!("{}{}…", FLAG, STACKED));
+		assert_eq!(abbreviate_for_display(&name, 1), format!("{}…", FLAG));
+	}
+}
@@ -17,24 +17,168 @@
 //! ```
 //! use bem::{BEMBlock, to_json, from_json};
 //!
-//! let bem_block = BEMBlock { name: "media-player".to_string(), modifiers: vec![], elements: vec![] };
+//! let bem_block = BEMBlock::new("media-player");
 //! let json = to_json(&bem_block).unwrap();
 //! let bem_block_from_json = from_json(&json).unwrap();
 //! ```
 //!
 //! Please see the individual function and structure documentation for detailed information and examples.
+//!
+//! # Thread safety
+//!
+//! Every public type is `Send + Sync`, and the only interior mutability, the name index that
+//! [`Elements`] rebuilds on the first read after [`Elements::iter_mut`], is a `OnceLock`, so a parsed document can be read from any
+//! number of threads at once. To share one without cloning it,
+//! wrap it in a [`SharedDocument`] with [`BEMDocument::into_shared`]: the handle is an `Arc`
+//! that is cheap to clone and dereferences to the document. Emitters are `Send + Sync` too, so
+//! an [`EmitterRegistry`] can be shared the same way.
 
-pub use models::{ BEMBlock, BEMElement };
-pub use parser::parse;
+pub use a11y::A11yPropsConfig;
+pub use abbreviation::{ AbbreviationError, AbbreviationMap };
+pub use attributes::AttributeScheme;
+pub use audit::DesignAudit;
+pub use budget::{ BudgetError, ClassBudget };
+pub use builder::ClassBuilder;
+pub use changelog::{ changelog, ChangelogOptions };
+pub use classes::{ from_classes, from_classes_with_counts, UsageCounts };
+pub use codegen::{ generate_css_skeleton, to_css_modules, to_css_modules_dts, to_css_nested, to_css_nested_with_attributes, to_css_nested_with_config, to_css_split, to_scss, to_scss_mixins, to_scss_placeholders, SplitOptions };
+pub use components::{ to_astro, to_html, to_html_with_attributes, to_html_with_budget, to_jsx_with_abbreviations, to_jsx_with_attributes, to_svelte };
+pub use diagnostic::{ Diagnostic, Severity };
+pub use diff::{ BEMDiff, BEMElementDiff };
+pub use elements::{ ElementMut, Elements, ElementsMut };
+pub use emit::{ EmitContext, EmitError, Emitter, EmitterRegistry };
+pub use error::{ BEMParseError, BemError, Error, Result };
+pub use files::{ parse_dir, parse_file, write_json };
+pub use filter::{ GenerateFilter, Glob };
+pub use format::{ decode, encode, BemFormat, Json };
+pub use generate::{ generate, generate_document, GenerateConfig };
+pub use ident::{ check_css_names, css_escape, CssNameError, CssNameMode, IdentifierError };
+pub use ids::{ entity_id, to_json_with_ids };
+pub use impact::{ semver_document_impact, semver_impact, ImpactChange, ImpactLevel, ImpactReport };
+pub use js::{ scan_js, JsScan };
+pub use length::LengthMode;
+pub use lint::{ lint_css, lint_css_verbose, CssLintConfig, CssLintFinding, CssLintOutcome, CssLintRule, CssLintSuppression, SuppressionScope };
+pub use models::{ BEMBlock, BEMDocument, BEMElement, BEMFile, BEMModifier, SharedDocument };
+pub use naming::{ BemConfig, NamingScheme };
+pub use order::EmitOrder;
+pub use parser::{ parse, parse_all, parse_block_line, parse_element, parse_verbose, parse_verbose_with_grammar_version, parse_strict, parse_with_config, parse_with_grammar_version, ParseOutcome, GRAMMAR_VERSION };
+pub use patch::apply_merge_patch;
+pub use path::{ BEMPath, BEMPathError };
+pub use schema::{ schema_dump, SchemaBlock, SchemaDump, SchemaElement, SchemaLocation, SchemaModifier, SCHEMA_DUMP_VERSION };
+pub use search::{ find, Match, SearchScope };
+pub use sniff::{ sniff, InputFormat, SNIFF_LIMIT };
+pub use testid::{ to_testid_map, TestIdCasing, TestIdFormat, TestIdOptions };
+pub use theme::{ to_theme_keys_ts, to_theme_keys_ts_with_abbreviations };
+#[cfg(feature = "toml")]
+pub use toml::{ from_toml, to_toml, Toml };
+pub use tooling::{ to_purgecss_safelist, to_stylelint_config, SafelistMode };
+pub use usage::{ usage_report, ScanSource, SourceKind, UsageReport, UsageRow };
+pub use validator::{ validate, validate_with_options, ValidationError, ValidationErrorKind, ValidationOptions };
+pub use variants::Breakpoint;
+#[cfg(feature = "yaml")]
+pub use yaml::{ from_yaml, to_yaml, Yaml };
 
+mod a11y;
+mod abbreviation;
+mod attributes;
+mod audit;
+mod budget;
+mod builder;
+mod changelog;
+mod classes;
+mod codegen;
+mod commands;
+mod components;
+#[cfg(feature = "tokio")]
+pub mod aio;
+pub mod cookbook;
+mod css;
+mod diagnostic;
+mod diff;
+mod elements;
+mod emit;
+mod error;
+mod files;
+mod filter;
+mod format;
+mod fs_scheme;
+mod generate;
+mod i18n;
+mod ident;
+mod ids;
+mod impact;
+mod js;
+#[cfg(test)]
+mod laws;
+mod length;
+mod lint;
+mod lookup;
 mod models;
+mod naming;
+mod native;
+mod order;
 mod parser;
+mod patch;
+mod path;
+pub mod pipeline;
+pub mod raw;
+mod schema;
+mod search;
+mod service_worker;
+mod sniff;
+mod testid;
+mod theme;
+#[cfg(feature = "toml")]
+mod toml;
+mod tooling;
+mod usage;
+mod validator;
+mod variants;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+/// Writes a `BEMBlock` in the `.bem` syntax read by [`parse`], like its `Display`
+/// implementation.
+///
+/// The block is written on the first line and each element on its own line, with modifiers in
+/// square brackets separated by commas and without whitespace, e.g. `button[fast-forward,rewind]`.
+/// Empty modifier lists are left out, and there is no trailing line break.
+///
+/// # Invariants
+///
+/// These laws are checked by property tests over randomly generated blocks and inputs:
+///
+/// * For every block whose names follow the grammar, `parse(&to_bem(&x))` returns `x`.
+/// * For every input `s` that parses, `to_bem(&parse(s)?)` is its normal form: parsing and
+///   writing the normal form again returns it unchanged.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to write.
+///
+/// # Returns
+///
+/// * `String` - The `.bem` source.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_bem };
+///
+/// let bem_block = parse("media-player[ dark ]\nbutton[\n\tfast-forward,\n\trewind,\n]").unwrap();
+///
+/// assert_eq!(to_bem(&bem_block), "media-player[dark]\nbutton[fast-forward,rewind]");
+/// assert_eq!(parse(&to_bem(&bem_block)).unwrap(), bem_block);
+/// ```
+pub fn to_bem(bem_block: &BEMBlock) -> String {
+	bem_block.to_string()
+}
 
 /// Converts a `BEMBlock` into a JSON string.
 ///
 /// This function takes a reference to a `BEMBlock` and serializes it into a JSON string.
 /// It returns a `Result` containing the JSON string if the conversion is successful, or
-/// a `serde_json::Error` if there is a problem during serialization.
+/// an [`Error::Serialize`] if there is a problem during serialization.
 ///
 /// # Arguments
 ///
@@ -42,18 +186,23 @@ mod parser;
 ///
 /// # Returns
 ///
-/// * `Result<String, serde_json::Error>` - A result containing the JSON string or an error.
+/// * `Result<String>` - A result containing the JSON string or an error.
+///
+/// # Invariants
+///
+/// For every block `x`, `from_json(&to_json(&x)?)` returns `x`. This is checked by property
+/// tests over randomly generated blocks.
 ///
 /// # Examples
 ///
 /// ```
 /// use bem::{BEMBlock, to_json};
 ///
-/// let bem_block = BEMBlock { name: "media-player".to_string(), modifiers: vec![], elements: vec![] };
+/// let bem_block = BEMBlock::new("media-player");
 /// let json = to_json(&bem_block).unwrap();
 /// ```
-pub fn to_json(bem_block: &BEMBlock) -> Result<String, serde_json::Error> {
-	let json_output = serde_json::to_string(&bem_block)?;
+pub fn to_json(bem_block: &BEMBlock) -> Result<String> {
+	let json_output = serde_json::to_string(&bem_block).map_err(Error::Serialize)?;
 
 	Ok(json_output)
 }
@@ -62,7 +211,7 @@ pub fn to_json(bem_block: &BEMBlock) -> Result<String, serde_json::Error> {
 ///
 /// This function takes a reference to a `BEMBlock` and serializes it into a prett-printed JSON string.
 /// It returns a `Result` containing the pretty-printed JSON string if the conversion is successful, or
-/// a `serde_json::Error` if there is a problem during serialization.
+/// an [`Error::Serialize`] if there is a problem during serialization.
 ///
 /// # Arguments
 ///
@@ -70,18 +219,84 @@ pub fn to_json(bem_block: &BEMBlock) -> Result<String, serde_json::Error> {
 ///
 /// # Returns
 ///
-/// * `Result<String, serde_json::Error>` - A result containing the pretty-printed JSON string or an error.
+/// * `Result<String>` - A result containing the pretty-printed JSON string or an error.
 ///
 /// # Examples
 ///
 /// ```
 /// use bem::{BEMBlock, to_json_pretty};
 ///
-/// let bem_block = BEMBlock { name: "media-player".to_string(), modifiers: vec![], elements: vec![] };
+/// let bem_block = BEMBlock::new("media-player");
 /// let json = to_json_pretty(&bem_block).unwrap();
 /// ```
-pub fn to_json_pretty(bem_block: &BEMBlock) -> Result<String, serde_json::Error> {
-	let json_output = serde_json::to_string_pretty(&bem_block)?;
+pub fn to_json_pretty(bem_block: &BEMBlock) -> Result<String> {
+	let json_output = serde_json::to_string_pretty(&bem_block).map_err(Error::Serialize)?;
+
+	Ok(json_output)
+}
+
+/// Writes a `BEMBlock` as JSON, like [`to_json`], to a writer instead of a string.
+///
+/// The JSON is written as it is serialized, so large blocks are not held in memory twice. The
+/// writer is not buffered: wrap files in a [`BufWriter`](std::io::BufWriter).
+///
+/// # Arguments
+///
+/// * `writer`: W - The writer, e.g. a file or a socket.
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to be converted to JSON.
+///
+/// # Returns
+///
+/// * `Result<()>` - An [`Error::Serialize`] if serializing or writing failed.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_json, to_writer };
+///
+/// let bem_block = parse("media-player[dark]").unwrap();
+/// let mut output = Vec::new();
+///
+/// to_writer(&mut output, &bem_block).unwrap();
+///
+/// assert_eq!(output, to_json(&bem_block).unwrap().into_bytes());
+/// ```
+pub fn to_writer<W: std::io::Write>(writer: W, bem_block: &BEMBlock) -> Result<()> {
+	serde_json::to_writer(writer, bem_block).map_err(Error::Serialize)
+}
+
+/// Converts a `BEMBlock` into canonical JSON, following the JSON Canonicalization Scheme (RFC 8785).
+///
+/// The output is byte-for-byte reproducible, so it can be signed or checksummed. Object keys are
+/// sorted (`elements`, `modifiers`, `name`), no whitespace is emitted, and strings use the
+/// shortest escapes: `\b`, `\f`, `\n`, `\r`, `\t`, `\"` and `\\`, `\u00xx` for other control
+/// characters, and everything else as literal UTF-8. The schema has no numbers, so the number
+/// rules of the scheme do not apply. Array order is significant and kept as is.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to be converted to JSON.
+///
+/// # Returns
+///
+/// * `Result<String>` - A result containing the canonical JSON string or an error.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{BEMBlock, to_json_jcs};
+///
+/// let bem_block = BEMBlock::new("media-player");
+/// let json = to_json_jcs(&bem_block).unwrap();
+///
+/// assert_eq!(json, "{\"elements\":[],\"modifiers\":[],\"name\":\"media-player\"}");
+/// ```
+pub fn to_json_jcs(bem_block: &BEMBlock) -> Result<String> {
+	// Without the `preserve_order` feature, `serde_json::Value` objects are ordered maps, sorted by
+	// UTF-8 bytes. RFC 8785 sorts by UTF-16 code units, which gives the same order for the
+	// ASCII keys of this schema.
+	let value = serde_json::to_value(bem_block).map_err(Error::Serialize)?;
+	let json_output = serde_json::to_string(&value).map_err(Error::Serialize)?;
 
 	Ok(json_output)
 }
@@ -90,7 +305,11 @@ pub fn to_json_pretty(bem_block: &BEMBlock) -> Result<String, serde_json::Error>
 ///
 /// This function takes a JSON string and deserializes it into a `BEMBlock`.
 /// It returns a `Result` containing the `BEMBlock` if the conversion is successful, or
-/// a `serde_json::Error` if there is a problem during deserialization.
+/// an [`Error::Deserialize`] if there is a problem during deserialization.
+///
+/// Elements are unique by name, so an element listed twice in the `elements` array is merged
+/// into its first occurrence, see [`Elements`], and the block may have fewer elements than the
+/// array. Its JSON is then not the input JSON.
 ///
 /// # Arguments
 ///
@@ -98,7 +317,11 @@ pub fn to_json_pretty(bem_block: &BEMBlock) -> Result<String, serde_json::Error>
 ///
 /// # Returns
 ///
-/// * `Result<BEMBlock, serde_json::Error>` - A result containing the `BEMBlock` or an error.
+/// * `Result<BEMBlock>` - A result containing the `BEMBlock` or an error.
+///
+/// # Invariants
+///
+/// For every block `x`, `from_json(&to_json(&x)?)` returns `x`, see [`to_json`].
 ///
 /// # Examples
 ///
@@ -107,13 +330,150 @@ pub fn to_json_pretty(bem_block: &BEMBlock) -> Result<String, serde_json::Error>
 ///
 /// let json = "{\"name\":\"media-player\",\"modifiers\":[],\"elements\":[]}";
 /// let bem_block = from_json(json).unwrap();
+///
+/// let json = "{\"name\":\"media-player\",\"modifiers\":[],\"elements\":[\
+///     {\"name\":\"button\",\"modifiers\":[\"rewind\"]},{\"name\":\"button\",\"modifiers\":[\"fast-forward\"]}]}";
+/// let bem_block = from_json(json).unwrap();
+///
+/// assert_eq!(bem_block.elements.len(), 1);
+/// assert_eq!(bem_block.elements[0].modifiers, vec!["rewind", "fast-forward"]);
 /// ```
-pub fn from_json(json: &str) -> Result<BEMBlock, serde_json::Error> {
-	let bem_block = serde_json::from_str(json)?;
+pub fn from_json(json: &str) -> Result<BEMBlock> {
+	let bem_block = serde_json::from_str(json).map_err(Error::Deserialize)?;
 
 	Ok(bem_block)
 }
 
+/// Reads a `BEMBlock` from JSON, like [`from_json`], from a reader instead of a string.
+///
+/// The reader is not buffered: wrap files in a [`BufReader`](std::io::BufReader).
+///
+/// # Arguments
+///
+/// * `reader`: R - The reader, e.g. a file or stdin.
+///
+/// # Returns
+///
+/// * `Result<BEMBlock>` - The block, or an [`Error::Deserialize`] if reading failed or the JSON
+///   does not describe a block.
+///
+/// # Examples
+///
+/// ```
+/// use bem::from_reader;
+///
+/// let json = "{\"name\":\"media-player\",\"modifiers\":[\"dark\"],\"elements\":[]}";
+/// let bem_block = from_reader(json.as_bytes()).unwrap();
+///
+/// assert_eq!(bem_block.modifiers, vec!["dark"]);
+/// ```
+pub fn from_reader<R: std::io::Read>(reader: R) -> Result<BEMBlock> {
+	serde_json::from_reader(reader).map_err(Error::Deserialize)
+}
+
+/// Converts a `BEMBlock` into a `serde_json::Value`, shaped like the output of [`to_json`].
+/// Objects of a `Value` sort their keys, so writing it gives the order of [`to_json_jcs`].
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to be converted.
+///
+/// # Returns
+///
+/// * `Result<serde_json::Value>` - The JSON value, or an [`Error::Serialize`].
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_json_value };
+///
+/// let value = to_json_value(&parse("media-player[dark]").unwrap()).unwrap();
+///
+/// assert_eq!(value["modifiers"][0], "dark");
+/// ```
+pub fn to_json_value(bem_block: &BEMBlock) -> Result<serde_json::Value> {
+	serde_json::to_value(bem_block).map_err(Error::Serialize)
+}
+
+/// Converts a `serde_json::Value` into a `BEMBlock`, like [`from_json`] without going through a
+/// string.
+///
+/// # Arguments
+///
+/// * `value`: serde_json::Value - The JSON value, e.g. the body of an HTTP response.
+///
+/// # Returns
+///
+/// * `Result<BEMBlock>` - The block, or an [`Error::Deserialize`] if the value does not describe
+///   a block.
+///
+/// # Examples
+///
+/// ```
+/// use bem::from_json_value;
+///
+/// let value = serde_json::json!({ "name": "media-player", "modifiers": [], "elements": [{ "name": "button", "modifiers": [] }] });
+/// let bem_block = from_json_value(value).unwrap();
+///
+/// assert!(bem_block.contains_element("button"));
+/// assert!(from_json_value(serde_json::json!({ "name": "media-player" })).is_err());
+/// ```
+pub fn from_json_value(value: serde_json::Value) -> Result<BEMBlock> {
+	serde_json::from_value(value).map_err(Error::Deserialize)
+}
+
+/// Converts the blocks of a `.bem` file into a JSON string, as an object with a `blocks` array
+/// holding every block in the form of [`to_json`].
+///
+/// # Arguments
+///
+/// * `file`: &BEMFile - The blocks of the file, e.g. from [`parse_all`].
+///
+/// # Returns
+///
+/// * `Result<String>` - The JSON string, or an [`Error::Serialize`].
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse_all, to_json_file, BEMFile };
+///
+/// let file = BEMFile::from(parse_all("media-player[dark]\n\nplay-list").unwrap());
+///
+/// assert_eq!(
+///     to_json_file(&file).unwrap(),
+///     "{\"blocks\":[{\"name\":\"media-player\",\"modifiers\":[\"dark\"],\"elements\":[]},{\"name\":\"play-list\",\"modifiers\":[],\"elements\":[]}]}"
+/// );
+/// ```
+pub fn to_json_file(file: &BEMFile) -> Result<String> {
+	serde_json::to_string(file).map_err(Error::Serialize)
+}
+
+/// Converts a JSON string written by [`to_json_file`] back into the blocks of a file.
+///
+/// # Arguments
+///
+/// * `json`: &str - The JSON string, an object with a `blocks` array.
+///
+/// # Returns
+///
+/// * `Result<BEMFile>` - The blocks of the file, or an [`Error::Deserialize`] if the JSON does
+///   not describe them.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ from_json_file, parse_all, to_json_file, BEMFile };
+///
+/// let file = BEMFile::from(parse_all("media-player[dark]\nbutton\n\nplay-list").unwrap());
+///
+/// assert_eq!(from_json_file(&to_json_file(&file).unwrap()).unwrap(), file);
+/// assert!(from_json_file("[]").is_err());
+/// ```
+pub fn from_json_file(json: &str) -> Result<BEMFile> {
+	serde_json::from_str(json).map_err(Error::Deserialize)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::{ BEMBlock, BEMElement };
@@ -121,17 +481,17 @@ mod tests {
 	fn create_test_bem_block() -> BEMBlock {
 		BEMBlock {
 			name: "media-player".to_string(),
-			modifiers: vec!["dark".to_string()],
+			modifiers: vec!["dark".into()],
 			elements: vec![
 				BEMElement {
 					name: "button".to_string(),
-					modifiers: vec!["fast-forward".to_string(), "rewind".to_string()],
+					modifiers: vec!["fast-forward".into(), "rewind".into()],
 				},
 				BEMElement {
 					name: "timeline".to_string(),
 					modifiers: vec![],
 				}
-			],
+			].into(),
 		}
 	}
 
@@ -155,6 +515,66 @@ mod tests {
 		insta::assert_snapshot!(result.unwrap());
 	}
 
+	#[test]
+	fn test_to_writer_and_from_reader() {
+		let bem_block = create_test_bem_block();
+		let file = tempfile::NamedTempFile::new().unwrap();
+
+		super::to_writer(std::io::BufWriter::new(file.reopen().unwrap()), &bem_block).unwrap();
+
+		assert_eq!(std::fs::read_to_string(file.path()).unwrap(), super::to_json(&bem_block).unwrap());
+		assert_eq!(super::from_reader(std::io::BufReader::new(file.reopen().unwrap())).unwrap(), bem_block);
+		assert_eq!(super::from_reader("{\"name\":".as_bytes()).unwrap_err().code(), "deserialize");
+	}
+
+	#[test]
+	fn test_json_value() {
+		let bem_block = create_test_bem_block();
+		let value = super::to_json_value(&bem_block).unwrap();
+
+		assert_eq!(value.to_string(), super::to_json_jcs(&bem_block).unwrap());
+		assert_eq!(serde_json::Value::from(bem_block.clone()), value);
+		assert_eq!(BEMBlock::try_from(value.clone()).unwrap(), bem_block);
+		assert_eq!(super::from_json_value(value).unwrap(), bem_block);
+		assert_eq!(BEMBlock::try_from(serde_json::json!([])).unwrap_err().code(), "deserialize");
+	}
+
+	#[test]
+	fn test_json_file() {
+		let blocks = super::parse_all("media-player[dark]\nbutton[fast-forward,rewind]\n\nplay-list\nitem[playing]\n\nsearch-box[compact]").unwrap();
+		let file = super::BEMFile::from(blocks);
+		let json = super::to_json_file(&file).unwrap();
+
+		insta::assert_snapshot!(json);
+		assert_eq!(super::from_json_file(&json).unwrap(), file);
+		assert_eq!(super::from_json_file("{\"blocks\":[{\"name\":\"media-player\"}]}").unwrap_err().code(), "deserialize");
+	}
+
+	#[test]
+	fn test_to_json_jcs() {
+		let bem_block = create_test_bem_block();
+		let result = super::to_json_jcs(&bem_block);
+
+		assert_eq!(
+			result.unwrap(),
+			"{\"elements\":[{\"modifiers\":[\"fast-forward\",\"rewind\"],\"name\":\"button\"},{\"modifiers\":[],\"name\":\"timeline\"}],\"modifiers\":[\"dark\"],\"name\":\"media-player\"}"
+		);
+	}
+
+	#[test]
+	fn test_to_json_jcs_escapes() {
+		let bem_block = BEMBlock {
+			name: "a\u{1}\t\"/\u{e9}\u{1f600}".to_string(),
+			modifiers: vec![],
+			elements: vec![].into(),
+		};
+
+		assert_eq!(
+			super::to_json_jcs(&bem_block).unwrap(),
+			"{\"elements\":[],\"modifiers\":[],\"name\":\"a\\u0001\\t\\\"/\u{e9}\u{1f600}\"}"
+		);
+	}
+
 	#[test]
 	fn test_from_json() {
 		let json =
@@ -165,4 +585,154 @@ mod tests {
 
 		assert_eq!(result.unwrap(), create_test_bem_block());
 	}
+
+	#[test]
+	fn test_json_modifier_values() {
+		let bem_block = super::parse("button[color=red,disabled]").unwrap();
+		let json = super::to_json(&bem_block).unwrap();
+
+		assert_eq!(json, "{\"name\":\"button\",\"modifiers\":[\"color=red\",\"disabled\"],\"elements\":[]}");
+		assert_eq!(super::from_json(&json).unwrap(), bem_block);
+	}
+
+	#[test]
+	fn test_json_modifier_with_underscore() {
+		let bem_block = super::from_json("{\"name\":\"button\",\"modifiers\":[\"fast_forward\"],\"elements\":[]}").unwrap();
+
+		assert_eq!(bem_block.modifiers, vec![super::BEMModifier::new("fast_forward")]);
+		assert_eq!(bem_block.to_string(), "button[fast_forward]");
+		assert_eq!(bem_block.to_css_classes(), vec!["button", "button--fast_forward"]);
+		assert_eq!(super::from_json(&super::to_json(&bem_block).unwrap()).unwrap(), bem_block);
+	}
+
+	fn assert_send_sync<T: Send + Sync>() {}
+
+	#[test]
+	fn test_public_types_are_send_and_sync() {
+		assert_send_sync::<super::A11yPropsConfig>();
+		assert_send_sync::<super::AbbreviationError>();
+		assert_send_sync::<super::AbbreviationMap>();
+		assert_send_sync::<super::AttributeScheme>();
+		assert_send_sync::<super::BEMBlock>();
+		assert_send_sync::<super::BEMDiff>();
+		assert_send_sync::<super::BEMDocument>();
+		assert_send_sync::<super::BEMElement>();
+		assert_send_sync::<super::BEMElementDiff>();
+		assert_send_sync::<super::BEMModifier>();
+		assert_send_sync::<super::BEMParseError>();
+		assert_send_sync::<super::BEMPath>();
+		assert_send_sync::<super::BEMPathError>();
+		assert_send_sync::<super::Breakpoint>();
+		assert_send_sync::<super::BudgetError>();
+		assert_send_sync::<super::ChangelogOptions>();
+		assert_send_sync::<super::ClassBudget>();
+		assert_send_sync::<super::ClassBuilder>();
+		assert_send_sync::<super::CssLintConfig>();
+		assert_send_sync::<super::CssLintFinding>();
+		assert_send_sync::<super::CssLintOutcome>();
+		assert_send_sync::<super::CssLintRule>();
+		assert_send_sync::<super::CssLintSuppression>();
+		assert_send_sync::<super::CssNameError>();
+		assert_send_sync::<super::CssNameMode>();
+		assert_send_sync::<super::DesignAudit>();
+		assert_send_sync::<super::Diagnostic>();
+		assert_send_sync::<super::ElementMut<'static>>();
+		assert_send_sync::<super::ElementsMut<'static>>();
+		assert_send_sync::<super::Elements>();
+		assert_send_sync::<super::EmitContext>();
+		assert_send_sync::<super::EmitError>();
+		assert_send_sync::<super::EmitOrder>();
+		assert_send_sync::<super::EmitterRegistry>();
+		assert_send_sync::<super::Error>();
+		assert_send_sync::<super::GenerateConfig>();
+		assert_send_sync::<super::GenerateFilter>();
+		assert_send_sync::<super::Glob>();
+		assert_send_sync::<super::IdentifierError>();
+		assert_send_sync::<super::ImpactChange>();
+		assert_send_sync::<super::ImpactLevel>();
+		assert_send_sync::<super::ImpactReport>();
+		assert_send_sync::<super::Json>();
+		assert_send_sync::<super::InputFormat>();
+		assert_send_sync::<super::JsScan>();
+		assert_send_sync::<super::LengthMode>();
+		assert_send_sync::<super::Match>();
+		assert_send_sync::<super::NamingScheme>();
+		assert_send_sync::<super::ParseOutcome>();
+		assert_send_sync::<super::SafelistMode>();
+		assert_send_sync::<super::ScanSource>();
+		assert_send_sync::<super::SchemaBlock>();
+		assert_send_sync::<super::SchemaDump>();
+		assert_send_sync::<super::SchemaElement>();
+		assert_send_sync::<super::SchemaLocation>();
+		assert_send_sync::<super::SchemaModifier>();
+		assert_send_sync::<super::SearchScope>();
+		assert_send_sync::<super::Severity>();
+		assert_send_sync::<super::SharedDocument>();
+		assert_send_sync::<super::SourceKind>();
+		assert_send_sync::<super::SplitOptions>();
+		assert_send_sync::<super::SuppressionScope>();
+		assert_send_sync::<super::TestIdCasing>();
+		assert_send_sync::<super::TestIdFormat>();
+		assert_send_sync::<super::TestIdOptions>();
+		assert_send_sync::<super::UsageCounts>();
+		assert_send_sync::<super::UsageReport>();
+		assert_send_sync::<super::UsageRow>();
+		assert_send_sync::<super::ValidationError>();
+		assert_send_sync::<super::ValidationErrorKind>();
+		assert_send_sync::<super::ValidationOptions>();
+		#[cfg(feature = "toml")]
+		assert_send_sync::<super::Toml>();
+		#[cfg(feature = "yaml")]
+		assert_send_sync::<super::Yaml>();
+		assert_send_sync::<super::pipeline::ChecksumAlgorithm>();
+		assert_send_sync::<super::pipeline::InputReport>();
+		assert_send_sync::<super::pipeline::MemoryIo>();
+		assert_send_sync::<super::pipeline::RunOptions>();
+		assert_send_sync::<super::pipeline::RunReport>();
+		assert_send_sync::<super::pipeline::StdIo>();
+		assert_send_sync::<super::raw::RawNode>();
+		assert_send_sync::<super::raw::RawRule>();
+		assert_send_sync::<Box<dyn super::Emitter>>();
+	}
+
+	#[test]
+	fn test_shared_document_across_threads() {
+		use super::{ generate_document, EmitContext, EmitterRegistry, GenerateConfig, NamingScheme };
+
+		let config = GenerateConfig { blocks: 20..21, ..GenerateConfig::default() };
+		let shared = generate_document(7, &config).into_shared();
+		let registry = EmitterRegistry::new();
+		let read = |document: &super::BEMDocument| {
+			let mut results: Vec<Vec<u8>> = registry
+				.ids()
+				.iter()
+				.map(|id| registry.emit(id, document, &EmitContext::default()).unwrap())
+				.collect();
+
+			for bem_block in &document.blocks {
+				let found = document.get_block(&bem_block.name).unwrap();
+
+				results.push(NamingScheme::default().class_names(found).join(" ").into_bytes());
+			}
+
+			results
+		};
+		let expected = read(&shared);
+
+		std::thread::scope(|scope| {
+			let handles: Vec<_> = (0..16)
+				.map(|_| {
+					let handle = shared.clone();
+
+					scope.spawn(move || (0..20).map(|_| read(&handle)).collect::<Vec<_>>())
+				})
+				.collect();
+
+			for handle in handles {
+				for results in handle.join().unwrap() {
+					assert_eq!(results, expected);
+				}
+			}
+		});
+	}
 }
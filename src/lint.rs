@@ -0,0 +1,473 @@
+//! This module lints stylesheets for selectors that break the structure of BEM itself, such as
+//! elements of elements or modifiers used without their base class. It checks existing CSS,
+//! so unlike [`validate`](crate::validate) it works on classes found in selectors rather than
+//! on parsed blocks.
+
+use std::collections::HashSet;
+use std::fmt;
+use crate::css::{ scan_comments, scan_selectors, Selector };
+
+/// A structural rule checked by [`lint_css`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CssLintRule {
+	/// A class names an element of an element, like `card__header__title`.
+	ElementOfElement,
+	/// A modifier class is used, but its base class appears nowhere in the stylesheet.
+	OrphanModifier,
+	/// A selector chains more elements of the same block than allowed, like
+	/// `.card__body .card__list .card__item`.
+	DeepElementChain,
+	/// A modifier class of one block is applied to an element of another block, like
+	/// `.menu__item.card--active`.
+	ForeignModifier,
+}
+
+impl CssLintRule {
+	/// Returns the stable code of the rule, e.g. `BEM001`, for filtering and suppressing
+	/// findings in tooling.
+	pub fn code(&self) -> &'static str {
+		match self {
+			CssLintRule::ElementOfElement => "BEM001",
+			CssLintRule::OrphanModifier => "BEM002",
+			CssLintRule::DeepElementChain => "BEM003",
+			CssLintRule::ForeignModifier => "BEM004",
+		}
+	}
+}
+
+/// Configures [`lint_css`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CssLintConfig {
+	/// The number of elements of the same block a selector may chain with combinators before
+	/// [`CssLintRule::DeepElementChain`] is reported. Defaults to 2.
+	pub max_element_chain: usize,
+}
+
+impl Default for CssLintConfig {
+	fn default() -> Self {
+		CssLintConfig { max_element_chain: 2 }
+	}
+}
+
+/// A violation found by [`lint_css`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CssLintFinding {
+	/// The rule that is violated.
+	pub rule: CssLintRule,
+	/// The offending selector, with nested selectors resolved against their parent rule.
+	pub selector: String,
+	/// The class the finding is about.
+	pub class: String,
+	/// The line of the rule in the stylesheet, starting at 1.
+	pub line: usize,
+}
+
+impl fmt::Display for CssLintFinding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {} `{}`: ", self.line, self.rule.code(), self.selector)?;
+
+		match self.rule {
+			CssLintRule::ElementOfElement => write!(f, "`{}` is an element of an element", self.class),
+			CssLintRule::OrphanModifier => write!(
+				f,
+				"modifier `{}` is used without its base class `{}`",
+				self.class,
+				ClassParts::of(&self.class).base
+			),
+			CssLintRule::DeepElementChain => write!(f, "too many elements of block `{}` are chained", self.class),
+			CssLintRule::ForeignModifier => write!(f, "modifier `{}` is applied to an element of another block", self.class),
+		}
+	}
+}
+
+/// The lines a [`CssLintSuppression`] applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SuppressionScope {
+	/// `bem-lint-disable`, on its own line: the rest of the stylesheet.
+	File,
+	/// `bem-lint-disable-next-line`, on its own line: the line after the comment.
+	NextLine,
+	/// `bem-lint-disable-line`, after a rule on the same line: the line of the comment.
+	Line,
+}
+
+impl SuppressionScope {
+	/// Returns the directive of the scope, as written in comments.
+	pub fn directive(&self) -> &'static str {
+		match self {
+			SuppressionScope::File => "bem-lint-disable",
+			SuppressionScope::NextLine => "bem-lint-disable-next-line",
+			SuppressionScope::Line => "bem-lint-disable-line",
+		}
+	}
+}
+
+/// A comment suppressing findings of [`lint_css`], like
+/// `/* bem-lint-disable-next-line BEM001, BEM002 */`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CssLintSuppression {
+	/// The lines the suppression applies to.
+	pub scope: SuppressionScope,
+	/// The codes of the rules suppressed, as written, or empty to suppress every rule.
+	pub rules: Vec<String>,
+	/// The line of the comment, starting at 1.
+	pub line: usize,
+	/// The number of findings suppressed.
+	pub matched: usize,
+}
+
+impl CssLintSuppression {
+	/// Returns the suppression of a comment, if it is one. File and next-line suppressions must
+	/// be on their own line.
+	fn of(text: &str, line: usize, end_line: usize, own_line: bool) -> Option<CssLintSuppression> {
+		let (directive, rules) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+		let (scope, line) = match directive {
+			"bem-lint-disable" if own_line => (SuppressionScope::File, line),
+			"bem-lint-disable-next-line" if own_line => (SuppressionScope::NextLine, end_line + 1),
+			"bem-lint-disable-line" => (SuppressionScope::Line, line),
+			_ => return None,
+		};
+		let rules = rules
+			.split(|c: char| c == ',' || c.is_whitespace())
+			.filter(|rule| !rule.is_empty())
+			.map(str::to_string)
+			.collect();
+
+		Some(CssLintSuppression { scope, rules, line, matched: 0 })
+	}
+
+	/// Returns whether the suppression applies to `finding`.
+	fn covers(&self, finding: &CssLintFinding) -> bool {
+		let lines = match self.scope {
+			SuppressionScope::File => finding.line >= self.line,
+			SuppressionScope::NextLine | SuppressionScope::Line => finding.line == self.line,
+		};
+
+		lines && (self.rules.is_empty() || self.rules.iter().any(|rule| rule == finding.rule.code()))
+	}
+}
+
+impl fmt::Display for CssLintSuppression {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let line = match self.scope {
+			SuppressionScope::NextLine => self.line - 1,
+			_ => self.line,
+		};
+
+		write!(f, "{}: `{}", line, self.scope.directive())?;
+
+		if !self.rules.is_empty() {
+			write!(f, " {}", self.rules.join(", "))?;
+		}
+
+		write!(f, "` suppresses nothing")
+	}
+}
+
+/// The findings of [`lint_css_verbose`], with the suppression comments of the stylesheet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CssLintOutcome {
+	/// The findings that are not suppressed, in the order of the stylesheet.
+	pub findings: Vec<CssLintFinding>,
+	/// Every suppression comment, in the order of the stylesheet.
+	pub suppressions: Vec<CssLintSuppression>,
+}
+
+impl CssLintOutcome {
+	/// Returns the suppressions that suppressed nothing, e.g. because the selector they were
+	/// written for was fixed.
+	pub fn unused_suppressions(&self) -> Vec<&CssLintSuppression> {
+		self.suppressions.iter().filter(|suppression| suppression.matched == 0).collect()
+	}
+}
+
+/// The BEM parts of a class found in a stylesheet. Unlike the class parser, this accepts any
+/// class, so malformed BEM classes can be reported instead of ignored.
+struct ClassParts<'a> {
+	/// The class without its modifier, e.g. `card__title` for `card__title--large`.
+	base: &'a str,
+	/// The block of the class.
+	block: &'a str,
+	/// The number of element separators in the base.
+	elements: usize,
+	/// Whether the class has a modifier.
+	modifier: bool,
+}
+
+impl<'a> ClassParts<'a> {
+	fn of(class: &'a str) -> ClassParts<'a> {
+		let (base, modifier) = match class.split_once("--") {
+			Some((base, _)) => (base, true),
+			None => (class, false),
+		};
+
+		ClassParts {
+			base,
+			block: base.split("__").next().unwrap_or(base),
+			elements: base.matches("__").count(),
+			modifier,
+		}
+	}
+}
+
+/// Lints a stylesheet for selectors that violate the structure of BEM.
+///
+/// Every class of every selector is checked against the rules of [`CssLintRule`]. Classes
+/// inside functional pseudo-classes like `:not()` are ignored, as are classes without BEM
+/// separators, which may be blocks or utility classes alike. Selectors nested with CSS nesting
+/// are resolved against their parent rule first.
+///
+/// Findings can be suppressed with comments naming the codes of their rules, or no code to
+/// suppress every rule, see [`SuppressionScope`]:
+///
+/// - `/* bem-lint-disable BEM002 */` on its own line, for the rest of the stylesheet.
+/// - `/* bem-lint-disable-next-line BEM001, BEM003 */` on its own line, for the next line.
+/// - `/* bem-lint-disable-line */` after a rule, for its line.
+///
+/// Findings are reported on the line their selector starts on, which is the line suppressions
+/// are matched against.
+///
+/// # Arguments
+///
+/// * `css`: &str - The stylesheet to lint.
+/// * `config`: &CssLintConfig - The thresholds of the rules.
+///
+/// # Returns
+///
+/// * `Vec<CssLintFinding>` - Every violation found, in the order of the stylesheet. An empty
+///   vector means the stylesheet follows BEM.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ lint_css, CssLintConfig, CssLintRule };
+///
+/// let findings = lint_css(".card { }\n.card__header__title { }\n", &CssLintConfig::default());
+///
+/// assert_eq!(findings[0].rule, CssLintRule::ElementOfElement);
+/// assert_eq!(findings[0].rule.code(), "BEM001");
+/// assert_eq!(findings[0].line, 2);
+/// ```
+pub fn lint_css(css: &str, config: &CssLintConfig) -> Vec<CssLintFinding> {
+	lint_css_verbose(css, config).findings
+}
+
+/// Lints a stylesheet like [`lint_css`], and also returns its suppression comments with the
+/// number of findings each suppressed, so suppressions that no longer match anything can be
+/// reported.
+///
+/// # Arguments
+///
+/// * `css`: &str - The stylesheet to lint.
+/// * `config`: &CssLintConfig - The thresholds of the rules.
+///
+/// # Returns
+///
+/// * `CssLintOutcome` - The findings that are not suppressed, and the suppressions.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ lint_css_verbose, CssLintConfig };
+///
+/// let css = "/* bem-lint-disable-next-line BEM001 */\n.card__header__title { }\n.card { } /* bem-lint-disable-line */\n";
+/// let outcome = lint_css_verbose(css, &CssLintConfig::default());
+///
+/// assert!(outcome.findings.is_empty());
+/// assert_eq!(outcome.suppressions[0].matched, 1);
+/// assert_eq!(outcome.unused_suppressions()[0].to_string(), "3: `bem-lint-disable-line` suppresses nothing");
+/// ```
+pub fn lint_css_verbose(css: &str, config: &CssLintConfig) -> CssLintOutcome {
+	let mut suppressions: Vec<CssLintSuppression> = scan_comments(css)
+		.iter()
+		.filter_map(|comment| CssLintSuppression::of(&comment.text, comment.line, comment.end_line, comment.own_line))
+		.collect();
+	let mut findings = unsuppressed_findings(css, config);
+
+	findings.retain(|finding| {
+		let mut suppressed = false;
+
+		for suppression in suppressions.iter_mut().filter(|suppression| suppression.covers(finding)) {
+			suppression.matched += 1;
+			suppressed = true;
+		}
+
+		!suppressed
+	});
+
+	CssLintOutcome { findings, suppressions }
+}
+
+/// Returns every finding of `css`, ignoring suppression comments.
+fn unsuppressed_findings(css: &str, config: &CssLintConfig) -> Vec<CssLintFinding> {
+	let selectors = scan_selectors(css);
+	let classes: HashSet<&str> = selectors
+		.iter()
+		.flat_map(|selector| selector.compounds.iter().flatten())
+		.map(String::as_str)
+		.collect();
+	let mut findings = Vec::new();
+
+	for selector in &selectors {
+		let mut finding = |rule: CssLintRule, class: &str| {
+			let finding = CssLintFinding {
+				rule,
+				selector: selector.text.clone(),
+				class: class.to_string(),
+				line: selector.line,
+			};
+
+			if !findings.contains(&finding) {
+				findings.push(finding);
+			}
+		};
+
+		for compound in &selector.compounds {
+			for class in compound {
+				let parts = ClassParts::of(class);
+
+				if parts.elements > 1 {
+					finding(CssLintRule::ElementOfElement, class);
+				}
+				if parts.modifier && !parts.base.is_empty() && !classes.contains(parts.base) {
+					finding(CssLintRule::OrphanModifier, class);
+				}
+				if parts.modifier && is_foreign(&parts, compound) {
+					finding(CssLintRule::ForeignModifier, class);
+				}
+			}
+		}
+
+		for block in deep_element_chains(selector, config.max_element_chain) {
+			finding(CssLintRule::DeepElementChain, block);
+		}
+	}
+
+	findings
+}
+
+/// Returns whether a modifier shares its compound selector with an element of another block.
+fn is_foreign(modifier: &ClassParts<'_>, compound: &[String]) -> bool {
+	compound
+		.iter()
+		.map(|class| ClassParts::of(class))
+		.any(|parts| !parts.modifier && parts.elements > 0 && parts.block != modifier.block)
+}
+
+/// Returns the blocks that have elements in more than `max` compound selectors of `selector`.
+fn deep_element_chains(selector: &Selector, max: usize) -> Vec<&str> {
+	let mut chains: Vec<(&str, usize)> = Vec::new();
+
+	for compound in &selector.compounds {
+		let mut blocks: Vec<&str> = compound
+			.iter()
+			.map(|class| ClassParts::of(class))
+			.filter(|parts| parts.elements > 0)
+			.map(|parts| parts.block)
+			.collect();
+
+		blocks.dedup();
+
+		for block in blocks {
+			match chains.iter_mut().find(|(existing, _)| *existing == block) {
+				Some((_, count)) => *count += 1,
+				None => chains.push((block, 1)),
+			}
+		}
+	}
+
+	chains
+		.into_iter()
+		.filter(|(_, count)| *count > max)
+		.map(|(block, _)| block)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ lint_css, lint_css_verbose, CssLintConfig, CssLintRule, SuppressionScope };
+
+	const VIOLATIONS: &str = include_str!("../fixtures/lint/violations.css");
+	const CLEAN: &str = include_str!("../fixtures/lint/clean.css");
+	const SUPPRESSIONS: &str = include_str!("../fixtures/lint/suppressions.css");
+
+	#[test]
+	fn test_lint_css() {
+		let findings: Vec<String> = lint_css(VIOLATIONS, &CssLintConfig::default())
+			.iter()
+			.map(|finding| finding.to_string())
+			.collect();
+
+		insta::assert_snapshot!(findings.join("\n"));
+	}
+
+	#[test]
+	fn test_lint_css_suppressions() {
+		let outcome = lint_css_verbose(SUPPRESSIONS, &CssLintConfig::default());
+		let findings: Vec<(usize, &str)> = outcome.findings.iter().map(|finding| (finding.line, finding.rule.code())).collect();
+		let suppressions: Vec<(SuppressionScope, usize, usize)> = outcome.suppressions
+			.iter()
+			.map(|suppression| (suppression.scope, suppression.line, suppression.matched))
+			.collect();
+
+		// The footer link is not suppressed, nor is the foreign card modifier on the line that
+		// only suppresses BEM001.
+		assert_eq!(findings, vec![(10, "BEM001"), (14, "BEM004")]);
+		assert_eq!(suppressions, vec![
+			(SuppressionScope::NextLine, 6, 1),
+			(SuppressionScope::Line, 12, 1),
+			(SuppressionScope::Line, 14, 0),
+			(SuppressionScope::File, 16, 3),
+			(SuppressionScope::NextLine, 24, 0),
+			(SuppressionScope::NextLine, 27, 1),
+		]);
+		assert_eq!(outcome.suppressions[3].rules, vec!["BEM002", "BEM004"]);
+		assert_eq!(lint_css(SUPPRESSIONS, &CssLintConfig::default()), outcome.findings);
+	}
+
+	#[test]
+	fn test_lint_css_unused_suppressions() {
+		let outcome = lint_css_verbose(SUPPRESSIONS, &CssLintConfig::default());
+		let unused: Vec<String> = outcome.unused_suppressions().iter().map(|suppression| suppression.to_string()).collect();
+
+		assert_eq!(unused, vec!["14: `bem-lint-disable-line BEM001` suppresses nothing", "23: `bem-lint-disable-next-line BEM003` suppresses nothing"]);
+		assert!(lint_css_verbose(".a { } /* bem-lint-disable */\n.a__b__c { }", &CssLintConfig::default()).suppressions.is_empty());
+	}
+
+	#[test]
+	fn test_lint_css_triggers_every_rule() {
+		let rules: Vec<CssLintRule> = lint_css(VIOLATIONS, &CssLintConfig::default())
+			.iter()
+			.map(|finding| finding.rule)
+			.collect();
+
+		for rule in [
+			CssLintRule::ElementOfElement,
+			CssLintRule::OrphanModifier,
+			CssLintRule::DeepElementChain,
+			CssLintRule::ForeignModifier,
+		] {
+			assert!(rules.contains(&rule), "{:?} is not triggered", rule);
+		}
+	}
+
+	#[test]
+	fn test_lint_css_clean() {
+		assert_eq!(lint_css(CLEAN, &CssLintConfig::default()), vec![]);
+	}
+
+	#[test]
+	fn test_lint_css_max_element_chain() {
+		let css = ".card__body .card__list .card__item { }";
+
+		assert_eq!(lint_css(css, &CssLintConfig::default())[0].class, "card");
+		assert!(lint_css(css, &CssLintConfig { max_element_chain: 3 }).is_empty());
+	}
+
+	#[test]
+	fn test_lint_css_generated_stylesheet() {
+		let bem_block = crate::parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+
+		assert!(lint_css(&crate::to_css_nested(&bem_block), &CssLintConfig::default()).is_empty());
+	}
+}
@@ -0,0 +1,247 @@
+//! This module looks up elements and modifiers of BEM blocks by name.
+//!
+//! Lookups are exact by default, like the rest of the crate. The `_ignore_case` variants compare
+//! names by their Unicode lowercase mapping instead, so `Button`, `BUTTON` and `button` match,
+//! as do `Élan` and `élan`. They are meant for data whose case was changed along the way, and
+//! should not be used to decide whether two names produce the same class: classes are case
+//! sensitive.
+
+use crate::models::{ BEMBlock, BEMDocument, BEMElement, BEMModifier };
+
+/// Returns whether `a` and `b` are equal once lowercased.
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+	a.chars().flat_map(char::to_lowercase).eq(b.chars().flat_map(char::to_lowercase))
+}
+
+impl BEMBlock {
+	/// Returns the first element named exactly `name`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let bem_block = parse("media-player\nbutton[rewind]").unwrap();
+	///
+	/// assert_eq!(bem_block.get_element("button").unwrap().modifiers, vec!["rewind"]);
+	/// assert!(bem_block.get_element("Button").is_none());
+	/// ```
+	pub fn get_element(&self, name: &str) -> Option<&BEMElement> {
+		self.elements.get(name)
+	}
+
+	/// Returns the first element whose name matches `name` regardless of case. Names are compared
+	/// by their Unicode lowercase mapping, so `Button` matches `button` and `Écran` matches
+	/// `écran`. Unlike exact lookups, several elements may match: use
+	/// [`find_all_ignore_case`](BEMBlock::find_all_ignore_case) to get all of them.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let bem_block = parse("media-player\nbutton[rewind]").unwrap();
+	///
+	/// assert_eq!(bem_block.get_element_ignore_case("BUTTON").unwrap().name, "button");
+	/// ```
+	pub fn get_element_ignore_case(&self, name: &str) -> Option<&BEMElement> {
+		self.elements.iter().find(|element| eq_ignore_case(&element.name, name))
+	}
+
+	/// Returns every element whose name matches `name` regardless of case, in document order.
+	pub fn find_all_ignore_case(&self, name: &str) -> Vec<&BEMElement> {
+		self.elements
+			.iter()
+			.filter(|element| eq_ignore_case(&element.name, name))
+			.collect()
+	}
+
+	/// Returns whether the block has the modifier `name`, compared exactly.
+	pub fn has_modifier(&self, name: &str) -> bool {
+		self.modifiers.iter().any(|modifier| modifier == name)
+	}
+
+	/// Returns whether the block has the modifier `name`, regardless of case.
+	pub fn has_modifier_ignore_case(&self, name: &str) -> bool {
+		self.modifiers.iter().any(|modifier| eq_ignore_case(&modifier.to_string(), name))
+	}
+
+	/// Returns whether every modifier of this block is a modifier of `other`, compared exactly.
+	/// The names of the blocks are not compared.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let compact = parse("media-player[dark]").unwrap();
+	/// let full = parse("media-player[dark,compact]").unwrap();
+	///
+	/// assert!(compact.is_modifier_subset_of(&full));
+	/// assert!(full.is_modifier_superset_of(&compact));
+	/// assert!(!full.is_modifier_subset_of(&compact));
+	/// ```
+	pub fn is_modifier_subset_of(&self, other: &BEMBlock) -> bool {
+		self.modifiers.iter().all(|modifier| other.modifiers.contains(modifier))
+	}
+
+	/// Returns whether every modifier of `other` is a modifier of this block, see
+	/// [`is_modifier_subset_of`](BEMBlock::is_modifier_subset_of).
+	pub fn is_modifier_superset_of(&self, other: &BEMBlock) -> bool {
+		other.is_modifier_subset_of(self)
+	}
+
+	/// Returns whether every element of this block is an element of `other`, comparing their
+	/// names exactly and ignoring their modifiers. The names of the blocks are not compared.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let full = parse("media-player\nbutton[rewind]\ntimeline").unwrap();
+	/// let compact = parse("media-player[compact]\nbutton\ntimeline").unwrap();
+	///
+	/// // The compact variant still has every element of the full version.
+	/// assert!(full.is_element_subset_of(&compact));
+	/// assert!(compact.is_element_superset_of(&full));
+	/// assert!(!parse("media-player\nvolume").unwrap().is_element_subset_of(&compact));
+	/// ```
+	pub fn is_element_subset_of(&self, other: &BEMBlock) -> bool {
+		self.elements.iter().all(|element| other.contains_element(&element.name))
+	}
+
+	/// Returns whether every element of `other` is an element of this block, see
+	/// [`is_element_subset_of`](BEMBlock::is_element_subset_of).
+	pub fn is_element_superset_of(&self, other: &BEMBlock) -> bool {
+		other.is_element_subset_of(self)
+	}
+
+	/// Compares two blocks like `==`, except that names are compared regardless of case.
+	/// Modifiers and elements must still appear in the same order.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	/// use bem::BEMBlock;
+	///
+	/// let bem_block = parse("media-player[dark]\nbutton").unwrap();
+	/// let shouted = BEMBlock { name: "MEDIA-PLAYER".to_string(), ..bem_block.clone() };
+	///
+	/// assert_ne!(bem_block, shouted);
+	/// assert!(bem_block.eq_ignore_case(&shouted));
+	/// ```
+	pub fn eq_ignore_case(&self, other: &BEMBlock) -> bool {
+		eq_ignore_case(&self.name, &other.name)
+			&& modifiers_eq_ignore_case(&self.modifiers, &other.modifiers)
+			&& self.elements.len() == other.elements.len()
+			&& self.elements
+				.iter()
+				.zip(&other.elements)
+				.all(|(a, b)| eq_ignore_case(&a.name, &b.name) && modifiers_eq_ignore_case(&a.modifiers, &b.modifiers))
+	}
+}
+
+impl BEMElement {
+	/// Returns whether the element has the modifier `name`, compared exactly.
+	pub fn has_modifier(&self, name: &str) -> bool {
+		self.modifiers.iter().any(|modifier| modifier == name)
+	}
+
+	/// Returns whether the element has the modifier `name`, regardless of case.
+	pub fn has_modifier_ignore_case(&self, name: &str) -> bool {
+		self.modifiers.iter().any(|modifier| eq_ignore_case(&modifier.to_string(), name))
+	}
+}
+
+impl BEMDocument {
+	/// Returns the first block named exactly `name`.
+	pub fn get_block(&self, name: &str) -> Option<&BEMBlock> {
+		self.blocks.iter().find(|bem_block| bem_block.name == name)
+	}
+}
+
+fn modifiers_eq_ignore_case(a: &[BEMModifier], b: &[BEMModifier]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq_ignore_case(&a.to_string(), &b.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::models::{ BEMBlock, BEMElement };
+	use crate::parse;
+
+	fn element(name: &str, modifiers: &[&str]) -> BEMElement {
+		BEMElement {
+			name: name.to_string(),
+			modifiers: modifiers.iter().map(|modifier| (*modifier).into()).collect(),
+		}
+	}
+
+	#[test]
+	fn test_ascii_case() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward]").unwrap();
+
+		assert!(bem_block.get_element("Button").is_none());
+		assert_eq!(bem_block.get_element_ignore_case("Button"), bem_block.get_element("button"));
+		assert!(!bem_block.has_modifier("DARK"));
+		assert!(bem_block.has_modifier_ignore_case("DARK"));
+		assert!(bem_block.elements[0].has_modifier_ignore_case("Fast-Forward"));
+		assert!(!bem_block.elements[0].has_modifier_ignore_case("rewind"));
+	}
+
+	#[test]
+	fn test_accented_case() {
+		let bem_block = BEMBlock {
+			name: "carte".to_string(),
+			modifiers: vec!["élan".into()],
+			elements: vec![element("écran", &["été"])].into(),
+		};
+		let upper = BEMBlock {
+			name: "CARTE".to_string(),
+			modifiers: vec!["ÉLAN".into()],
+			elements: vec![element("Écran", &["ÉTÉ"])].into(),
+		};
+
+		assert!(bem_block.has_modifier_ignore_case("Élan"));
+		assert_eq!(bem_block.get_element_ignore_case("ÉCRAN").unwrap().name, "écran");
+		assert!(bem_block.eq_ignore_case(&upper));
+		assert!(!bem_block.eq_ignore_case(&BEMBlock { name: "carté".to_string(), ..upper }));
+	}
+
+	#[test]
+	fn test_multiple_matches() {
+		let bem_block = BEMBlock {
+			name: "media-player".to_string(),
+			modifiers: vec![],
+			elements: vec![element("Button", &["a"]), element("timeline", &[]), element("button", &["b"])].into(),
+		};
+
+		assert_eq!(bem_block.get_element_ignore_case("BUTTON").unwrap().modifiers, vec!["a"]);
+		assert_eq!(bem_block.find_all_ignore_case("BUTTON"), vec![&bem_block.elements[0], &bem_block.elements[2]]);
+		assert!(bem_block.find_all_ignore_case("volume").is_empty());
+	}
+
+	#[test]
+	fn test_subsets() {
+		let bem_block = parse("media-player[dark,light]\nbutton[rewind]\ntimeline").unwrap();
+		let empty = BEMBlock::new("play-list");
+
+		assert!(bem_block.is_modifier_subset_of(&parse("media-player[light,compact,dark]").unwrap()));
+		assert!(!bem_block.is_modifier_subset_of(&BEMBlock::new("media-player").with_modifier("DARK").with_modifier("light")));
+		assert!(bem_block.is_element_subset_of(&parse("media-player\ntimeline\nvolume\nbutton[play]").unwrap()));
+		assert!(!bem_block.is_element_subset_of(&parse("media-player\nbutton").unwrap()));
+		assert!(empty.is_modifier_subset_of(&bem_block) && empty.is_element_subset_of(&bem_block));
+		assert!(bem_block.is_modifier_superset_of(&bem_block) && bem_block.is_element_superset_of(&empty));
+		assert!(!empty.is_element_superset_of(&bem_block));
+	}
+
+	#[test]
+	fn test_eq_ignore_case_is_structural() {
+		let bem_block = parse("media-player[dark,light]\nbutton").unwrap();
+
+		assert!(!bem_block.eq_ignore_case(&parse("media-player[light,dark]\nbutton").unwrap()));
+		assert!(!bem_block.eq_ignore_case(&parse("media-player[dark,light]").unwrap()));
+		assert!(!bem_block.eq_ignore_case(&parse("media-player[dark]\nbutton").unwrap()));
+	}
+}
@@ -8,6 +8,44 @@
 //!
 //! - `cat media-player.bem | bem`: Parse BEM notation from stdin.
 //! - `echo [INPUT] | bem`: Parse BEM notation from piped input.
+//! - `bem --format safelist --safelist-mode pattern media-player.bem`: Generate a PurgeCSS safelist.
+//! - `bem --format css media-player.bem`: Generate a stylesheet skeleton, or any other registered format.
+//! - `bem --format jcs --checksum sha256 -o media-player.json media-player.bem`: Write canonical JSON and its checksum.
+//! - `bem --checksum sha256 --verify -o media-player.json`: Check an output file against its checksum.
+//! - `bem audit design/ --html 'public/**/*.html' --css 'src/**/*.css' --js 'src/**/*.tsx' --usage-report usage.csv`: Report naming inconsistencies and how often each entity is used.
+//! - `bem diff --impact old/ new/`: Classify the changes between two versions by semver impact.
+//! - `bem diff --changelog old/ new/`: Write Markdown release notes for the changes between two versions.
+//! - `bem generate --seed 42 --elements 5..10`: Generate a random block, e.g. for fixtures.
+//! - `bem patch --merge patch.json media-player.bem`: Apply a JSON merge patch, e.g. from a CMS, and print the result.
+//! - `bem lint --css styles.css`: Report selectors that break the structure of BEM, like elements of elements.
+//! - `bem lint --report-unused-suppressions --css styles.css`: Also report `bem-lint-disable` comments that suppress nothing.
+//! - `bem --lint-css styles.css --format css media-player.bem`: Lint stylesheets first, and only emit if they follow BEM.
+//! - `bem --summary-file summary.json -o design.json design/`: Emit every `.bem` file of a directory and report what happened.
+//! - `bem --grammar-version 1 media-player.bem`: Check that a file only uses syntax of an older grammar version.
+//! - `bem --format scss --include 'button*' --exclude 'x-*' media-player.bem`: Only emit some of the elements.
+//! - `bem --format css --order alpha media-player.bem`: Emit elements and modifiers in alphabetical order, for stable diffs.
+//! - `bem --normalize media-player.bem`: Sort elements and modifiers and drop repeated modifiers, for diff-friendly JSON.
+//! - `bem --split-by-modifier --out-dir themes/ media-player.bem`: Write the CSS of each block modifier, like a dark theme, to its own file.
+//! - `bem --no-atomic -o /mnt/share/design.json design/`: Write outputs in place, where renaming over a file fails.
+//! - `bem design-system.bem`: Emit a JSON array for a file holding several blocks, separated by blank lines.
+//! - `bem --format yaml media-player.bem`: Write YAML, when built with the `yaml` feature.
+//! - `bem --format bem < media-player.yaml`: Convert YAML back to `.bem` text, detected from its first line with the `yaml` feature.
+//! - `bem --pretty media-player.bem`: Print indented JSON, for reading in a terminal or a diff.
+//! - `bem --output-format css-flat media-player.bem`: Scaffold a stylesheet with one flat rule per class, without CSS nesting.
+//! - `bem --output-format scss media-player.bem`: Scaffold SCSS that nests modifiers as `&--modifier`.
+//! - `bem --format css-modules --ts-decl -o MediaPlayer.module.css media-player.bem`: Write a CSS Modules stylesheet and its TypeScript declarations.
+//! - `bem --format jsx --abbreviations abbreviations.json navigation-drawer.bem`: Generate React components with identifiers shortened by a team's abbreviations, like `navigation` into `nav`.
+//! - `bem --format classes --element-sep - --modifier-sep _ media-player.bem`: List classes for a project with its own separators.
+//! - `bem --output-format bem --pretty media-player.json`: Convert JSON back to `.bem` text, one modifier per line.
+//! - `bem --format css media-player.json`: Convert JSON from an earlier run, detected from its first bytes.
+//! - `bem --from json --format bem < blocks.txt`: Set the input format when it cannot be detected.
+//! - `bem --reverse < media-player.json`: Convert JSON back to `.bem` text, short for `--from json --format bem`.
+//! - `bem --deny-warnings design/`: Fail on inputs that parse with warnings, like elements listed twice.
+//! - `bem --validate design/`: Check the naming conventions in CI, exiting with code 2 on violations.
+//! - `bem schema-dump --json design/*.bem`: Dump blocks, classes, and source locations for editor plugins.
+//! - `bem grep '^fast-' design/*.bem`: Print the blocks, elements, and modifiers whose names match a regex.
+//! - `bem doctor media-player.bem`: Report the versions of the tool and what may keep a file from parsing.
+//! - `bem scan --js 'src/**/*.tsx'`: Bootstrap BEM notation from the class names of JS/TS sources.
 //!
 //! # Examples
 //!
@@ -17,145 +55,672 @@
 //! ```
 //!
 //! Please refer to the individual command documentation for detailed information and options.
+//!
+//! # Summary file
+//!
+//! `--summary-file` writes a JSON report of the run, whether it succeeds or not. When the input
+//! is a directory, files that fail to parse are reported and skipped, the others are emitted, and
+//! the run still fails. The report is an object with these keys, which are stable within a
+//! `schema` version:
+//!
+//! - `schema`: The version of this schema, currently `1`.
+//! - `ok`: Whether the run succeeded.
+//! - `inputs`: One object per input in processing order, with its `path` (`<stdin>` for stdin),
+//!   its `status` (`ok` or `error`), and its `error`: `null`, or an object holding a stable `code`
+//!   (see [`bem::Error::code`], or `denied-warnings` with `--deny-warnings`) and a `message`.
+//! - `totals`: The numbers of `blocks`, `elements`, and `modifiers` emitted.
+//! - `duration_ms`: The wall-clock duration of the run, in milliseconds.
+//! - `outputs`: The paths of the files written, including checksum files.
+//!
+//! With `--summary-file -`, the report is written to stderr as a single line.
+//!
+//! # Output formats
+//!
+//! `--format` selects an emitter of the [`EmitterRegistry`] by id. The built-in formats are
+//! `json` (the default), `json-with-ids`, `jcs`, `safelist`, `css`, `css-flat`, `css-modules`,
+//! `scss`, `scss-placeholders`, `classes`, `bem`, `theme-ts`, `jsx` and `react-native`, whose
+//! identifiers `--abbreviations` shortens. Tools
+//! embedding this CLI add their own formats by registering an [`Emitter`](bem::Emitter) on a
+//! registry and passing it to [`bem::pipeline::run_with_registry`], which makes the emitter
+//! selectable with the format of its [`RunOptions`].
+//!
+//! # Embedding
+//!
+//! Everything runs through [`bem::pipeline::run`], which takes the flags as [`RunOptions`] and
+//! reaches files and streams through a [`PipelineIo`](bem::pipeline::PipelineIo), so other tools
+//! can run the same pipeline without spawning this binary. Each subcommand has its own function
+//! there, like [`bem::pipeline::run_lint`] for `bem lint`.
 
-use clap::Parser;
-use std::fs::File;
-use std::io::{ self, Read, Write };
-use bem::parse;
+use clap::{ Args, Parser, Subcommand, ValueEnum };
+use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
+use bem::{ BemConfig, CssLintConfig, EmitOrder, EmitterRegistry, GenerateConfig, GenerateFilter, Glob, InputFormat, SafelistMode, SearchScope, GRAMMAR_VERSION };
+use bem::pipeline::{
+	self, AuditOptions, DiffOptions, DoctorOptions, GenerateOptions, GrepOptions, LintOptions, PatchOptions, RunOptions, ScanOptions, SchemaDumpOptions, StdIo,
+	StdinReader, StringReader, DEFAULT_FORMAT,
+};
 
-pub trait StringReader {
-	fn read_to_string(&mut self, s: &mut String) -> io::Result<()>;
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum ChecksumAlgorithm {
+	/// SHA-256, written as `OUTPUT_FILE.sha256` in the format of `sha256sum`
+	Sha256,
 }
 
-pub struct StdinReader;
+impl From<ChecksumAlgorithm> for pipeline::ChecksumAlgorithm {
+	fn from(algorithm: ChecksumAlgorithm) -> Self {
+		match algorithm {
+			ChecksumAlgorithm::Sha256 => pipeline::ChecksumAlgorithm::Sha256,
+		}
+	}
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+enum SafelistModeArg {
+	/// List every class literally
+	#[default]
+	Exact,
+	/// List one regex pattern per block
+	Pattern,
+}
 
-impl StringReader for StdinReader {
-	#[cfg(not(tarpaulin_include))]
-	fn read_to_string(&mut self, s: &mut String) -> io::Result<()> {
-		io::stdin()
-			.read_to_string(s)
-			.map(|_| ())
+impl From<SafelistModeArg> for SafelistMode {
+	fn from(mode: SafelistModeArg) -> Self {
+		match mode {
+			SafelistModeArg::Exact => SafelistMode::Exact,
+			SafelistModeArg::Pattern => SafelistMode::Pattern,
+		}
 	}
 }
 
-trait ContentReader {
-	fn read_content(&mut self) -> io::Result<String>;
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+enum OrderArg {
+	/// Alphabetical order, for stable diffs
+	Alpha,
+	/// The order of the source
+	#[default]
+	Source,
 }
 
-struct FileOrStdinReader {
-	input_file: Option<String>,
+impl From<OrderArg> for EmitOrder {
+	fn from(order: OrderArg) -> Self {
+		match order {
+			OrderArg::Alpha => EmitOrder::Alphabetical,
+			OrderArg::Source => EmitOrder::SourceOrder,
+		}
+	}
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum FromArg {
+	/// The `.bem` syntax
+	Bem,
+	/// A block as written by `--format json`, or an array of blocks
+	Json,
+	/// A block as written by `--format yaml`, or a sequence of blocks
+	#[cfg(feature = "yaml")]
+	Yaml,
 }
 
-fn read_input_file_or_stdin<R: StringReader>(
-	input_file: Option<&str>,
-	bem_input: &mut String,
-	reader: &mut R
-) -> io::Result<()> {
-	if let Some(input_file) = input_file {
-		File::open(input_file)?.read_to_string(bem_input)?;
-	} else {
-		reader.read_to_string(bem_input)?;
+impl From<FromArg> for InputFormat {
+	fn from(format: FromArg) -> Self {
+		match format {
+			FromArg::Bem => InputFormat::Bem,
+			FromArg::Json => InputFormat::Json,
+			#[cfg(feature = "yaml")]
+			FromArg::Yaml => InputFormat::Yaml,
+		}
 	}
-	Ok(())
 }
 
-impl ContentReader for FileOrStdinReader {
-	fn read_content(&mut self) -> io::Result<String> {
-		let mut bem_input = String::new();
-		let mut reader = StdinReader;
-		read_input_file_or_stdin(self.input_file.as_deref(), &mut bem_input, &mut reader)?;
-		Ok(bem_input)
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+enum SearchScopeArg {
+	/// Block names
+	Blocks,
+	/// Element names
+	Elements,
+	/// Modifiers of blocks and elements
+	Modifiers,
+	/// Every name
+	#[default]
+	Any,
+}
+
+impl From<SearchScopeArg> for SearchScope {
+	fn from(scope: SearchScopeArg) -> Self {
+		match scope {
+			SearchScopeArg::Blocks => SearchScope::Blocks,
+			SearchScopeArg::Elements => SearchScope::Elements,
+			SearchScopeArg::Modifiers => SearchScope::Modifiers,
+			SearchScopeArg::Any => SearchScope::Any,
+		}
 	}
 }
 
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[derive(Parser, Debug, Default)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Cli {
-	/// Input file name (default: <stdin>)
+	#[command(subcommand)]
+	command: Option<Command>,
+
+	/// Input file name, or a directory whose `.bem` files are emitted together (default: <stdin>)
 	#[arg(value_name = "INPUT_FILE")]
 	input_file: Option<String>,
 
+	/// Input format, detected from the first bytes of each input unless set
+	#[arg(long, value_enum, value_name = "FORMAT")]
+	from: Option<FromArg>,
+
 	/// Output file name (default: <stdout>)
 	#[arg(short, long, value_name = "OUTPUT_FILE")]
 	out: Option<String>,
+
+	/// Output format: json, json-with-ids, jcs, safelist, css, css-flat, css-modules, scss, scss-placeholders, classes, bem, theme-ts, jsx, or react-native (default: json)
+	#[arg(short, long, visible_alias = "output-format", value_name = "FORMAT")]
+	format: Option<String>,
+
+	/// Whether `--format safelist` lists exact classes or patterns
+	#[arg(long, value_enum, default_value_t = SafelistModeArg::Exact)]
+	safelist_mode: SafelistModeArg,
+
+	/// Write a checksum of the output file next to it
+	#[arg(long, value_enum, value_name = "ALGORITHM", requires = "out")]
+	checksum: Option<ChecksumAlgorithm>,
+
+	/// Check the output file against its checksum file instead of writing it
+	#[arg(long, requires = "checksum")]
+	verify: bool,
+
+	/// Write a JSON report of the run to this file, or to stderr as a single line if `-`
+	#[arg(long, value_name = "SUMMARY_FILE")]
+	summary_file: Option<String>,
+
+	/// Parse as an older grammar version would, rejecting newer syntax (default: the latest)
+	#[arg(long, value_name = "VERSION", value_parser = clap::value_parser!(u32).range(1..=GRAMMAR_VERSION as i64))]
+	grammar_version: Option<u32>,
+
+	/// Fail on inputs that parse with warnings, which are otherwise only printed to stderr
+	#[arg(long)]
+	deny_warnings: bool,
+
+	/// Check the names of every block against the BEM naming conventions, printing every violation and exiting with code 2 if any
+	#[arg(long)]
+	validate: bool,
+
+	/// Only emit the elements whose name matches one of these globs, e.g. `button*` (css, css-flat, css-modules, scss, scss-placeholders, classes, safelist)
+	#[arg(long, value_name = "GLOB")]
+	include: Vec<Glob>,
+
+	/// Leave out the elements whose name matches one of these globs, even if included
+	#[arg(long, value_name = "GLOB")]
+	exclude: Vec<Glob>,
+
+	/// Filter modifiers with `--include` and `--exclude` too
+	#[arg(long)]
+	filter_modifiers: bool,
+
+	/// The order of elements and modifiers in the output
+	#[arg(long, value_enum, default_value_t = OrderArg::Source)]
+	order: OrderArg,
+
+	/// Sort elements and modifiers and drop repeated modifiers before emitting, for diff-friendly outputs
+	#[arg(long)]
+	normalize: bool,
+
+	/// The directory `--split-by-modifier` writes its stylesheets to
+	#[arg(long, value_name = "DIR", requires = "split_by_modifier")]
+	out_dir: Option<String>,
+
+	/// Write one stylesheet per block, and one per block modifier with the elements scoped inside it
+	#[arg(long, requires = "out_dir", conflicts_with_all = ["out", "format"])]
+	split_by_modifier: bool,
+
+	/// Indent JSON output, or write modifier lists one modifier per line (json, bem)
+	#[arg(long)]
+	pretty: bool,
+
+	/// The separator between the block and element of a class, `__` by default
+	#[arg(long, value_name = "SEP")]
+	element_sep: Option<String>,
+
+	/// The separator before the modifier of a class, `--` by default
+	#[arg(long, value_name = "SEP")]
+	modifier_sep: Option<String>,
+
+	/// Also write the TypeScript declarations of the css-modules format, as OUTPUT_FILE.d.ts
+	#[arg(long, requires = "out")]
+	ts_decl: bool,
+
+	/// A JSON file of abbreviations of name segments, like `{"navigation": "nav"}`, for the identifiers of theme-ts, jsx, and react-native
+	#[arg(long, value_name = "FILE")]
+	abbreviations: Option<String>,
+
+	/// Lint these stylesheets before emitting, printing findings to stderr and failing if there are any
+	#[arg(long, value_name = "FILE")]
+	lint_css: Vec<String>,
+
+	/// Convert JSON back to `.bem` text, short for `--from json --format bem`
+	#[arg(long, visible_alias = "from-json", conflicts_with_all = ["from", "format", "split_by_modifier"])]
+	reverse: bool,
+
+	/// Write output files in place instead of renaming a temporary file over them, for file systems where that fails
+	#[arg(long, global = true)]
+	no_atomic: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+	/// Audit the naming of BEM files across blocks, and report how often each entity is used
+	Audit(AuditArgs),
+	/// Compare two versions of BEM files, or of directories of `.bem` files
+	Diff(DiffArgs),
+	/// Report the versions of the tool, and the encoding, line endings, and failing lines of a file
+	Doctor(DoctorArgs),
+	/// Generate a random block, always the same for a given seed
+	Generate(GenerateArgs),
+	/// Print the blocks, elements, and modifiers whose names match a regex
+	Grep(GrepArgs),
+	/// Check stylesheets for selectors that break the structure of BEM
+	Lint(LintArgs),
+	/// Apply a JSON merge patch (RFC 7386) to a BEM file and print the result
+	Patch(PatchArgs),
+	/// Reconstruct BEM notation from the class name literals of JS/TS source files
+	Scan(ScanArgs),
+	/// Dump the blocks, classes, and source locations of BEM files for editor plugins
+	SchemaDump(SchemaDumpArgs),
+}
+
+#[derive(Args, Debug, Default)]
+struct AuditArgs {
+	/// The BEM files, or directories of `.bem` files, of the design system
+	#[arg(value_name = "FILE", required = true)]
+	files: Vec<String>,
+
+	/// Markup files to count class uses in, as paths or globs
+	#[arg(long, value_name = "GLOB")]
+	html: Vec<String>,
+
+	/// Stylesheets to count class uses in, as paths or globs
+	#[arg(long, value_name = "GLOB")]
+	css: Vec<String>,
+
+	/// JS/TS files to count class uses in, as paths or globs
+	#[arg(long, value_name = "GLOB")]
+	js: Vec<String>,
+
+	/// Write the uses of every entity per kind of source to this file, as JSON if it ends in `.json` and CSV otherwise
+	#[arg(long, value_name = "REPORT_FILE")]
+	usage_report: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+struct DiffArgs {
+	/// The published version
+	#[arg(value_name = "OLD")]
+	old: String,
+
+	/// The version about to be published
+	#[arg(value_name = "NEW")]
+	new: String,
+
+	/// Print the semver impact of the changes before the changes themselves
+	#[arg(long)]
+	impact: bool,
+
+	/// Print Markdown release notes instead of the list of changes
+	#[arg(long, conflicts_with = "impact")]
+	changelog: bool,
+}
+
+#[derive(Args, Debug, Default)]
+struct DoctorArgs {
+	/// The BEM file to diagnose (default: only report the environment)
+	#[arg(value_name = "INPUT")]
+	input: Option<String>,
+
+	/// Print the diagnosis as JSON
+	#[arg(long)]
+	json: bool,
+}
+
+#[derive(Args, Debug, Default)]
+struct GenerateArgs {
+	/// The seed of the generator
+	#[arg(long, default_value_t = 0)]
+	seed: u64,
+
+	/// The number of elements, as a count or a half-open range like `5..10`
+	#[arg(long, value_name = "RANGE", value_parser = parse_range)]
+	elements: Option<Range<usize>>,
+
+	/// The number of modifiers of the block and of each element, as a count or a range
+	#[arg(long, value_name = "RANGE", value_parser = parse_range)]
+	modifiers: Option<Range<usize>>,
+
+	/// Output format (default: bem)
+	#[arg(short, long, value_name = "FORMAT")]
+	format: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+struct GrepArgs {
+	/// The regex names are matched against, anywhere in the name unless anchored
+	#[arg(value_name = "PATTERN")]
+	pattern: String,
+
+	/// The BEM files, or directories of `.bem` files, to search
+	#[arg(value_name = "FILE", required = true)]
+	files: Vec<String>,
+
+	/// Which names to search
+	#[arg(long, value_enum, default_value_t = SearchScopeArg::Any)]
+	scope: SearchScopeArg,
+
+	/// Print the matches as a JSON array, with their capture groups
+	#[arg(long)]
+	json: bool,
+}
+
+#[derive(Args, Debug)]
+struct LintArgs {
+	/// The stylesheets to lint
+	#[arg(long, value_name = "FILE", required = true)]
+	css: Vec<String>,
+
+	/// The number of elements of the same block a selector may chain
+	#[arg(long, value_name = "COUNT", default_value_t = CssLintConfig::default().max_element_chain)]
+	max_element_chain: usize,
+
+	/// Also report, and fail on, `bem-lint-disable` comments that suppress nothing
+	#[arg(long)]
+	report_unused_suppressions: bool,
+}
+
+#[derive(Args, Debug, Default)]
+struct PatchArgs {
+	/// The JSON merge patch to apply
+	#[arg(long, value_name = "PATCH_FILE")]
+	merge: String,
+
+	/// The BEM file to patch
+	#[arg(value_name = "INPUT")]
+	input: String,
+
+	/// Output format (default: bem)
+	#[arg(short, long, value_name = "FORMAT")]
+	format: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+struct ScanArgs {
+	/// The JS/TS files to scan, as paths or globs like `src/**/*.tsx` where `*` also matches `/`
+	#[arg(long, value_name = "GLOB", required = true)]
+	js: Vec<String>,
+
+	/// Output format (default: bem)
+	#[arg(short, long, value_name = "FORMAT")]
+	format: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+struct SchemaDumpArgs {
+	/// The BEM files, or directories of `.bem` files, to dump
+	#[arg(value_name = "FILE", required = true)]
+	files: Vec<String>,
+
+	/// Print the versioned JSON structure documented on `bem::SchemaDump`, instead of one class per line
+	#[arg(long)]
+	json: bool,
+}
+
+/// Parses a count (`5`) or a half-open range (`5..10`).
+fn parse_range(value: &str) -> Result<Range<usize>, String> {
+	let bound = |bound: &str| bound
+		.trim()
+		.parse::<usize>()
+		.map_err(|_| format!("invalid count `{}`", bound));
+
+	match value.split_once("..") {
+		Some((start, end)) => {
+			let (start, end) = (bound(start)?, bound(end)?);
+
+			if start >= end {
+				return Err(format!("empty range `{}`", value));
+			}
+
+			Ok(start..end)
+		}
+		None => bound(value).map(|count| count..count + 1),
+	}
+}
+
+/// Returns the [`StdIo`] the subcommands run with.
+fn std_io(in_place: bool) -> StdIo {
+	StdIo { stdin: StdinReader, in_place }
+}
+
+fn run_generate(args: &GenerateArgs) -> io::Result<String> {
+	let defaults = GenerateConfig::default();
+	let options = GenerateOptions {
+		seed: args.seed,
+		config: GenerateConfig {
+			elements: args.elements.clone().unwrap_or(defaults.elements),
+			modifiers: args.modifiers.clone().unwrap_or(defaults.modifiers),
+			..defaults
+		},
+		format: args.format.clone().unwrap_or_else(|| GenerateOptions::default().format),
+	};
+
+	Ok(pipeline::run_generate(&options, &mut std_io(false))?)
+}
+
+/// Prints one line per finding, prefixed with the stylesheet, and fails if there are any.
+fn run_lint(args: &LintArgs) -> io::Result<String> {
+	let options = LintOptions {
+		css: args.css.iter().map(PathBuf::from).collect(),
+		config: CssLintConfig { max_element_chain: args.max_element_chain },
+		report_unused_suppressions: args.report_unused_suppressions,
+	};
+
+	Ok(pipeline::run_lint(&options, &mut std_io(false))?)
+}
+
+fn run_grep(args: &GrepArgs) -> io::Result<String> {
+	let options = GrepOptions {
+		pattern: args.pattern.clone(),
+		files: args.files.iter().map(PathBuf::from).collect(),
+		scope: args.scope.into(),
+		json: args.json,
+	};
+
+	Ok(pipeline::run_grep(&options, &mut std_io(false))?)
+}
+
+/// Prints the versions of the tool, and with an input the diagnosis of the file, as text or JSON.
+fn run_doctor(args: &DoctorArgs) -> io::Result<String> {
+	let options = DoctorOptions { input: args.input.as_deref().map(PathBuf::from), json: args.json };
+
+	Ok(pipeline::run_doctor(&options, &mut std_io(false))?)
+}
+
+/// Applies a JSON merge patch to a BEM file and prints the patched block in the requested format.
+fn run_patch(args: &PatchArgs) -> io::Result<String> {
+	let options = PatchOptions {
+		merge: args.merge.clone().into(),
+		input: args.input.clone().into(),
+		format: args.format.clone().unwrap_or_else(|| PatchOptions::default().format),
+	};
+
+	Ok(pipeline::run_patch(&options, &mut std_io(false))?)
+}
+
+/// Prints the findings of the design audit of every block against the others, and writes the
+/// usage report of the sources if asked to, in place if `in_place` is set, see `--no-atomic`.
+fn run_audit(args: &AuditArgs, in_place: bool) -> io::Result<String> {
+	let options = AuditOptions {
+		files: args.files.iter().map(PathBuf::from).collect(),
+		html: args.html.clone(),
+		css: args.css.clone(),
+		js: args.js.clone(),
+		usage_report: args.usage_report.as_deref().map(PathBuf::from),
+	};
+
+	Ok(pipeline::run_audit(&options, &mut std_io(in_place))?)
+}
+
+/// Scans the files matching `--js`, prints their warnings to stderr, and emits the blocks found
+/// across all of them, merged by name.
+fn run_scan(args: &ScanArgs) -> io::Result<String> {
+	let options = ScanOptions {
+		js: args.js.clone(),
+		format: args.format.clone().unwrap_or_else(|| ScanOptions::default().format),
+	};
+
+	Ok(pipeline::run_scan(&options, &mut std_io(false))?)
+}
+
+/// Prints every class of the files with its location, or the schema dump as JSON.
+fn run_schema_dump(args: &SchemaDumpArgs) -> io::Result<String> {
+	let options = SchemaDumpOptions { files: args.files.iter().map(PathBuf::from).collect(), json: args.json };
+
+	Ok(pipeline::run_schema_dump(&options, &mut std_io(false))?)
+}
+
+fn run_diff(args: &DiffArgs) -> io::Result<String> {
+	let options = DiffOptions {
+		old: args.old.clone().into(),
+		new: args.new.clone().into(),
+		impact: args.impact,
+		changelog: args.changelog,
+	};
+
+	Ok(pipeline::run_diff(&options, &mut std_io(false))?)
 }
 
-fn run_cli<R: ContentReader>(mut reader: R, out: Option<String>) -> io::Result<String> {
-	let bem_input = reader.read_content()?;
+/// Returns the options of the pipeline for the flags of `cli`.
+fn run_options(cli: &Cli) -> RunOptions {
+	RunOptions {
+		input: cli.input_file.as_deref().map(PathBuf::from),
+		from: match cli.reverse {
+			true => Some(InputFormat::Json),
+			false => cli.from.map(Into::into),
+		},
+		out: cli.out.as_deref().map(PathBuf::from),
+		format: match cli.reverse {
+			true => "bem".to_string(),
+			false => cli.format.clone().unwrap_or_else(|| DEFAULT_FORMAT.to_string()),
+		},
+		safelist_mode: cli.safelist_mode.into(),
+		checksum: cli.checksum.map(Into::into),
+		verify: cli.verify,
+		summary_file: cli.summary_file.as_deref().map(PathBuf::from),
+		grammar_version: cli.grammar_version.unwrap_or(GRAMMAR_VERSION),
+		deny_warnings: cli.deny_warnings,
+		validate: cli.validate,
+		filter: GenerateFilter { include: cli.include.clone(), exclude: cli.exclude.clone(), modifiers: cli.filter_modifiers },
+		order: cli.order.into(),
+		normalize: cli.normalize,
+		out_dir: cli.out_dir.as_deref().map(PathBuf::from),
+		split_by_modifier: cli.split_by_modifier,
+		pretty: cli.pretty,
+		naming: bem_config(cli),
+		ts_decl: cli.ts_decl,
+		lint: match cli.lint_css.is_empty() {
+			true => None,
+			false => Some(LintOptions { css: cli.lint_css.iter().map(PathBuf::from).collect(), ..LintOptions::default() }),
+		},
+		abbreviations: cli.abbreviations.as_deref().map(PathBuf::from),
+	}
+}
 
-	let bem_block = parse(&bem_input).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
-	let json_output = bem::to_json(&bem_block)?;
+/// Returns the separators of `--element-sep` and `--modifier-sep`, standard BEM for those not given.
+fn bem_config(cli: &Cli) -> BemConfig {
+	let default = BemConfig::default();
 
-	if let Some(out) = out.as_deref() {
-		File::create(out)?.write_all(json_output.as_bytes())?;
-	} else {
-		io::stdout().write_all(json_output.as_bytes())?;
+	BemConfig {
+		element_separator: cli.element_sep.clone().unwrap_or(default.element_separator),
+		modifier_separator: cli.modifier_sep.clone().unwrap_or(default.modifier_separator),
 	}
+}
+
+fn run_cli<R: StringReader>(stdin: R, cli: &Cli) -> io::Result<String> {
+	run_cli_with_registry(stdin, cli, &EmitterRegistry::new())
+}
+
+/// Runs the pipeline with the output formats of `registry`, so `--format` can select custom
+/// emitters, see [`pipeline::run`].
+fn run_cli_with_registry<R: StringReader>(stdin: R, cli: &Cli, registry: &EmitterRegistry) -> io::Result<String> {
+	let report = pipeline::run_with_registry(&run_options(cli), &mut StdIo { stdin, in_place: cli.no_atomic }, registry)?;
 
-	Ok(json_output)
+	Ok(String::from_utf8_lossy(&report.output).into_owned())
 }
 
 #[cfg(not(tarpaulin_include))]
 fn main() {
 	let cli = Cli::parse();
-	let reader = FileOrStdinReader { input_file: cli.input_file };
-	if let Err(e) = run_cli(reader, cli.out) {
+	let result = match &cli.command {
+		Some(Command::Audit(args)) => run_audit(args, cli.no_atomic),
+		Some(Command::Diff(args)) => run_diff(args),
+		Some(Command::Doctor(args)) => run_doctor(args),
+		Some(Command::Generate(args)) => run_generate(args),
+		Some(Command::Grep(args)) => run_grep(args),
+		Some(Command::Lint(args)) => run_lint(args),
+		Some(Command::Patch(args)) => run_patch(args),
+		Some(Command::Scan(args)) => run_scan(args),
+		Some(Command::SchemaDump(args)) => run_schema_dump(args),
+		None => run_cli(StdinReader, &cli),
+	};
+	if let Err(e) = result {
 		eprintln!("An error occurred: {}", e);
-		std::process::exit(1);
+		std::process::exit(exit_code(&e));
+	}
+}
+
+/// Returns the exit code of a failed run: 2 if names break the naming conventions, see
+/// `--validate`, and 1 otherwise.
+fn exit_code(error: &io::Error) -> i32 {
+	match error.get_ref().and_then(|error| error.downcast_ref::<bem::Error>()) {
+		Some(error) if error.code() == "validation" => 2,
+		_ => 1,
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::{ run_cli, FileOrStdinReader };
-	use tempfile::NamedTempFile;
+	use super::{
+		exit_code, parse_range, run_audit, run_cli, run_cli_with_registry, run_diff, run_doctor, run_generate, run_grep, run_lint, run_patch, run_scan, run_schema_dump,
+		AuditArgs, ChecksumAlgorithm, Cli, DiffArgs, DoctorArgs, GenerateArgs, GrepArgs, LintArgs, PatchArgs, SafelistModeArg, ScanArgs, SchemaDumpArgs,
+		SearchScopeArg,
+	};
+	use clap::Parser;
+	use bem::{ BEMDocument, EmitContext, EmitError, Emitter, EmitterRegistry };
+	use tempfile::{ tempdir, NamedTempFile };
 
-	pub struct MockInputReader {
+	struct MockStdinReader {
 		content: String,
 	}
 
-	impl super::StringReader for MockInputReader {
+	impl super::StringReader for MockStdinReader {
 		fn read_to_string(&mut self, s: &mut String) -> std::io::Result<()> {
 			s.push_str(&self.content);
 			Ok(())
 		}
 	}
 
-	struct MockStdinReader {
-		content: String,
-	}
-
-	impl super::ContentReader for MockStdinReader {
-		fn read_content(&mut self) -> std::io::Result<String> {
-			Ok(self.content.clone())
-		}
-	}
-
 	const VALID_CONTENT: &str = "media-player[dark]\nbutton[fast-forward,rewind]\ntimeline";
 	const INVALID_CONTENT: &str = "media-player(dark)";
 
-	#[test]
-	fn test_read_input_file_or_stdin_with_stdin() {
-		let content = "content from standard input".to_string();
-		let mut mock_reader = MockInputReader { content };
-		let mut bem_input = String::new();
-
-		let result = super::read_input_file_or_stdin(None, &mut bem_input, &mut mock_reader);
-
-		assert!(result.is_ok());
-		assert_eq!(bem_input, "content from standard input");
-	}
-
 	#[test]
 	fn test_run_cli_with_stdin() {
 		let mock_reader = MockStdinReader {
 			content: VALID_CONTENT.to_string(),
 		};
 		let temp_output_file = NamedTempFile::new().unwrap();
-		let result = run_cli(
-			mock_reader,
-			Some(temp_output_file.path().to_str().unwrap().to_string())
-		);
+		let cli = Cli {
+			out: Some(temp_output_file.path().to_str().unwrap().to_string()),
+			..Cli::default()
+		};
+		let result = run_cli(mock_reader, &cli);
 
 		assert!(result.is_ok());
 		let output_content = std::fs::read_to_string(temp_output_file.path()).unwrap();
@@ -167,12 +732,117 @@ mod tests {
 		let mock_reader = MockStdinReader {
 			content: VALID_CONTENT.to_string(),
 		};
-		let result = run_cli(mock_reader, None);
+		let result = run_cli(mock_reader, &Cli::default());
+
+		assert!(result.is_ok());
+		insta::assert_snapshot!(result.unwrap());
+	}
+
+	#[cfg(feature = "yaml")]
+	#[test]
+	fn test_run_cli_with_yaml() {
+		let reader = MockStdinReader { content: "media-player[dark]".to_string() };
+		let cli = Cli::try_parse_from(["bem", "--format", "yaml"]).unwrap();
+
+		assert_eq!(run_cli(reader, &cli).unwrap(), "name: media-player\nmodifiers:\n- dark\nelements: []\n");
+	}
+
+	#[cfg(feature = "yaml")]
+	#[test]
+	fn test_run_cli_from_yaml() {
+		let reader = |content: &str| MockStdinReader { content: content.to_string() };
+		let cli = Cli::try_parse_from(["bem", "--format", "bem"]).unwrap();
+		let from_yaml = Cli::try_parse_from(["bem", "--from", "yaml", "--format", "bem"]).unwrap();
+		let blocks = "- name: media-player\n  modifiers: [dark]\n  elements: []\n- name: play-list\n  modifiers: []\n  elements: []\n";
+		// A YAML flow mapping starts like JSON.
+		let ambiguous = "{name: media-player, modifiers: [], elements: []}";
+
+		assert_eq!(run_cli(reader("name: media-player\nmodifiers:\n- dark\nelements: []\n"), &cli).unwrap(), "media-player[dark]\n");
+		assert_eq!(run_cli(reader(blocks), &cli).unwrap(), "media-player[dark]\n\nplay-list\n");
+		assert!(run_cli(reader(ambiguous), &cli).unwrap_err().to_string().starts_with("invalid JSON"));
+		assert_eq!(run_cli(reader(ambiguous), &from_yaml).unwrap(), "media-player\n");
+		assert!(run_cli(reader("name: media-player"), &cli).unwrap_err().to_string().contains("missing field `modifiers`"));
+	}
+
+	#[test]
+	fn test_run_cli_with_pretty() {
+		let mock_reader = MockStdinReader {
+			content: VALID_CONTENT.to_string(),
+		};
+		let cli = Cli::try_parse_from(["bem", "--pretty"]).unwrap();
+		let result = run_cli(mock_reader, &cli);
 
 		assert!(result.is_ok());
 		insta::assert_snapshot!(result.unwrap());
 	}
 
+	#[test]
+	fn test_run_cli_with_several_blocks() {
+		let mock_reader = MockStdinReader {
+			content: "media-player[dark]\nbutton\n\nplay-list".to_string(),
+		};
+		let cli = Cli::try_parse_from(["bem"]).unwrap();
+
+		assert_eq!(
+			run_cli(mock_reader, &cli).unwrap(),
+			"[{\"name\":\"media-player\",\"modifiers\":[\"dark\"],\"elements\":[{\"name\":\"button\",\"modifiers\":[]}]},{\"name\":\"play-list\",\"modifiers\":[],\"elements\":[]}]"
+		);
+	}
+
+	#[test]
+	fn test_run_cli_with_output_format_bem() {
+		let reader = || MockStdinReader { content: VALID_CONTENT.to_string() };
+		let cli = Cli::try_parse_from(["bem", "--output-format", "bem"]).unwrap();
+		let pretty = Cli::try_parse_from(["bem", "--output-format", "bem", "--pretty"]).unwrap();
+
+		assert_eq!(run_cli(reader(), &cli).unwrap(), "media-player[dark]\nbutton[fast-forward,rewind]\ntimeline\n");
+		assert_eq!(run_cli(reader(), &pretty).unwrap(), "media-player[dark]\nbutton[\n\tfast-forward,\n\trewind,\n]\ntimeline\n");
+	}
+
+	#[test]
+	fn test_run_cli_with_reverse() {
+		let reader = |content: &str| MockStdinReader { content: content.to_string() };
+		let json = "{\"name\":\"media-player\",\"modifiers\":[\"dark\"],\"elements\":[{\"name\":\"button\",\"modifiers\":[\"rewind\"]}]}";
+		let cli = Cli::try_parse_from(["bem", "--reverse"]).unwrap();
+		let pretty = Cli::try_parse_from(["bem", "--from-json", "--pretty"]).unwrap();
+
+		assert_eq!(run_cli(reader(json), &cli).unwrap(), "media-player[dark]\nbutton[rewind]\n");
+		assert_eq!(run_cli(reader(&format!("[{0},{0}]", json)), &pretty).unwrap(), "media-player[dark]\nbutton[rewind]\n\nmedia-player[dark]\nbutton[rewind]\n");
+		assert_eq!(run_cli(reader(VALID_CONTENT), &cli).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+		assert!(Cli::try_parse_from(["bem", "--reverse", "--format", "css"]).is_err());
+	}
+
+	#[test]
+	fn test_run_cli_sniffs_the_input_format() {
+		let reader = |content: &str| MockStdinReader { content: content.to_string() };
+		let cli = Cli::try_parse_from(["bem", "--format", "bem"]).unwrap();
+		let json = "[{\"name\":\"media-player\",\"modifiers\":[\"dark\"],\"elements\":[]},{\"name\":\"play-list\",\"modifiers\":[],\"elements\":[]}]";
+
+		assert_eq!(run_cli(reader(json), &cli).unwrap(), "media-player[dark]\n\nplay-list\n");
+		assert_eq!(run_cli(reader("#!bem 1\nmedia-player\nbutton"), &cli).unwrap(), "media-player\nbutton\n");
+		assert_eq!(run_cli(reader(VALID_CONTENT), &cli).unwrap(), "media-player[dark]\nbutton[fast-forward,rewind]\ntimeline\n");
+
+		let error = run_cli(reader("<media-player />"), &cli).unwrap_err();
+
+		assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+		assert!(error.to_string().contains("cannot detect the input format (json: does not start with `{` or `[`; bem: `<media-player />` is not a block line"));
+	}
+
+	#[test]
+	fn test_run_cli_with_from() {
+		let reader = |content: String| MockStdinReader { content };
+		let from_json = Cli::try_parse_from(["bem", "--from", "json", "--format", "bem"]).unwrap();
+		// Too much leading whitespace for the JSON to be detected.
+		let padded = format!("{}{{\"name\":\"media-player\",\"modifiers\":[],\"elements\":[]}}", " ".repeat(bem::SNIFF_LIMIT));
+
+		assert!(run_cli(reader(padded.clone()), &Cli::try_parse_from(["bem"]).unwrap()).is_err());
+		assert_eq!(run_cli(reader(padded), &from_json).unwrap(), "media-player\n");
+		assert!(run_cli(reader(VALID_CONTENT.to_string()), &from_json).unwrap_err().to_string().starts_with("invalid JSON"));
+		#[cfg(not(feature = "yaml"))]
+		assert!(Cli::try_parse_from(["bem", "--from", "yaml"]).is_err());
+		assert!(Cli::try_parse_from(["bem", "--from", "toml"]).is_err());
+	}
+
 	#[test]
 	fn test_run_cli_with_valid_input() {
 		// Set up some example BEM input as a string
@@ -185,13 +855,14 @@ mod tests {
 		// Create a temporary file for the output
 		let temp_output_file = NamedTempFile::new().unwrap();
 
-		// Create a reader with the input file
-		let reader = FileOrStdinReader {
+		// Run the CLI with the input and output files, and an empty stdin
+		let reader = MockStdinReader { content: String::new() };
+		let cli = Cli {
 			input_file: Some(temp_input_file.path().to_str().unwrap().to_string()),
+			out: Some(temp_output_file.path().to_str().unwrap().to_string()),
+			..Cli::default()
 		};
-
-		// Run the CLI with the reader and output file
-		let result = run_cli(reader, Some(temp_output_file.path().to_str().unwrap().to_string()));
+		let result = run_cli(reader, &cli);
 
 		// Check that the result is Ok and the contents of the output file are correct
 		assert!(result.is_ok());
@@ -208,25 +879,806 @@ mod tests {
 		};
 
 		// Run the CLI with the reader
-		let result = run_cli(mock_reader, None);
+		let result = run_cli(mock_reader, &Cli::default());
 
 		// Check that the result is an error, and that the error kind is what we expect
 		assert!(result.is_err());
 		assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
 	}
 
+	#[test]
+	fn test_run_cli_with_grammar_version() {
+		let reader = |content: &str| MockStdinReader { content: content.to_string() };
+		let cli = Cli {
+			grammar_version: Some(1),
+			..Cli::default()
+		};
+
+		assert!(run_cli(reader(VALID_CONTENT), &cli).is_ok());
+		assert!(run_cli(reader("@set sizes = small\nfoo[@sizes]"), &Cli::default()).is_ok());
+
+		let error = run_cli(reader("@set sizes = small\nfoo[@sizes]"), &cli).unwrap_err();
+
+		assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+		assert!(error.to_string().contains("modifier sets require grammar version 2"));
+
+		let error = run_cli(reader("#!bem 2\nfoo"), &cli).unwrap_err();
+
+		assert_eq!(error.to_string(), "unsupported grammar version 2, expected 1 to 1");
+		assert!(Cli::try_parse_from(["bem", "--grammar-version", "0"]).is_err());
+		assert!(Cli::try_parse_from(["bem", "--grammar-version", "1"]).is_ok());
+	}
+
+	#[test]
+	fn test_run_cli_with_include_and_exclude() {
+		let reader = MockStdinReader { content: "media-player[x-compact]\nbutton[x-loud]\nbutton-group\nx-carousel".to_string() };
+		let cli = Cli::try_parse_from([
+			"bem", "--format", "classes", "--include", "button*", "--include", "x-*", "--exclude", "x-*", "--filter-modifiers",
+		]).unwrap();
+
+		assert_eq!(run_cli(reader, &cli).unwrap(), "media-player\nmedia-player__button\nmedia-player__button-group\n");
+	}
+
+	#[test]
+	fn test_run_cli_with_order() {
+		let reader = || MockStdinReader { content: "media-player[dark,compact]\ntimeline\nbutton[rewind,fast-forward]".to_string() };
+		let alpha = Cli::try_parse_from(["bem", "--format", "bem", "--order", "alpha"]).unwrap();
+		let source = Cli::try_parse_from(["bem", "--format", "bem"]).unwrap();
+
+		assert_eq!(run_cli(reader(), &alpha).unwrap(), "media-player[compact,dark]\nbutton[fast-forward,rewind]\ntimeline\n");
+		assert_eq!(run_cli(reader(), &source).unwrap(), "media-player[dark,compact]\ntimeline\nbutton[rewind,fast-forward]\n");
+	}
+
+	#[test]
+	fn test_run_cli_with_no_atomic() {
+		let dir = tempdir().unwrap();
+		let out = dir.path().join("media-player.txt");
+		let reader = || MockStdinReader { content: "media-player[dark]".to_string() };
+
+		for flags in [vec!["--format", "classes"], vec!["--no-atomic", "--format", "classes"]] {
+			let cli = Cli::try_parse_from([vec!["bem", "-o", out.to_str().unwrap()], flags].concat()).unwrap();
+
+			assert_eq!(run_cli(reader(), &cli).unwrap(), std::fs::read_to_string(&out).unwrap());
+			assert_eq!(std::fs::read_to_string(&out).unwrap(), "media-player\nmedia-player--dark\n");
+			assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+		}
+	}
+
+	#[test]
+	fn test_run_cli_with_ts_decl() {
+		let dir = tempdir().unwrap();
+		let out = dir.path().join("MediaPlayer.module.css");
+		let reader = || MockStdinReader { content: "media-player[dark]".to_string() };
+		let cli = Cli::try_parse_from(["bem", "--format", "css-modules", "--ts-decl", "-o", out.to_str().unwrap()]).unwrap();
+
+		assert_eq!(run_cli(reader(), &cli).unwrap(), ".mediaPlayer { }\n\n.mediaPlayerDark { }\n");
+		assert!(std::fs::read_to_string(dir.path().join("MediaPlayer.module.css.d.ts")).unwrap().contains("\treadonly mediaPlayerDark: string;\n"));
+		assert!(Cli::try_parse_from(["bem", "--format", "css-modules", "--ts-decl"]).is_err());
+
+		let json = Cli::try_parse_from(["bem", "--ts-decl", "-o", out.to_str().unwrap()]).unwrap();
+
+		assert!(run_cli(reader(), &json).is_err());
+	}
+
+	#[test]
+	fn test_run_cli_with_split_by_modifier() {
+		let dir = tempdir().unwrap();
+		let out_dir = dir.path().to_str().unwrap();
+		let reader = || MockStdinReader { content: "media-player[dark]\nbutton".to_string() };
+		let cli = Cli::try_parse_from(["bem", "--split-by-modifier", "--out-dir", out_dir]).unwrap();
+
+		assert_eq!(run_cli(reader(), &cli).unwrap(), "");
+		assert_eq!(
+			std::fs::read_to_string(dir.path().join("media-player--dark.css")).unwrap(),
+			".media-player--dark { }\n\n.media-player--dark .media-player__button { }\n"
+		);
+		assert!(dir.path().join("media-player.css").exists());
+		assert!(Cli::try_parse_from(["bem", "--split-by-modifier"]).is_err());
+		assert!(Cli::try_parse_from(["bem", "--split-by-modifier", "--out-dir", out_dir, "--format", "json"]).is_err());
+	}
+
+	#[test]
+	fn test_run_cli_with_deny_warnings() {
+		let reader = || MockStdinReader { content: "\u{FEFF}media-player[dark,dark]\nbutton\nbutton".to_string() };
+		let deny = Cli { deny_warnings: true, ..Cli::default() };
+
+		assert!(run_cli(reader(), &Cli::default()).is_ok());
+
+		let error = run_cli(reader(), &deny).unwrap_err();
+
+		assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+		assert_eq!(error.to_string(), "3 warning(s) denied by --deny-warnings");
+		assert!(run_cli(MockStdinReader { content: "@set sizes = small\nbutton[@sizes]".to_string() }, &deny).is_ok());
+	}
+
+	#[test]
+	fn test_run_cli_with_lint_css() {
+		let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/lint");
+		let reader = || MockStdinReader { content: VALID_CONTENT.to_string() };
+		let clean = Cli::try_parse_from(["bem", "--format", "classes", "--lint-css", &format!("{}/clean.css", fixtures)]).unwrap();
+		let violations = Cli { lint_css: vec![format!("{}/violations.css", fixtures)], ..Cli::default() };
+
+		assert!(run_cli(reader(), &clean).unwrap().starts_with("media-player\n"));
+		assert_eq!(run_cli(reader(), &violations).unwrap_err().to_string(), "found 5 BEM violation(s)");
+	}
+
+	#[test]
+	fn test_run_cli_with_separators() {
+		let reader = || MockStdinReader { content: VALID_CONTENT.to_string() };
+		let cli = Cli::try_parse_from(["bem", "--format", "classes", "--element-sep", "-", "--modifier-sep", "_"]).unwrap();
+		let element_only = Cli::try_parse_from(["bem", "--format", "classes", "--element-sep", "_"]).unwrap();
+
+		assert_eq!(
+			run_cli(reader(), &cli).unwrap(),
+			"media-player\nmedia-player_dark\nmedia-player-button\nmedia-player-button_fast-forward\nmedia-player-button_rewind\nmedia-player-timeline\n"
+		);
+		assert!(run_cli(reader(), &element_only).unwrap().contains("media-player_button--rewind\n"));
+
+		for format in ["safelist", "scss", "css", "jsx"] {
+			let cli = Cli::try_parse_from(["bem", "--format", format, "--element-sep", "_", "--modifier-sep", "-"]).unwrap();
+			let output = run_cli(reader(), &cli).unwrap();
+
+			assert!(output.contains("media-player_button"), "{} ignores the separators:\n{}", format, output);
+			assert!(!output.contains("__") && !output.contains("--"), "{} ignores the separators:\n{}", format, output);
+		}
+	}
+
+	#[test]
+	fn test_run_cli_with_abbreviations() {
+		let dir = tempdir().unwrap();
+		let abbreviations = dir.path().join("abbreviations.json");
+		std::fs::write(&abbreviations, "{\"navigation\": \"nav\", \"button\": \"btn\"}").unwrap();
+
+		let reader = |content: &str| MockStdinReader { content: content.to_string() };
+		let cli = |format: &str| Cli::try_parse_from(["bem", "--format", format, "--abbreviations", abbreviations.to_str().unwrap()]).unwrap();
+		let drawer = "navigation-drawer\ntoggle-button[active]";
+
+		assert!(run_cli(reader(drawer), &cli("theme-ts")).unwrap().contains("\tnavDrawer: {\n\t\ttoggleBtn: {\n\t\t\tactive: string;\n"));
+		assert!(run_cli(reader(drawer), &cli("jsx")).unwrap().contains("export function NavDrawer(props: NavDrawerProps)"));
+		assert!(run_cli(reader(drawer), &cli("react-native")).unwrap().contains("\ttoggleBtn: {},\n\ttoggleBtnActive: {},\n"));
+		assert!(run_cli(reader(drawer), &cli("classes")).unwrap().contains("navigation-drawer__toggle-button--active\n"));
+		assert!(run_cli(reader(drawer), &Cli { format: Some("jsx".to_string()), ..Cli::default() }).unwrap().contains("export function NavigationDrawer("));
+
+		let error = run_cli(reader("navigation-drawer\ntoggle-btn[active]\ntoggle-button[active]"), &cli("jsx")).unwrap_err();
+
+		assert_eq!(
+			error.to_string(),
+			"identifier `toggleBtnActive` would be generated for each of `navigation-drawer__toggle-btn--active`, `navigation-drawer__toggle-button--active`"
+		);
+
+		std::fs::write(&abbreviations, "{\"toggle-button\": \"tgl\"}").unwrap();
+
+		let error = run_cli(reader(drawer), &cli("jsx")).unwrap_err();
+
+		assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+		assert!(error.to_string().starts_with(&format!("{}: ", abbreviations.display())));
+	}
+
+	#[test]
+	fn test_run_cli_with_css_flat() {
+		let reader = || MockStdinReader { content: "media-player[dark]\nbutton[rewind]".to_string() };
+		let cli = Cli::try_parse_from(["bem", "--output-format", "css-flat"]).unwrap();
+		let separators = Cli::try_parse_from(["bem", "--output-format", "css-flat", "--element-sep", "-"]).unwrap();
+
+		assert_eq!(
+			run_cli(reader(), &cli).unwrap(),
+			".media-player { }\n\n.media-player--dark { }\n\n.media-player__button { }\n\n.media-player__button--rewind { }\n"
+		);
+		assert!(run_cli(reader(), &separators).unwrap().contains("\n.media-player-button--rewind { }\n"));
+	}
+
+	#[test]
+	fn test_run_cli_with_scss() {
+		let cli = Cli::try_parse_from(["bem", "--output-format", "scss", "--modifier-sep", "_"]).unwrap();
+
+		assert_eq!(
+			run_cli(MockStdinReader { content: "media-player[dark]\nbutton".to_string() }, &cli).unwrap(),
+			".media-player {\n\t&_dark { }\n\n\t.media-player__button { }\n}\n"
+		);
+	}
+
+	#[test]
+	fn test_run_cli_with_normalize() {
+		let reader = || MockStdinReader { content: "media-player[dark,compact,dark]\ntimeline\nbutton".to_string() };
+		let cli = Cli { normalize: true, ..Cli::default() };
+
+		assert_eq!(
+			run_cli(reader(), &cli).unwrap(),
+			"{\"name\":\"media-player\",\"modifiers\":[\"compact\",\"dark\"],\"elements\":[{\"name\":\"button\",\"modifiers\":[]},{\"name\":\"timeline\",\"modifiers\":[]}]}"
+		);
+		assert!(run_cli(reader(), &Cli::default()).unwrap().contains("[\"dark\",\"compact\",\"dark\"]"));
+	}
+
+	#[test]
+	fn test_run_cli_with_validate() {
+		let reader = || MockStdinReader { content: "{\"name\":\"media--player\",\"modifiers\":[\"Dark\"],\"elements\":[]}".to_string() };
+		let validate = Cli { validate: true, ..Cli::default() };
+		let error = run_cli(reader(), &validate).unwrap_err();
+
+		assert_eq!(exit_code(&error), 2);
+		assert_eq!(error.to_string(), "invalid block: media--player: \"media--player\" contains consecutive dashes (and 1 more)");
+		assert!(run_cli(reader(), &Cli::default()).is_ok());
+		assert!(run_cli(MockStdinReader { content: VALID_CONTENT.to_string() }, &validate).is_ok());
+		assert_eq!(exit_code(&run_cli(MockStdinReader { content: INVALID_CONTENT.to_string() }, &validate).unwrap_err()), 1);
+	}
+
 	#[test]
 	fn test_run_cli_with_nonexistent_input_file() {
-		// Create a reader with a nonexistent file path as the input file
-		let reader = FileOrStdinReader {
+		// Run the CLI with a nonexistent file path as the input file
+		let reader = MockStdinReader { content: String::new() };
+		let cli = Cli {
 			input_file: Some("/path/to/nonexistent/file".to_string()),
+			..Cli::default()
 		};
-
-		// Run the CLI with the reader
-		let result = run_cli(reader, None);
+		let result = run_cli(reader, &cli);
 
 		// Check that the result is an error, and that the error kind is what we expect
 		assert!(result.is_err());
 		assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::NotFound);
 	}
+
+	#[test]
+	fn test_run_cli_with_safelist_format() {
+		let mock_reader = MockStdinReader {
+			content: VALID_CONTENT.to_string(),
+		};
+		let cli = Cli {
+			format: Some("safelist".to_string()),
+			..Cli::default()
+		};
+		let result = run_cli(mock_reader, &cli);
+
+		assert!(result.is_ok());
+		insta::assert_snapshot!(result.unwrap());
+	}
+
+	#[test]
+	fn test_run_cli_with_safelist_pattern_mode() {
+		let mock_reader = MockStdinReader {
+			content: VALID_CONTENT.to_string(),
+		};
+		let cli = Cli {
+			format: Some("safelist".to_string()),
+			safelist_mode: SafelistModeArg::Pattern,
+			..Cli::default()
+		};
+		let result = run_cli(mock_reader, &cli);
+
+		assert_eq!(result.unwrap(), "[\n  \"^media-player(__[a-z0-9-]+)?(--[a-z0-9-]+)?$\"\n]");
+	}
+
+	#[test]
+	fn test_run_cli_with_json_with_ids_format() {
+		let mock_reader = MockStdinReader {
+			content: VALID_CONTENT.to_string(),
+		};
+		let cli = Cli {
+			format: Some("json-with-ids".to_string()),
+			..Cli::default()
+		};
+		let output = run_cli(mock_reader, &cli).unwrap();
+
+		assert!(output.starts_with("{\"id\":\"cf6f3680dc69\",\"name\":\"media-player\""));
+	}
+
+	#[test]
+	fn test_run_cli_with_css_format() {
+		let mock_reader = MockStdinReader {
+			content: VALID_CONTENT.to_string(),
+		};
+		let cli = Cli {
+			format: Some("css".to_string()),
+			..Cli::default()
+		};
+
+		assert!(run_cli(mock_reader, &cli).unwrap().starts_with(".media-player {\n\t&.media-player--dark { }"));
+	}
+
+	#[test]
+	fn test_run_cli_with_unknown_format() {
+		let mock_reader = MockStdinReader {
+			content: VALID_CONTENT.to_string(),
+		};
+		let cli = Cli {
+			format: Some("tokens".to_string()),
+			..Cli::default()
+		};
+		let error = run_cli(mock_reader, &cli).unwrap_err();
+
+		assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+		assert!(error.to_string().starts_with("unknown format `tokens` (expected one of: json, json-with-ids, jcs, safelist, css, css-flat, css-modules, scss, scss-placeholders, classes, bem"));
+	}
+
+	#[test]
+	fn test_run_cli_with_registered_emitter() {
+		struct Tokens;
+
+		impl Emitter for Tokens {
+			fn id(&self) -> &str {
+				"tokens"
+			}
+
+			fn emit(&self, doc: &BEMDocument, _opts: &EmitContext) -> Result<Vec<u8>, EmitError> {
+				Ok(format!("--{}-color: inherit;", doc.blocks[0].name).into_bytes())
+			}
+		}
+
+		let mut registry = EmitterRegistry::new();
+		registry.register(Tokens);
+
+		let run = |format: &str| run_cli_with_registry(
+			MockStdinReader { content: VALID_CONTENT.to_string() },
+			&Cli { format: Some(format.to_string()), ..Cli::default() },
+			&registry
+		);
+
+		assert_eq!(run("tokens").unwrap(), "--media-player-color: inherit;");
+		assert_eq!(run("json").unwrap(), run_cli(MockStdinReader { content: VALID_CONTENT.to_string() }, &Cli::default()).unwrap());
+	}
+
+	#[test]
+	fn test_run_cli_with_checksum() {
+		let temp_dir = tempdir().unwrap();
+		let out = temp_dir.path().join("media-player.json").to_str().unwrap().to_string();
+		let cli = Cli {
+			out: Some(out.clone()),
+			format: Some("jcs".to_string()),
+			checksum: Some(ChecksumAlgorithm::Sha256),
+			..Cli::default()
+		};
+		let mock_reader = MockStdinReader {
+			content: VALID_CONTENT.to_string(),
+		};
+
+		run_cli(mock_reader, &cli).unwrap();
+
+		assert_eq!(
+			std::fs::read_to_string(&out).unwrap(),
+			"{\"elements\":[{\"modifiers\":[\"fast-forward\",\"rewind\"],\"name\":\"button\"},{\"modifiers\":[],\"name\":\"timeline\"}],\"modifiers\":[\"dark\"],\"name\":\"media-player\"}"
+		);
+		assert_eq!(
+			std::fs::read_to_string(format!("{}.sha256", out)).unwrap(),
+			"4b3bf41fe63a3bca0f56c30d6036bf4e1e5b9f4b7fc0a9467336ad1dc0a1455b  media-player.json\n"
+		);
+	}
+
+	#[test]
+	fn test_run_cli_with_summary_file() {
+		let temp_dir = tempdir().unwrap();
+		let path = |name: &str| temp_dir.path().join(name).to_str().unwrap().to_string();
+		let cli = Cli {
+			out: Some(path("media-player.json")),
+			checksum: Some(ChecksumAlgorithm::Sha256),
+			summary_file: Some(path("summary.json")),
+			..Cli::default()
+		};
+		let mock_reader = MockStdinReader {
+			content: VALID_CONTENT.to_string(),
+		};
+
+		run_cli(mock_reader, &cli).unwrap();
+
+		let summary: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path("summary.json")).unwrap()).unwrap();
+
+		assert_eq!(summary["ok"], true);
+		assert_eq!(summary["inputs"], serde_json::json!([{ "path": "<stdin>", "status": "ok", "error": null }]));
+		assert_eq!(summary["totals"], serde_json::json!({ "blocks": 1, "elements": 2, "modifiers": 3 }));
+		assert_eq!(summary["outputs"], serde_json::json!([path("media-player.json"), path("media-player.json.sha256")]));
+	}
+
+	#[test]
+	fn test_run_cli_with_summary_file_of_mixed_batch() {
+		let temp_dir = tempdir().unwrap();
+		let path = |name: &str| temp_dir.path().join(name).to_str().unwrap().to_string();
+		let design = path("design");
+
+		std::fs::create_dir(&design).unwrap();
+		std::fs::write(format!("{}/a.bem", design), VALID_CONTENT).unwrap();
+		std::fs::write(format!("{}/b.bem", design), "play-list(dark)").unwrap();
+		std::fs::write(format!("{}/c.bem", design), "#!bem 99\nplay-list").unwrap();
+		std::fs::write(format!("{}/d.bem", design), "play-list\nitem[active]").unwrap();
+
+		let cli = Cli {
+			input_file: Some(design.clone()),
+			out: Some(path("design.json")),
+			summary_file: Some(path("summary.json")),
+			..Cli::default()
+		};
+		let error = run_cli(MockStdinReader { content: String::new() }, &cli).unwrap_err();
+
+		assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+		assert!(error.to_string().starts_with(&format!("{}/b.bem: parse error", design)));
+
+		let summary: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path("summary.json")).unwrap()).unwrap();
+		let keys: Vec<&String> = summary.as_object().unwrap().keys().collect();
+		let inputs = summary["inputs"].as_array().unwrap();
+
+		assert_eq!(keys, vec!["duration_ms", "inputs", "ok", "outputs", "schema", "totals"]);
+		assert_eq!(summary["schema"], 1);
+		assert_eq!(summary["ok"], false);
+		assert!(summary["duration_ms"].is_u64());
+		assert_eq!(inputs.iter().map(|input| input["status"].as_str().unwrap()).collect::<Vec<_>>(), vec!["ok", "error", "error", "ok"]);
+		assert_eq!(inputs[0], serde_json::json!({ "path": format!("{}/a.bem", design), "status": "ok", "error": null }));
+		assert_eq!(inputs[1]["error"]["code"], "parse");
+		assert_eq!(inputs[2]["error"]["code"], "unsupported-grammar-version");
+		assert_eq!(inputs[2]["error"]["message"], "unsupported grammar version 99, expected 1 to 5");
+		assert_eq!(summary["totals"], serde_json::json!({ "blocks": 2, "elements": 3, "modifiers": 4 }));
+		assert_eq!(summary["outputs"], serde_json::json!([path("design.json")]));
+		assert!(std::fs::read_to_string(path("design.json")).unwrap().starts_with("[{\"name\":\"media-player\""));
+	}
+
+	#[test]
+	fn test_run_cli_with_summary_on_stderr() {
+		let cli = Cli {
+			summary_file: Some("-".to_string()),
+			..Cli::default()
+		};
+		let mock_reader = MockStdinReader {
+			content: INVALID_CONTENT.to_string(),
+		};
+
+		assert_eq!(run_cli(mock_reader, &cli).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+	}
+
+	#[test]
+	fn test_run_cli_with_verify() {
+		let temp_dir = tempdir().unwrap();
+		let out = temp_dir.path().join("media-player.json").to_str().unwrap().to_string();
+		let cli = |verify| Cli {
+			out: Some(out.clone()),
+			format: Some("jcs".to_string()),
+			checksum: Some(ChecksumAlgorithm::Sha256),
+			verify,
+			..Cli::default()
+		};
+		let verify = || run_cli(MockStdinReader { content: String::new() }, &cli(true));
+
+		run_cli(MockStdinReader { content: VALID_CONTENT.to_string() }, &cli(false)).unwrap();
+
+		assert_eq!(verify().unwrap(), format!("{}: OK\n", out));
+
+		std::fs::write(&out, std::fs::read_to_string(&out).unwrap().replace("dark", "light")).unwrap();
+
+		assert_eq!(verify().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn test_run_diff_with_impact() {
+		let old = NamedTempFile::new().unwrap();
+		let new = NamedTempFile::new().unwrap();
+		std::fs::write(old.path(), VALID_CONTENT).unwrap();
+		std::fs::write(new.path(), "media-player[dark,light]\nbutton[rewind]\ntimeline").unwrap();
+
+		let args = DiffArgs {
+			old: old.path().to_str().unwrap().to_string(),
+			new: new.path().to_str().unwrap().to_string(),
+			impact: true,
+			..DiffArgs::default()
+		};
+
+		assert_eq!(
+			run_diff(&args).unwrap(),
+			"Impact: major\n\
+			- added modifier `media-player--light` (minor)\n\
+			- removed modifier `media-player__button--fast-forward` (major)\n"
+		);
+	}
+
+	#[test]
+	fn test_run_diff_with_directories() {
+		let old = tempdir().unwrap();
+		let new = tempdir().unwrap();
+		std::fs::write(old.path().join("media-player.bem"), VALID_CONTENT).unwrap();
+		std::fs::write(new.path().join("media-player.bem"), VALID_CONTENT).unwrap();
+		std::fs::write(new.path().join("play-list.bem"), "play-list").unwrap();
+		std::fs::write(new.path().join("notes.txt"), INVALID_CONTENT).unwrap();
+
+		let args = DiffArgs {
+			old: old.path().to_str().unwrap().to_string(),
+			new: new.path().to_str().unwrap().to_string(),
+			impact: true,
+			..DiffArgs::default()
+		};
+
+		assert_eq!(run_diff(&args).unwrap(), "Impact: minor\n- added block `play-list` (minor)\n");
+	}
+
+	#[test]
+	fn test_run_diff_without_changes() {
+		let old = NamedTempFile::new().unwrap();
+		std::fs::write(old.path(), VALID_CONTENT).unwrap();
+
+		let path = old.path().to_str().unwrap().to_string();
+		let args = DiffArgs { old: path.clone(), new: path.clone(), ..DiffArgs::default() };
+
+		assert_eq!(run_diff(&args).unwrap(), "No structural changes\n");
+		assert_eq!(run_diff(&DiffArgs { impact: true, ..args }).unwrap(), "Impact: patch\n");
+	}
+
+	#[test]
+	fn test_run_diff_with_invalid_input() {
+		let old = NamedTempFile::new().unwrap();
+		std::fs::write(old.path(), INVALID_CONTENT).unwrap();
+
+		let path = old.path().to_str().unwrap().to_string();
+		let result = run_diff(&DiffArgs { old: path.clone(), new: path, impact: true, ..DiffArgs::default() });
+
+		assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+	}
+
+	#[test]
+	fn test_run_diff_with_changelog() {
+		let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/changelog");
+		let args = DiffArgs {
+			old: format!("{}/old", fixtures),
+			new: format!("{}/new", fixtures),
+			changelog: true,
+			..DiffArgs::default()
+		};
+
+		insta::assert_snapshot!(run_diff(&args).unwrap());
+	}
+
+	#[test]
+	fn test_run_generate() {
+		let args = GenerateArgs { seed: 42, elements: Some(5..10), ..GenerateArgs::default() };
+		let output = run_generate(&args).unwrap();
+
+		assert_eq!(output, run_generate(&args).unwrap());
+		assert!((5..10).contains(&bem::parse(&output).unwrap().elements.len()));
+	}
+
+	#[test]
+	fn test_run_generate_with_format() {
+		let args = GenerateArgs { seed: 42, format: Some("json".to_string()), ..GenerateArgs::default() };
+		let bem_block = bem::from_json(&run_generate(&args).unwrap()).unwrap();
+
+		assert_eq!(bem_block, bem::generate(42, &bem::GenerateConfig::default()));
+	}
+
+	#[test]
+	fn test_parse_range() {
+		assert_eq!(parse_range("5..10"), Ok(5..10));
+		assert_eq!(parse_range("3"), Ok(3..4));
+		assert_eq!(parse_range("10..5"), Err("empty range `10..5`".to_string()));
+		assert_eq!(parse_range("a..5"), Err("invalid count `a`".to_string()));
+	}
+
+	#[test]
+	fn test_run_doctor_with_clean_file() {
+		let file = NamedTempFile::new().unwrap();
+		std::fs::write(file.path(), VALID_CONTENT).unwrap();
+
+		let path = file.path().to_str().unwrap().to_string();
+		let features = match bem::pipeline::features() {
+			features if features.is_empty() => "none".to_string(),
+			features => features.join(", "),
+		};
+		let output = run_doctor(&DoctorArgs { input: Some(path.clone()), json: false }).unwrap();
+
+		assert_eq!(output, format!(
+			"bem {} (grammar version {})\nfeatures: {}\ninput: {}\nencoding: utf-8\nline endings: lf\nlines: 3 clean, 0 failing\n",
+			env!("CARGO_PKG_VERSION"),
+			bem::GRAMMAR_VERSION,
+			features,
+			path
+		));
+		assert!(run_doctor(&DoctorArgs::default()).unwrap().ends_with(&format!("features: {}\n", features)));
+	}
+
+	#[test]
+	fn test_run_doctor_with_invalid_lines() {
+		let file = NamedTempFile::new().unwrap();
+		std::fs::write(file.path(), "media-player[dark]\nbutton\n\ntimeline(slow)\nVolume\r\n").unwrap();
+
+		let args = DoctorArgs { input: Some(file.path().to_str().unwrap().to_string()), json: true };
+		let output: serde_json::Value = serde_json::from_str(&run_doctor(&args).unwrap()).unwrap();
+		let input = &output["input"];
+
+		assert_eq!(output["version"], env!("CARGO_PKG_VERSION"));
+		assert_eq!(output["features"], serde_json::json!(bem::pipeline::features()));
+		assert_eq!(input["encoding"], "utf-8");
+		assert_eq!(input["line_endings"], "mixed");
+		assert_eq!(input["lines"], serde_json::json!({ "clean": 2, "failing": 2 }));
+		assert_eq!(input["failures"].as_array().unwrap().iter().map(|failure| failure["line"].as_u64().unwrap()).collect::<Vec<_>>(), vec![4, 5]);
+		assert_eq!(input["suggestions"], serde_json::json!([
+			"file uses mixed line endings; convert them to LF or CRLF",
+			"line 4 uses parentheses; write modifiers in square brackets, like `button[dark]`",
+			"line 5 has uppercase letters; names are lowercase",
+		]));
+	}
+
+	#[test]
+	fn test_run_grep() {
+		let dir = tempdir().unwrap();
+		let media_player = dir.path().join("media-player.bem");
+		let play_list = dir.path().join("play-list.bem");
+		std::fs::write(&media_player, VALID_CONTENT).unwrap();
+		std::fs::write(&play_list, "play-list[dark]\nitem[playing]").unwrap();
+
+		let args = GrepArgs {
+			pattern: "^(dark|play)".to_string(),
+			files: vec![media_player.to_str().unwrap().to_string(), play_list.to_str().unwrap().to_string()],
+			..GrepArgs::default()
+		};
+
+		assert_eq!(
+			run_grep(&args).unwrap(),
+			format!(
+				"{0}:media-player--dark:dark\n{1}:play-list:play-list\n{1}:play-list--dark:dark\n{1}:play-list__item--playing:playing\n",
+				media_player.display(),
+				play_list.display(),
+			)
+		);
+
+		let args = GrepArgs { files: vec![dir.path().to_str().unwrap().to_string()], scope: SearchScopeArg::Blocks, ..args };
+
+		assert_eq!(run_grep(&args).unwrap(), format!("{}:play-list:play-list\n", play_list.display()));
+	}
+
+	#[test]
+	fn test_run_grep_with_json() {
+		let file = NamedTempFile::new().unwrap();
+		std::fs::write(file.path(), VALID_CONTENT).unwrap();
+
+		let args = GrepArgs {
+			pattern: "^(fast|slow)-(.+)$".to_string(),
+			files: vec![file.path().to_str().unwrap().to_string()],
+			json: true,
+			..GrepArgs::default()
+		};
+		let output: serde_json::Value = serde_json::from_str(&run_grep(&args).unwrap()).unwrap();
+
+		assert_eq!(output, serde_json::json!([{
+			"file": file.path().to_str().unwrap(),
+			"path": "media-player__button--fast-forward",
+			"name": "fast-forward",
+			"captures": ["fast", "forward"],
+		}]));
+	}
+
+	#[test]
+	fn test_run_grep_with_invalid_pattern() {
+		let args = GrepArgs { pattern: "(".to_string(), files: vec!["missing.bem".to_string()], ..GrepArgs::default() };
+		let error = run_grep(&args).unwrap_err();
+
+		assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+		assert!(error.to_string().starts_with("invalid pattern `(`: "), "{}", error);
+	}
+
+	#[test]
+	fn test_run_audit() {
+		let dir = tempdir().unwrap();
+		let design = dir.path().join("design");
+		let report = dir.path().join("usage.csv");
+		let fixture = |name: &str| format!("{}/fixtures/usage/{}", env!("CARGO_MANIFEST_DIR"), name);
+		std::fs::create_dir(&design).unwrap();
+		std::fs::write(design.join("card.bem"), "card[active,compact]\ntitle[large]\nbody\nbadge").unwrap();
+		std::fs::write(design.join("badge.bem"), "badge").unwrap();
+
+		let args = AuditArgs {
+			files: vec![design.to_str().unwrap().to_string()],
+			html: vec![fixture("*.html")],
+			css: vec![fixture("styles.css")],
+			js: vec![fixture("*.tsx")],
+			usage_report: Some(report.to_str().unwrap().to_string()),
+		};
+
+		assert_eq!(run_audit(&args, false).unwrap(), "card: element `badge` is also the name of a block\n");
+		assert_eq!(
+			std::fs::read_to_string(&report).unwrap().lines().take(3).collect::<Vec<_>>(),
+			vec!["path,html,css,js,total", "badge,0,0,0,0", "card,2,2,1,5"]
+		);
+
+		let json = dir.path().join("usage.json");
+		let args = AuditArgs { usage_report: Some(json.to_str().unwrap().to_string()), ..args };
+
+		run_audit(&args, true).unwrap();
+
+		let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+
+		assert_eq!(report["rows"][1], serde_json::json!({ "path": "card", "html": 2, "css": 2, "js": 1, "total": 5 }));
+		assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 3);
+	}
+
+	#[test]
+	fn test_no_atomic_reaches_subcommands() {
+		let cli = Cli::try_parse_from(["bem", "audit", "--no-atomic", "--usage-report", "usage.csv", "design"]).unwrap();
+
+		assert!(cli.no_atomic);
+		assert!(!Cli::try_parse_from(["bem", "audit", "design"]).unwrap().no_atomic);
+	}
+
+	#[test]
+	fn test_run_patch() {
+		let dir = tempdir().unwrap();
+		let input = dir.path().join("media-player.bem");
+		let patch = dir.path().join("patch.json");
+		let path = |path: &std::path::Path| path.to_str().unwrap().to_string();
+		std::fs::write(&input, VALID_CONTENT).unwrap();
+		std::fs::write(&patch, "{\"modifiers\": null, \"elements\": [{\"name\": \"timeline\", \"modifiers\": [\"live\"]}]}").unwrap();
+
+		let args = PatchArgs { merge: path(&patch), input: path(&input), format: None };
+
+		assert_eq!(run_patch(&args).unwrap(), "media-player\ntimeline[live]\n");
+
+		std::fs::write(&patch, "{\"name\": \"Media-Player\"}").unwrap();
+
+		assert_eq!(run_patch(&args).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+
+		std::fs::write(&patch, "{").unwrap();
+
+		assert_eq!(run_patch(&args).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn test_run_scan() {
+		let dir = tempdir().unwrap();
+		let components = dir.path().join("components");
+		std::fs::create_dir(&components).unwrap();
+		std::fs::copy("fixtures/js/MediaPlayer.tsx", components.join("MediaPlayer.tsx")).unwrap();
+		std::fs::write(dir.path().join("App.tsx"), "<main className=\"media-player media-player__footer\" />").unwrap();
+		std::fs::write(dir.path().join("styles.css"), ".x-carousel {}").unwrap();
+
+		let args = ScanArgs { js: vec![format!("{}/**/*.tsx", dir.path().display())], ..ScanArgs::default() };
+
+		assert_eq!(
+			run_scan(&args).unwrap(),
+			"media-player[dark]\nfooter\nbutton[fast-forward,rewind]\ntimeline[live]\nvolume[muted]\n\nplay-list[compact]\n"
+		);
+
+		let args = ScanArgs { js: vec![format!("{}/*.css", dir.path().display())], format: Some("toml".to_string()) };
+
+		assert_eq!(run_scan(&args).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+	}
+
+	#[test]
+	fn test_run_schema_dump() {
+		let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/schema");
+		let args = SchemaDumpArgs { files: vec![format!("{}/play-list.bem", fixtures)], json: false };
+
+		assert_eq!(run_schema_dump(&args).unwrap(), format!(
+			"{0}:1: play-list\n{0}:1: play-list--compact\n{0}:2: play-list__item\n{0}:3: play-list__item--active\n{0}:5: play-list__title\n",
+			format!("{}/play-list.bem", fixtures)
+		));
+
+		let args = SchemaDumpArgs { files: vec![fixtures.to_string()], json: true };
+		let output: serde_json::Value = serde_json::from_str(&run_schema_dump(&args).unwrap()).unwrap();
+
+		assert_eq!(output["schemaVersion"], 1);
+		assert_eq!(output["blocks"].as_array().unwrap().len(), 2);
+		assert_eq!(output["blocks"][0]["elements"][0]["location"]["line"], 4);
+	}
+
+	#[test]
+	fn test_run_lint() {
+		let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/lint");
+		let clean = LintArgs { css: vec![format!("{}/clean.css", fixtures)], max_element_chain: 2, report_unused_suppressions: false };
+
+		assert_eq!(run_lint(&clean).unwrap(), "");
+
+		let violations = LintArgs { css: vec![format!("{}/violations.css", fixtures)], ..clean };
+		let error = run_lint(&violations).unwrap_err();
+
+		assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+		assert_eq!(error.to_string(), "found 5 BEM violation(s)");
+	}
+
+	#[test]
+	fn test_run_lint_with_unused_suppressions() {
+		let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/lint");
+		let path = format!("{}/suppressions.css", fixtures);
+		let args = LintArgs { css: vec![path.clone()], max_element_chain: 2, report_unused_suppressions: false };
+
+		assert_eq!(run_lint(&args).unwrap_err().to_string(), "found 2 BEM violation(s)");
+
+		let args = LintArgs { report_unused_suppressions: true, ..args };
+		let error = run_lint(&args).unwrap_err();
+
+		assert_eq!(error.to_string(), "found 2 BEM violation(s) and 2 unused suppression(s)");
+
+		let clean = LintArgs { css: vec![format!("{}/clean.css", fixtures)], ..args };
+
+		assert_eq!(run_lint(&clean).unwrap(), "");
+	}
 }
@@ -2,7 +2,19 @@
 //! for representing BEM (Block Element Modifier) components. These structures
 //! are used for both parsing and serializing BEM notation.
 
-use serde::{ Serialize, Deserialize };
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{ Hash, Hasher };
+use std::ops::Deref;
+use std::str::FromStr;
+use std::sync::Arc;
+use serde::{ Deserialize, Deserializer, Serialize, Serializer };
+use crate::elements::{ ElementMut, Elements };
+use crate::error::Error;
+use crate::naming::{ BemConfig, NamingScheme };
+use crate::parser::parse;
 
 /// Represents a BEM (Block Element Modifier) block, which consists of a name,
 /// a list of modifiers, and a list of elements.
@@ -17,15 +29,594 @@ use serde::{ Serialize, Deserialize };
 ///
 /// let block = BEMBlock {
 ///     name: "media-player".to_string(),
-///     modifiers: vec!["dark".to_string()],
-///     elements: vec![/* BEMElement structs go here */],
+///     modifiers: vec!["dark".into()],
+///     elements: vec![/* BEMElement structs go here */].into(),
 /// };
 /// ```
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BEMBlock {
 	pub name: String,
-	pub modifiers: Vec<String>,
-	pub elements: Vec<BEMElement>,
+	pub modifiers: Vec<BEMModifier>,
+	/// The elements of the block, in the order they were written and unique by name, see
+	/// [`Elements`].
+	pub elements: Elements,
+}
+
+impl BEMBlock {
+	/// Creates a block without modifiers or elements.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ BEMBlock, BEMElement };
+	///
+	/// let mut block = BEMBlock::new("media-player");
+	///
+	/// block.modifiers.push("dark".into());
+	/// block.elements.insert(BEMElement::new("button"));
+	///
+	/// assert_eq!(bem::to_bem(&block), "media-player[dark]\nbutton");
+	/// ```
+	pub fn new(name: impl Into<String>) -> BEMBlock {
+		BEMBlock { name: name.into(), modifiers: Vec::new(), elements: Elements::new() }
+	}
+
+	/// Adds a modifier to the block, unless it already has it.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ to_bem, BEMBlock, BEMElement };
+	///
+	/// let block = BEMBlock::new("card")
+	///     .with_modifier("dark")
+	///     .with_element(BEMElement::new("title").with_modifier("large"));
+	///
+	/// assert_eq!(to_bem(&block), "card[dark]\ntitle[large]");
+	/// ```
+	pub fn with_modifier(mut self, modifier: impl Into<BEMModifier>) -> BEMBlock {
+		self.add_modifier(modifier);
+		self
+	}
+
+	/// Adds a modifier to the block, unless it already has it, see
+	/// [`has_modifier`](BEMBlock::has_modifier).
+	pub fn add_modifier(&mut self, modifier: impl Into<BEMModifier>) {
+		let modifier = modifier.into();
+
+		if !self.modifiers.contains(&modifier) {
+			self.modifiers.push(modifier);
+		}
+	}
+
+	/// Removes the first occurrence of a modifier, written in the `.bem` syntax like `dark` or
+	/// `size=lg`, from the block. Returns whether it had the modifier.
+	pub fn remove_modifier(&mut self, modifier: &str) -> bool {
+		match self.modifiers.iter().position(|existing| existing == modifier) {
+			Some(position) => {
+				self.modifiers.remove(position);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Returns a copy of the block with only the elements for which `pred` returns `true`.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let block = parse("media-player[dark]\nbutton[rewind]\ntimeline").unwrap();
+	///
+	/// assert_eq!(block.filter_elements(|element| !element.modifiers.is_empty()).to_string(), "media-player[dark]\nbutton[rewind]");
+	/// ```
+	pub fn filter_elements<F: Fn(&BEMElement) -> bool>(&self, pred: F) -> BEMBlock {
+		let mut block = self.clone();
+
+		block.retain_elements(pred);
+		block
+	}
+
+	/// Keeps only the elements for which `pred` returns `true`, in their order.
+	pub fn retain_elements<F: Fn(&BEMElement) -> bool>(&mut self, pred: F) {
+		self.elements.retain(pred);
+	}
+
+	/// Returns a copy of the block with only the block modifiers for which `pred` returns
+	/// `true`. The modifiers of the elements are kept.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let block = parse("media-player[dark,size=lg]\nbutton[size=sm]").unwrap();
+	///
+	/// assert_eq!(block.filter_modifiers(|modifier| modifier.name != "size").to_string(), "media-player[dark]\nbutton[size=sm]");
+	/// ```
+	pub fn filter_modifiers<F: Fn(&BEMModifier) -> bool>(&self, pred: F) -> BEMBlock {
+		let mut block = self.clone();
+
+		block.retain_modifiers(pred);
+		block
+	}
+
+	/// Keeps only the block modifiers for which `pred` returns `true`, in their order.
+	pub fn retain_modifiers<F: Fn(&BEMModifier) -> bool>(&mut self, pred: F) {
+		self.modifiers.retain(pred);
+	}
+
+	/// Returns a copy of the block with every element renamed to `f(name)`, e.g. to rename the
+	/// inner elements of a component. Elements renamed to the same name are merged, see the
+	/// [duplicate policy](crate::Elements#duplicate-policy).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let block = parse("media-player[dark]\nbutton[rewind]\ntimeline").unwrap();
+	/// let renamed = block.map_element_names(|name| format!("player-{}", name));
+	///
+	/// assert_eq!(renamed.to_string(), "media-player[dark]\nplayer-button[rewind]\nplayer-timeline");
+	/// assert!(renamed.elements.contains("player-button"));
+	/// ```
+	pub fn map_element_names<F: Fn(&str) -> String>(&self, f: F) -> BEMBlock {
+		BEMBlock {
+			name: self.name.clone(),
+			modifiers: self.modifiers.clone(),
+			elements: self.elements
+				.iter()
+				.map(|element| BEMElement { name: f(&element.name), modifiers: element.modifiers.clone() })
+				.collect(),
+		}
+	}
+
+	/// Returns a copy of the block with every modifier of the block and of its elements renamed
+	/// to `f(name)`. Modifiers with a value keep it, so `size=lg` is passed `size`. Modifiers
+	/// renamed to the same name are all kept, see [`dedup_modifiers`](BEMBlock::dedup_modifiers).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let block = parse("media-player[dark,size=lg]\nbutton[rewind]").unwrap();
+	/// let renamed = block.map_modifier_names(|name| name.replace("dark", "night").replace("size", "scale"));
+	///
+	/// assert_eq!(renamed.to_string(), "media-player[night,scale=lg]\nbutton[rewind]");
+	/// ```
+	pub fn map_modifier_names<F: Fn(&str) -> String>(&self, f: F) -> BEMBlock {
+		let map = |modifiers: &[BEMModifier]| modifiers
+			.iter()
+			.map(|modifier| BEMModifier { name: f(&modifier.name), value: modifier.value.clone() })
+			.collect();
+
+		BEMBlock {
+			name: self.name.clone(),
+			modifiers: map(&self.modifiers),
+			elements: self.elements
+				.iter()
+				.map(|element| BEMElement { name: element.name.clone(), modifiers: map(&element.modifiers) })
+				.collect(),
+		}
+	}
+
+	/// Sorts the modifiers of the block by name, comparing Unicode code points like
+	/// [`EmitOrder::Alphabetical`](crate::EmitOrder::Alphabetical).
+	pub fn sort_modifiers(&mut self) {
+		self.modifiers.sort();
+	}
+
+	/// Sorts the elements of the block by name, like
+	/// [`EmitOrder::Alphabetical`](crate::EmitOrder::Alphabetical).
+	pub fn sort_elements(&mut self) {
+		self.elements.sort_by(|a, b| a.name.cmp(&b.name));
+	}
+
+	/// Removes the repeated modifiers of the block, keeping their first occurrence. The
+	/// modifiers do not need to be sorted.
+	pub fn dedup_modifiers(&mut self) {
+		dedup(&mut self.modifiers);
+	}
+
+	/// Sorts and deduplicates the modifiers of the block and of every element, and sorts the
+	/// elements, so blocks listing the same entities in any order compare and serialize equal.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let mut block = parse("media-player[dark,compact,dark]\ntimeline\nbutton[rewind,fast-forward]").unwrap();
+	///
+	/// block.normalize();
+	///
+	/// assert_eq!(block.to_string(), "media-player[compact,dark]\nbutton[fast-forward,rewind]\ntimeline");
+	/// ```
+	pub fn normalize(&mut self) {
+		self.sort_modifiers();
+		self.sort_elements();
+		self.dedup_modifiers();
+
+		let mut elements = std::mem::take(&mut self.elements).into_vec();
+
+		for element in &mut elements {
+			element.normalize();
+		}
+
+		self.elements = elements.into();
+	}
+
+	/// Adds an element to the block, or merges its modifiers into the element of the same name,
+	/// see [`Elements::insert`].
+	pub fn with_element(mut self, element: BEMElement) -> BEMBlock {
+		self.add_element(element);
+		self
+	}
+
+	/// Adds an element to the block, or merges its modifiers into the element of the same name,
+	/// see [`Elements::insert`].
+	pub fn add_element(&mut self, element: BEMElement) {
+		self.elements.insert(element);
+	}
+
+	/// Returns a block combining `self`, e.g. a base block, with `other`, e.g. an override: the
+	/// modifiers of `self` followed by those of `other` it lacks, and the elements of `self`
+	/// followed by those of `other`, where elements of the same name are merged like
+	/// [`Elements::insert`] does.
+	///
+	/// # Panics
+	///
+	/// Panics if the blocks have different names, since merging unrelated blocks is a bug of the
+	/// caller. Compare the names first to handle it.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ parse, to_bem };
+	///
+	/// let base = parse("media-player[dark]\nbutton[rewind]").unwrap();
+	/// let theme = parse("media-player[dark,compact]\nbutton[fast-forward]\ntimeline").unwrap();
+	///
+	/// assert_eq!(to_bem(&base.merge(&theme)), "media-player[dark,compact]\nbutton[rewind,fast-forward]\ntimeline");
+	/// ```
+	pub fn merge(&self, other: &BEMBlock) -> BEMBlock {
+		assert_eq!(self.name, other.name, "only blocks of the same name can be merged");
+
+		let mut merged = self.clone();
+
+		for modifier in &other.modifiers {
+			merged.add_modifier(modifier.clone());
+		}
+
+		for element in &other.elements {
+			merged.add_element(element.clone());
+		}
+
+		merged
+	}
+
+	/// Removes the element named exactly `name` and returns it, keeping the order of the others.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ to_bem, BEMBlock, BEMElement };
+	///
+	/// let mut block = BEMBlock::new("media-player").with_element(BEMElement::new("button")).with_element(BEMElement::new("timeline"));
+	///
+	/// assert_eq!(block.remove_element("button").unwrap().name, "button");
+	/// assert!(block.remove_element("button").is_none());
+	/// assert_eq!(to_bem(&block), "media-player\ntimeline");
+	/// ```
+	pub fn remove_element(&mut self, name: &str) -> Option<BEMElement> {
+		self.elements.remove(name)
+	}
+
+	/// Returns the element named exactly `name`.
+	pub fn find_element(&self, name: &str) -> Option<&BEMElement> {
+		self.elements.get(name)
+	}
+
+	/// Returns the element named exactly `name` for modification. Renaming it keeps the elements
+	/// unique by name, see [`Elements::get_mut`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ BEMBlock, BEMElement };
+	///
+	/// let mut block = BEMBlock::new("media-player").with_element(BEMElement::new("button"));
+	///
+	/// block.find_element_mut("button").unwrap().add_modifier("rewind");
+	///
+	/// assert!(block.find_element("button").unwrap().has_modifier("rewind"));
+	/// assert!(block.contains_element("button") && !block.contains_element("timeline"));
+	/// ```
+	pub fn find_element_mut(&mut self, name: &str) -> Option<ElementMut<'_>> {
+		self.elements.get_mut(name)
+	}
+
+	/// Returns whether an element is named exactly `name`.
+	pub fn contains_element(&self, name: &str) -> bool {
+		self.elements.contains(name)
+	}
+
+	/// Returns whether the block has neither modifiers nor elements, whatever its name.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ parse, BEMBlock };
+	///
+	/// assert!(parse("media-player").unwrap().is_empty());
+	/// assert!(!parse("media-player\nbutton").unwrap().is_empty());
+	/// assert!(BEMBlock::default().is_empty());
+	/// ```
+	pub fn is_empty(&self) -> bool {
+		self.modifiers.is_empty() && self.elements.is_empty()
+	}
+
+	/// Returns the number of elements of the block.
+	pub fn element_count(&self) -> usize {
+		self.elements.len()
+	}
+
+	/// Returns the number of modifiers of the block itself, without those of its elements.
+	pub fn modifier_count(&self) -> usize {
+		self.modifiers.len()
+	}
+
+	/// Writes the block in the `.bem` syntax, like its [`Display`](fmt::Display) implementation
+	/// and [`to_bem`](crate::to_bem).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let block = parse("media-player[ dark ]\nbutton").unwrap();
+	///
+	/// assert_eq!(block.to_bem_string(), "media-player[dark]\nbutton");
+	/// ```
+	pub fn to_bem_string(&self) -> String {
+		self.to_string()
+	}
+
+	/// Writes the block in the `.bem` syntax for reading, with every modifier list of more than
+	/// one modifier written one indented modifier per line. It parses back to the same block.
+	/// The alternate flag of [`Display`](fmt::Display), `{:#}`, writes the same text.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+	///
+	/// assert_eq!(
+	///     block.to_bem_string_pretty(),
+	///     "media-player[dark]\nbutton[\n\tfast-forward,\n\trewind,\n]\ntimeline"
+	/// );
+	/// assert_eq!(parse(&block.to_bem_string_pretty()).unwrap(), block);
+	/// ```
+	pub fn to_bem_string_pretty(&self) -> String {
+		format!("{:#}", self)
+	}
+
+	/// Returns the classes of the block itself in standard BEM: the block class and then its
+	/// modifier classes. The classes of the elements are returned by
+	/// [`BEMElement::to_css_classes`], and every class of a block by
+	/// [`NamingScheme::class_names`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+	///
+	/// assert_eq!(block.to_css_classes(), vec!["media-player", "media-player--dark"]);
+	/// ```
+	pub fn to_css_classes(&self) -> Vec<String> {
+		self.to_css_classes_with_config(&NamingScheme::default())
+	}
+
+	/// Returns the classes of the block itself like [`to_css_classes`](BEMBlock::to_css_classes),
+	/// composed with the separators of `config`.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ parse, BemConfig };
+	///
+	/// let block = parse("media-player[dark]").unwrap();
+	/// let config = BemConfig { element_separator: "__".to_string(), modifier_separator: "_".to_string() };
+	///
+	/// assert_eq!(block.to_css_classes_with_config(&config), vec!["media-player", "media-player_dark"]);
+	/// ```
+	pub fn to_css_classes_with_config(&self, config: &BemConfig) -> Vec<String> {
+		let mut classes = vec![self.name.clone()];
+
+		classes.extend(self.modifiers.iter().map(|modifier| config.modifier_class(&self.name, &modifier.class_name())));
+		classes
+	}
+}
+
+/// Writes the block in the `.bem` syntax, like [`to_bem`](crate::to_bem).
+///
+/// # Example
+///
+/// ```
+/// use bem::{ parse, BEMBlock, BEMElement };
+///
+/// let block = BEMBlock::new("media-player").with_modifier("dark").with_element(BEMElement::new("timeline"));
+///
+/// assert_eq!(block.to_string(), "media-player[dark]\ntimeline");
+/// assert_eq!(parse(&block.to_string()).unwrap(), block);
+/// ```
+impl fmt::Display for BEMBlock {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write_line(f, &self.name, &self.modifiers)?;
+
+		for element in &self.elements {
+			match f.alternate() {
+				true => write!(f, "\n{:#}", element)?,
+				false => write!(f, "\n{}", element)?,
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Hashes the name, the modifiers in sorted order, and the elements in order of their names,
+/// so the hash does not depend on the order entities were written in.
+///
+/// Blocks that are equal always have the same hash, as `HashMap` and `HashSet` require. The
+/// converse does not hold: blocks listing the same modifiers or elements in a different order
+/// have the same hash but are not equal, see [`normalize`](BEMBlock::normalize).
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use bem::parse;
+///
+/// let blocks: HashSet<_> = ["media-player[dark]", "media-player[dark]", "play-list"]
+///     .into_iter()
+///     .map(|input| parse(input).unwrap())
+///     .collect();
+///
+/// assert_eq!(blocks.len(), 2);
+/// ```
+impl Hash for BEMBlock {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		let mut elements: Vec<&BEMElement> = self.elements.iter().collect();
+
+		elements.sort_by(|a, b| a.name.cmp(&b.name));
+		self.name.hash(state);
+		sorted(&self.modifiers).hash(state);
+		elements.hash(state);
+	}
+}
+
+/// Orders blocks by name, then by number of modifiers, then by number of elements, so sorting
+/// blocks lists them alphabetically.
+///
+/// Blocks that still tie are ordered by their modifiers and then their elements, compared in
+/// the order they were written, so only equal blocks compare as equal.
+///
+/// # Example
+///
+/// ```
+/// use bem::parse;
+///
+/// let mut blocks = vec![parse("play-list").unwrap(), parse("media-player[dark]").unwrap(), parse("media-player").unwrap()];
+///
+/// blocks.sort();
+///
+/// assert_eq!(blocks.iter().map(|block| block.to_string()).collect::<Vec<_>>(), vec!["media-player", "media-player[dark]", "play-list"]);
+/// ```
+impl Ord for BEMBlock {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.name.cmp(&other.name)
+			.then(self.modifiers.len().cmp(&other.modifiers.len()))
+			.then(self.elements.len().cmp(&other.elements.len()))
+			.then_with(|| self.modifiers.cmp(&other.modifiers))
+			.then_with(|| self.elements.iter().cmp(other.elements.iter()))
+	}
+}
+
+impl PartialOrd for BEMBlock {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+/// Reads a block from the `.bem` syntax, like [`parse`].
+///
+/// # Example
+///
+/// ```
+/// use bem::BEMBlock;
+///
+/// let block: BEMBlock = "media-player[dark]\nbutton".parse().unwrap();
+///
+/// assert!(block.has_modifier("dark") && block.contains_element("button"));
+/// assert!("Media-Player".parse::<BEMBlock>().is_err());
+/// ```
+impl FromStr for BEMBlock {
+	type Err = Error;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		parse(input)
+	}
+}
+
+/// Converts a block into its JSON form, see [`to_json_value`](crate::to_json_value).
+impl From<BEMBlock> for serde_json::Value {
+	fn from(bem_block: BEMBlock) -> Self {
+		crate::to_json_value(&bem_block).expect("blocks only hold strings, which always serialize")
+	}
+}
+
+/// Reads a block from its JSON form, see [`from_json_value`](crate::from_json_value).
+impl TryFrom<serde_json::Value> for BEMBlock {
+	type Error = Error;
+
+	fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+		crate::from_json_value(value)
+	}
+}
+
+/// Iterates over the elements of a block, in order.
+///
+/// # Example
+///
+/// ```
+/// use bem::parse;
+///
+/// let block = parse("media-player\nbutton[rewind]\ntimeline").unwrap();
+/// let names: Vec<&str> = (&block).into_iter().map(|element| element.name.as_str()).collect();
+///
+/// assert_eq!(names, vec!["button", "timeline"]);
+/// ```
+impl<'a> IntoIterator for &'a BEMBlock {
+	type Item = &'a BEMElement;
+	type IntoIter = std::slice::Iter<'a, BEMElement>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.elements.iter()
+	}
+}
+
+/// Iterates over the elements of a block for modification, in order, see
+/// [`Elements::iter_mut`].
+///
+/// # Example
+///
+/// ```
+/// use bem::parse;
+///
+/// let mut block = parse("media-player\nbutton[rewind]\ntimeline").unwrap();
+///
+/// for element in &mut block {
+///     element.add_modifier("compact");
+/// }
+///
+/// assert_eq!(block.to_string(), "media-player\nbutton[rewind,compact]\ntimeline[compact]");
+/// ```
+impl<'a> IntoIterator for &'a mut BEMBlock {
+	type Item = &'a mut BEMElement;
+	type IntoIter = std::slice::IterMut<'a, BEMElement>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.elements.iter_mut()
+	}
 }
 
 /// Represents an element within a BEM block, with its own name and list of modifiers.
@@ -40,11 +631,727 @@ pub struct BEMBlock {
 ///
 /// let element = BEMElement {
 ///     name: "button".to_string(),
-///     modifiers: vec!["fast-forward".to_string(), "rewind".to_string()],
+///     modifiers: vec!["fast-forward".into(), "rewind".into()],
 /// };
 /// ```
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BEMElement {
 	pub name: String,
-	pub modifiers: Vec<String>,
+	pub modifiers: Vec<BEMModifier>,
+}
+
+impl BEMElement {
+	/// Creates an element without modifiers.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::BEMElement;
+	///
+	/// let element = BEMElement::new("button");
+	///
+	/// assert_eq!(element.name, "button");
+	/// assert!(element.modifiers.is_empty());
+	/// ```
+	pub fn new(name: impl Into<String>) -> BEMElement {
+		BEMElement { name: name.into(), modifiers: Vec::new() }
+	}
+
+	/// Adds a modifier to the element, unless it already has it.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::BEMElement;
+	///
+	/// let element = BEMElement::new("button").with_modifier("rewind").with_modifier("rewind");
+	///
+	/// assert_eq!(element.modifiers, vec!["rewind"]);
+	/// ```
+	pub fn with_modifier(mut self, modifier: impl Into<BEMModifier>) -> BEMElement {
+		self.add_modifier(modifier);
+		self
+	}
+
+	/// Adds a modifier to the element, unless it already has it, see
+	/// [`has_modifier`](BEMElement::has_modifier).
+	pub fn add_modifier(&mut self, modifier: impl Into<BEMModifier>) {
+		let modifier = modifier.into();
+
+		if !self.modifiers.contains(&modifier) {
+			self.modifiers.push(modifier);
+		}
+	}
+
+	/// Removes the first occurrence of a modifier, written in the `.bem` syntax, from the
+	/// element. Returns whether it had the modifier.
+	pub fn remove_modifier(&mut self, modifier: &str) -> bool {
+		match self.modifiers.iter().position(|existing| existing == modifier) {
+			Some(position) => {
+				self.modifiers.remove(position);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Returns whether the element has no modifiers, whatever its name.
+	pub fn is_empty(&self) -> bool {
+		self.modifiers.is_empty()
+	}
+
+	/// Returns the number of modifiers of the element.
+	pub fn modifier_count(&self) -> usize {
+		self.modifiers.len()
+	}
+
+	/// Returns a copy of the element with only the modifiers for which `pred` returns `true`,
+	/// see [`BEMBlock::filter_modifiers`].
+	pub fn filter_modifiers<F: Fn(&BEMModifier) -> bool>(&self, pred: F) -> BEMElement {
+		let mut element = self.clone();
+
+		element.retain_modifiers(pred);
+		element
+	}
+
+	/// Keeps only the modifiers for which `pred` returns `true`, in their order.
+	pub fn retain_modifiers<F: Fn(&BEMModifier) -> bool>(&mut self, pred: F) {
+		self.modifiers.retain(pred);
+	}
+
+	/// Sorts the modifiers of the element by name, see [`BEMBlock::sort_modifiers`].
+	pub fn sort_modifiers(&mut self) {
+		self.modifiers.sort();
+	}
+
+	/// Removes the repeated modifiers of the element, keeping their first occurrence.
+	pub fn dedup_modifiers(&mut self) {
+		dedup(&mut self.modifiers);
+	}
+
+	/// Sorts and deduplicates the modifiers of the element.
+	pub fn normalize(&mut self) {
+		self.sort_modifiers();
+		self.dedup_modifiers();
+	}
+
+	/// Returns the classes of the element within the block named `block_name` in standard BEM:
+	/// the element class and then its modifier classes.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::BEMElement;
+	///
+	/// let element = BEMElement::new("button").with_modifier("fast-forward").with_modifier("rewind");
+	///
+	/// assert_eq!(element.to_css_classes("media-player"), vec![
+	///     "media-player__button",
+	///     "media-player__button--fast-forward",
+	///     "media-player__button--rewind",
+	/// ]);
+	/// ```
+	pub fn to_css_classes(&self, block_name: &str) -> Vec<String> {
+		self.to_css_classes_with_config(block_name, &NamingScheme::default())
+	}
+
+	/// Returns the classes of the element like [`to_css_classes`](BEMElement::to_css_classes),
+	/// composed with the separators of `config`.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ BemConfig, BEMElement };
+	///
+	/// let element = BEMElement::new("button").with_modifier("rewind");
+	/// let config = BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+	///
+	/// assert_eq!(element.to_css_classes_with_config("player", &config), vec!["player-button", "player-button_rewind"]);
+	/// ```
+	pub fn to_css_classes_with_config(&self, block_name: &str, config: &BemConfig) -> Vec<String> {
+		let class = config.element_class(block_name, &self.name);
+		let mut classes = vec![class.clone()];
+
+		classes.extend(self.modifiers.iter().map(|modifier| config.modifier_class(&class, &modifier.class_name())));
+		classes
+	}
+
+	/// Returns the class of the element within the block named `block_name` in standard BEM,
+	/// e.g. `media-player__button`.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::BEMElement;
+	///
+	/// let element = BEMElement::new("button");
+	///
+	/// assert_eq!(element.full_class_name("media-player"), "media-player__button");
+	/// assert_eq!(element.css_class_with_modifier("media-player", "rewind"), "media-player__button--rewind");
+	/// ```
+	pub fn full_class_name(&self, block_name: &str) -> String {
+		NamingScheme::default().element_class(block_name, &self.name)
+	}
+
+	/// Returns the class of the element with `modifier` within the block named `block_name` in
+	/// standard BEM, e.g. `media-player__button--rewind`, whether the element has the modifier
+	/// or not.
+	pub fn css_class_with_modifier(&self, block_name: &str, modifier: &str) -> String {
+		NamingScheme::default().modifier_class(&self.full_class_name(block_name), modifier)
+	}
+}
+
+/// Hashes the name and the modifiers in sorted order, see the [`Hash`] implementation of
+/// [`BEMBlock`]. Elements that are equal always have the same hash.
+impl Hash for BEMElement {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.name.hash(state);
+		sorted(&self.modifiers).hash(state);
+	}
+}
+
+/// Orders elements by name, then by number of modifiers, then by their modifiers, see the
+/// [`Ord`] implementation of [`BEMBlock`].
+impl Ord for BEMElement {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.name.cmp(&other.name)
+			.then(self.modifiers.len().cmp(&other.modifiers.len()))
+			.then_with(|| self.modifiers.cmp(&other.modifiers))
+	}
+}
+
+impl PartialOrd for BEMElement {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+/// Writes the element as a line of the `.bem` syntax, e.g. `button[fast-forward,rewind]`, or
+/// only its name if it has no modifiers.
+impl fmt::Display for BEMElement {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write_line(f, &self.name, &self.modifiers)
+	}
+}
+
+/// Removes the repeated modifiers of a list, keeping their first occurrence.
+fn dedup(modifiers: &mut Vec<BEMModifier>) {
+	let mut seen = HashSet::new();
+
+	modifiers.retain(|modifier| seen.insert(modifier.clone()));
+}
+
+/// Returns the modifiers of a list in sorted order, for hashing.
+fn sorted(modifiers: &[BEMModifier]) -> Vec<&BEMModifier> {
+	let mut sorted: Vec<&BEMModifier> = modifiers.iter().collect();
+
+	sorted.sort();
+	sorted
+}
+
+/// Writes a line of the `.bem` syntax, leaving out an empty modifier list. With the alternate
+/// flag, a list of several modifiers is written one indented modifier per line.
+fn write_line(f: &mut fmt::Formatter<'_>, name: &str, modifiers: &[BEMModifier]) -> fmt::Result {
+	let modifiers: Vec<String> = modifiers.iter().map(|modifier| modifier.to_string()).collect();
+
+	match modifiers.as_slice() {
+		[] => write!(f, "{}", name),
+		[_, _, ..] if f.alternate() => write!(f, "{}[\n\t{},\n]", name, modifiers.join(",\n\t")),
+		_ => write!(f, "{}[{}]", name, modifiers.join(",")),
+	}
+}
+
+/// A modifier of a block or element: a name and an optional value, e.g. `color=red` in the
+/// `.bem` syntax.
+///
+/// A modifier with a value composes its class with an underscore, following the key-value
+/// modifiers of BEM (`block--color_red`), see [`class_name`](BEMModifier::class_name). Modifiers
+/// are written in the `.bem` syntax everywhere else, including JSON, where they are strings like
+/// `"dark"` or `"color=red"`; a string without `=` is a modifier without a value, whatever it
+/// contains, so `"fast_forward"` stays the modifier `fast_forward`.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ parse, BEMModifier };
+///
+/// let bem_block = parse("button[color=red,disabled]").unwrap();
+/// let color = &bem_block.modifiers[0];
+///
+/// assert_eq!(bem_block.modifiers, vec!["color=red", "disabled"]);
+/// assert_eq!((color.name.as_str(), color.value.as_deref()), ("color", Some("red")));
+/// assert_eq!(color.to_string(), "color=red");
+/// assert_eq!(color.class_name(), "color_red");
+/// assert_eq!(BEMModifier::from("fast_forward"), BEMModifier::new("fast_forward"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BEMModifier {
+	pub name: String,
+	pub value: Option<String>,
+}
+
+impl BEMModifier {
+	/// Creates a modifier without a value.
+	pub fn new(name: impl Into<String>) -> BEMModifier {
+		BEMModifier { name: name.into(), value: None }
+	}
+
+	/// Sets the value of the modifier.
+	pub fn with_value(mut self, value: impl Into<String>) -> BEMModifier {
+		self.value = Some(value.into());
+		self
+	}
+
+	/// Returns the modifier as it appears in classes, e.g. `color_red`, or only its name if it
+	/// has no value.
+	pub fn class_name(&self) -> Cow<'_, str> {
+		match &self.value {
+			Some(value) => Cow::Owned(format!("{}_{}", self.name, value)),
+			None => Cow::Borrowed(&self.name),
+		}
+	}
+}
+
+/// Writes the modifier in the `.bem` syntax, e.g. `color=red`, or only its name if it has no
+/// value.
+impl fmt::Display for BEMModifier {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.value {
+			Some(value) => write!(f, "{}={}", self.name, value),
+			None => write!(f, "{}", self.name),
+		}
+	}
+}
+
+/// Reads a modifier in the `.bem` syntax, e.g. `color=red`. The first `=` separates the name
+/// from the value.
+impl From<&str> for BEMModifier {
+	fn from(modifier: &str) -> Self {
+		match modifier.split_once('=') {
+			Some((name, value)) => BEMModifier::new(name).with_value(value),
+			None => BEMModifier::new(modifier),
+		}
+	}
+}
+
+impl From<String> for BEMModifier {
+	fn from(modifier: String) -> Self {
+		BEMModifier::from(modifier.as_str())
+	}
+}
+
+impl From<&String> for BEMModifier {
+	fn from(modifier: &String) -> Self {
+		BEMModifier::from(modifier.as_str())
+	}
+}
+
+/// Compares the modifier with one written in the `.bem` syntax, e.g. `color=red`.
+impl PartialEq<str> for BEMModifier {
+	fn eq(&self, other: &str) -> bool {
+		match &self.value {
+			Some(value) => other.split_once('=') == Some((self.name.as_str(), value.as_str())),
+			None => self.name == other,
+		}
+	}
+}
+
+impl PartialEq<&str> for BEMModifier {
+	fn eq(&self, other: &&str) -> bool {
+		self == *other
+	}
+}
+
+impl PartialEq<String> for BEMModifier {
+	fn eq(&self, other: &String) -> bool {
+		self == other.as_str()
+	}
+}
+
+/// Serializes the modifier as a string in the `.bem` syntax, e.g. `"color=red"`.
+impl Serialize for BEMModifier {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for BEMModifier {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Ok(BEMModifier::from(String::deserialize(deserializer)?))
+	}
+}
+
+/// Represents a set of BEM blocks processed together, such as the `.bem` files of a design system.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ parse, BEMDocument };
+///
+/// let document = BEMDocument::from(parse("media-player[dark]").unwrap());
+///
+/// assert_eq!(document.blocks.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BEMDocument {
+	pub blocks: Vec<BEMBlock>,
+}
+
+/// The blocks of a `.bem` file holding several blocks, as read by [`parse_all`](crate::parse_all)
+/// and written as JSON by [`to_json_file`](crate::to_json_file).
+///
+/// # Example
+///
+/// ```
+/// use bem::{ parse_all, BEMFile };
+///
+/// let file = BEMFile::from(parse_all("media-player\n\nplay-list").unwrap());
+///
+/// assert_eq!(file.blocks[1].name, "play-list");
+/// ```
+pub type BEMFile = BEMDocument;
+
+impl From<BEMBlock> for BEMDocument {
+	fn from(bem_block: BEMBlock) -> Self {
+		BEMDocument { blocks: vec![bem_block] }
+	}
+}
+
+impl From<Vec<BEMBlock>> for BEMDocument {
+	fn from(blocks: Vec<BEMBlock>) -> Self {
+		BEMDocument { blocks }
+	}
+}
+
+impl BEMDocument {
+	/// Moves the document behind a [`SharedDocument`], to read it from several threads without
+	/// cloning it.
+	pub fn into_shared(self) -> SharedDocument {
+		SharedDocument(Arc::new(self))
+	}
+}
+
+/// A read-only handle to a [`BEMDocument`], cheap to clone and safe to send to other threads.
+///
+/// The handle dereferences to the document, so the whole read API is available on it, and it
+/// can be passed wherever a `&BEMDocument` is expected, e.g. to an emitter.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ parse, BEMDocument, EmitContext, EmitterRegistry };
+///
+/// let shared = BEMDocument::from(parse("media-player[dark]").unwrap()).into_shared();
+/// let handle = shared.clone();
+///
+/// let css = std::thread::spawn(move || {
+///     EmitterRegistry::new().emit("css", &handle, &EmitContext::default()).unwrap()
+/// }).join().unwrap();
+///
+/// assert!(String::from_utf8(css).unwrap().contains(".media-player"));
+/// assert!(shared.get_block("media-player").unwrap().has_modifier("dark"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedDocument(Arc<BEMDocument>);
+
+impl Deref for SharedDocument {
+	type Target = BEMDocument;
+
+	fn deref(&self) -> &BEMDocument {
+		&self.0
+	}
+}
+
+impl From<BEMDocument> for SharedDocument {
+	fn from(document: BEMDocument) -> Self {
+		document.into_shared()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+	use super::{ BEMBlock, BEMElement };
+
+	#[test]
+	fn test_block_modifiers() {
+		let mut block = BEMBlock::new("media-player");
+
+		block.add_modifier("dark");
+		block.add_modifier("dark");
+		block.add_modifier(String::from("compact"));
+
+		assert_eq!(block.modifiers, vec!["dark", "compact"]);
+		assert!(block.has_modifier("dark"));
+		assert!(block.remove_modifier("dark"));
+		assert!(!block.has_modifier("dark"));
+		assert!(!block.remove_modifier("dark"));
+		assert_eq!(block.modifiers, vec!["compact"]);
+	}
+
+	#[test]
+	fn test_remove_modifier_removes_the_first_occurrence() {
+		let mut block = BEMBlock::new("media-player");
+		let mut element = BEMElement::new("button");
+
+		block.modifiers = vec!["dark".into(), "compact".into(), "dark".into()];
+		element.modifiers = vec!["rewind".into(), "rewind".into()];
+
+		assert!(block.remove_modifier("dark"));
+		assert_eq!(block.modifiers, vec!["compact", "dark"]);
+		assert!(block.has_modifier("dark"));
+		assert!(element.remove_modifier("rewind"));
+		assert_eq!(element.modifiers, vec!["rewind"]);
+		assert!(element.remove_modifier("rewind"));
+		assert!(!element.has_modifier("rewind") && !element.remove_modifier("rewind"));
+	}
+
+	#[test]
+	fn test_block_elements() {
+		let mut block = BEMBlock::new("media-player");
+
+		block.add_element(BEMElement::new("button").with_modifier("rewind"));
+		block.add_element(BEMElement::new("timeline"));
+		block.add_element(BEMElement::new("button").with_modifier("fast-forward"));
+
+		assert_eq!(block.elements.len(), 2);
+		assert_eq!(block.find_element("button").unwrap().modifiers, vec!["rewind", "fast-forward"]);
+
+		block.find_element_mut("timeline").unwrap().name = "button".to_string();
+
+		assert_eq!(block.elements.len(), 1);
+		assert!(!block.contains_element("timeline"));
+		assert_eq!(block.remove_element("button").unwrap().modifiers, vec!["rewind", "fast-forward"]);
+		assert!(block.find_element("button").is_none() && block.find_element_mut("button").is_none());
+		assert!(block.elements.is_empty());
+	}
+
+	#[test]
+	fn test_from_str() {
+		let block: BEMBlock = "media-player[dark]\nbutton[rewind]".parse().unwrap();
+		let error = "media-player(dark)".parse::<BEMBlock>().unwrap_err();
+
+		assert_eq!(block, BEMBlock::new("media-player").with_modifier("dark").with_element(BEMElement::new("button").with_modifier("rewind")));
+		assert_eq!(block.to_string().parse::<BEMBlock>().unwrap(), block);
+		assert_eq!(error.code(), "parse");
+	}
+
+	#[test]
+	fn test_to_css_classes() {
+		let block: BEMBlock = "media-player[dark,size=lg]\nbutton[rewind]\ntimeline".parse().unwrap();
+		let mut classes = block.to_css_classes();
+
+		for element in &block.elements {
+			classes.extend(element.to_css_classes(&block.name));
+		}
+
+		assert_eq!(classes, crate::NamingScheme::default().class_names(&block));
+		assert_eq!(block.to_css_classes(), vec!["media-player", "media-player--dark", "media-player--size_lg"]);
+		assert_eq!(block.elements[1].to_css_classes(&block.name), vec!["media-player__timeline"]);
+		assert_eq!(block.elements[0].full_class_name(&block.name), block.elements[0].to_css_classes(&block.name)[0]);
+		assert_eq!(block.elements[0].css_class_with_modifier(&block.name, "rewind"), block.elements[0].to_css_classes(&block.name)[1]);
+		assert_eq!(block.elements[1].css_class_with_modifier(&block.name, "size_lg"), "media-player__timeline--size_lg");
+
+		let config = crate::BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+
+		assert_eq!(block.to_css_classes_with_config(&config), vec!["media-player", "media-player_dark", "media-player_size_lg"]);
+		assert_eq!(block.elements[0].to_css_classes_with_config(&block.name, &config), vec!["media-player-button", "media-player-button_rewind"]);
+	}
+
+	#[test]
+	fn test_to_bem_string() {
+		let block: BEMBlock = "media-player[dark,size=lg]\nbutton[rewind]\ntimeline[live,paused]".parse().unwrap();
+		let pretty = block.to_bem_string_pretty();
+
+		assert_eq!(block.to_bem_string(), block.to_string());
+		assert_eq!(pretty, "media-player[\n\tdark,\n\tsize=lg,\n]\nbutton[rewind]\ntimeline[\n\tlive,\n\tpaused,\n]");
+		assert_eq!(pretty, format!("{:#}", block));
+		assert_eq!(pretty.parse::<BEMBlock>().unwrap(), block);
+		assert_eq!(BEMBlock::new("media-player").to_bem_string_pretty(), "media-player");
+	}
+
+	#[test]
+	fn test_merge() {
+		let base: BEMBlock = "media-player[dark]\nbutton[rewind]\ntimeline".parse().unwrap();
+		let other: BEMBlock = "media-player[compact,dark]\nvolume\nbutton[rewind,fast-forward]".parse().unwrap();
+		let merged = base.merge(&other);
+
+		assert_eq!(merged.to_string(), "media-player[dark,compact]\nbutton[rewind,fast-forward]\ntimeline\nvolume");
+		assert_eq!(merged.merge(&base), merged);
+		assert_eq!(base.merge(&BEMBlock::new("media-player")), base);
+		assert!(merged.contains_element("volume"));
+	}
+
+	#[test]
+	#[should_panic(expected = "only blocks of the same name can be merged")]
+	fn test_merge_different_blocks() {
+		BEMBlock::new("media-player").merge(&BEMBlock::new("play-list"));
+	}
+
+	#[test]
+	fn test_normalize() {
+		let mut block: BEMBlock = "media-player[dark,compact,dark]\ntimeline[paused,live,paused]\nbutton".parse().unwrap();
+		let mut unsorted = block.clone();
+
+		unsorted.dedup_modifiers();
+		assert_eq!(unsorted.modifiers, vec!["dark", "compact"]);
+
+		block.normalize();
+
+		assert_eq!(block.to_string(), "media-player[compact,dark]\nbutton\ntimeline[live,paused]");
+		assert_eq!(block.find_element("timeline").unwrap().modifiers, vec!["live", "paused"]);
+		assert_eq!(block, crate::EmitOrder::Alphabetical.apply(&"media-player[dark,compact]\ntimeline[live,paused]\nbutton".parse().unwrap()));
+	}
+
+	#[test]
+	// Hashing and equality read the merged elements, never the `OnceLock` they are cached in.
+	#[allow(clippy::mutable_key_type)]
+	fn test_hash() {
+		use std::collections::hash_map::DefaultHasher;
+		use std::hash::{ Hash, Hasher };
+
+		let hash = |block: &BEMBlock| {
+			let mut hasher = DefaultHasher::new();
+
+			block.hash(&mut hasher);
+			hasher.finish()
+		};
+		let block: BEMBlock = "media-player[dark,compact]\nbutton[rewind,play]\ntimeline".parse().unwrap();
+		let reordered: BEMBlock = "media-player[compact,dark]\ntimeline\nbutton[play,rewind]".parse().unwrap();
+		let blocks: HashSet<BEMBlock> = [block.clone(), block.clone()].into_iter().collect();
+
+		assert_eq!(blocks.len(), 1);
+		assert!(blocks.contains(&block));
+		assert_eq!(hash(&block), hash(&reordered));
+		assert_ne!(block, reordered);
+		assert_ne!(hash(&block), hash(&block.filter_modifiers(|modifier| modifier != "dark")));
+		assert_eq!(HashSet::from([BEMElement::new("button"), BEMElement::new("button")]).len(), 1);
+	}
+
+	#[test]
+	fn test_ord() {
+		let block = |input: &str| input.parse::<BEMBlock>().unwrap();
+		let mut blocks: Vec<BEMBlock> = [
+			block("search-box"),
+			block("media-player[dark,compact]"),
+			block("play-list\nitem"),
+			block("media-player[dark]\nbutton\ntimeline"),
+			block("media-player[dark]\nbutton"),
+			block("media-player[compact]\nbutton"),
+			block("play-list"),
+		].into();
+
+		blocks.sort();
+
+		let sorted: Vec<String> = blocks.iter().map(|block| block.to_string().replace('\n', " ")).collect();
+
+		assert_eq!(sorted, vec![
+			"media-player[compact] button",
+			"media-player[dark] button",
+			"media-player[dark] button timeline",
+			"media-player[dark,compact]",
+			"play-list",
+			"play-list item",
+			"search-box",
+		]);
+		assert_eq!(block("media-player[dark]").cmp(&block("media-player[dark]")), std::cmp::Ordering::Equal);
+		assert!(block("media-player\nbutton").elements[0] < block("media-player\nbutton[rewind]").elements[0]);
+		assert!(BEMElement::new("button").with_modifier("play") < BEMElement::new("button").with_modifier("rewind"));
+	}
+
+	#[test]
+	fn test_counts() {
+		let block: BEMBlock = "media-player[dark,compact]\nbutton[rewind]\ntimeline".parse().unwrap();
+
+		assert_eq!((block.element_count(), block.modifier_count()), (2, 2));
+		assert!(!block.is_empty());
+		assert!(!BEMBlock::new("media-player").with_modifier("dark").is_empty());
+		assert!(!BEMBlock::new("media-player").with_element(BEMElement::new("button")).is_empty());
+		assert!(BEMBlock::new("media-player").is_empty());
+		assert_eq!(block.elements[0].modifier_count(), 1);
+		assert!(!block.elements[0].is_empty() && block.elements[1].is_empty());
+	}
+
+	#[test]
+	fn test_default() {
+		#[derive(Default)]
+		struct Theme {
+			block: BEMBlock,
+		}
+
+		let block = BEMBlock::default();
+
+		assert_eq!(block, BEMBlock { name: String::new(), modifiers: vec![], elements: vec![].into() });
+		assert!(block.is_empty());
+		assert_eq!(Theme::default().block, block);
+		assert_eq!(BEMElement::default(), BEMElement { name: String::new(), modifiers: vec![] });
+	}
+
+	#[test]
+	fn test_into_iterator() {
+		let mut block: BEMBlock = "media-player[dark]\nbutton[rewind]\ntimeline".parse().unwrap();
+		let names: Vec<&str> = block.into_iter().map(|element| element.name.as_str()).collect();
+
+		assert_eq!(names, vec!["button", "timeline"]);
+		assert_eq!((&block).into_iter().filter(|element| element.modifiers.is_empty()).count(), 1);
+
+		for element in &mut block {
+			element.modifiers.clear();
+			element.name.insert_str(0, "player-");
+		}
+
+		assert_eq!(block.to_string(), "media-player[dark]\nplayer-button\nplayer-timeline");
+		assert_eq!(block.find_element("player-timeline"), Some(&BEMElement::new("player-timeline")));
+		assert!(block.contains_element("player-button") && !block.contains_element("timeline"));
+	}
+
+	#[test]
+	fn test_filter_and_retain() {
+		let mut block: BEMBlock = "media-player[dark,compact]\nbutton[rewind,fast-forward]\ntimeline\nvolume[muted]".parse().unwrap();
+		let with_modifiers = block.filter_elements(|element| !element.modifiers.is_empty());
+
+		assert_eq!(with_modifiers.to_string(), "media-player[dark,compact]\nbutton[rewind,fast-forward]\nvolume[muted]");
+		assert!(with_modifiers.find_element("volume").is_some() && with_modifiers.find_element("timeline").is_none());
+		assert_eq!(block.elements.len(), 3);
+
+		block.retain_elements(|element| element.name != "button");
+		block.retain_modifiers(|modifier| modifier != "dark");
+
+		assert_eq!(block.to_string(), "media-player[compact]\ntimeline\nvolume[muted]");
+		assert_eq!(block.elements.get("volume").unwrap().name, "volume");
+		assert_eq!(block.filter_modifiers(|_| false).modifiers, Vec::<String>::new());
+
+		let element = BEMElement::new("button").with_modifier("rewind").with_modifier("play");
+		let mut retained = element.clone();
+
+		retained.retain_modifiers(|modifier| modifier.name.starts_with('p'));
+
+		assert_eq!(element.filter_modifiers(|modifier| modifier.name.starts_with('p')), retained);
+		assert_eq!(retained.modifiers, vec!["play"]);
+	}
+
+	#[test]
+	fn test_map_names() {
+		let block: BEMBlock = "media-player[dark]\nbutton[rewind]\nplay-button[play]\ntimeline[dark]".parse().unwrap();
+		let merged = block.map_element_names(|name| name.trim_start_matches("play-").to_string());
+		let modifiers = block.map_modifier_names(|modifier| format!("is-{}", modifier));
+
+		assert_eq!(merged.to_string(), "media-player[dark]\nbutton[rewind,play]\ntimeline[dark]");
+		assert_eq!(modifiers.to_string(), "media-player[is-dark]\nbutton[is-rewind]\nplay-button[is-play]\ntimeline[is-dark]");
+		assert_eq!(block.map_modifier_names(|_| "on".to_string()).modifiers, vec!["on"]);
+		assert_eq!(block.map_element_names(str::to_string), block);
+		assert_eq!(block.to_string(), "media-player[dark]\nbutton[rewind]\nplay-button[play]\ntimeline[dark]");
+	}
+
+	#[test]
+	fn test_element_modifiers() {
+		let mut element = BEMElement::new("button").with_modifier("rewind");
+
+		element.add_modifier("fast-forward");
+		element.add_modifier("rewind");
+
+		assert_eq!(element.modifiers, vec!["rewind", "fast-forward"]);
+		assert!(element.remove_modifier("rewind"));
+		assert_eq!(element.modifiers, vec!["fast-forward"]);
+	}
 }
@@ -0,0 +1,178 @@
+//! This module defines how BEM entities are composed into class names. Standard BEM joins
+//! elements with `__` and modifiers with `--`, but projects are free to pick other separators.
+
+use crate::budget::{ BudgetError, ClassBudget };
+use crate::models::BEMBlock;
+
+/// The separators used to compose class names from BEM entities.
+///
+/// The default scheme is standard BEM: `block__element--modifier`.
+///
+/// # Example
+///
+/// ```
+/// use bem::NamingScheme;
+///
+/// let scheme = NamingScheme { element_separator: "_".to_string(), modifier_separator: "-".to_string() };
+///
+/// assert_eq!(scheme.element_class("media-player", "button"), "media-player_button");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamingScheme {
+	pub element_separator: String,
+	pub modifier_separator: String,
+}
+
+/// The separators a project composes its class names with, under the name configuration files
+/// and command-line flags use for them. The default is standard BEM.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ parse, BemConfig };
+///
+/// let config = BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+/// let block = parse("player[dark]").unwrap();
+///
+/// assert_eq!(block.to_css_classes_with_config(&config), vec!["player", "player_dark"]);
+/// assert_eq!(BemConfig::default().element_separator, "__");
+/// ```
+pub type BemConfig = NamingScheme;
+
+impl Default for NamingScheme {
+	fn default() -> Self {
+		NamingScheme {
+			element_separator: "__".to_string(),
+			modifier_separator: "--".to_string(),
+		}
+	}
+}
+
+impl NamingScheme {
+	/// Returns the class of an element, e.g. `media-player__button`.
+	pub fn element_class(&self, block_name: &str, element_name: &str) -> String {
+		format!("{}{}{}", block_name, self.element_separator, element_name)
+	}
+
+	/// Returns the modifier class of a block or element class, e.g. `media-player--dark`.
+	pub fn modifier_class(&self, base_class: &str, modifier: &str) -> String {
+		format!("{}{}{}", base_class, self.modifier_separator, modifier)
+	}
+
+	/// Returns every class a block can produce: the block class, its modifier classes, and then
+	/// for each element the element class followed by its modifier classes. Repeated classes
+	/// are only listed once.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ parse, NamingScheme };
+	///
+	/// let bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+	///
+	/// assert_eq!(NamingScheme::default().class_names(&bem_block), vec![
+	///     "media-player",
+	///     "media-player--dark",
+	///     "media-player__button",
+	///     "media-player__button--rewind",
+	/// ]);
+	/// ```
+	pub fn class_names(&self, bem_block: &BEMBlock) -> Vec<String> {
+		let mut classes = vec![bem_block.name.clone()];
+
+		for modifier in &bem_block.modifiers {
+			classes.push(self.modifier_class(&bem_block.name, &modifier.class_name()));
+		}
+
+		for element in &bem_block.elements {
+			let element_class = self.element_class(&bem_block.name, &element.name);
+
+			let modifier_classes: Vec<String> = element.modifiers
+				.iter()
+				.map(|modifier| self.modifier_class(&element_class, &modifier.class_name()))
+				.collect();
+
+			classes.push(element_class);
+			classes.extend(modifier_classes);
+		}
+
+		let mut unique = Vec::with_capacity(classes.len());
+
+		for class in classes {
+			if !unique.contains(&class) {
+				unique.push(class);
+			}
+		}
+
+		unique
+	}
+
+	/// Returns the classes of [`class_names`](NamingScheme::class_names) within the limits of
+	/// `budget`: classes that are too long are abbreviated or rejected, see [`ClassBudget`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// use bem::{ parse, ClassBudget, NamingScheme };
+	///
+	/// let bem_block = parse("media-player\nfast-forward-button").unwrap();
+	/// let budget = ClassBudget { max_class_len: 20, max_attr_len: 255, abbreviate: true, length_mode: Default::default() };
+	/// let classes = NamingScheme::default().class_names_with_budget(&bem_block, &budget).unwrap();
+	///
+	/// assert_eq!(classes[0], "media-player");
+	/// assert!(classes[1].starts_with("media-playe-") && classes[1].len() == 20);
+	/// ```
+	pub fn class_names_with_budget(&self, bem_block: &BEMBlock, budget: &ClassBudget) -> Result<Vec<String>, BudgetError> {
+		budget.apply(&self.class_names(bem_block))
+	}
+}
+
+/// Escapes the characters that have a special meaning in regular expressions, so `text` is
+/// matched literally. The output is valid in both JavaScript and Rust regex syntax.
+pub(crate) fn regex_escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+
+	for c in text.chars() {
+		if "\\.+*?()|[]{}^$/".contains(c) {
+			escaped.push('\\');
+		}
+		escaped.push(c);
+	}
+
+	escaped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::NamingScheme;
+
+	#[test]
+	fn test_class_names_custom_scheme() {
+		let bem_block = crate::parse("media-player[dark]\nbutton[rewind]\ntimeline").unwrap();
+		let scheme = NamingScheme {
+			element_separator: "_".to_string(),
+			modifier_separator: "-".to_string(),
+		};
+
+		assert_eq!(scheme.class_names(&bem_block), vec![
+			"media-player",
+			"media-player-dark",
+			"media-player_button",
+			"media-player_button-rewind",
+			"media-player_timeline"
+		]);
+	}
+
+	#[test]
+	fn test_class_names_skips_repeated_classes() {
+		let bem_block = crate::parse("foo[bar,bar]\nbaz\nbaz").unwrap();
+
+		assert_eq!(NamingScheme::default().class_names(&bem_block), vec!["foo", "foo--bar", "foo__baz"]);
+	}
+
+	#[test]
+	fn test_regex_escape() {
+		assert_eq!(super::regex_escape("a.b+c(d)|[e]{f}^$\\/"), "a\\.b\\+c\\(d\\)\\|\\[e\\]\\{f\\}\\^\\$\\\\\\/");
+		assert_eq!(super::regex_escape("media-player__button"), "media-player__button");
+	}
+}
@@ -0,0 +1,126 @@
+//! This module generates React Native styles from BEM blocks. React Native has no class names,
+//! so every class becomes a key of a `StyleSheet` instead.
+
+use crate::abbreviation::AbbreviationMap;
+use crate::ident::{ convert_case, numbered_identifiers, Case };
+use crate::models::BEMBlock;
+use crate::naming::NamingScheme;
+
+impl BEMBlock {
+	/// Generates a TypeScript module exporting a React Native `StyleSheet` with one empty style
+	/// per class of the block.
+	///
+	/// Keys are the camelCased names of the entities: the block name for the block
+	/// (`mediaPlayer`), the element name for elements (`button`), and the name of the block or
+	/// element followed by the modifier for modifiers (`mediaPlayerDark`, `buttonFastForward`).
+	/// Repeated elements and modifiers only produce one key. A class whose key is taken by an
+	/// earlier class gets it numbered instead, e.g. `mediaPlayerDark_2` for the element
+	/// `media-player-dark` after the `dark` modifier of `media-player`.
+	///
+	/// # Returns
+	///
+	/// * `String` - The module source.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let module = parse("media-player[dark]\nbutton").unwrap().to_bem_native_module();
+	///
+	/// assert!(module.contains("const styles = StyleSheet.create({\n\tmediaPlayer: {},\n\tmediaPlayerDark: {},\n\tbutton: {},\n});"));
+	/// ```
+	pub fn to_bem_native_module(&self) -> String {
+		self.to_bem_native_module_with_abbreviations(&AbbreviationMap::default())
+	}
+
+	/// Generates the React Native module of [`to_bem_native_module`](BEMBlock::to_bem_native_module),
+	/// with the segments of every key abbreviated before it is camelCased.
+	///
+	/// # Returns
+	///
+	/// * `String` - The module source, with keys numbered once abbreviated.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::{ parse, AbbreviationMap };
+	///
+	/// let abbreviations = AbbreviationMap::from_json("{\"button\": \"btn\"}").unwrap();
+	/// let module = parse("media-player\nbutton[rewind]").unwrap().to_bem_native_module_with_abbreviations(&abbreviations);
+	///
+	/// assert!(module.contains("\tbtn: {},\n\tbtnRewind: {},\n"));
+	/// ```
+	pub fn to_bem_native_module_with_abbreviations(&self, abbreviations: &AbbreviationMap) -> String {
+		let scheme = NamingScheme::default();
+		let mut sources = vec![(self.name.clone(), self.name.clone())];
+
+		for modifier in &self.modifiers {
+			sources.push((scheme.modifier_class(&self.name, &modifier.class_name()), format!("{}-{}", self.name, modifier.class_name())));
+		}
+
+		for element in &self.elements {
+			let class = scheme.element_class(&self.name, &element.name);
+
+			sources.push((class.clone(), element.name.clone()));
+
+			for modifier in &element.modifiers {
+				sources.push((scheme.modifier_class(&class, &modifier.class_name()), format!("{}-{}", element.name, modifier.class_name())));
+			}
+		}
+
+		let keys = sources
+			.into_iter()
+			.map(|(class, name)| {
+				let key = convert_case(&abbreviations.abbreviate(&name), Case::Camel);
+
+				(class, key)
+			});
+		let styles: String = numbered_identifiers(keys)
+			.iter()
+			.map(|(_, key)| format!("\t{}: {{}},\n", key))
+			.collect();
+
+		format!(
+			"import {{ StyleSheet }} from \"react-native\";\n\
+			\n\
+			const styles = StyleSheet.create({{\n{}}});\n\
+			\n\
+			export default styles;\n",
+			styles
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::parse;
+
+	#[test]
+	fn test_to_bem_native_module() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let module = bem_block.to_bem_native_module();
+
+		assert!(module.contains("StyleSheet.create({"));
+
+		for key in ["mediaPlayer", "mediaPlayerDark", "button", "buttonFastForward", "buttonRewind", "timeline"] {
+			assert!(module.contains(&format!("\t{}: {{}},\n", key)), "{}", key);
+		}
+
+		insta::assert_snapshot!(module);
+	}
+
+	#[test]
+	fn test_to_bem_native_module_with_repeated_elements() {
+		let bem_block = parse("foo\nbar[baz]\nbar[baz]").unwrap();
+
+		assert_eq!(bem_block.to_bem_native_module().matches("barBaz: {}").count(), 1);
+	}
+
+	#[test]
+	fn test_to_bem_native_module_with_colliding_keys() {
+		let module = parse("media-player[dark]\nmedia-player-dark").unwrap().to_bem_native_module();
+
+		assert!(module.contains("\tmediaPlayer: {},\n\tmediaPlayerDark: {},\n\tmediaPlayerDark_2: {},\n"));
+	}
+}
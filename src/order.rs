@@ -0,0 +1,109 @@
+//! This module orders the elements and modifiers of blocks before they are emitted, so outputs
+//! can either follow the source or stay stable under reordering for diff-friendly files.
+
+use serde::{ Deserialize, Serialize };
+use crate::models::BEMBlock;
+
+/// The order in which generators emit elements and modifiers.
+///
+/// Both orders apply to the elements of a block and to every modifier list, of the block and of
+/// its elements, while blocks keep their order in the document.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ parse, to_bem, EmitOrder };
+///
+/// let bem_block = parse("media-player[dark,compact]\ntimeline\nbutton[rewind,fast-forward]").unwrap();
+///
+/// assert_eq!(to_bem(&EmitOrder::SourceOrder.apply(&bem_block)), to_bem(&bem_block));
+/// assert_eq!(
+///     to_bem(&EmitOrder::Alphabetical.apply(&bem_block)),
+///     "media-player[compact,dark]\nbutton[fast-forward,rewind]\ntimeline"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmitOrder {
+	/// The order of the source, as written in the `.bem` file.
+	#[default]
+	SourceOrder,
+	/// Alphabetical order of the names, comparing them by Unicode code points.
+	Alphabetical,
+}
+
+impl EmitOrder {
+	/// Returns a copy of `bem_block` with its elements and modifiers in this order.
+	pub fn apply(&self, bem_block: &BEMBlock) -> BEMBlock {
+		if *self == EmitOrder::SourceOrder {
+			return bem_block.clone();
+		}
+
+		let mut modifiers = bem_block.modifiers.clone();
+		let mut elements = bem_block.elements.to_vec();
+
+		modifiers.sort();
+		elements.sort_by(|a, b| a.name.cmp(&b.name));
+
+		for element in &mut elements {
+			element.modifiers.sort();
+		}
+
+		BEMBlock { name: bem_block.name.clone(), modifiers, elements: elements.into() }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::EmitOrder;
+	use crate::emit::{ EmitContext, EmitterRegistry };
+	use crate::models::BEMDocument;
+	use crate::parse;
+
+	const SCRAMBLED: &str = include_str!("../fixtures/order/scrambled.bem");
+
+	fn emit(format: &str, order: EmitOrder) -> String {
+		let document = BEMDocument::from(parse(SCRAMBLED).unwrap());
+		let context = EmitContext { order, ..EmitContext::default() };
+
+		String::from_utf8(EmitterRegistry::new().emit(format, &document, &context).unwrap()).unwrap()
+	}
+
+	#[test]
+	fn test_source_order_is_the_default() {
+		let bem_block = parse(SCRAMBLED).unwrap();
+
+		assert_eq!(EmitOrder::default().apply(&bem_block), bem_block);
+		assert_eq!(emit("bem", EmitOrder::SourceOrder), SCRAMBLED);
+	}
+
+	#[test]
+	fn test_css_source_order() {
+		insta::assert_snapshot!(emit("css", EmitOrder::SourceOrder));
+	}
+
+	#[test]
+	fn test_css_alphabetical() {
+		insta::assert_snapshot!(emit("css", EmitOrder::Alphabetical));
+	}
+
+	#[test]
+	fn test_scss_source_order() {
+		insta::assert_snapshot!(emit("scss", EmitOrder::SourceOrder));
+	}
+
+	#[test]
+	fn test_scss_alphabetical() {
+		insta::assert_snapshot!(emit("scss", EmitOrder::Alphabetical));
+	}
+
+	#[test]
+	fn test_classes_source_order() {
+		insta::assert_snapshot!(emit("classes", EmitOrder::SourceOrder));
+	}
+
+	#[test]
+	fn test_classes_alphabetical() {
+		insta::assert_snapshot!(emit("classes", EmitOrder::Alphabetical));
+	}
+}
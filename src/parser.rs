@@ -2,9 +2,32 @@
 //! notation into structured data formats. It leverages the Rust nom parser combinator library
 //! to provide efficient and robust parsing capabilities.
 
+use std::collections::HashMap;
 use pest::Parser;
+use pest::iterators::Pair;
 use pest_derive::Parser;
-use crate::models::{ BEMBlock, BEMElement };
+use crate::diagnostic::{ Diagnostic, Severity };
+use crate::elements::Elements;
+use crate::error::{ BEMParseError, Error };
+use crate::models::{ BEMBlock, BEMElement, BEMModifier };
+use crate::naming::BemConfig;
+use crate::raw::{ RawNode, RawRule };
+use crate::validator::{ separator_violations, strict_violations };
+
+/// The version of the `.bem` grammar this crate parses.
+///
+/// Documents may declare the version they are written for with a `#!bem <version>` directive on
+/// their first line, so files using newer syntax fail with a clear error on older parsers. The
+/// versions so far are:
+///
+/// 1. Blocks, elements, and modifier lists, with whitespace and trailing commas.
+/// 2. Modifier sets (`@set` and `@name` aliases), and empty modifier lists.
+/// 3. Modifier values, e.g. `[color=red]`, see [`BEMModifier`](crate::BEMModifier).
+/// 4. `//` line comments.
+/// 5. Several blocks in one file, separated by blank lines, see [`parse_all`].
+///
+/// The directive itself is accepted whatever the version.
+pub const GRAMMAR_VERSION: u32 = 5;
 
 #[derive(Parser)]
 #[grammar = "grammar/bem.pest"]
@@ -18,7 +41,23 @@ struct BEMGrammar;
 /// The expected format for the input string follows the BEM naming convention:
 /// - The block name is defined first.
 /// - Modifiers are enclosed in square brackets and separated by commas, e.g. `[modifier1,modifier2]`.
-/// - Elements are listed on new lines after the block, with their own names and modifiers.
+///   Whitespace, line breaks, and a trailing comma are allowed inside the brackets, which may
+///   also be empty.
+/// - Elements are listed on new lines after the block, with their own names and modifiers. An
+///   element listed again is merged into its first occurrence, see [`Elements`](crate::Elements),
+///   so the block may have fewer elements than the input has lines. [`parse_verbose`] reports
+///   every merged line as a `duplicate-element` warning.
+/// - Modifier sets shared by several lists can be defined before the block, one per line, e.g.
+///   `@set sizes = small,medium,large`, and used in any modifier list as `@sizes`. They are
+///   expanded in place, so they never appear in the output.
+/// - Modifiers may have a value, e.g. `[color=red,size=lg]`, stored apart from their name, see
+///   [`BEMModifier`](crate::BEMModifier). Their classes follow the key-value modifiers of BEM,
+///   e.g. `color_red`.
+/// - `//` starts a comment running to the end of the line. Comments may follow the block line,
+///   an element line, or a set definition, stand on their own lines, and appear inside modifier
+///   lists, e.g. `media-player[dark] // legacy theme`.
+/// - The first line may declare the grammar version of the document, e.g. `#!bem 2`, see
+///   [`GRAMMAR_VERSION`].
 ///
 /// # Arguments
 ///
@@ -26,8 +65,9 @@ struct BEMGrammar;
 ///
 /// # Returns
 ///
-/// * `Result<BEMBlock, String>` - A result containing the parsed `BEMBlock` structure if parsing was successful,
-///   or an error message if there was a problem during parsing.
+/// * `Result<BEMBlock, Error>` - A result containing the parsed `BEMBlock` structure if parsing was successful,
+///   or an [`Error::Parse`] locating the problem in the input, or an
+///   [`Error::UnsupportedGrammarVersion`] if the document declares a newer grammar version.
 ///
 /// # Examples
 ///
@@ -50,39 +90,542 @@ struct BEMGrammar;
 /// ```
 ///
 /// You can now access `bem_block.name`, `bem_block.modifiers`, and `bem_block.elements`.
-pub fn parse(input: &str) -> Result<BEMBlock, String> {
+///
+/// # Invariants
+///
+/// Parsing does not depend on formatting: whitespace and line breaks inside modifier lists,
+/// trailing commas, empty modifier lists, trailing line breaks, comments, and modifier sets are all
+/// normalized away. [`to_bem`](crate::to_bem) writes the normal form back, and property tests
+/// check that `parse(&to_bem(&x))` returns `x` for every block whose names follow the grammar.
+pub fn parse(input: &str) -> Result<BEMBlock, Error> {
+	parse_with_grammar_version(input, GRAMMAR_VERSION)
+}
+
+/// Parses a `.bem` document like [`parse`], for a project whose classes are composed with the
+/// separators of `config`.
+///
+/// The grammar does not depend on the separators, but a name containing one would produce
+/// classes that cannot be told apart from those of other entities, e.g. a `play-list` block
+/// under a `-` element separator, so such names are rejected.
+///
+/// # Arguments
+///
+/// * `input`: &str - The `.bem` document to be parsed.
+/// * `config`: &BemConfig - The separators the classes of the block are composed with.
+///
+/// # Returns
+///
+/// * `Result<BEMBlock, Error>` - The parsed block, a parse error, or an [`Error::Validation`]
+///   listing every name that contains a separator.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse_with_config, BemConfig };
+///
+/// let config = BemConfig { element_separator: "-".to_string(), modifier_separator: "_".to_string() };
+/// let block = parse_with_config("player[dark]\nbutton", &config).unwrap();
+///
+/// assert_eq!(block.elements[0].to_css_classes_with_config(&block.name, &config), vec!["player-button"]);
+/// assert!(parse_with_config("media-player", &config).is_err());
+/// ```
+pub fn parse_with_config(input: &str, config: &BemConfig) -> Result<BEMBlock, Error> {
+	let bem_block = parse(input)?;
+	let errors = separator_violations(&bem_block, config);
+
+	if !errors.is_empty() {
+		return Err(Error::Validation(errors));
+	}
+
+	Ok(bem_block)
+}
+
+/// Parses a `.bem` document like [`parse`], and then enforces strict BEM: every name, modifier
+/// name, and modifier value must match `^[a-z][a-z0-9-]*$`.
+///
+/// The grammar accepts uppercase letters after the first character of a name, and modifier
+/// values made of digits or uppercase letters, e.g. `size=XL` or `columns=2`. [`parse`] keeps
+/// accepting them, for teams that need it.
+///
+/// # Arguments
+///
+/// * `input`: &str - The `.bem` document to be parsed.
+///
+/// # Returns
+///
+/// * `Result<BEMBlock, Error>` - The parsed block, a parse error, or an [`Error::Validation`]
+///   listing every offending name.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, parse_strict, Error };
+///
+/// assert!(parse_strict("media-player[dark]\nbutton[size=lg]").is_ok());
+/// assert!(parse("media-player[columns=2]\nplayButton").is_ok());
+///
+/// let Err(Error::Validation(errors)) = parse_strict("media-player[columns=2]\nplayButton") else {
+///     panic!("expected a validation error");
+/// };
+///
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].to_string(), "media-player--columns_2: \"2\" does not match ^[a-z][a-z0-9-]*$");
+/// ```
+pub fn parse_strict(input: &str) -> Result<BEMBlock, Error> {
+	let bem_block = parse(input)?;
+	let errors = strict_violations(&bem_block);
+
+	if !errors.is_empty() {
+		return Err(Error::Validation(errors));
+	}
+
+	Ok(bem_block)
+}
+
+/// The result of [`parse_verbose`]: the parsed block, and the notes and warnings about input
+/// that was accepted but may not mean what its author intended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOutcome {
+	pub block: BEMBlock,
+	/// The diagnostics, in document order. See [`Diagnostic`] for the codes.
+	pub warnings: Vec<Diagnostic>,
+}
+
+/// Parses a `.bem` document like [`parse`], and also reports what was accepted silently: a byte
+/// order mark, elements listed twice, repeated modifiers, and expanded modifier sets.
+///
+/// # Arguments
+///
+/// * `input`: &str - The input string containing the BEM syntax to be parsed.
+///
+/// # Returns
+///
+/// * `Result<ParseOutcome, Error>` - The parsed block with its diagnostics, or the error
+///   [`parse`] would return.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, parse_verbose, Severity };
+///
+/// let outcome = parse_verbose("media-player\nbutton[rewind]\nbutton[fast-forward]").unwrap();
+///
+/// assert_eq!(outcome.block, parse("media-player\nbutton[rewind,fast-forward]").unwrap());
+/// assert_eq!(outcome.warnings[0].code, "duplicate-element");
+/// assert_eq!(outcome.warnings[0].severity, Severity::Warning);
+/// assert_eq!((outcome.warnings[0].line, outcome.warnings[0].column), (3, 1));
+/// ```
+pub fn parse_verbose(input: &str) -> Result<ParseOutcome, Error> {
+	parse_verbose_with_grammar_version(input, GRAMMAR_VERSION)
+}
+
+/// Parses a `.bem` document like [`parse_with_grammar_version`], with the diagnostics of
+/// [`parse_verbose`].
+pub fn parse_verbose_with_grammar_version(input: &str, version: u32) -> Result<ParseOutcome, Error> {
+	let (mut blocks, warnings) = parse_blocks(input, version, Rule::bem)?;
+
+	Ok(ParseOutcome { block: blocks.remove(0), warnings })
+}
+
+/// Parses a `.bem` file holding one or more blocks, separated by blank lines.
+///
+/// Every block is written like a document of [`parse`]. The directive and the modifier sets
+/// defined before the first block apply to every block of the file, and comments may stand
+/// between blocks.
+///
+/// # Arguments
+///
+/// * `input`: &str - The input string containing the blocks to be parsed.
+///
+/// # Returns
+///
+/// * `Result<Vec<BEMBlock>, Error>` - The blocks in file order, or an [`Error::Parse`] locating
+///   the problem in the input.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, parse_all };
+///
+/// let blocks = parse_all("@set sizes = small,large\n\nmedia-player[@sizes]\nbutton\n\n// lists\nplay-list\nitem").unwrap();
+///
+/// assert_eq!(blocks, vec![
+///     parse("media-player[small,large]\nbutton").unwrap(),
+///     parse("play-list\nitem").unwrap(),
+/// ]);
+/// assert!(parse("media-player\n\nplay-list").is_err());
+/// ```
+pub fn parse_all(input: &str) -> Result<Vec<BEMBlock>, Error> {
+	parse_all_verbose(input, GRAMMAR_VERSION).map(|(blocks, _)| blocks)
+}
+
+/// Parses a `.bem` file like [`parse_all`], as [`parse_verbose_with_grammar_version`] would.
+pub(crate) fn parse_all_verbose(input: &str, version: u32) -> Result<(Vec<BEMBlock>, Vec<Diagnostic>), Error> {
+	parse_blocks(input, version, Rule::bem_file)
+}
+
+/// Parses the blocks of a document matched by `rule`, skipping and reporting a byte order mark.
+fn parse_blocks(input: &str, version: u32, rule: Rule) -> Result<(Vec<BEMBlock>, Vec<Diagnostic>), Error> {
+	let Some(rest) = input.strip_prefix('\u{FEFF}') else {
+		return parse_document(input, version, rule);
+	};
+	let bom = '\u{FEFF}'.len_utf8();
+	// Spans point into `rest`, so move them past the byte order mark, which is one more
+	// character on the first line.
+	let shift = |span: &mut std::ops::Range<usize>, line: usize, column: &mut usize| {
+		*span = span.start + bom..span.end + bom;
+
+		if line == 1 {
+			*column += 1;
+		}
+	};
+	let (blocks, mut warnings) = parse_document(rest, version, rule).map_err(|error| match error {
+		Error::Parse(mut error) => {
+			shift(&mut error.span, error.line, &mut error.column);
+			Error::Parse(error)
+		}
+		error => error,
+	})?;
+
+	for warning in &mut warnings {
+		shift(&mut warning.span, warning.line, &mut warning.column);
+	}
+
+	warnings.insert(0, Diagnostic::at(
+		input,
+		Severity::Warning,
+		"bom",
+		0..bom,
+		"the input starts with a byte order mark, which was ignored".to_string()
+	));
+
+	Ok((blocks, warnings))
+}
+
+/// Parses a `.bem` document like [`parse`], but as a parser of an older grammar version would.
+///
+/// Documents declaring a version newer than `version` are rejected, and syntax introduced after
+/// the version in effect, which is the declared one or else `version`, fails with an error naming
+/// the feature and the version it requires. This checks that documents stay readable by older
+/// tools.
+///
+/// # Arguments
+///
+/// * `input`: &str - The input string containing the BEM syntax to be parsed.
+/// * `version`: u32 - The newest grammar version to accept, at most [`GRAMMAR_VERSION`].
+///
+/// # Returns
+///
+/// * `Result<BEMBlock, Error>` - The parsed block, an [`Error::UnsupportedGrammarVersion`] if
+///   the document declares a version newer than `version`, or an [`Error::Parse`] locating the
+///   problem in the input.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse_with_grammar_version, Error };
+///
+/// let error = parse_with_grammar_version("@set sizes = small\nbutton[@sizes]", 1).unwrap_err();
+///
+/// assert!(error.to_string().contains("modifier sets require grammar version 2"));
+/// assert!(matches!(
+///     parse_with_grammar_version("#!bem 2\nbutton", 1),
+///     Err(Error::UnsupportedGrammarVersion { version: 2, supported: 1 })
+/// ));
+/// assert!(parse_with_grammar_version("#!bem 1\nbutton[small]", 1).is_ok());
+/// ```
+pub fn parse_with_grammar_version(input: &str, version: u32) -> Result<BEMBlock, Error> {
+	parse_verbose_with_grammar_version(input, version).map(|outcome| outcome.block)
+}
+
+/// Parses a document without byte order mark, collecting its diagnostics.
+fn parse_document(input: &str, version: u32, rule: Rule) -> Result<(Vec<BEMBlock>, Vec<Diagnostic>), Error> {
+	let supported = version.min(GRAMMAR_VERSION);
+	let tree = parse_rule(rule, input)?;
+	let declared = tree.children
+		.iter()
+		.find(|node| node.rule == RawRule::Directive)
+		.map(|directive| directive.children[0].as_str(input).parse::<u32>().unwrap_or(u32::MAX));
+
+	if let Some(declared) = declared.filter(|declared| *declared == 0 || *declared > supported) {
+		return Err(Error::UnsupportedGrammarVersion { version: declared, supported });
+	}
+
+	check_features(&tree, input, declared.unwrap_or(supported))?;
+
+	let documents = split_blocks(&tree);
+
+	if let Some(second) = documents.get(1).filter(|_| declared.unwrap_or(supported) < 5) {
+		return Err(Error::Parse(BEMParseError::at(
+			input,
+			second.span.clone(),
+			format!("multiple blocks require grammar version 5, but the document is parsed as version {}", declared.unwrap_or(supported))
+		)));
+	}
+
+	let mut warnings = Vec::new();
+	let blocks = documents
+		.iter()
+		.map(|document| lower(document, input, &mut warnings))
+		.collect::<Result<_, _>>()?;
+
+	Ok((blocks, warnings))
+}
+
+/// Splits the tree of a file into one tree per block, each holding the directive, set
+/// definitions, and comments before the first block, so modifier sets apply to every block.
+fn split_blocks(tree: &RawNode) -> Vec<RawNode> {
+	let first = tree.children
+		.iter()
+		.position(|node| node.rule == RawRule::Block)
+		.unwrap_or(tree.children.len());
+	let (header, rest) = tree.children.split_at(first);
+	let mut documents: Vec<RawNode> = Vec::new();
+
+	for node in rest {
+		if node.rule == RawRule::Block {
+			documents.push(RawNode { rule: RawRule::Bem, span: node.span.clone(), children: header.to_vec() });
+		}
+
+		let document = documents.last_mut().expect("the nodes after the header start with a block");
+
+		document.span.end = node.span.end;
+		document.children.push(node.clone());
+	}
+
+	documents
+}
+
+/// Fails on the first node of the tree using syntax introduced after grammar `version`.
+fn check_features(node: &RawNode, input: &str, version: u32) -> Result<(), BEMParseError> {
+	let feature = match node.rule {
+		RawRule::SetDefinition | RawRule::Alias => Some(("modifier sets", 2)),
+		RawRule::Modifiers if node.children.iter().all(|child| child.rule == RawRule::Comment) => Some(("empty modifier lists", 2)),
+		RawRule::KeyValue => Some(("modifier values", 3)),
+		RawRule::Comment => Some(("comments", 4)),
+		_ => None,
+	};
+
+	if let Some((feature, required)) = feature.filter(|(_, required)| version < *required) {
+		return Err(BEMParseError::at(
+			input,
+			node.span.clone(),
+			format!("{} require grammar version {}, but the document is parsed as version {}", feature, required, version)
+		));
+	}
+
+	node.children.iter().try_for_each(|child| check_features(child, input, version))
+}
+
+/// Parses a document into the crate-owned parse tree, see [`parse_raw`](crate::raw::parse_raw).
+pub(crate) fn parse_tree(input: &str) -> Result<RawNode, pest::error::Error<Rule>> {
+	parse_rule(Rule::bem, input)
+}
+
+/// Parses a document matched by `rule` into the crate-owned parse tree.
+fn parse_rule(rule: Rule, input: &str) -> Result<RawNode, pest::error::Error<Rule>> {
+	let children = BEMGrammar::parse(rule, input)?
+		.filter(|pair| pair.as_rule() != Rule::EOI)
+		.map(raw_node)
+		.collect();
+
+	Ok(RawNode {
+		rule: RawRule::Bem,
+		span: 0..input.len(),
+		children,
+	})
+}
+
+fn raw_node(pair: Pair<Rule>) -> RawNode {
+	let rule = match pair.as_rule() {
+		Rule::block => RawRule::Block,
+		Rule::element | Rule::element_line => RawRule::Element,
+		Rule::name => RawRule::Name,
+		Rule::modifiers => RawRule::Modifiers,
+		Rule::set_definition => RawRule::SetDefinition,
+		Rule::alias => RawRule::Alias,
+		Rule::key_value => RawRule::KeyValue,
+		Rule::value => RawRule::Value,
+		Rule::directive => RawRule::Directive,
+		Rule::version => RawRule::Version,
+		Rule::comment => RawRule::Comment,
+		rule => panic!("Unexpected rule encountered: {:?}", rule),
+	};
+	let span = pair.as_span();
+
+	RawNode {
+		rule,
+		span: span.start()..span.end(),
+		children: pair
+			.into_inner()
+			.filter(|pair| pair.as_rule() != Rule::EOI)
+			.map(raw_node)
+			.collect(),
+	}
+}
+
+/// Parses a single element line, such as `button[fast-forward,rewind]`.
+///
+/// The line follows the same rules as the element lines of a `.bem` document: a name, then an
+/// optional modifier list, which may span several lines and contain whitespace between
+/// modifiers. Trailing line breaks are ignored, but any further line is rejected.
+///
+/// # Arguments
+///
+/// * `input`: &str - The element line to be parsed.
+///
+/// # Returns
+///
+/// * `Result<BEMElement, BEMParseError>` - The parsed element, or an error pointing at the
+///   offending part of the input.
+///
+/// # Examples
+///
+/// ```
+/// use bem::parse_element;
+///
+/// let element = parse_element("button[fast-forward, rewind]").unwrap();
+///
+/// assert_eq!(element.name, "button");
+/// assert_eq!(element.modifiers, vec!["fast-forward", "rewind"]);
+/// assert!(parse_element("button\ntimeline").is_err());
+/// ```
+pub fn parse_element(input: &str) -> Result<BEMElement, BEMParseError> {
+	let parse_line = |input| BEMGrammar::parse(Rule::element_line, input);
+	let pair = match parse_line(input) {
+		Ok(mut pairs) => pairs.next().expect("an element line is matched as a whole"),
+		Err(e) => {
+			let mut error = BEMParseError::from(e);
+
+			// The input up to the error is a complete element line followed by a line break, so the
+			// error is at the start of a second line.
+			let before = &input[..error.span.start];
+
+			if before.ends_with('\n') && parse_line(before).is_ok() {
+				error.message = "expected a single element line, found another line".to_string();
+			}
+
+			return Err(error);
+		}
+	};
+	let (name, modifiers) = parse_part(&raw_node(pair), input, &ModifierSets::new(), &mut Vec::new())?;
+
+	Ok(BEMElement { name, modifiers })
+}
+
+/// Parses the block header at the start of a `.bem` document, ignoring the lines after it.
+///
+/// The header follows the same rules as the first line of a document: a block name, then an
+/// optional modifier list. It must be followed by a line break or the end of the input. Parsing
+/// stops before that line break, and the number of bytes consumed is returned along with the
+/// header, so the rest of the input can be processed separately.
+///
+/// # Arguments
+///
+/// * `input`: &str - The input starting with a block header.
+///
+/// # Returns
+///
+/// * `Result<(String, Vec<BEMModifier>, usize), BEMParseError>` - The block name, its modifiers, and
+///   the length in bytes of the header, or an error pointing at the offending part of the input.
+///
+/// # Examples
+///
+/// ```
+/// use bem::parse_block_line;
+///
+/// let input = "media-player[dark]\nbutton";
+/// let (name, modifiers, consumed) = parse_block_line(input).unwrap();
+///
+/// assert_eq!(name, "media-player");
+/// assert_eq!(modifiers, vec!["dark"]);
+/// assert_eq!(&input[consumed..], "\nbutton");
+/// ```
+pub fn parse_block_line(input: &str) -> Result<(String, Vec<BEMModifier>, usize), BEMParseError> {
+	let pair = BEMGrammar::parse(Rule::block_line, input)?
+		.next()
+		.expect("a block line starts with a block");
+	let node = raw_node(pair);
+	let (name, modifiers) = parse_part(&node, input, &ModifierSets::new(), &mut Vec::new())?;
+
+	Ok((name, modifiers, node.span.end))
+}
+
+/// A modifier set defined by an `@set` line.
+struct ModifierSet {
+	/// The line of the definition, starting at 1.
+	line: usize,
+	modifiers: Vec<String>,
+}
+
+/// The modifier sets defined in a document, by name.
+type ModifierSets = HashMap<String, ModifierSet>;
+
+/// Lowers the parse tree of `input` into the `BEMBlock` it describes, expanding modifier sets.
+///
+/// Fails if a modifier set is defined twice, or if an alias refers to an undefined set. The
+/// diagnostics of [`parse_verbose`] are added to `diagnostics`.
+pub(crate) fn lower(tree: &RawNode, input: &str, diagnostics: &mut Vec<Diagnostic>) -> Result<BEMBlock, BEMParseError> {
+	let mut sets = ModifierSets::new();
 	let mut name = String::new();
 	let mut modifiers = Vec::new();
-	let mut elements = Vec::new();
-
-	match BEMGrammar::parse(Rule::bem, input) {
-		Ok(bem) => {
-			for pair in bem.into_iter() {
-				match pair.as_rule() {
-					Rule::block => {
-						let (block_name, block_modifiers) = parse_part(pair).expect(
-							"Error parsing block should never happen, ensured by grammar"
-						);
-						name = block_name;
-						modifiers = block_modifiers;
-					}
-					Rule::element => {
-						let (element_name, element_modifiers) = parse_part(pair).expect(
-							"Error parsing block should never happen, ensured by grammar"
-						);
-						elements.push(BEMElement {
-							name: element_name,
-							modifiers: element_modifiers,
-						});
-					}
-					_ => {
-						break;
-					}
+	let mut elements = Elements::new();
+
+	for node in &tree.children {
+		match node.rule {
+			RawRule::SetDefinition => {
+				let (set_name, set_modifiers) = node.children
+					.split_first()
+					.expect("a set definition starts with its name");
+				let set_name = set_name.as_str(input).to_string();
+				let (line, _) = pest::Position::new(input, node.span.start)
+					.expect("spans lie within the input")
+					.line_col();
+
+				if let Some(existing) = sets.get(&set_name) {
+					return Err(BEMParseError::at(
+						input,
+						node.span.clone(),
+						format!("modifier set `{}` is already defined on line {}", set_name, existing.line)
+					));
 				}
+
+				sets.insert(set_name, ModifierSet {
+					line,
+					modifiers: set_modifiers
+						.iter()
+						.map(|modifier| modifier.as_str(input).to_string())
+						.collect(),
+				});
+			}
+			RawRule::Directive | RawRule::Comment => {}
+			RawRule::Block => {
+				(name, modifiers) = parse_part(node, input, &sets, diagnostics)?;
+			}
+			RawRule::Element => {
+				let (element_name, element_modifiers) = parse_part(node, input, &sets, diagnostics)?;
+
+				if elements.contains(&element_name) {
+					// The element node starts with the line break before its name.
+					let start = node.children[0].span.start;
+
+					diagnostics.push(Diagnostic::at(
+						input,
+						Severity::Warning,
+						"duplicate-element",
+						start..start + element_name.len(),
+						format!("element `{}` is listed again, its modifiers are merged into its first occurrence", element_name)
+					));
+				}
+
+				elements.insert(BEMElement {
+					name: element_name,
+					modifiers: element_modifiers,
+				});
+			}
+			_ => {
+				break;
 			}
-		}
-		Err(e) => {
-			return Err(format!("Pest parsing error: {}", e));
 		}
 	}
 
@@ -93,24 +636,60 @@ pub fn parse(input: &str) -> Result<BEMBlock, String> {
 	})
 }
 
-fn parse_part(pair: pest::iterators::Pair<Rule>) -> Result<(String, Vec<String>), String> {
+fn parse_part(node: &RawNode, input: &str, sets: &ModifierSets, diagnostics: &mut Vec<Diagnostic>) -> Result<(String, Vec<BEMModifier>), BEMParseError> {
 	let mut name = String::new();
-	let mut modifiers = Vec::new();
+	let mut modifiers: Vec<BEMModifier> = Vec::new();
 
-	for inner_pair in pair.into_inner() {
-		match inner_pair.as_rule() {
-			Rule::name => {
-				name = inner_pair.as_str().to_string();
+	for child in &node.children {
+		match child.rule {
+			RawRule::Name => {
+				name = child.as_str(input).to_string();
 			}
-			Rule::modifiers => {
-				for modifier in inner_pair.into_inner() {
-					if let Rule::name = modifier.as_rule() {
-						modifiers.push(modifier.as_str().to_string());
+			RawRule::Modifiers => {
+				for modifier in &child.children {
+					match modifier.rule {
+						RawRule::Name | RawRule::KeyValue => {
+							let parsed = match modifier.rule {
+								RawRule::KeyValue => BEMModifier::new(modifier.children[0].as_str(input)).with_value(modifier.children[1].as_str(input)),
+								_ => BEMModifier::new(modifier.as_str(input)),
+							};
+
+							if modifiers.contains(&parsed) {
+								diagnostics.push(Diagnostic::at(
+									input,
+									Severity::Warning,
+									"duplicate-modifier",
+									modifier.span.clone(),
+									format!("modifier `{}` is repeated in the list", modifier.as_str(input))
+								));
+							}
+
+							modifiers.push(parsed);
+						}
+						RawRule::Alias => {
+							let set_name = modifier.children[0].as_str(input);
+							let set = sets.get(set_name).ok_or_else(|| BEMParseError::at(
+								input,
+								modifier.span.clone(),
+								format!("modifier set `{}` is not defined", set_name)
+							))?;
+
+							diagnostics.push(Diagnostic::at(
+								input,
+								Severity::Note,
+								"alias-expanded",
+								modifier.span.clone(),
+								format!("`@{}` is expanded to `{}`", set_name, set.modifiers.join(","))
+							));
+							modifiers.extend(set.modifiers.iter().map(BEMModifier::from));
+						}
+						_ => {}
 					}
 				}
 			}
+			RawRule::Comment => {}
 			_ => {
-				panic!("Unexpected rule encountered: {:?}", inner_pair.as_rule());
+				panic!("Unexpected rule encountered: {:?}", child.rule);
 			}
 		}
 	}
@@ -118,8 +697,47 @@ fn parse_part(pair: pest::iterators::Pair<Rule>) -> Result<(String, Vec<String>)
 	Ok((name, modifiers))
 }
 
+/// A single BEM class name split into its parts, e.g. `media-player__button--rewind`.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ClassName {
+	pub block: String,
+	pub element: Option<String>,
+	pub modifier: Option<String>,
+}
+
+/// Splits a class name into its block, element, and modifier parts, using the same name rules
+/// as the `.bem` grammar. Returns `None` for anything that is not a BEM class.
+pub(crate) fn parse_class(input: &str) -> Option<ClassName> {
+	let pair = BEMGrammar::parse(Rule::class, input).ok()?.next()?;
+	let mut class_name = ClassName {
+		block: String::new(),
+		element: None,
+		modifier: None,
+	};
+
+	for inner_pair in pair.into_inner() {
+		match inner_pair.as_rule() {
+			Rule::name => {
+				class_name.block = inner_pair.as_str().to_string();
+			}
+			Rule::class_element => {
+				class_name.element = Some(inner_pair.as_str()[2..].to_string());
+			}
+			Rule::class_modifier => {
+				class_name.modifier = Some(inner_pair.as_str()[2..].to_string());
+			}
+			_ => {}
+		}
+	}
+
+	Some(class_name)
+}
+
 #[cfg(test)]
 mod tests {
+	use crate::diagnostic::Severity;
+	use crate::models::BEMModifier;
+
 	#[test]
 	fn test_parse_block() {
 		let input = "foo";
@@ -132,7 +750,7 @@ mod tests {
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "foo".to_string(),
 			modifiers: vec![],
-			elements: vec![],
+			elements: vec![].into(),
 		});
 	}
 
@@ -148,7 +766,7 @@ mod tests {
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "foo-bar-baz".to_string(),
 			modifiers: vec![],
-			elements: vec![],
+			elements: vec![].into(),
 		});
 	}
 
@@ -163,8 +781,8 @@ mod tests {
 
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "foo".to_string(),
-			modifiers: vec!["bar".to_string()],
-			elements: vec![],
+			modifiers: vec!["bar".into()],
+			elements: vec![].into(),
 		});
 	}
 
@@ -179,8 +797,8 @@ mod tests {
 
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "foo".to_string(),
-			modifiers: vec!["bar-baz-qux".to_string()],
-			elements: vec![],
+			modifiers: vec!["bar-baz-qux".into()],
+			elements: vec![].into(),
 		});
 	}
 
@@ -195,8 +813,8 @@ mod tests {
 
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "foo".to_string(),
-			modifiers: vec!["bar".to_string(), "baz".to_string(), "qux".to_string()],
-			elements: vec![],
+			modifiers: vec!["bar".into(), "baz".into(), "qux".into()],
+			elements: vec![].into(),
 		});
 	}
 
@@ -211,8 +829,8 @@ mod tests {
 
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "foo".to_string(),
-			modifiers: vec!["bar".to_string(), "baz".to_string()],
-			elements: vec![],
+			modifiers: vec!["bar".into(), "baz".into()],
+			elements: vec![].into(),
 		});
 	}
 
@@ -227,8 +845,8 @@ mod tests {
 
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "foo".to_string(),
-			modifiers: vec!["bar".to_string(), "baz".to_string()],
-			elements: vec![],
+			modifiers: vec!["bar".into(), "baz".into()],
+			elements: vec![].into(),
 		});
 	}
 
@@ -243,11 +861,19 @@ mod tests {
 
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "foo".to_string(),
-			modifiers: vec!["bar".to_string(), "baz".to_string()],
-			elements: vec![],
+			modifiers: vec!["bar".into(), "baz".into()],
+			elements: vec![].into(),
 		});
 	}
 
+	#[test]
+	fn test_parse_empty_modifiers() {
+		let bem_block = super::parse("foo[]\nbar[ \n\t]").unwrap();
+
+		assert_eq!(bem_block, super::parse("foo\nbar").unwrap());
+		assert!(super::parse("foo[,]").is_err());
+	}
+
 	#[test]
 	fn test_parse_block_with_parentheses() {
 		let input = "foo(bar,baz)";
@@ -277,7 +903,7 @@ mod tests {
 			elements: vec![super::BEMElement {
 				name: "bar".to_string(),
 				modifiers: vec![],
-			}],
+			}].into(),
 		});
 	}
 
@@ -296,7 +922,7 @@ mod tests {
 			elements: vec![super::BEMElement {
 				name: "bar-baz-qux".to_string(),
 				modifiers: vec![],
-			}],
+			}].into(),
 		});
 	}
 
@@ -314,8 +940,8 @@ mod tests {
 			modifiers: vec![],
 			elements: vec![super::BEMElement {
 				name: "bar".to_string(),
-				modifiers: vec!["baz".to_string()],
-			}],
+				modifiers: vec!["baz".into()],
+			}].into(),
 		});
 	}
 
@@ -333,8 +959,8 @@ mod tests {
 			modifiers: vec![],
 			elements: vec![super::BEMElement {
 				name: "bar".to_string(),
-				modifiers: vec!["baz".to_string(), "qux".to_string()],
-			}],
+				modifiers: vec!["baz".into(), "qux".into()],
+			}].into(),
 		});
 	}
 
@@ -352,8 +978,8 @@ mod tests {
 			modifiers: vec![],
 			elements: vec![super::BEMElement {
 				name: "bar".to_string(),
-				modifiers: vec!["baz".to_string(), "qux".to_string()],
-			}],
+				modifiers: vec!["baz".into(), "qux".into()],
+			}].into(),
 		});
 	}
 
@@ -371,8 +997,8 @@ mod tests {
 			modifiers: vec![],
 			elements: vec![super::BEMElement {
 				name: "bar".to_string(),
-				modifiers: vec!["baz".to_string(), "qux".to_string()],
-			}],
+				modifiers: vec!["baz".into(), "qux".into()],
+			}].into(),
 		});
 	}
 
@@ -390,8 +1016,8 @@ mod tests {
 			modifiers: vec![],
 			elements: vec![super::BEMElement {
 				name: "bar".to_string(),
-				modifiers: vec!["baz".to_string(), "qux".to_string()],
-			}],
+				modifiers: vec!["baz".into(), "qux".into()],
+			}].into(),
 		});
 	}
 
@@ -434,7 +1060,7 @@ mod tests {
 					name: "qux".to_string(),
 					modifiers: vec![],
 				}
-			],
+			].into(),
 		});
 	}
 
@@ -449,11 +1075,11 @@ mod tests {
 
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "a".to_string(),
-			modifiers: vec!["b".to_string(), "c".to_string()],
+			modifiers: vec!["b".into(), "c".into()],
 			elements: vec![
 				super::BEMElement {
 					name: "d".to_string(),
-					modifiers: vec!["e".to_string(), "f".to_string()],
+					modifiers: vec!["e".into(), "f".into()],
 				},
 				super::BEMElement {
 					name: "g".to_string(),
@@ -461,12 +1087,155 @@ mod tests {
 				},
 				super::BEMElement {
 					name: "h".to_string(),
-					modifiers: vec!["i".to_string()],
+					modifiers: vec!["i".into()],
 				}
-			],
+			].into(),
 		});
 	}
 
+	#[test]
+	fn test_parse_class() {
+		assert_eq!(super::parse_class("media-player"), Some(super::ClassName {
+			block: "media-player".to_string(),
+			element: None,
+			modifier: None,
+		}));
+		assert_eq!(super::parse_class("media-player--dark"), Some(super::ClassName {
+			block: "media-player".to_string(),
+			element: None,
+			modifier: Some("dark".to_string()),
+		}));
+		assert_eq!(super::parse_class("media-player__button--fast-forward"), Some(super::ClassName {
+			block: "media-player".to_string(),
+			element: Some("button".to_string()),
+			modifier: Some("fast-forward".to_string()),
+		}));
+	}
+
+	#[test]
+	fn test_parse_class_rejects_non_bem_classes() {
+		assert_eq!(super::parse_class("Button"), None);
+		assert_eq!(super::parse_class("media-player__a__b"), None);
+		assert_eq!(super::parse_class("media-player---dark"), None);
+		assert_eq!(super::parse_class("media player"), None);
+		assert_eq!(super::parse_class(""), None);
+	}
+
+	#[test]
+	fn test_parse_element_line() {
+		assert_eq!(super::parse_element("button[fast-forward,rewind]"), Ok(super::BEMElement {
+			name: "button".to_string(),
+			modifiers: vec!["fast-forward".into(), "rewind".into()],
+		}));
+		assert_eq!(super::parse_element("timeline\n"), Ok(super::BEMElement {
+			name: "timeline".to_string(),
+			modifiers: vec![],
+		}));
+	}
+
+	#[test]
+	fn test_parse_element_whitespace() {
+		let expected = super::parse("foo\nbutton[\n\tfast-forward,\n\trewind,\n]").unwrap().elements.remove("button").unwrap();
+
+		assert_eq!(super::parse_element("button[\n\tfast-forward,\n\trewind,\n]"), Ok(expected));
+		assert!(super::parse_element(" button").is_err());
+		assert!(super::parse_element("button ").is_err());
+	}
+
+	#[test]
+	fn test_parse_element_rejects_multiple_lines() {
+		let error = super::parse_element("button[rewind]\n\ntimeline").unwrap_err();
+
+		assert_eq!(error.to_string(), "line 3, column 1: expected a single element line, found another line");
+		assert_eq!(error.span, 16..16);
+	}
+
+	#[test]
+	fn test_parse_element_with_invalid_modifiers() {
+		let error = super::parse_element("button(rewind)").unwrap_err();
+
+		assert_eq!(error.to_string(), "line 1, column 7: expected EOI, modifiers, or comment");
+	}
+
+	#[test]
+	fn test_parse_block_line() {
+		assert_eq!(super::parse_block_line("media-player"), Ok(("media-player".to_string(), vec![], 12)));
+		assert_eq!(
+			super::parse_block_line("media-player[dark, light]"),
+			Ok(("media-player".to_string(), vec!["dark".into(), "light".into()], 25))
+		);
+	}
+
+	#[test]
+	fn test_parse_block_line_stops_at_newline() {
+		let input = "media-player[dark]\nbutton(rewind)";
+		let (name, modifiers, consumed) = super::parse_block_line(input).unwrap();
+
+		assert_eq!((name.as_str(), modifiers, consumed), ("media-player", vec!["dark".into()], 18));
+		assert_eq!(&input[consumed..], "\nbutton(rewind)");
+	}
+
+	#[test]
+	fn test_parse_block_line_with_invalid_name() {
+		let error = super::parse_block_line("Media-player").unwrap_err();
+
+		assert_eq!((error.line, error.column), (1, 1));
+
+		let error = super::parse_block_line("media_player\nbutton").unwrap_err();
+
+		assert_eq!((error.line, error.column), (1, 6));
+		assert_eq!(error.span, 5..5);
+	}
+
+	#[test]
+	fn test_parse_modifier_sets() {
+		let input = "@set sizes = small, medium,large\n@set themes=dark,light\n\nbutton[@themes]\nicon[@sizes]\nlabel[@sizes, bold]";
+
+		assert_eq!(
+			super::parse(input).unwrap(),
+			super::parse("button[dark,light]\nicon[small,medium,large]\nlabel[small,medium,large,bold]").unwrap()
+		);
+	}
+
+	#[test]
+	fn test_parse_modifier_sets_mixed_with_modifiers() {
+		let input = "@set sizes = small,large\nbutton[primary, @sizes, ghost]";
+
+		assert_eq!(super::parse(input).unwrap().modifiers, vec!["primary", "small", "large", "ghost"]);
+	}
+
+	#[test]
+	fn test_parse_modifier_set_defined_after_use() {
+		assert!(super::parse("button[@sizes]\n@set sizes = small,large").is_err());
+	}
+
+	#[test]
+	fn test_parse_undefined_modifier_set() {
+		let error = super::parse("@set sizes = small,large\nbutton\nicon[bold, @size]").unwrap_err();
+
+		assert_eq!(error.to_string(), "parse error at line 3, column 12: modifier set `size` is not defined");
+		assert_eq!(super::parse_element("icon[@sizes]").unwrap_err().to_string(), "line 1, column 6: modifier set `sizes` is not defined");
+	}
+
+	#[test]
+	fn test_parse_duplicate_modifier_set() {
+		let error = super::parse("@set sizes = small\n@set themes = dark\n@set sizes = large\nbutton").unwrap_err();
+
+		assert_eq!(error.to_string(), "parse error at line 3, column 1: modifier set `sizes` is already defined on line 1");
+	}
+
+	#[test]
+	fn test_parse_merges_duplicate_elements() {
+		let input = "media-player\nbutton[rewind]\ntimeline\nbutton[fast-forward,rewind]";
+		let bem_block = super::parse(input).unwrap();
+		let outcome = super::parse_verbose(input).unwrap();
+
+		assert_eq!(bem_block.to_string(), "media-player\nbutton[rewind,fast-forward]\ntimeline");
+		assert_eq!(outcome.block, bem_block);
+		assert_eq!(outcome.warnings.len(), 1);
+		assert_eq!((outcome.warnings[0].code, outcome.warnings[0].line), ("duplicate-element", 4));
+	}
+
 	#[test]
 	fn test_parse_final_newlines() {
 		let input = "foo\n\n\n";
@@ -479,7 +1248,180 @@ mod tests {
 		assert_eq!(bem_block, super::BEMBlock {
 			name: "foo".to_string(),
 			modifiers: vec![],
-			elements: vec![],
+			elements: vec![].into(),
 		});
 	}
+
+	#[test]
+	fn test_parse_grammar_version_directive() {
+		let bem_block = super::parse("foo[bar]").unwrap();
+
+		assert_eq!(super::parse("#!bem 2\nfoo[bar]").unwrap(), bem_block);
+		assert_eq!(super::parse("#!bem 1 \r\n\nfoo[bar]").unwrap(), bem_block);
+		assert_eq!(super::parse_with_grammar_version("foo[bar]", 1).unwrap(), bem_block);
+		assert!(super::parse("foo\n#!bem 2").is_err());
+	}
+
+	#[test]
+	fn test_parse_newer_grammar_version() {
+		let error = super::parse(&format!("#!bem {}\nfoo", super::GRAMMAR_VERSION + 1)).unwrap_err();
+
+		assert!(matches!(error, super::Error::UnsupportedGrammarVersion { version, supported }
+			if version == super::GRAMMAR_VERSION + 1 && supported == super::GRAMMAR_VERSION));
+		assert_eq!(error.to_string(), format!(
+			"unsupported grammar version {}, expected 1 to {}",
+			super::GRAMMAR_VERSION + 1,
+			super::GRAMMAR_VERSION
+		));
+		assert!(matches!(super::parse("#!bem 0\nfoo"), Err(super::Error::UnsupportedGrammarVersion { version: 0, .. })));
+		assert!(matches!(
+			super::parse("#!bem 99999999999\nfoo"),
+			Err(super::Error::UnsupportedGrammarVersion { version: u32::MAX, .. })
+		));
+	}
+
+	#[test]
+	fn test_parse_older_grammar_version_rejects_newer_syntax() {
+		let error = |input: &str, version: u32| match super::parse_with_grammar_version(input, version) {
+			Err(super::Error::Parse(error)) => error,
+			result => panic!("expected a parse error, got {:?}", result),
+		};
+
+		let set = error("@set sizes = small\nfoo", 1);
+
+		assert_eq!(set.message, "modifier sets require grammar version 2, but the document is parsed as version 1");
+		assert_eq!((set.line, set.column), (1, 1));
+		assert_eq!(error("foo\nbar[\n]", 1).message, "empty modifier lists require grammar version 2, but the document is parsed as version 1");
+		assert_eq!(error("#!bem 1\nfoo[]", 2).line, 2);
+		assert!(super::parse_with_grammar_version("#!bem 2\nfoo[]", 2).is_ok());
+		assert_eq!(error("foo[color=red]", 2).message, "modifier values require grammar version 3, but the document is parsed as version 2");
+		assert_eq!(error("foo\nbar[size=lg]", 2).column, 5);
+		assert_eq!(error("foo // legacy", 3).message, "comments require grammar version 4, but the document is parsed as version 3");
+		assert_eq!(error("foo[\n// none\n]", 3).message, "comments require grammar version 4, but the document is parsed as version 3");
+	}
+
+	#[test]
+	fn test_parse_modifier_values() {
+		let bem_block = super::parse("button[color=red, size=lg,disabled]\nicon[cols=12]").unwrap();
+
+		assert_eq!(bem_block.modifiers, vec!["color=red", "size=lg", "disabled"]);
+		assert_eq!(bem_block.modifiers[0], BEMModifier::new("color").with_value("red"));
+		assert_eq!(bem_block.elements[0].modifiers, vec!["cols=12"]);
+		assert_eq!(bem_block.to_css_classes(), vec!["button", "button--color_red", "button--size_lg", "button--disabled"]);
+		assert_eq!(bem_block.elements[0].to_css_classes("button"), vec!["button__icon", "button__icon--cols_12"]);
+		assert_eq!(bem_block.to_string(), "button[color=red,size=lg,disabled]\nicon[cols=12]");
+		assert_eq!(super::parse(&bem_block.to_string()).unwrap(), bem_block);
+		assert_eq!(super::parse_element("icon[cols=12]").unwrap().modifiers, vec!["cols=12"]);
+		assert!(super::parse("button[color=]").is_err());
+		assert!(super::parse("button[color=red=dark]").is_err());
+		assert!(super::parse("button[color = red]").is_err());
+		assert_eq!(super::parse_verbose("button[color=red,color=red]").unwrap().warnings[0].message, "modifier `color=red` is repeated in the list");
+		assert_eq!(super::parse_class("button--color_red").unwrap().modifier.as_deref(), Some("color_red"));
+		assert!(super::parse_class("button--color_").is_none());
+	}
+
+	#[test]
+	fn test_parse_comments() {
+		let bem_block = super::parse("media-player[dark]\nbutton[rewind]\ntimeline").unwrap();
+
+		assert_eq!(super::parse("media-player[dark] // legacy theme\nbutton[rewind]\ntimeline").unwrap(), bem_block);
+		assert_eq!(super::parse("media-player[dark]\nbutton[rewind]\t// seek back\ntimeline //").unwrap(), bem_block);
+		assert_eq!(super::parse("media-player[dark]\n// controls\n  // and more\nbutton[rewind]\ntimeline").unwrap(), bem_block);
+		assert_eq!(super::parse("// media player\n@set themes = dark // one for now\nmedia-player[@themes]\nbutton[\n\trewind, // seek back\n]\ntimeline\n// end\n").unwrap(), bem_block);
+		assert_eq!(super::parse_element("button[rewind] // seek back").unwrap(), bem_block.elements[0]);
+		assert!(super::parse("media-player[dark]// legacy theme").is_ok());
+		assert!(super::parse("media-player[dark] / legacy theme").is_err());
+		assert!(super::parse("media-player // legacy theme [dark]").is_ok_and(|bem_block| bem_block.modifiers.is_empty()));
+	}
+
+	#[test]
+	fn test_parse_all() {
+		let blocks = super::parse_all("#!bem 5\n@set sizes = small\n\nmedia-player[dark]\nbutton[@sizes]\n\n\t\n// lists\n\nplay-list // sidebar\n\nsearch-box\ninput\n\n").unwrap();
+		let names: Vec<&str> = blocks.iter().map(|bem_block| bem_block.name.as_str()).collect();
+
+		assert_eq!(names, vec!["media-player", "play-list", "search-box"]);
+		assert_eq!(blocks[0].elements[0].modifiers, vec!["small"]);
+		assert_eq!(super::parse_all("media-player[dark]\nbutton").unwrap(), vec![super::parse("media-player[dark]\nbutton").unwrap()]);
+		assert!(super::parse_all("media-player\n\n").is_ok_and(|blocks| blocks.len() == 1));
+		assert!(super::parse_all("media-player\n\n@set sizes = small\nplay-list").is_err());
+		assert!(super::parse_all("media-player\n\nbutton(rewind)").is_err());
+		assert!(super::parse("media-player\n\nplay-list").is_err());
+	}
+
+	#[test]
+	fn test_parse_with_config() {
+		let config = crate::BemConfig { element_separator: "-".to_string(), modifier_separator: "__".to_string() };
+		let errors = match super::parse_with_config("play-list[dark]\nitem[now-playing]", &config) {
+			Err(crate::Error::Validation(errors)) => errors,
+			result => panic!("expected a validation error, got {:?}", result),
+		};
+		let locations: Vec<String> = errors.iter().map(|error| error.location.clone()).collect();
+
+		assert_eq!(locations, vec!["play-list", "play-list-item__now-playing"]);
+		assert_eq!(errors[0].to_string(), "play-list: \"play-list\" contains a separator of the naming scheme");
+		assert_eq!(super::parse_with_config("play-list", &crate::BemConfig::default()).unwrap(), super::parse("play-list").unwrap());
+		assert!(super::parse_with_config("media-player(dark)", &config).is_err_and(|error| error.code() != "validation"));
+	}
+
+	#[test]
+	fn test_parse_strict() {
+		let input = "media-player[darkMode,size=XL,count=2]\nplay-Button[rewind]\ntimeline[live,v2]";
+		let errors = match super::parse_strict(input) {
+			Err(crate::Error::Validation(errors)) => errors,
+			result => panic!("expected a validation error, got {:?}", result),
+		};
+		let names: Vec<&str> = errors.iter().map(|error| error.name.as_str()).collect();
+
+		assert_eq!(names, vec!["darkMode", "XL", "2", "play-Button"]);
+		assert_eq!(errors[3].location, "media-player__play-Button");
+		assert!(super::parse(input).is_ok());
+		assert!(super::parse_strict("media-player(dark)").is_err_and(|error| error.code() == "parse"));
+	}
+
+	#[test]
+	fn test_parse_all_warnings() {
+		let (blocks, warnings) = super::parse_all_verbose("\u{FEFF}media-player\n\nplay-list\nitem\nitem", super::GRAMMAR_VERSION).unwrap();
+		let codes: Vec<(&str, usize, usize)> = warnings.iter().map(|warning| (warning.code, warning.line, warning.column)).collect();
+
+		assert_eq!(blocks.len(), 2);
+		assert_eq!(codes, vec![("bom", 1, 1), ("duplicate-element", 5, 1)]);
+		assert!(matches!(
+			super::parse_all_verbose("#!bem 4\nmedia-player\n\nplay-list", 5),
+			Err(super::Error::Parse(error)) if error.line == 4
+				&& error.message == "multiple blocks require grammar version 5, but the document is parsed as version 4"
+		));
+	}
+
+	#[test]
+	fn test_parse_verbose_warnings() {
+		let input = "\u{FEFF}@set sizes = small\nmedia-player[dark, dark]\nbutton[@sizes]\nbutton[rewind]";
+		let outcome = super::parse_verbose(input).unwrap();
+		let codes: Vec<(&str, Severity, usize, usize)> = outcome.warnings
+			.iter()
+			.map(|warning| (warning.code, warning.severity, warning.line, warning.column))
+			.collect();
+
+		assert_eq!(outcome.block, super::parse("media-player[dark,dark]\nbutton[small,rewind]").unwrap());
+		assert_eq!(codes, vec![
+			("bom", Severity::Warning, 1, 1),
+			("duplicate-modifier", Severity::Warning, 2, 20),
+			("alias-expanded", Severity::Note, 3, 8),
+			("duplicate-element", Severity::Warning, 4, 1),
+		]);
+		assert_eq!(&input[outcome.warnings[1].span.clone()], "dark");
+		assert_eq!(&input[outcome.warnings[2].span.clone()], "@sizes");
+		assert_eq!(&input[outcome.warnings[3].span.clone()], "button");
+		assert_eq!(outcome.warnings[2].to_string(), "note[alias-expanded]: line 3, column 8: `@sizes` is expanded to `small`");
+		assert!(super::parse_verbose("media-player\nbutton").unwrap().warnings.is_empty());
+	}
+
+	#[test]
+	fn test_parse_verbose_errors_after_bom() {
+		let error = match super::parse("\u{FEFF}foo(bar)") {
+			Err(super::Error::Parse(error)) => error,
+			result => panic!("expected a parse error, got {:?}", result),
+		};
+
+		assert_eq!((error.span, error.line, error.column), (6..6, 1, 5));
+	}
 }
@@ -0,0 +1,162 @@
+//! This module applies JSON merge patches (RFC 7386) to blocks, the format content management
+//! systems emit to describe changes to a document without repeating the unchanged parts.
+
+use std::collections::HashSet;
+use serde::de::Error as _;
+use serde_json::{ Map, Value };
+use crate::error::{ Error, Result };
+use crate::models::BEMBlock;
+use crate::validator::validate;
+
+/// Applies a JSON merge patch to the JSON form of a block, as written by
+/// [`to_json`](crate::to_json).
+///
+/// Following RFC 7386, `null` members of the patch remove the member of the block, object members
+/// are merged recursively, and every other member, including arrays, replaces the member of the
+/// block wholesale. A patch that is not an object replaces the whole block. Since every block has
+/// modifiers and elements, removing either empties it.
+///
+/// The result is checked before `block` is changed: it must describe a block, its names must
+/// follow the naming conventions, see [`validate`], and no two of its elements may share a name.
+/// If any check fails, `block` is left as it was.
+///
+/// # Arguments
+///
+/// * `block`: &mut BEMBlock - The block to patch.
+/// * `patch`: &serde_json::Value - The merge patch.
+///
+/// # Returns
+///
+/// * `Result<(), Error>` - An [`Error::Deserialize`] if the result does not describe a block or
+///   repeats an element, or an [`Error::Validation`] if it breaks the naming conventions.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ apply_merge_patch, parse, to_bem };
+///
+/// let mut bem_block = parse("media-player[dark]\nbutton[rewind]").unwrap();
+/// let patch = serde_json::json!({ "modifiers": null, "elements": [{ "name": "timeline", "modifiers": ["live"] }] });
+///
+/// apply_merge_patch(&mut bem_block, &patch).unwrap();
+///
+/// assert_eq!(to_bem(&bem_block), "media-player\ntimeline[live]");
+/// assert!(apply_merge_patch(&mut bem_block, &serde_json::json!({ "name": "Media-Player" })).is_err());
+/// assert_eq!(bem_block.name, "media-player");
+/// ```
+pub fn apply_merge_patch(block: &mut BEMBlock, patch: &Value) -> Result<()> {
+	let mut target = serde_json::to_value(&*block).map_err(Error::Serialize)?;
+
+	merge(&mut target, patch);
+
+	if let Value::Object(members) = &mut target {
+		members.entry("modifiers").or_insert_with(|| Value::Array(Vec::new()));
+		members.entry("elements").or_insert_with(|| Value::Array(Vec::new()));
+	}
+
+	check_unique_elements(&target)?;
+
+	let patched: BEMBlock = serde_json::from_value(target).map_err(Error::Deserialize)?;
+	let errors = validate(&patched);
+
+	if !errors.is_empty() {
+		return Err(Error::Validation(errors));
+	}
+
+	*block = patched;
+	Ok(())
+}
+
+/// Merges `patch` into `target` as RFC 7386 describes.
+fn merge(target: &mut Value, patch: &Value) {
+	let Value::Object(patch) = patch else {
+		*target = patch.clone();
+		return;
+	};
+
+	if !target.is_object() {
+		*target = Value::Object(Map::new());
+	}
+
+	let Value::Object(members) = target else {
+		unreachable!("the target was just made an object");
+	};
+
+	for (key, value) in patch {
+		if value.is_null() {
+			members.remove(key);
+		} else {
+			merge(members.entry(key.clone()).or_insert(Value::Null), value);
+		}
+	}
+}
+
+/// Fails if two elements of the JSON form of a block share a name, which deserializing would
+/// silently merge.
+fn check_unique_elements(target: &Value) -> Result<()> {
+	let Some(Value::Array(elements)) = target.get("elements") else {
+		return Ok(());
+	};
+	let mut names = HashSet::new();
+
+	for name in elements.iter().filter_map(|element| element.get("name")?.as_str()) {
+		if !names.insert(name) {
+			return Err(Error::Deserialize(serde_json::Error::custom(format!("duplicate element `{}`", name))));
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+	use super::apply_merge_patch;
+	use crate::error::Error;
+	use crate::{ parse, to_bem };
+
+	#[test]
+	fn test_null_removes_modifiers() {
+		let mut bem_block = parse("media-player[dark,compact]\nbutton[rewind]").unwrap();
+
+		apply_merge_patch(&mut bem_block, &json!({ "modifiers": null })).unwrap();
+
+		assert_eq!(to_bem(&bem_block), "media-player\nbutton[rewind]");
+	}
+
+	#[test]
+	fn test_arrays_replace_wholesale() {
+		let mut bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let patch = json!({ "modifiers": ["light"], "elements": [{ "name": "button", "modifiers": ["play"] }] });
+
+		apply_merge_patch(&mut bem_block, &patch).unwrap();
+
+		assert_eq!(to_bem(&bem_block), "media-player[light]\nbutton[play]");
+	}
+
+	#[test]
+	fn test_objects_merge_recursively() {
+		let mut bem_block = parse("media-player[dark]").unwrap();
+
+		apply_merge_patch(&mut bem_block, &json!({ "name": "video-player", "extra": { "a": null } })).unwrap();
+
+		assert_eq!(bem_block.name, "video-player");
+		assert_eq!(bem_block.modifiers, vec!["dark"]);
+	}
+
+	#[test]
+	fn test_invalid_results_are_rejected_atomically() {
+		let mut bem_block = parse("media-player[dark]\nbutton").unwrap();
+		let original = bem_block.clone();
+
+		let invalid_name = apply_merge_patch(&mut bem_block, &json!({ "name": "media--player", "modifiers": ["light"] })).unwrap_err();
+		let duplicate = apply_merge_patch(&mut bem_block, &json!({ "elements": [{ "name": "button", "modifiers": [] }, { "name": "button", "modifiers": ["rewind"] }] })).unwrap_err();
+		let missing_name = apply_merge_patch(&mut bem_block, &json!({ "name": null })).unwrap_err();
+
+		assert!(matches!(invalid_name, Error::Validation(ref errors) if errors.len() == 1));
+		assert_eq!(duplicate.to_string(), "invalid JSON: duplicate element `button`");
+		assert_eq!(missing_name.code(), "deserialize");
+		assert!(apply_merge_patch(&mut bem_block, &json!(["media-player"])).is_err());
+		assert_eq!(bem_block, original);
+	}
+}
@@ -0,0 +1,170 @@
+//! This module defines paths addressing a single BEM entity, so entities can be referred to
+//! independently of where they appear in a block.
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{ Deserialize, Deserializer, Serialize, Serializer };
+use crate::models::BEMBlock;
+use crate::naming::NamingScheme;
+use crate::parser::parse_class;
+
+/// The address of a block, an element, or a modifier of either.
+///
+/// Paths are displayed in their normalized form, which is the class name of the entity under the
+/// default [`NamingScheme`], e.g. `media-player__button--rewind`. They are parsed from and
+/// serialized as that form too, so they can key JSON objects.
+///
+/// # Example
+///
+/// ```
+/// use bem::BEMPath;
+///
+/// let path = BEMPath::new("media-player").with_element("button").with_modifier("rewind");
+///
+/// assert_eq!(path.to_string(), "media-player__button--rewind");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BEMPath {
+	pub block: String,
+	pub element: Option<String>,
+	pub modifier: Option<String>,
+}
+
+impl BEMPath {
+	/// Returns the path of a block.
+	pub fn new(block: &str) -> BEMPath {
+		BEMPath {
+			block: block.to_string(),
+			element: None,
+			modifier: None,
+		}
+	}
+
+	/// Returns the path of an element of the block this path points into.
+	pub fn with_element(self, element: &str) -> BEMPath {
+		BEMPath { element: Some(element.to_string()), ..self }
+	}
+
+	/// Returns the path of a modifier of the block or element this path points to.
+	pub fn with_modifier(self, modifier: &str) -> BEMPath {
+		BEMPath { modifier: Some(modifier.to_string()), ..self }
+	}
+
+	/// Returns whether `bem_block` contains the entity this path points to.
+	pub fn exists_in(&self, bem_block: &BEMBlock) -> bool {
+		if self.block != bem_block.name {
+			return false;
+		}
+
+		match (&self.element, &self.modifier) {
+			(None, None) => true,
+			(None, Some(modifier)) => bem_block.modifiers.iter().any(|candidate| candidate.class_name() == *modifier),
+			(Some(element), modifier) => bem_block.elements
+				.iter()
+				.filter(|candidate| candidate.name == *element)
+				.any(|candidate| modifier.as_ref().is_none_or(|modifier| candidate.modifiers.iter().any(|other| other.class_name() == *modifier))),
+		}
+	}
+}
+
+impl fmt::Display for BEMPath {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let scheme = NamingScheme::default();
+		let mut class = self.block.clone();
+
+		if let Some(element) = &self.element {
+			class = scheme.element_class(&class, element);
+		}
+		if let Some(modifier) = &self.modifier {
+			class = scheme.modifier_class(&class, modifier);
+		}
+
+		write!(f, "{}", class)
+	}
+}
+
+/// The error returned when parsing a [`BEMPath`] from something that is not a BEM class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BEMPathError(String);
+
+impl fmt::Display for BEMPathError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "`{}` is not a BEM class", self.0)
+	}
+}
+
+impl std::error::Error for BEMPathError {}
+
+impl FromStr for BEMPath {
+	type Err = BEMPathError;
+
+	fn from_str(class: &str) -> Result<Self, Self::Err> {
+		let class_name = parse_class(class).ok_or_else(|| BEMPathError(class.to_string()))?;
+
+		Ok(BEMPath {
+			block: class_name.block,
+			element: class_name.element,
+			modifier: class_name.modifier,
+		})
+	}
+}
+
+impl Serialize for BEMPath {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for BEMPath {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::BEMPath;
+	use crate::parse;
+
+	#[test]
+	fn test_display() {
+		assert_eq!(BEMPath::new("media-player").to_string(), "media-player");
+		assert_eq!(BEMPath::new("media-player").with_modifier("dark").to_string(), "media-player--dark");
+		assert_eq!(BEMPath::new("media-player").with_element("button").to_string(), "media-player__button");
+		assert_eq!(
+			BEMPath::new("media-player").with_element("button").with_modifier("rewind").to_string(),
+			"media-player__button--rewind"
+		);
+	}
+
+	#[test]
+	fn test_exists_in() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward]\nbutton[rewind]").unwrap();
+		let block = BEMPath::new("media-player");
+
+		assert!(block.exists_in(&bem_block));
+		assert!(block.clone().with_modifier("dark").exists_in(&bem_block));
+		assert!(block.clone().with_element("button").with_modifier("rewind").exists_in(&bem_block));
+		assert!(!block.clone().with_modifier("rewind").exists_in(&bem_block));
+		assert!(!block.with_element("timeline").exists_in(&bem_block));
+		assert!(!BEMPath::new("play-list").exists_in(&bem_block));
+	}
+
+	#[test]
+	fn test_from_str() {
+		let path = BEMPath::new("media-player").with_element("button").with_modifier("rewind");
+
+		assert_eq!("media-player__button--rewind".parse::<BEMPath>(), Ok(path.clone()));
+		assert_eq!(path.to_string().parse::<BEMPath>(), Ok(path));
+		assert_eq!("a__b__c".parse::<BEMPath>().unwrap_err().to_string(), "`a__b__c` is not a BEM class");
+	}
+
+	#[test]
+	fn test_serde() {
+		let path = BEMPath::new("media-player").with_modifier("dark");
+
+		assert_eq!(serde_json::to_string(&path).unwrap(), "\"media-player--dark\"");
+		assert_eq!(serde_json::from_str::<BEMPath>("\"media-player--dark\"").unwrap(), path);
+		assert!(serde_json::from_str::<BEMPath>("\"Media\"").is_err());
+	}
+}
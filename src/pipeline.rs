@@ -0,0 +1,1202 @@
+//! This module runs the whole pipeline of the `bem` command-line tool, reading inputs, parsing
+//! them, and emitting the result, so other tools can embed it without spawning the binary.
+//!
+//! [`run`] takes the flags of the tool as [`RunOptions`], and reaches files, stdin, stdout and
+//! stderr only through a [`PipelineIo`]: [`StdIo`] is the one of the binary, and [`MemoryIo`]
+//! keeps everything in memory. The subcommands run the same way, like [`run_lint`] with its
+//! [`LintOptions`] for `bem lint`.
+//!
+//! # Example
+//!
+//! ```
+//! use bem::pipeline::{ run, MemoryIo, RunOptions };
+//!
+//! let mut io = MemoryIo::default();
+//! io.files.insert("design/media-player.bem".into(), b"media-player[dark]".to_vec());
+//! io.files.insert("design/play-list.bem".into(), b"play-list".to_vec());
+//!
+//! let options = RunOptions { input: Some("design".into()), format: "classes".to_string(), ..RunOptions::default() };
+//! let report = run(&options, &mut io).unwrap();
+//!
+//! assert_eq!(io.stdout, b"media-player\nmedia-player--dark\nplay-list\n");
+//! assert_eq!(report.blocks, 2);
+//! ```
+
+use std::collections::{ BTreeMap, HashMap };
+use std::fs::{ self, File, OpenOptions };
+use std::io::{ self, Read, Write };
+use std::path::{ Path, PathBuf };
+use std::process;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::sync::{ Arc, Mutex, OnceLock };
+use std::time::{ Duration, Instant };
+use sha2::{ Digest, Sha256 };
+use crate::abbreviation::AbbreviationMap;
+use crate::codegen::{ to_css_modules_dts, to_css_split, SplitOptions };
+use crate::commands::lint_stylesheets;
+use crate::diagnostic::{ Diagnostic, Severity };
+use crate::emit::{ EmitContext, EmitError, EmitterRegistry };
+use crate::error::Error;
+use crate::files::bem_files;
+use crate::filter::GenerateFilter;
+use crate::models::{ BEMBlock, BEMDocument };
+use crate::naming::BemConfig;
+use crate::order::EmitOrder;
+use crate::parser::{ parse_all_verbose, GRAMMAR_VERSION };
+use crate::sniff::{ sniff, InputFormat };
+use crate::tooling::SafelistMode;
+use crate::validator::validate;
+
+pub use crate::commands::{
+	features, run_audit, run_diff, run_doctor, run_generate, run_grep, run_lint, run_patch, run_scan, run_schema_dump,
+	AuditOptions, DiffOptions, DoctorOptions, GenerateOptions, GrepOptions, LintOptions, PatchOptions, ScanOptions, SchemaDumpOptions,
+};
+
+/// The format of [`RunOptions::default`].
+pub const DEFAULT_FORMAT: &str = "json";
+
+/// The version of the summary schema, bumped on incompatible changes, see [`RunReport::to_json`].
+pub const SUMMARY_SCHEMA: u32 = 1;
+
+/// The path inputs read from stdin are reported with.
+const STDIN_PATH: &str = "<stdin>";
+
+/// Reads text from stdin, or from anything standing in for it.
+pub trait StringReader {
+	fn read_to_string(&mut self, s: &mut String) -> io::Result<()>;
+}
+
+/// Reads the actual stdin of the process.
+#[derive(Debug, Default)]
+pub struct StdinReader;
+
+impl StringReader for StdinReader {
+	#[cfg(not(tarpaulin_include))]
+	fn read_to_string(&mut self, s: &mut String) -> io::Result<()> {
+		io::stdin()
+			.read_to_string(s)
+			.map(|_| ())
+	}
+}
+
+/// Everything the pipeline reads and writes, so [`run`] can work on the file system, in memory,
+/// or on a virtual file system of the embedding tool.
+pub trait PipelineIo {
+	/// Reads the whole of stdin.
+	fn read_stdin(&mut self) -> io::Result<String>;
+
+	/// Reads the file at `path`.
+	fn read(&mut self, path: &Path) -> io::Result<Vec<u8>>;
+
+	/// Returns whether `path` is a directory.
+	fn is_dir(&mut self, path: &Path) -> bool;
+
+	/// Returns the paths of the entries of the directory at `path`, in any order.
+	fn read_dir(&mut self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+	/// Creates or replaces the file at `path`.
+	fn write(&mut self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+	/// Writes to stdout.
+	fn write_stdout(&mut self, contents: &[u8]) -> io::Result<()>;
+
+	/// Writes to stderr.
+	fn write_stderr(&mut self, contents: &str) -> io::Result<()>;
+
+	/// Reads the file at `path` as UTF-8 text.
+	fn read_to_string(&mut self, path: &Path) -> io::Result<String> {
+		String::from_utf8(self.read(path)?).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+	}
+}
+
+/// The [`PipelineIo`] of the command-line tool: the file system, stdout, stderr, and the stdin
+/// read by `stdin`.
+///
+/// Files are written with [`write_atomic`], so a killed process never leaves a half-written
+/// output behind, unless `in_place` is set.
+#[derive(Debug, Default)]
+pub struct StdIo<R = StdinReader> {
+	pub stdin: R,
+	/// Writes files in place, for file systems where renaming over a file fails.
+	pub in_place: bool,
+}
+
+impl<R: StringReader> PipelineIo for StdIo<R> {
+	fn read_stdin(&mut self) -> io::Result<String> {
+		let mut input = String::new();
+
+		self.stdin.read_to_string(&mut input)?;
+		Ok(input)
+	}
+
+	fn read(&mut self, path: &Path) -> io::Result<Vec<u8>> {
+		fs::read(path)
+	}
+
+	fn is_dir(&mut self, path: &Path) -> bool {
+		path.is_dir()
+	}
+
+	fn read_dir(&mut self, path: &Path) -> io::Result<Vec<PathBuf>> {
+		fs::read_dir(path)?.map(|entry| entry.map(|entry| entry.path())).collect()
+	}
+
+	fn write(&mut self, path: &Path, contents: &[u8]) -> io::Result<()> {
+		match self.in_place {
+			true => fs::write(path, contents),
+			false => write_atomic(path, contents),
+		}
+	}
+
+	#[cfg(not(tarpaulin_include))]
+	fn write_stdout(&mut self, contents: &[u8]) -> io::Result<()> {
+		io::stdout().write_all(contents)
+	}
+
+	#[cfg(not(tarpaulin_include))]
+	fn write_stderr(&mut self, contents: &str) -> io::Result<()> {
+		io::stderr().write_all(contents.as_bytes())
+	}
+}
+
+/// Writes `contents` to `path` without ever leaving a partial file behind.
+///
+/// The contents are written and synced to a temporary file next to `path`, which is then renamed
+/// over it, so readers see either the old file or the new one. An existing file keeps its
+/// permissions. Writes to the same path from several threads are serialized, and the last one
+/// wins.
+///
+/// # Arguments
+///
+/// * `path`: &Path - The file to write.
+/// * `contents`: &[u8] - The contents of the file.
+///
+/// # Returns
+///
+/// * `io::Result<()>` - The error of creating, writing, or renaming the temporary file, which is
+///   removed on failure.
+///
+/// # Examples
+///
+/// ```
+/// use bem::pipeline::write_atomic;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("media-player.json");
+///
+/// write_atomic(&path, b"{}").unwrap();
+///
+/// assert_eq!(std::fs::read(&path).unwrap(), b"{}");
+/// assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+/// ```
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+	write_atomic_with(path, |file| file.write_all(contents))
+}
+
+/// Writes `path` like [`write_atomic`], with `write` filling the temporary file.
+fn write_atomic_with(path: &Path, write: impl FnOnce(&mut File) -> io::Result<()>) -> io::Result<()> {
+	static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+	let lock = path_lock(path);
+	let _guard = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+	let file_name = path
+		.file_name()
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{}: not a file path", path.display())))?;
+	let temp = path.with_file_name(format!(
+		".{}.{}.{}.tmp",
+		file_name.to_string_lossy(),
+		process::id(),
+		COUNTER.fetch_add(1, Ordering::Relaxed)
+	));
+	let result = OpenOptions::new()
+		.write(true)
+		.create_new(true)
+		.open(&temp)
+		.and_then(|mut file| {
+			write(&mut file)?;
+			file.sync_all()?;
+
+			if let Ok(metadata) = fs::metadata(path) {
+				file.set_permissions(metadata.permissions())?;
+			}
+
+			Ok(())
+		})
+		.and_then(|()| fs::rename(&temp, path));
+
+	if result.is_err() {
+		let _ = fs::remove_file(&temp);
+	}
+
+	result
+}
+
+/// Returns the lock serializing the writes to `path`.
+fn path_lock(path: &Path) -> Arc<Mutex<()>> {
+	static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+
+	let mut locks = LOCKS
+		.get_or_init(Mutex::default)
+		.lock()
+		.unwrap_or_else(|poisoned| poisoned.into_inner());
+
+	locks.entry(path.to_path_buf()).or_default().clone()
+}
+
+/// A [`PipelineIo`] keeping files and streams in memory, e.g. for tests or tools that generate
+/// their inputs.
+///
+/// Directories are implied by the paths of the files: a path is a directory if a file lies
+/// below it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryIo {
+	/// The files, by path.
+	pub files: BTreeMap<PathBuf, Vec<u8>>,
+	/// What reading stdin returns.
+	pub stdin: String,
+	/// Everything written to stdout.
+	pub stdout: Vec<u8>,
+	/// Everything written to stderr.
+	pub stderr: String,
+}
+
+impl PipelineIo for MemoryIo {
+	fn read_stdin(&mut self) -> io::Result<String> {
+		Ok(self.stdin.clone())
+	}
+
+	fn read(&mut self, path: &Path) -> io::Result<Vec<u8>> {
+		self.files
+			.get(path)
+			.cloned()
+			.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{}: no such file", path.display())))
+	}
+
+	fn is_dir(&mut self, path: &Path) -> bool {
+		self.files.keys().any(|file| file != path && file.starts_with(path))
+	}
+
+	fn read_dir(&mut self, path: &Path) -> io::Result<Vec<PathBuf>> {
+		if !self.is_dir(path) {
+			return Err(io::Error::new(io::ErrorKind::NotFound, format!("{}: no such directory", path.display())));
+		}
+
+		let mut entries: Vec<PathBuf> = self.files
+			.keys()
+			.filter_map(|file| file.strip_prefix(path).ok()?.components().next().map(|entry| path.join(entry)))
+			.collect();
+
+		entries.dedup();
+		Ok(entries)
+	}
+
+	fn write(&mut self, path: &Path, contents: &[u8]) -> io::Result<()> {
+		self.files.insert(path.to_path_buf(), contents.to_vec());
+		Ok(())
+	}
+
+	fn write_stdout(&mut self, contents: &[u8]) -> io::Result<()> {
+		self.stdout.extend_from_slice(contents);
+		Ok(())
+	}
+
+	fn write_stderr(&mut self, contents: &str) -> io::Result<()> {
+		self.stderr.push_str(contents);
+		Ok(())
+	}
+}
+
+/// The checksums [`run`] can write next to the output file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+	/// SHA-256, written as `OUTPUT_FILE.sha256` in the format of `sha256sum`.
+	Sha256,
+}
+
+/// The flags of the command-line tool, without its subcommands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunOptions {
+	/// The input file, or a directory whose `.bem` files are emitted together, or stdin if `None`.
+	pub input: Option<PathBuf>,
+	/// The format of the inputs, or `None` to [`sniff`] the format of each input.
+	pub from: Option<InputFormat>,
+	/// The output file, or stdout if `None`.
+	pub out: Option<PathBuf>,
+	/// The id of the emitter of the output, see [`EmitterRegistry`].
+	pub format: String,
+	/// Whether the `safelist` format lists exact classes or patterns.
+	pub safelist_mode: SafelistMode,
+	/// The checksum to write next to `out`.
+	pub checksum: Option<ChecksumAlgorithm>,
+	/// Whether to check `out` against its checksum file instead of writing it.
+	pub verify: bool,
+	/// The file to write the summary of the run to, or stderr as a single line if `-`.
+	pub summary_file: Option<PathBuf>,
+	/// The grammar version to parse as, rejecting newer syntax.
+	pub grammar_version: u32,
+	/// Whether inputs that parse with warnings fail, rather than only printing the warnings.
+	pub deny_warnings: bool,
+	/// Whether inputs whose blocks break the naming conventions fail, see [`validate`]. Every
+	/// violation is printed.
+	pub validate: bool,
+	/// The elements, and optionally modifiers, to emit.
+	pub filter: GenerateFilter,
+	/// The order of elements and modifiers in the output.
+	pub order: EmitOrder,
+	/// Whether every block is normalized before it is emitted, see [`BEMBlock::normalize`].
+	pub normalize: bool,
+	/// The directory the files of [`RunOptions::split_by_modifier`] are written to.
+	pub out_dir: Option<PathBuf>,
+	/// Whether to write the CSS of every block as one file per block modifier into `out_dir`,
+	/// see [`to_css_split`], instead of emitting `format`.
+	pub split_by_modifier: bool,
+	/// Whether the `json` format is indented, see [`to_json_pretty`](crate::to_json_pretty).
+	pub pretty: bool,
+	/// The separators every format writing classes composes them with, see [`BemConfig`].
+	pub naming: BemConfig,
+	/// Whether to write the TypeScript declarations of the `css-modules` format next to `out`,
+	/// as `out` with a `.d.ts` suffix, see [`to_css_modules_dts`].
+	pub ts_decl: bool,
+	/// The stylesheets to lint before emitting, see [`run_lint`]. Findings are printed to stderr
+	/// and fail the run before anything is emitted.
+	pub lint: Option<LintOptions>,
+	/// A JSON file of the abbreviations of name segments, like `{"navigation": "nav"}`, applied
+	/// to the identifiers of the `theme-ts`, `jsx` and `react-native` formats, see
+	/// [`AbbreviationMap`].
+	pub abbreviations: Option<PathBuf>,
+}
+
+impl Default for RunOptions {
+	fn default() -> Self {
+		RunOptions {
+			input: None,
+			from: None,
+			out: None,
+			format: DEFAULT_FORMAT.to_string(),
+			safelist_mode: SafelistMode::default(),
+			checksum: None,
+			verify: false,
+			summary_file: None,
+			grammar_version: GRAMMAR_VERSION,
+			deny_warnings: false,
+			validate: false,
+			filter: GenerateFilter::default(),
+			order: EmitOrder::default(),
+			normalize: false,
+			out_dir: None,
+			split_by_modifier: false,
+			pretty: false,
+			naming: BemConfig::default(),
+			ts_decl: false,
+			lint: None,
+			abbreviations: None,
+		}
+	}
+}
+
+/// An input of a run, with its error if it failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputReport {
+	/// The path of the input, or `<stdin>`.
+	pub path: String,
+	/// The stable code and the message of the error, see [`Error::code`]. Inputs denied by
+	/// [`RunOptions::deny_warnings`] have the code `denied-warnings`, and inputs failing
+	/// [`RunOptions::validate`] the code `validation`.
+	pub error: Option<(&'static str, String)>,
+}
+
+/// What a run did, see [`run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunReport {
+	/// Every input, in processing order.
+	pub inputs: Vec<InputReport>,
+	/// The numbers of blocks, elements, and modifiers emitted.
+	pub blocks: usize,
+	pub elements: usize,
+	pub modifiers: usize,
+	/// The files written, including checksum files.
+	pub outputs: Vec<PathBuf>,
+	/// The output, whether written to a file or stdout, or the result of a checksum verification.
+	pub output: Vec<u8>,
+	/// The wall-clock duration of the run.
+	pub duration: Duration,
+}
+
+impl RunReport {
+	/// Counts the blocks, elements, and modifiers of the emitted document.
+	fn count(&mut self, blocks: &[BEMBlock]) {
+		for bem_block in blocks {
+			self.blocks += 1;
+			self.elements += bem_block.elements.len();
+			self.modifiers += bem_block.modifiers.len();
+			self.modifiers += bem_block.elements.iter().map(|element| element.modifiers.len()).sum::<usize>();
+		}
+	}
+
+	/// Returns the summary of the run, as written to [`RunOptions::summary_file`]. Its keys are
+	/// stable within a `schema` version:
+	///
+	/// - `schema`: [`SUMMARY_SCHEMA`].
+	/// - `ok`: Whether the run succeeded.
+	/// - `inputs`: One object per input, with its `path`, its `status` (`ok` or `error`), and its
+	///   `error`: `null`, or an object holding a stable `code` and a `message`.
+	/// - `totals`: The numbers of `blocks`, `elements`, and `modifiers` emitted.
+	/// - `duration_ms`: The wall-clock duration of the run, in milliseconds.
+	/// - `outputs`: The paths of the files written, including checksum files.
+	pub fn to_json(&self, ok: bool) -> serde_json::Value {
+		let inputs: Vec<serde_json::Value> = self.inputs
+			.iter()
+			.map(|input| match &input.error {
+				None => serde_json::json!({ "path": input.path, "status": "ok", "error": null }),
+				Some((code, message)) => serde_json::json!({
+					"path": input.path,
+					"status": "error",
+					"error": { "code": code, "message": message },
+				}),
+			})
+			.collect();
+		let outputs: Vec<String> = self.outputs.iter().map(|output| output.display().to_string()).collect();
+
+		serde_json::json!({
+			"schema": SUMMARY_SCHEMA,
+			"ok": ok,
+			"inputs": inputs,
+			"totals": { "blocks": self.blocks, "elements": self.elements, "modifiers": self.modifiers },
+			"duration_ms": self.duration.as_millis() as u64,
+			"outputs": outputs,
+		})
+	}
+}
+
+/// Runs the pipeline of the command-line tool with the built-in output formats.
+///
+/// Parses the input file, every `.bem` file of the input directory, or stdin, and emits the
+/// blocks that parsed to the output file or stdout. When some files of a directory fail, the
+/// others are still emitted and the run fails with the first error, prefixed with its path.
+/// Inputs are read as `.bem` text, JSON, or YAML with the `yaml` feature, as
+/// [`RunOptions::from`] says or [`sniff`] detects.
+/// Warnings are written to stderr. The summary is written whether the run succeeds or not.
+///
+/// # Arguments
+///
+/// * `options`: &RunOptions - The flags of the run.
+/// * `io`: &mut dyn PipelineIo - Where inputs are read from and outputs written to.
+///
+/// # Returns
+///
+/// * `Result<RunReport, Error>` - What the run did, or the first error. Errors of the run itself,
+///   like an unknown format or a checksum mismatch, are [`Error::Io`].
+///
+/// # Examples
+///
+/// ```
+/// use bem::pipeline::{ run, MemoryIo, RunOptions };
+///
+/// let mut io = MemoryIo { stdin: "media-player[dark]".to_string(), ..MemoryIo::default() };
+/// let options = RunOptions { out: Some("media-player.css".into()), format: "css".to_string(), ..RunOptions::default() };
+/// let report = run(&options, &mut io).unwrap();
+///
+/// assert!(String::from_utf8(io.files[&options.out.unwrap()].clone()).unwrap().starts_with(".media-player {"));
+/// assert_eq!(report.outputs, vec![std::path::PathBuf::from("media-player.css")]);
+/// assert!(io.stdout.is_empty());
+/// ```
+pub fn run(options: &RunOptions, io: &mut dyn PipelineIo) -> Result<RunReport, Error> {
+	run_with_registry(options, io, &EmitterRegistry::new())
+}
+
+/// Runs the pipeline with the output formats of `registry`, so [`RunOptions::format`] can select
+/// custom emitters, see [`run`].
+pub fn run_with_registry(options: &RunOptions, io: &mut dyn PipelineIo, registry: &EmitterRegistry) -> Result<RunReport, Error> {
+	let started = Instant::now();
+	let mut report = RunReport::default();
+	let result = run_inputs(options, io, registry, &mut report);
+
+	report.duration = started.elapsed();
+
+	let written = match options.summary_file.as_deref() {
+		Some(path) => write_summary(io, path, &report.to_json(result.is_ok())),
+		None => Ok(()),
+	};
+
+	result?;
+	written?;
+
+	Ok(report)
+}
+
+/// Writes a summary to `path` as pretty JSON, or to stderr as a single line if `path` is `-`.
+fn write_summary(io: &mut dyn PipelineIo, path: &Path, summary: &serde_json::Value) -> Result<(), Error> {
+	if path == Path::new("-") {
+		return Ok(io.write_stderr(&format!("{}\n", summary))?);
+	}
+
+	let summary = serde_json::to_string_pretty(summary).map_err(Error::Serialize)? + "\n";
+
+	Ok(io.write(path, summary.as_bytes())?)
+}
+
+/// Returns the message of `error` as the command-line tool prints it, without the `I/O error`
+/// prefix of errors raised by the run itself.
+fn message(error: &Error) -> String {
+	match error {
+		Error::Io(error) => error.to_string(),
+		error => error.to_string(),
+	}
+}
+
+fn run_inputs(options: &RunOptions, io: &mut dyn PipelineIo, registry: &EmitterRegistry, report: &mut RunReport) -> Result<(), Error> {
+	if let (true, Some(out)) = (options.verify, options.out.as_deref()) {
+		report.output = verify_checksum(io, out)?.into_bytes();
+		io.write_stdout(&report.output)?;
+
+		return Ok(());
+	}
+
+	if options.ts_decl && (options.out.is_none() || options.format != "css-modules") {
+		return Err(Error::Io(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"TypeScript declarations require the css-modules format and an output file"
+		)));
+	}
+
+	let abbreviations = match options.abbreviations.as_deref() {
+		Some(path) => io.read_to_string(path)
+			.map_err(Error::from)
+			.and_then(|json| AbbreviationMap::from_json(&json))
+			.map_err(|error| error.in_file(path))?,
+		None => AbbreviationMap::default(),
+	};
+	let inputs: Vec<(String, Result<String, Error>)> = match options.input.as_deref() {
+		Some(path) if io.is_dir(path) => bem_files(io.read_dir(path)?, |entry| io.is_dir(entry))
+			.into_iter()
+			.map(|path| (path.display().to_string(), io.read_to_string(&path).map_err(Error::from)))
+			.collect(),
+		Some(path) => vec![(path.display().to_string(), io.read_to_string(path).map_err(Error::from))],
+		None => vec![(STDIN_PATH.to_string(), io.read_stdin().map_err(Error::from))],
+	};
+	let batch = inputs.len() > 1;
+	let mut blocks = Vec::new();
+	let mut failure = None;
+
+	for (path, bem_input) in inputs {
+		let result = bem_input
+			.and_then(|bem_input| read_blocks(&bem_input, options))
+			.map_err(|error| (error.code(), error))
+			.and_then(|(read, diagnostics)| {
+				let warnings: Vec<_> = diagnostics
+					.iter()
+					.filter(|warning| warning.severity == Severity::Warning)
+					.collect();
+
+				for warning in &warnings {
+					io.write_stderr(&format!("{}: {}\n", path, warning)).map_err(|error| ("io", Error::from(error)))?;
+				}
+
+				match options.deny_warnings && !warnings.is_empty() {
+					true => Err(("denied-warnings", Error::Io(io::Error::new(
+						io::ErrorKind::InvalidData,
+						format!("{} warning(s) denied by --deny-warnings", warnings.len())
+					)))),
+					false => Ok(read),
+				}
+			})
+			.and_then(|read| {
+				let violations: Vec<_> = match options.validate {
+					true => read.iter().flat_map(validate).collect(),
+					false => Vec::new(),
+				};
+
+				for violation in &violations {
+					io.write_stderr(&format!("{}: {}\n", path, violation)).map_err(|error| ("io", Error::from(error)))?;
+				}
+
+				match violations.is_empty() {
+					true => Ok(read),
+					false => Err(("validation", Error::Validation(violations))),
+				}
+			});
+
+		match result {
+			Ok(read) => {
+				report.inputs.push(InputReport { path, error: None });
+				blocks.extend(read);
+			}
+			Err((code, error)) => {
+				report.inputs.push(InputReport { path: path.clone(), error: Some((code, message(&error))) });
+
+				// Errors of files carry their path, so editors can jump to `path: line N, column M`.
+				failure.get_or_insert(match batch || options.input.is_some() {
+					true => error.in_file(&path),
+					false => error,
+				});
+			}
+		}
+	}
+
+	if blocks.is_empty() {
+		if let Some(error) = failure {
+			return Err(error);
+		}
+	}
+
+	if let Some(lint) = &options.lint {
+		let findings = lint_stylesheets(lint, io)?;
+
+		io.write_stderr(&findings.output)?;
+		findings.result()?;
+	}
+
+	if options.normalize {
+		blocks.iter_mut().for_each(BEMBlock::normalize);
+	}
+
+	if options.split_by_modifier {
+		write_split(options, io, blocks, report)?;
+
+		return match failure {
+			Some(error) => Err(error),
+			None => Ok(()),
+		};
+	}
+
+	let context = EmitContext {
+		safelist_mode: options.safelist_mode,
+		filter: options.filter.clone(),
+		order: options.order,
+		pretty: options.pretty,
+		naming: options.naming.clone(),
+		abbreviations,
+		..EmitContext::default()
+	};
+	let document = BEMDocument::from(blocks);
+	let output = emit_with_registry(registry, &options.format, &document, &context)?;
+
+	report.count(&document.blocks);
+
+	if let Some(out) = options.out.as_deref() {
+		io.write(out, &output)?;
+		report.outputs.push(out.to_path_buf());
+
+		if options.checksum.is_some() {
+			write_checksum(io, out, &output)?;
+			report.outputs.push(checksum_path(out));
+		}
+
+		if options.ts_decl {
+			write_declarations(io, out, &document.blocks, options)?;
+			report.outputs.push(declarations_path(out));
+		}
+	} else {
+		io.write_stdout(&output)?;
+	}
+
+	report.output = output;
+
+	match failure {
+		Some(error) => Err(error),
+		None => Ok(()),
+	}
+}
+
+/// Emits `doc` in `format` with the built-in output formats, as the command-line tool and its
+/// subcommands do.
+///
+/// # Arguments
+///
+/// * `format`: &str - The id of the emitter, see [`EmitterRegistry`].
+/// * `doc`: &BEMDocument - The blocks to emit.
+/// * `context`: &EmitContext - The options of the emitter.
+///
+/// # Returns
+///
+/// * `Result<Vec<u8>, Error>` - The output, or an [`Error::Io`]: of kind `InvalidInput` listing
+///   the known formats if `format` is unknown, and of kind `Other` if the emitter fails.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, EmitContext };
+/// use bem::pipeline::emit_to_bytes;
+///
+/// let doc = parse("media-player[dark]").unwrap().into();
+///
+/// assert_eq!(emit_to_bytes("classes", &doc, &EmitContext::default()).unwrap(), b"media-player\nmedia-player--dark\n");
+/// assert!(emit_to_bytes("tokens", &doc, &EmitContext::default()).is_err());
+/// ```
+pub fn emit_to_bytes(format: &str, doc: &BEMDocument, context: &EmitContext) -> Result<Vec<u8>, Error> {
+	emit_with_registry(&EmitterRegistry::new(), format, doc, context)
+}
+
+/// Emits `doc` like [`emit_to_bytes`], with the output formats of `registry`.
+fn emit_with_registry(registry: &EmitterRegistry, format: &str, doc: &BEMDocument, context: &EmitContext) -> Result<Vec<u8>, Error> {
+	registry
+		.emit(format, doc, context)
+		.map_err(|e| match e {
+			EmitError::UnknownFormat(_) => Error::Io(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				format!("{} (expected one of: {})", e, registry.ids().join(", "))
+			)),
+			e => Error::Io(io::Error::other(e)),
+		})
+}
+
+/// Reads the blocks of an input in [`RunOptions::from`], or in the format [`sniff`] detects,
+/// with the diagnostics of parsing `.bem` text. `.bem` text may hold several blocks, see [`parse_all`](crate::parse_all).
+fn read_blocks(input: &str, options: &RunOptions) -> Result<(Vec<BEMBlock>, Vec<Diagnostic>), Error> {
+	match options.from.map_or_else(|| sniff(input), Ok)? {
+		InputFormat::Bem => parse_all_verbose(input, options.grammar_version),
+		InputFormat::Json => {
+			let json = input.trim_start_matches('\u{FEFF}');
+			let blocks = match json.trim_start().starts_with('[') {
+				true => serde_json::from_str(json),
+				false => serde_json::from_str(json).map(|bem_block| vec![bem_block]),
+			};
+
+			Ok((blocks.map_err(Error::Deserialize)?, Vec::new()))
+		}
+		#[cfg(feature = "yaml")]
+		InputFormat::Yaml => {
+			let value: serde_yaml::Value = serde_yaml::from_str(input.trim_start_matches('\u{FEFF}')).map_err(Error::Yaml)?;
+			let blocks = match value.is_sequence() {
+				true => serde_yaml::from_value(value),
+				false => serde_yaml::from_value(value).map(|bem_block| vec![bem_block]),
+			};
+
+			Ok((blocks.map_err(Error::Yaml)?, Vec::new()))
+		}
+	}
+}
+
+/// Writes the files of [`to_css_split`] of every block into [`RunOptions::out_dir`], with the
+/// filter and order of the run applied.
+fn write_split(options: &RunOptions, io: &mut dyn PipelineIo, blocks: Vec<BEMBlock>, report: &mut RunReport) -> Result<(), Error> {
+	let out_dir = options.out_dir.as_deref().ok_or_else(|| Error::Io(io::Error::new(
+		io::ErrorKind::InvalidInput,
+		"splitting by modifier requires an output directory"
+	)))?;
+	let blocks: Vec<BEMBlock> = blocks
+		.iter()
+		.map(|bem_block| options.order.apply(&options.filter.apply(bem_block)))
+		.collect();
+
+	for bem_block in &blocks {
+		for (name, css) in to_css_split(bem_block, &SplitOptions { naming: options.naming.clone(), ..SplitOptions::default() }) {
+			let path = out_dir.join(name);
+
+			io.write(&path, css.as_bytes())?;
+			report.outputs.push(path);
+		}
+	}
+
+	report.count(&blocks);
+
+	Ok(())
+}
+
+fn checksum_path(out: &Path) -> PathBuf {
+	let mut path = out.as_os_str().to_owned();
+
+	path.push(".sha256");
+	path.into()
+}
+
+fn declarations_path(out: &Path) -> PathBuf {
+	let mut path = out.as_os_str().to_owned();
+
+	path.push(".d.ts");
+	path.into()
+}
+
+/// Writes the TypeScript declarations of the CSS Modules stylesheet `out`, with the filter and
+/// order of the run applied like the emitter does.
+fn write_declarations(io: &mut dyn PipelineIo, out: &Path, blocks: &[BEMBlock], options: &RunOptions) -> Result<(), Error> {
+	let blocks: Vec<BEMBlock> = blocks
+		.iter()
+		.map(|bem_block| options.order.apply(&options.filter.apply(bem_block)))
+		.collect();
+	let declarations = to_css_modules_dts(&blocks, &options.naming);
+
+	Ok(io.write(&declarations_path(out), declarations.as_bytes())?)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+	Sha256::digest(bytes)
+		.iter()
+		.map(|byte| format!("{:02x}", byte))
+		.collect()
+}
+
+/// Writes the checksum of `output` to the checksum file of `out`, as `sha256sum` would.
+fn write_checksum(io: &mut dyn PipelineIo, out: &Path, output: &[u8]) -> io::Result<()> {
+	let file_name = out
+		.file_name()
+		.map(|name| name.to_string_lossy())
+		.unwrap_or_default();
+
+	io.write(&checksum_path(out), format!("{}  {}\n", sha256_hex(output), file_name).as_bytes())
+}
+
+/// Recomputes the checksum of `out` and compares it with the one recorded in its checksum file.
+fn verify_checksum(io: &mut dyn PipelineIo, out: &Path) -> io::Result<String> {
+	let recorded = io.read_to_string(&checksum_path(out))?;
+	let expected = recorded.split_whitespace().next().unwrap_or_default();
+	let actual = sha256_hex(&io.read(out)?);
+
+	if actual != expected {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("checksum mismatch for {}: expected {}, found {}", out.display(), expected, actual)
+		));
+	}
+
+	Ok(format!("{}: OK\n", out.display()))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::io::{ self, ErrorKind, Write };
+	use std::path::PathBuf;
+	use std::thread;
+	use tempfile::tempdir;
+	use super::{ run, write_atomic, write_atomic_with, ChecksumAlgorithm, LintOptions, MemoryIo, RunOptions, StdIo, StdinReader };
+	use crate::filter::GenerateFilter;
+	use crate::order::EmitOrder;
+	use crate::sniff::InputFormat;
+
+	const MEDIA_PLAYER: &str = "media-player[dark]\nbutton[fast-forward,rewind]\ntimeline";
+
+	fn io(files: &[(&str, &str)]) -> MemoryIo {
+		MemoryIo {
+			files: files.iter().map(|(path, content)| (PathBuf::from(path), content.as_bytes().to_vec())).collect(),
+			..MemoryIo::default()
+		}
+	}
+
+	fn file(io: &MemoryIo, path: &str) -> String {
+		String::from_utf8(io.files[&PathBuf::from(path)].clone()).unwrap()
+	}
+
+	#[test]
+	fn test_run_converts_stdin() {
+		let mut io = MemoryIo { stdin: MEDIA_PLAYER.to_string(), ..MemoryIo::default() };
+		let options = RunOptions { format: "bem".to_string(), order: EmitOrder::Alphabetical, ..RunOptions::default() };
+		let report = run(&options, &mut io).unwrap();
+
+		assert_eq!(io.stdout, b"media-player[dark]\nbutton[fast-forward,rewind]\ntimeline\n");
+		assert_eq!(report.output, io.stdout);
+		assert_eq!(report.inputs[0].path, "<stdin>");
+		assert_eq!((report.blocks, report.elements, report.modifiers), (1, 2, 3));
+		assert!(report.outputs.is_empty() && io.files.is_empty());
+	}
+
+	#[test]
+	fn test_run_converts_files_of_several_blocks() {
+		let mut io = MemoryIo { stdin: format!("{}\n\nplay-list\nitem", MEDIA_PLAYER), ..MemoryIo::default() };
+		let report = run(&RunOptions::default(), &mut io).unwrap();
+		let blocks: Vec<crate::BEMBlock> = serde_json::from_slice(&io.stdout).unwrap();
+
+		assert_eq!(blocks, crate::parse_all(&format!("{}\n\nplay-list\nitem", MEDIA_PLAYER)).unwrap());
+		assert_eq!((report.blocks, report.elements), (2, 3));
+	}
+
+	#[test]
+	fn test_run_writes_and_verifies_checksums() {
+		let mut io = io(&[("media-player.bem", MEDIA_PLAYER)]);
+		let options = RunOptions {
+			input: Some("media-player.bem".into()),
+			out: Some("out/media-player.json".into()),
+			format: "jcs".to_string(),
+			checksum: Some(ChecksumAlgorithm::Sha256),
+			..RunOptions::default()
+		};
+		let report = run(&options, &mut io).unwrap();
+
+		assert_eq!(report.outputs, vec![PathBuf::from("out/media-player.json"), PathBuf::from("out/media-player.json.sha256")]);
+		assert_eq!(
+			file(&io, "out/media-player.json.sha256"),
+			"4b3bf41fe63a3bca0f56c30d6036bf4e1e5b9f4b7fc0a9467336ad1dc0a1455b  media-player.json\n"
+		);
+
+		let verify = RunOptions { verify: true, ..options };
+
+		assert_eq!(run(&verify, &mut io).unwrap().output, b"out/media-player.json: OK\n");
+
+		io.files.insert("out/media-player.json".into(), b"{}".to_vec());
+
+		assert_eq!(std::io::Error::from(run(&verify, &mut io).unwrap_err()).kind(), ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn test_run_writes_typescript_declarations() {
+		let mut io = io(&[("media-player.bem", "media-player[dark]\nbutton[rewind]\ntimeline")]);
+		let options = RunOptions {
+			input: Some("media-player.bem".into()),
+			out: Some("out/media-player.module.css".into()),
+			format: "css-modules".to_string(),
+			filter: GenerateFilter { include: vec!["button".parse().unwrap()], ..GenerateFilter::default() },
+			ts_decl: true,
+			..RunOptions::default()
+		};
+		let report = run(&options, &mut io).unwrap();
+
+		assert_eq!(report.outputs, vec![PathBuf::from("out/media-player.module.css"), PathBuf::from("out/media-player.module.css.d.ts")]);
+		assert_eq!(file(&io, "out/media-player.module.css"), ".mediaPlayer { }\n\n.mediaPlayerDark { }\n\n.mediaPlayerButton { }\n\n.mediaPlayerButtonRewind { }\n");
+		assert_eq!(
+			file(&io, "out/media-player.module.css.d.ts"),
+			"declare const styles: {\n\treadonly mediaPlayer: string;\n\treadonly mediaPlayerDark: string;\n\treadonly mediaPlayerButton: string;\n\treadonly mediaPlayerButtonRewind: string;\n};\n\nexport default styles;\n"
+		);
+
+		let stdout = RunOptions { out: None, ..options.clone() };
+		let css = RunOptions { format: "css".to_string(), ..options };
+
+		assert_eq!(std::io::Error::from(run(&stdout, &mut io).unwrap_err()).kind(), ErrorKind::InvalidInput);
+		assert!(run(&css, &mut io).is_err());
+	}
+
+	#[test]
+	fn test_run_reads_json_inputs() {
+		let json = "{\"name\":\"media-player\",\"modifiers\":[\"dark\"],\"elements\":[]}";
+		let mut io = io(&[("design/a.bem", json), ("design/b.bem", MEDIA_PLAYER)]);
+		let options = RunOptions { input: Some("design".into()), format: "classes".to_string(), ..RunOptions::default() };
+		let report = run(&options, &mut io).unwrap();
+
+		assert_eq!((report.blocks, report.elements), (2, 2));
+
+		let options = RunOptions { from: Some(InputFormat::Json), ..options };
+		let error = run(&options, &mut io).unwrap_err();
+
+		assert_eq!(error.code(), "deserialize");
+		assert!(error.to_string().starts_with("design/b.bem: invalid JSON"));
+	}
+
+	#[test]
+	fn test_run_splits_by_modifier() {
+		let mut io = io(&[("design/media-player.bem", MEDIA_PLAYER), ("design/play-list.bem", "play-list\nitem")]);
+		let options = RunOptions {
+			input: Some("design".into()),
+			out_dir: Some("themes".into()),
+			split_by_modifier: true,
+			..RunOptions::default()
+		};
+		let report = run(&options, &mut io).unwrap();
+
+		assert_eq!(report.outputs, vec![
+			PathBuf::from("themes/media-player.css"),
+			PathBuf::from("themes/media-player--dark.css"),
+			PathBuf::from("themes/play-list.css"),
+		]);
+		assert!(file(&io, "themes/media-player--dark.css").contains(".media-player--dark .media-player__timeline { }"));
+		assert!(io.stdout.is_empty());
+
+		let error = run(&RunOptions { out_dir: None, ..options }, &mut io).unwrap_err();
+
+		assert_eq!(error.to_string(), "I/O error: splitting by modifier requires an output directory");
+	}
+
+	#[test]
+	fn test_run_reports_warnings() {
+		let mut io = MemoryIo { stdin: "media-player[dark,dark]\nbutton\nbutton".to_string(), ..MemoryIo::default() };
+		let deny = RunOptions { deny_warnings: true, summary_file: Some("-".into()), ..RunOptions::default() };
+
+		run(&RunOptions::default(), &mut io).unwrap();
+
+		assert_eq!(io.stderr.lines().count(), 2);
+		assert!(io.stderr.lines().all(|line| line.starts_with("<stdin>: warning[duplicate-")));
+
+		let error = run(&deny, &mut io).unwrap_err();
+		let summary: serde_json::Value = serde_json::from_str(io.stderr.lines().last().unwrap()).unwrap();
+
+		assert_eq!(error.code(), "io");
+		assert_eq!(std::io::Error::from(error).to_string(), "2 warning(s) denied by --deny-warnings");
+		assert_eq!(summary["ok"], false);
+		assert_eq!(summary["inputs"][0]["error"], serde_json::json!({
+			"code": "denied-warnings",
+			"message": "2 warning(s) denied by --deny-warnings",
+		}));
+	}
+
+	#[test]
+	fn test_run_normalizes_blocks() {
+		let mut io = MemoryIo { stdin: "media-player[dark,compact,dark]\ntimeline\nbutton[rewind,fast-forward,rewind]".to_string(), ..MemoryIo::default() };
+		let options = RunOptions { format: "bem".to_string(), normalize: true, ..RunOptions::default() };
+		let report = run(&options, &mut io).unwrap();
+
+		assert_eq!(io.stdout, b"media-player[compact,dark]\nbutton[fast-forward,rewind]\ntimeline\n");
+		assert_eq!(report.modifiers, 4);
+	}
+
+	#[test]
+	fn test_run_validates_inputs() {
+		let json = "[{\"name\":\"Media-Player\",\"modifiers\":[\"dark-\"],\"elements\":[]},{\"name\":\"play-list\",\"modifiers\":[],\"elements\":[]}]";
+		let mut io = io(&[("design/a.bem", json), ("design/b.bem", MEDIA_PLAYER)]);
+		let options = RunOptions { input: Some("design".into()), format: "classes".to_string(), validate: true, ..RunOptions::default() };
+		let error = run(&options, &mut io).unwrap_err();
+
+		assert_eq!(error.code(), "validation");
+		assert!(error.to_string().starts_with("design/a.bem: invalid block: Media-Player: \"Media-Player\" contains uppercase letters (and 1 more)"));
+		assert_eq!(
+			io.stderr,
+			"design/a.bem: Media-Player: \"Media-Player\" contains uppercase letters\ndesign/a.bem: Media-Player--dark-: \"dark-\" ends with a dash\n"
+		);
+		assert!(String::from_utf8(io.stdout.clone()).unwrap().starts_with("media-player\n"));
+		assert!(run(&RunOptions { validate: false, ..options }, &mut io).is_ok());
+	}
+
+	#[test]
+	fn test_run_emits_every_file_of_a_directory() {
+		let mut io = io(&[
+			("design/b.bem", "play-list\nitem[active]"),
+			("design/a.bem", MEDIA_PLAYER),
+			("design/notes.txt", "not a block"),
+			("design/drafts/c.bem", "draft"),
+		]);
+		let options = RunOptions {
+			input: Some("design".into()),
+			format: "classes".to_string(),
+			filter: GenerateFilter { exclude: vec!["button".into()], ..GenerateFilter::default() },
+			..RunOptions::default()
+		};
+		let report = run(&options, &mut io).unwrap();
+
+		assert_eq!(
+			String::from_utf8(io.stdout).unwrap(),
+			"media-player\nmedia-player--dark\nmedia-player__timeline\nplay-list\nplay-list__item\nplay-list__item--active\n"
+		);
+		assert_eq!(report.inputs.iter().map(|input| input.path.as_str()).collect::<Vec<_>>(), vec!["design/a.bem", "design/b.bem"]);
+	}
+
+	#[test]
+	fn test_run_emits_the_files_that_parse() {
+		let mut io = io(&[("design/a.bem", MEDIA_PLAYER), ("design/b.bem", "play-list(dark)"), ("design/c.bem", "#!bem 99\nplay-list")]);
+		let options = RunOptions {
+			input: Some("design".into()),
+			out: Some("design.json".into()),
+			summary_file: Some("summary.json".into()),
+			..RunOptions::default()
+		};
+		let error = run(&options, &mut io).unwrap_err();
+		let summary: serde_json::Value = serde_json::from_str(&file(&io, "summary.json")).unwrap();
+
+		assert_eq!(error.code(), "parse");
+		assert!(error.to_string().starts_with("design/b.bem: parse error"));
+		assert!(file(&io, "design.json").starts_with("{\"name\":\"media-player\""));
+		assert_eq!(summary["inputs"][2]["error"]["code"], "unsupported-grammar-version");
+		assert_eq!(summary["totals"], serde_json::json!({ "blocks": 1, "elements": 2, "modifiers": 3 }));
+		assert_eq!(summary["outputs"], serde_json::json!(["design.json"]));
+	}
+
+	#[test]
+	fn test_run_lints_stylesheets_before_emitting() {
+		let mut io = io(&[("media-player.bem", MEDIA_PLAYER), ("clean.css", ".media-player__button { }"), ("nested.css", ".media-player__button__icon { }")]);
+		let lint = |css: &str| Some(LintOptions { css: vec![css.into()], ..LintOptions::default() });
+		let clean = RunOptions { input: Some("media-player.bem".into()), format: "classes".to_string(), lint: lint("clean.css"), ..RunOptions::default() };
+		let nested = RunOptions { lint: lint("nested.css"), ..clean.clone() };
+
+		assert_eq!(run(&clean, &mut io).unwrap().blocks, 1);
+		assert!(io.stderr.is_empty());
+
+		io.stdout.clear();
+		let error = std::io::Error::from(run(&nested, &mut io).unwrap_err());
+
+		assert_eq!((error.kind(), error.to_string()), (ErrorKind::InvalidData, "found 1 BEM violation(s)".to_string()));
+		assert!(io.stderr.starts_with("nested.css:1: "));
+		assert!(io.stdout.is_empty());
+	}
+
+	#[test]
+	fn test_run_fails_without_input() {
+		let missing = RunOptions { input: Some("missing.bem".into()), ..RunOptions::default() };
+		let unknown = RunOptions { format: "tokens".to_string(), ..RunOptions::default() };
+
+		assert_eq!(std::io::Error::from(run(&missing, &mut MemoryIo::default()).unwrap_err()).kind(), ErrorKind::NotFound);
+		assert!(run(&unknown, &mut MemoryIo { stdin: MEDIA_PLAYER.to_string(), ..MemoryIo::default() }).unwrap_err().to_string().contains("unknown format `tokens` (expected one of: json,"));
+	}
+
+	#[test]
+	fn test_run_reports_the_location_of_parse_errors() {
+		let mut io = io(&[("design/media-player.bem", "media-player\nbutton(rewind)")]);
+		let file = RunOptions { input: Some("design/media-player.bem".into()), ..RunOptions::default() };
+		let stdin = RunOptions::default();
+		let error = run(&file, &mut io).unwrap_err();
+
+		assert_eq!(error.to_string(), "design/media-player.bem: parse error at line 2, column 7: expected EOI, modifiers, element, or comment");
+		assert_eq!(error.code(), "parse");
+
+		io.stdin = "media-player\nbutton(rewind)".to_string();
+
+		assert_eq!(run(&stdin, &mut io).unwrap_err().to_string(), "parse error at line 2, column 7: expected EOI, modifiers, element, or comment");
+	}
+
+	#[test]
+	fn test_write_atomic_leaves_outputs_alone_on_failure() {
+		let dir = tempdir().unwrap();
+		let input = dir.path().join("media-player.bem");
+		let out = dir.path().join("media-player.json");
+
+		fs::write(&input, MEDIA_PLAYER).unwrap();
+		fs::write(&out, "{}").unwrap();
+
+		let modified = fs::metadata(&out).unwrap().modified().unwrap();
+		let options = RunOptions { input: Some(input), out: Some(out.clone()), format: "toml".to_string(), ..RunOptions::default() };
+
+		assert!(run(&options, &mut StdIo { stdin: StdinReader, in_place: false }).is_err());
+		assert_eq!(fs::read_to_string(&out).unwrap(), "{}");
+		assert_eq!(fs::metadata(&out).unwrap().modified().unwrap(), modified);
+
+		let options = RunOptions { format: "classes".to_string(), ..options };
+
+		run(&options, &mut StdIo { stdin: StdinReader, in_place: false }).unwrap();
+		assert_eq!(fs::read_to_string(&out).unwrap(), "media-player\nmedia-player--dark\nmedia-player__button\nmedia-player__button--fast-forward\nmedia-player__button--rewind\nmedia-player__timeline\n");
+		assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+	}
+
+	#[test]
+	fn test_write_atomic_keeps_the_original_when_interrupted() {
+		let dir = tempdir().unwrap();
+		let out = dir.path().join("media-player.json");
+
+		fs::write(&out, "{\"name\":\"media-player\"}").unwrap();
+
+		let error = write_atomic_with(&out, |file| {
+			file.write_all(b"{\"name\":")?;
+			Err(io::Error::new(ErrorKind::Interrupted, "killed"))
+		}).unwrap_err();
+
+		assert_eq!(error.kind(), ErrorKind::Interrupted);
+		assert_eq!(fs::read_to_string(&out).unwrap(), "{\"name\":\"media-player\"}");
+		assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn test_write_atomic_preserves_permissions() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let dir = tempdir().unwrap();
+		let out = dir.path().join("media-player.json");
+
+		fs::write(&out, "{}").unwrap();
+		fs::set_permissions(&out, fs::Permissions::from_mode(0o640)).unwrap();
+		write_atomic(&out, b"[]").unwrap();
+
+		assert_eq!(fs::metadata(&out).unwrap().permissions().mode() & 0o777, 0o640);
+		assert_eq!(fs::read_to_string(&out).unwrap(), "[]");
+	}
+
+	#[test]
+	fn test_write_atomic_serializes_concurrent_writes() {
+		let dir = tempdir().unwrap();
+		let out = dir.path().join("classes.txt");
+		let contents: Vec<String> = (0..8).map(|i| format!("block-{}\n", i).repeat(1000)).collect();
+
+		thread::scope(|scope| {
+			for content in &contents {
+				scope.spawn(|| write_atomic(&out, content.as_bytes()).unwrap());
+			}
+		});
+
+		assert!(contents.contains(&fs::read_to_string(&out).unwrap()));
+		assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+	}
+}
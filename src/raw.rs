@@ -0,0 +1,187 @@
+//! This module exposes the parse tree of `.bem` documents, for tools that need more than the
+//! lowered [`BEMBlock`](crate::BEMBlock), such as formatters and syntax highlighters.
+//!
+//! The tree is made of crate-owned types, so it stays stable regardless of the parser generator
+//! used internally. [`parse`](crate::parse) lowers the same tree, so both always agree.
+
+use std::ops::Range;
+use crate::error::BEMParseError;
+use crate::parser::parse_tree;
+
+/// The grammar rule a [`RawNode`] was matched by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RawRule {
+	/// The whole document: a block followed by its elements.
+	Bem,
+	/// The first line: the block name and its optional modifiers.
+	Block,
+	/// Any following line: an element name and its optional modifiers.
+	Element,
+	/// A block, element, or modifier name.
+	Name,
+	/// A bracketed list of modifier names, modifiers with values, and aliases, e.g.
+	/// `[fast-forward, size=lg, @sizes]`.
+	Modifiers,
+	/// A line defining a modifier set, e.g. `@set sizes = small,medium,large`. Holds the name of
+	/// the set and then the names of its modifiers.
+	SetDefinition,
+	/// A reference to a modifier set within a modifier list, e.g. `@sizes`. Holds the name of the set.
+	Alias,
+	/// A modifier with a value within a modifier list, e.g. `color=red`. Holds the name of the
+	/// modifier and then its value.
+	KeyValue,
+	/// The value of a [`RawRule::KeyValue`] modifier.
+	Value,
+	/// The optional first line declaring the grammar version of the document, e.g. `#!bem 2`.
+	/// Holds the version.
+	Directive,
+	/// The version number of a directive.
+	Version,
+	/// A `//` comment, running to the end of its line.
+	Comment,
+}
+
+/// A node of the parse tree, covering the byte range `span` of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawNode {
+	pub rule: RawRule,
+	pub span: Range<usize>,
+	pub children: Vec<RawNode>,
+}
+
+impl RawNode {
+	/// Returns the text of the input this node was matched from.
+	///
+	/// # Panics
+	///
+	/// Panics if `input` is not the input the tree was parsed from (or at least as long).
+	pub fn as_str<'a>(&self, input: &'a str) -> &'a str {
+		&input[self.span.clone()]
+	}
+}
+
+/// Parses a `.bem` document into its parse tree.
+///
+/// The root is a [`RawRule::Bem`] node spanning the whole input. Its children are the optional
+/// directive node, one node per modifier set definition, the block node, and then one node per element, each holding a name
+/// node and an optional modifiers node, which in turn holds one name, key-value, or alias node per
+/// modifier.
+/// Comments are kept as comment nodes, among the children of the root or of the modifiers node
+/// they appear in.
+/// Aliases are not expanded. Whitespace and line breaks are not represented, but
+/// can be recovered from the gaps between spans.
+///
+/// # Arguments
+///
+/// * `input`: &str - The input string containing the BEM syntax to be parsed.
+///
+/// # Returns
+///
+/// * `Result<RawNode, BEMParseError>` - The root of the parse tree, or an error pointing at the
+///   offending part of the input.
+///
+/// # Examples
+///
+/// ```
+/// use bem::raw::{ parse_raw, RawRule };
+///
+/// let input = "media-player[dark]\nbutton";
+/// let tree = parse_raw(input).unwrap();
+///
+/// assert_eq!(tree.children[0].rule, RawRule::Block);
+/// assert_eq!(tree.children[1].as_str(input), "\nbutton");
+/// ```
+pub fn parse_raw(input: &str) -> Result<RawNode, BEMParseError> {
+	Ok(parse_tree(input)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ parse_raw, RawNode, RawRule };
+	use crate::parser::lower;
+
+	const FIXTURES: &[&str] = &[
+		"foo",
+		"foo[bar,baz,]",
+		"foo[\n\tbar,\n\tbaz]",
+		"foo\nbar\nbaz\nqux",
+		"media-player[dark]\nbutton[fast-forward,rewind]\ntimeline",
+		"foo\n\n\n",
+		"@set sizes = small, large\n\nfoo[@sizes,dark]\nbar[@sizes]",
+		"#!bem 2\n@set sizes = small\nfoo[]\nbar[@sizes]",
+		"foo[color=red, size=lg,dark]\nbar[cols=12]",
+		"// foo\nfoo[dark, // dark\n] // block\n// bar\nbar",
+	];
+
+	/// Renders the shape of a tree, e.g. `bem(block(name modifiers(name)))`.
+	fn shape(node: &RawNode) -> String {
+		let rule = match node.rule {
+			RawRule::SetDefinition => "set_definition".to_string(),
+			RawRule::KeyValue => "key_value".to_string(),
+			rule => format!("{:?}", rule).to_lowercase(),
+		};
+
+		if node.children.is_empty() {
+			return rule;
+		}
+
+		let children: Vec<String> = node.children.iter().map(shape).collect();
+
+		format!("{}({})", rule, children.join(" "))
+	}
+
+	#[test]
+	fn test_parse_raw() {
+		let input = "media-player[dark]\nbutton[fast-forward,rewind]\ntimeline";
+		let tree = parse_raw(input).unwrap();
+
+		assert_eq!(
+			shape(&tree),
+			"bem(block(name modifiers(name)) element(name modifiers(name name)) element(name))"
+		);
+		assert_eq!(tree.rule, RawRule::Bem);
+		assert_eq!(tree.span, 0..input.len());
+		assert_eq!(tree.children[0].children[0].as_str(input), "media-player");
+		assert_eq!(tree.children[0].children[1].as_str(input), "[dark]");
+		assert_eq!(tree.children[1].children[1].children[1].as_str(input), "rewind");
+		assert_eq!(tree.children[2].span, 46..55);
+	}
+
+	#[test]
+	fn test_parse_raw_shapes() {
+		let shapes: Vec<String> = FIXTURES
+			.iter()
+			.map(|input| shape(&parse_raw(input).unwrap()))
+			.collect();
+
+		assert_eq!(shapes, vec![
+			"bem(block(name))",
+			"bem(block(name modifiers(name name)))",
+			"bem(block(name modifiers(name name)))",
+			"bem(block(name) element(name) element(name) element(name))",
+			"bem(block(name modifiers(name)) element(name modifiers(name name)) element(name))",
+			"bem(block(name))",
+			"bem(set_definition(name name name) block(name modifiers(alias(name) name)) element(name modifiers(alias(name))))",
+			"bem(directive(version) set_definition(name name) block(name modifiers) element(name modifiers(alias(name))))",
+			"bem(block(name modifiers(key_value(name value) key_value(name value) name)) element(name modifiers(key_value(name value))))",
+			"bem(comment block(name modifiers(name comment)) comment comment element(name))",
+		]);
+	}
+
+	#[test]
+	fn test_parse_raw_error() {
+		let error = parse_raw("foo\nbar(baz,qux)").unwrap_err();
+
+		assert_eq!(error.span, 7..7);
+		assert_eq!((error.line, error.column), (2, 4));
+		assert_eq!(error.to_string(), "line 2, column 4: expected EOI, modifiers, element, or comment");
+	}
+
+	#[test]
+	fn test_lowering_matches_parse() {
+		for input in FIXTURES {
+			assert_eq!(lower(&parse_raw(input).unwrap(), input, &mut Vec::new()).unwrap(), crate::parse(input).unwrap(), "{:?}", input);
+		}
+	}
+}
@@ -0,0 +1,270 @@
+//! This module dumps everything editor plugins need to offer completions, as a versioned JSON
+//! structure, so they do not have to link this crate.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{ Path, PathBuf };
+use serde::{ Deserialize, Serialize };
+use crate::models::{ BEMBlock, BEMDocument, BEMModifier };
+use crate::naming::NamingScheme;
+use crate::path::BEMPath;
+use crate::raw::{ parse_raw, RawNode, RawRule };
+
+/// The version of the [`SchemaDump`] structure, bumped on incompatible changes.
+pub const SCHEMA_DUMP_VERSION: u32 = 1;
+
+/// Every block of a set of documents, with what editors need to complete and describe its
+/// classes, see [`schema_dump`].
+///
+/// Serialized with camelCase keys:
+///
+/// - `schemaVersion`: [`SCHEMA_DUMP_VERSION`].
+/// - `blocks`: One object per block, in input order, with its `name`, composed `class`,
+///   `description`, `deprecated` flag, `location`, `modifiers`, and `elements`. Elements have the
+///   same keys but `elements`, and modifiers the same keys but `modifiers` and `elements`.
+/// - `location`: The `file` the entity was read from and the `line` it is written on, starting at
+///   1, or `null` if the file cannot be read back. Modifiers from a modifier set point at the alias.
+///
+/// The `.bem` syntax has no descriptions or deprecations yet, so `description` is always `null`
+/// and `deprecated` always `false`. Both are part of the structure so plugins can rely on them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDump {
+	pub schema_version: u32,
+	pub blocks: Vec<SchemaBlock>,
+}
+
+/// A block of a [`SchemaDump`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaBlock {
+	pub name: String,
+	pub class: String,
+	pub description: Option<String>,
+	pub deprecated: bool,
+	pub location: SchemaLocation,
+	pub modifiers: Vec<SchemaModifier>,
+	pub elements: Vec<SchemaElement>,
+}
+
+/// An element of a [`SchemaBlock`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaElement {
+	pub name: String,
+	pub class: String,
+	pub description: Option<String>,
+	pub deprecated: bool,
+	pub location: SchemaLocation,
+	pub modifiers: Vec<SchemaModifier>,
+}
+
+/// A modifier of a [`SchemaBlock`] or [`SchemaElement`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaModifier {
+	pub name: String,
+	pub class: String,
+	pub description: Option<String>,
+	pub deprecated: bool,
+	pub location: SchemaLocation,
+}
+
+/// Where an entity of a [`SchemaDump`] is defined.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaLocation {
+	pub file: PathBuf,
+	/// The line, starting at 1, or `None` if the file could not be read back.
+	pub line: Option<usize>,
+}
+
+/// Dumps the blocks of documents for editor plugins, with their classes composed by `scheme`.
+///
+/// Documents are paired with the `.bem` file they were parsed from. The file is read again to
+/// locate every entity, so lines are `None` when it cannot be read or no longer parses.
+///
+/// # Arguments
+///
+/// * `docs`: &[(PathBuf, BEMDocument)] - The documents and the files they were parsed from.
+/// * `scheme`: &NamingScheme - The separators composing the classes.
+///
+/// # Returns
+///
+/// * `SchemaDump` - Every block of the documents, in order.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse_file, schema_dump, BEMDocument, NamingScheme };
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("media-player.bem");
+/// std::fs::write(&path, "media-player\nbutton[rewind]").unwrap();
+///
+/// let document = BEMDocument::from(parse_file(&path).unwrap());
+/// let dump = schema_dump(&[(path, document)], &NamingScheme::default());
+/// let button = &dump.blocks[0].elements[0];
+///
+/// assert_eq!(button.class, "media-player__button");
+/// assert_eq!(button.modifiers[0].class, "media-player__button--rewind");
+/// assert_eq!(button.location.line, Some(2));
+/// ```
+pub fn schema_dump(docs: &[(PathBuf, BEMDocument)], scheme: &NamingScheme) -> SchemaDump {
+	schema_dump_with(docs, scheme, |path| fs::read_to_string(path).ok())
+}
+
+/// Dumps the blocks of documents like [`schema_dump`], with `read` reading the `.bem` files back.
+pub(crate) fn schema_dump_with(docs: &[(PathBuf, BEMDocument)], scheme: &NamingScheme, mut read: impl FnMut(&Path) -> Option<String>) -> SchemaDump {
+	let blocks = docs
+		.iter()
+		.flat_map(|(path, document)| {
+			let lines = read(path).map(|input| source_lines(&input)).unwrap_or_default();
+
+			document.blocks.iter().map(move |bem_block| schema_block(bem_block, path, &lines, scheme)).collect::<Vec<_>>()
+		})
+		.collect();
+
+	SchemaDump { schema_version: SCHEMA_DUMP_VERSION, blocks }
+}
+
+fn schema_block(bem_block: &BEMBlock, path: &Path, lines: &HashMap<BEMPath, usize>, scheme: &NamingScheme) -> SchemaBlock {
+	let location = |entity: &BEMPath| SchemaLocation { file: path.to_path_buf(), line: lines.get(entity).copied() };
+	let modifiers = |base: &BEMPath, class: &str, modifiers: &[BEMModifier]| -> Vec<SchemaModifier> {
+		modifiers
+			.iter()
+			.map(|modifier| SchemaModifier {
+				name: modifier.to_string(),
+				class: scheme.modifier_class(class, &modifier.class_name()),
+				description: None,
+				deprecated: false,
+				location: location(&base.clone().with_modifier(&modifier.class_name())),
+			})
+			.collect()
+	};
+	let block_path = BEMPath::new(&bem_block.name);
+
+	SchemaBlock {
+		name: bem_block.name.clone(),
+		class: bem_block.name.clone(),
+		description: None,
+		deprecated: false,
+		location: location(&block_path),
+		modifiers: modifiers(&block_path, &bem_block.name, &bem_block.modifiers),
+		elements: bem_block.elements
+			.iter()
+			.map(|element| {
+				let element_path = block_path.clone().with_element(&element.name);
+				let class = scheme.element_class(&bem_block.name, &element.name);
+
+				SchemaElement {
+					name: element.name.clone(),
+					modifiers: modifiers(&element_path, &class, &element.modifiers),
+					class,
+					description: None,
+					deprecated: false,
+					location: location(&element_path),
+				}
+			})
+			.collect(),
+	}
+}
+
+/// Returns the lines every entity of a `.bem` source is first written on, or no lines if it
+/// does not parse.
+fn source_lines(input: &str) -> HashMap<BEMPath, usize> {
+	let mut lines = HashMap::new();
+	let Ok(tree) = parse_raw(input) else {
+		return lines;
+	};
+	let line = |node: &RawNode| input[..node.span.start].matches('\n').count() + 1;
+	let mut sets: HashMap<&str, Vec<&str>> = HashMap::new();
+	let mut block = BEMPath::new("");
+
+	for node in &tree.children {
+		let entity = match node.rule {
+			RawRule::SetDefinition => {
+				let names: Vec<&str> = node.children.iter().map(|child| child.as_str(input)).collect();
+
+				sets.insert(names[0], names[1..].to_vec());
+				continue;
+			}
+			RawRule::Block => {
+				block = BEMPath::new(node.children[0].as_str(input));
+				block.clone()
+			}
+			RawRule::Element => block.clone().with_element(node.children[0].as_str(input)),
+			_ => continue,
+		};
+
+		lines.entry(entity.clone()).or_insert(line(&node.children[0]));
+
+		for modifier in node.children.iter().filter(|child| child.rule == RawRule::Modifiers).flat_map(|modifiers| &modifiers.children) {
+			let names = match modifier.rule {
+				RawRule::Alias => sets.get(modifier.children[0].as_str(input)).cloned().unwrap_or_default(),
+				RawRule::Comment => continue,
+				_ => vec![modifier.as_str(input)],
+			};
+
+			for name in names {
+				lines.entry(entity.clone().with_modifier(&BEMModifier::from(name).class_name())).or_insert(line(modifier));
+			}
+		}
+	}
+
+	lines
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::PathBuf;
+	use super::schema_dump;
+	use crate::files::parse_file;
+	use crate::models::BEMDocument;
+	use crate::naming::NamingScheme;
+
+	fn fixtures() -> Vec<(PathBuf, BEMDocument)> {
+		["media-player.bem", "play-list.bem"]
+			.iter()
+			.map(|file| {
+				let path = PathBuf::from("fixtures/schema").join(file);
+				let document = BEMDocument::from(parse_file(&path).unwrap());
+
+				(path, document)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_schema_dump() {
+		let dump = schema_dump(&fixtures(), &NamingScheme::default());
+
+		insta::assert_snapshot!(serde_json::to_string_pretty(&dump).unwrap());
+	}
+
+	#[test]
+	fn test_schema_dump_locations() {
+		let dump = schema_dump(&fixtures(), &NamingScheme::default());
+		let media_player = &dump.blocks[0];
+		let play_list = &dump.blocks[1];
+		let button = &media_player.elements[0];
+
+		assert_eq!(media_player.location.line, Some(3));
+		assert_eq!(media_player.location.file, PathBuf::from("fixtures/schema/media-player.bem"));
+		assert_eq!(media_player.modifiers.iter().map(|modifier| modifier.location.line).collect::<Vec<_>>(), vec![Some(3), Some(3), Some(3)]);
+		assert_eq!((button.name.as_str(), button.location.line), ("button", Some(4)));
+		assert_eq!(button.modifiers[1].location.line, Some(6));
+		assert_eq!(media_player.elements[1].location.line, Some(8));
+		assert_eq!(play_list.elements[1].location.line, Some(5));
+		assert_eq!(play_list.location.file, PathBuf::from("fixtures/schema/play-list.bem"));
+	}
+
+	#[test]
+	fn test_schema_dump_without_source() {
+		let document = BEMDocument::from(crate::parse("media-player").unwrap());
+		let dump = schema_dump(&[(PathBuf::from("missing.bem"), document)], &NamingScheme::default());
+
+		assert_eq!(dump.blocks[0].location.line, None);
+		assert_eq!(dump.schema_version, super::SCHEMA_DUMP_VERSION);
+	}
+}
@@ -0,0 +1,170 @@
+//! This module searches the names of BEM blocks with regular expressions.
+
+use regex::Regex;
+use crate::models::BEMBlock;
+use crate::path::BEMPath;
+
+/// Selects which names [`find`] searches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchScope {
+	/// Block names only.
+	Blocks,
+	/// Element names only.
+	Elements,
+	/// Modifiers of blocks and elements only.
+	Modifiers,
+	/// Every name.
+	#[default]
+	Any,
+}
+
+impl SearchScope {
+	fn includes(self, scope: SearchScope) -> bool {
+		self == SearchScope::Any || self == scope
+	}
+}
+
+/// A name matched by [`find`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+	/// The entity the matched name belongs to.
+	pub path: BEMPath,
+	/// The matched name, e.g. `rewind` for the `rewind` modifier of `media-player__button`.
+	pub name: String,
+	/// The capture groups of the first match within the name, in order, not including the whole
+	/// match. Groups that did not participate in the match are `None`.
+	pub captures: Vec<Option<String>>,
+}
+
+/// Searches the names of blocks, elements, and modifiers for a regular expression.
+///
+/// A name matches if the pattern matches anywhere in it, so anchor the pattern with `^` and `$`
+/// to match whole names. Only the names themselves are searched, not the class names they form.
+/// Matches are returned in document order, block by block, and an entity repeated within a
+/// block is only reported once.
+///
+/// # Arguments
+///
+/// * `blocks`: &[BEMBlock] - The blocks to search.
+/// * `pattern`: &Regex - The pattern names are matched against.
+/// * `scope`: SearchScope - The kinds of names to search.
+///
+/// # Returns
+///
+/// * `Vec<Match>` - One match per matching entity.
+///
+/// # Examples
+///
+/// ```
+/// use regex::Regex;
+/// use bem::{ find, parse, SearchScope };
+///
+/// let blocks = vec![parse("media-player[dark]\nbutton[fast-forward,rewind]").unwrap()];
+/// let matches = find(&blocks, &Regex::new("^(fast|slow)-").unwrap(), SearchScope::Modifiers);
+///
+/// assert_eq!(matches[0].path.to_string(), "media-player__button--fast-forward");
+/// assert_eq!(matches[0].captures, vec![Some("fast".to_string())]);
+/// ```
+pub fn find(blocks: &[BEMBlock], pattern: &Regex, scope: SearchScope) -> Vec<Match> {
+	let mut matches: Vec<Match> = Vec::new();
+	let mut push = |path: BEMPath, name: &str| {
+		let Some(captures) = pattern.captures(name) else {
+			return;
+		};
+
+		if matches.iter().any(|existing| existing.path == path) {
+			return;
+		}
+
+		matches.push(Match {
+			path,
+			name: name.to_string(),
+			captures: captures
+				.iter()
+				.skip(1)
+				.map(|group| group.map(|group| group.as_str().to_string()))
+				.collect(),
+		});
+	};
+
+	for bem_block in blocks {
+		let block_path = BEMPath::new(&bem_block.name);
+
+		if scope.includes(SearchScope::Blocks) {
+			push(block_path.clone(), &bem_block.name);
+		}
+
+		if scope.includes(SearchScope::Modifiers) {
+			for modifier in &bem_block.modifiers {
+				push(block_path.clone().with_modifier(&modifier.class_name()), &modifier.class_name());
+			}
+		}
+
+		for element in &bem_block.elements {
+			let element_path = block_path.clone().with_element(&element.name);
+
+			if scope.includes(SearchScope::Elements) {
+				push(element_path.clone(), &element.name);
+			}
+
+			if scope.includes(SearchScope::Modifiers) {
+				for modifier in &element.modifiers {
+					push(element_path.clone().with_modifier(&modifier.class_name()), &modifier.class_name());
+				}
+			}
+		}
+	}
+
+	matches
+}
+
+#[cfg(test)]
+mod tests {
+	use regex::Regex;
+	use super::{ find, SearchScope };
+	use crate::models::BEMBlock;
+	use crate::parse;
+
+	fn blocks() -> Vec<BEMBlock> {
+		vec![
+			parse("media-player[dark]\nbutton[fast-forward,rewind]\nbutton[rewind]\ntimeline").unwrap(),
+			parse("play-list[dark]\nitem[playing]").unwrap(),
+		]
+	}
+
+	fn paths(scope: SearchScope, pattern: &str) -> Vec<String> {
+		find(&blocks(), &Regex::new(pattern).unwrap(), scope)
+			.iter()
+			.map(|found| found.path.to_string())
+			.collect()
+	}
+
+	#[test]
+	fn test_find_scopes() {
+		assert_eq!(paths(SearchScope::Blocks, "play"), vec!["media-player", "play-list"]);
+		assert_eq!(paths(SearchScope::Elements, "^t"), vec!["media-player__timeline"]);
+		assert_eq!(paths(SearchScope::Modifiers, "^(dark|playing)$"), vec![
+			"media-player--dark",
+			"play-list--dark",
+			"play-list__item--playing",
+		]);
+		assert_eq!(paths(SearchScope::Any, "play"), vec!["media-player", "play-list", "play-list__item--playing"]);
+	}
+
+	#[test]
+	fn test_find_reports_repeated_entities_once() {
+		assert_eq!(paths(SearchScope::Any, "^(button|rewind)$"), vec![
+			"media-player__button",
+			"media-player__button--rewind",
+		]);
+	}
+
+	#[test]
+	fn test_find_captures() {
+		let matches = find(&blocks(), &Regex::new(r"^(\w+)-(\w+)(-x)?$").unwrap(), SearchScope::Any);
+
+		assert_eq!(matches.len(), 3);
+		assert_eq!(matches[1].name, "fast-forward");
+		assert_eq!(matches[1].captures, vec![Some("fast".to_string()), Some("forward".to_string()), None]);
+	}
+}
@@ -0,0 +1,70 @@
+//! This module generates service worker code for progressive web apps built from BEM blocks.
+
+use crate::models::BEMBlock;
+
+impl BEMBlock {
+	/// Generates a service worker snippet precaching the stylesheets of the block with Workbox.
+	///
+	/// The precached URLs are the CSS files of [`to_bem_fs_scheme`](BEMBlock::to_bem_fs_scheme),
+	/// relative to the location of the service worker, passed to `precacheAndRoute` from
+	/// `workbox-precaching`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let service_worker = parse("media-player").unwrap().to_bem_service_worker_cache();
+	///
+	/// assert!(service_worker.contains("precacheAndRoute([\n\t\"media-player/media-player.css\",\n]);"));
+	/// ```
+	pub fn to_bem_service_worker_cache(&self) -> String {
+		let urls: Vec<String> = self
+			.to_bem_fs_scheme("css")
+			.iter()
+			.map(|path| format!("\t{},\n", serde_json::to_string(path).expect("strings always serialize")))
+			.collect();
+
+		format!(
+			"import {{ precacheAndRoute }} from \"workbox-precaching\";\n\nprecacheAndRoute([\n{}]);\n",
+			urls.concat()
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::parse;
+
+	fn assert_balanced(source: &str) {
+		let mut stack = Vec::new();
+
+		for c in source.chars() {
+			match c {
+				'(' | '[' | '{' => stack.push(c),
+				')' => assert_eq!(stack.pop(), Some('('), "unbalanced `)` in:\n{}", source),
+				']' => assert_eq!(stack.pop(), Some('['), "unbalanced `]` in:\n{}", source),
+				'}' => assert_eq!(stack.pop(), Some('{'), "unbalanced `}}` in:\n{}", source),
+				_ => {}
+			}
+		}
+
+		assert!(stack.is_empty(), "unclosed brackets in:\n{}", source);
+	}
+
+	#[test]
+	fn test_to_bem_service_worker_cache() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let service_worker = bem_block.to_bem_service_worker_cache();
+
+		assert_balanced(&service_worker);
+		assert!(service_worker.contains("precacheAndRoute(["));
+		assert_eq!(service_worker.matches(".css\",").count(), 6);
+
+		for path in bem_block.to_bem_fs_scheme("css") {
+			assert!(service_worker.contains(&format!("\"{}\"", path)));
+		}
+
+		insta::assert_snapshot!(service_worker);
+	}
+}
@@ -0,0 +1,186 @@
+//! This module detects the format of an input from its first bytes, so tools reading blocks from
+//! pipes do not need to be told whether they receive `.bem` text, JSON, or YAML.
+
+use std::fmt;
+use std::str::FromStr;
+use crate::error::{ BEMParseError, Error, Result };
+use crate::parser::parse_block_line;
+
+/// The number of bytes of an input [`sniff`] looks at, at most.
+pub const SNIFF_LIMIT: usize = 1024;
+
+/// The formats blocks can be read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputFormat {
+	/// The `.bem` syntax, see [`parse`](crate::parse).
+	Bem,
+	/// A block as written by [`to_json`](crate::to_json), or an array of blocks.
+	Json,
+	/// A block as written by [`to_yaml`](crate::to_yaml), or a sequence of blocks.
+	#[cfg(feature = "yaml")]
+	Yaml,
+}
+
+impl fmt::Display for InputFormat {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			InputFormat::Bem => write!(f, "bem"),
+			InputFormat::Json => write!(f, "json"),
+			#[cfg(feature = "yaml")]
+			InputFormat::Yaml => write!(f, "yaml"),
+		}
+	}
+}
+
+impl FromStr for InputFormat {
+	type Err = String;
+
+	fn from_str(format: &str) -> std::result::Result<Self, Self::Err> {
+		match format {
+			"bem" => Ok(InputFormat::Bem),
+			"json" => Ok(InputFormat::Json),
+			#[cfg(feature = "yaml")]
+			"yaml" => Ok(InputFormat::Yaml),
+			#[cfg(feature = "yaml")]
+			format => Err(format!("unknown input format `{}`, expected bem, json or yaml", format)),
+			#[cfg(not(feature = "yaml"))]
+			format => Err(format!("unknown input format `{}`, expected bem or json", format)),
+		}
+	}
+}
+
+/// Detects the format of `input` from its first [`SNIFF_LIMIT`] bytes, so an input can be
+/// sniffed before it is read to the end.
+///
+/// Leading whitespace and a byte order mark are skipped. Inputs starting with `{` or `[` are
+/// JSON. Inputs whose first line is a `#!bem` directive, an `@set` definition, or starts like a
+/// block line, e.g. `media-player[dark]`, are `.bem` text. With the `yaml` feature, inputs whose
+/// first line is a `---` document marker or starts with a `key:` mapping entry, optionally as the
+/// first item of a sequence (`- key:`), are YAML.
+///
+/// # Arguments
+///
+/// * `input`: &str - The input, or its first bytes.
+///
+/// # Returns
+///
+/// * `Result<InputFormat>` - The format detected, or a parse error listing why each format was
+///   ruled out.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ sniff, InputFormat };
+///
+/// assert_eq!(sniff("{\"name\":\"media-player\"}").unwrap(), InputFormat::Json);
+/// assert_eq!(sniff("media-player[dark]\nbutton").unwrap(), InputFormat::Bem);
+/// assert_eq!(sniff("#!bem 2\nmedia-player").unwrap(), InputFormat::Bem);
+/// assert!(sniff("<media-player />").is_err());
+/// ```
+pub fn sniff(input: &str) -> Result<InputFormat> {
+	let mut end = input.len().min(SNIFF_LIMIT);
+
+	while !input.is_char_boundary(end) {
+		end -= 1;
+	}
+
+	let prefix = input[..end].trim_start_matches('\u{FEFF}').trim_start();
+
+	if prefix.starts_with('{') || prefix.starts_with('[') {
+		return Ok(InputFormat::Json);
+	}
+
+	let line = prefix.lines().next().unwrap_or_default().trim_end();
+	// Only the name of the block is checked, since its modifiers may not fit in the prefix.
+	let name = line.split('[').next().unwrap_or_default();
+
+	if line.starts_with("#!bem") || line.starts_with("@set") || parse_block_line(name).is_ok() {
+		return Ok(InputFormat::Bem);
+	}
+
+	#[cfg(feature = "yaml")]
+	if line == "---" || line.starts_with("--- ") || is_yaml_key(line.strip_prefix("- ").unwrap_or(line)) {
+		return Ok(InputFormat::Yaml);
+	}
+
+	let found = match line.chars().count() {
+		0 => "the input is empty".to_string(),
+		count if count > 40 => format!("`{}…` is not a block line", line.chars().take(40).collect::<String>()),
+		_ => format!("`{}` is not a block line", line),
+	};
+	#[cfg(feature = "yaml")]
+	let found = format!("{}; yaml: does not start with `---` or a `key:` line", found);
+	let message = format!("cannot detect the input format (json: does not start with `{{` or `[`; bem: {})", found);
+
+	Err(Error::Parse(BEMParseError::at(input, 0..0, message)))
+}
+
+/// Whether `line` starts with a plain YAML mapping key followed by a colon, e.g. `name:` or
+/// `name: media-player`.
+#[cfg(feature = "yaml")]
+fn is_yaml_key(line: &str) -> bool {
+	let Some((key, rest)) = line.split_once(':') else {
+		return false;
+	};
+
+	key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+		&& key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+		&& (rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ sniff, InputFormat, SNIFF_LIMIT };
+
+	#[test]
+	fn test_sniff_json() {
+		assert_eq!(sniff("  \n{\"name\":\"media-player\",\"modifiers\":[],\"elements\":[]}").unwrap(), InputFormat::Json);
+		assert_eq!(sniff("\u{FEFF}[]").unwrap(), InputFormat::Json);
+	}
+
+	#[test]
+	fn test_sniff_bem() {
+		assert_eq!(sniff("media-player").unwrap(), InputFormat::Bem);
+		assert_eq!(sniff("media-player[dark]\r\nbutton").unwrap(), InputFormat::Bem);
+		assert_eq!(sniff("@set sizes = small, large\nbutton[@sizes]").unwrap(), InputFormat::Bem);
+		assert_eq!(sniff("#!bem 1\nmedia-player").unwrap(), InputFormat::Bem);
+	}
+
+	#[test]
+	fn test_sniff_only_reads_a_prefix() {
+		let long = format!("media-player[{}]", vec!["dark"; SNIFF_LIMIT].join(","));
+
+		assert_eq!(sniff(&long).unwrap(), InputFormat::Bem);
+		assert!(sniff(&format!("{}media-player", " ".repeat(SNIFF_LIMIT))).is_err());
+		assert_eq!(sniff(&format!("{}{}", "{", "x".repeat(SNIFF_LIMIT * 4))).unwrap(), InputFormat::Json);
+	}
+
+	#[cfg(feature = "yaml")]
+	#[test]
+	fn test_sniff_yaml() {
+		assert_eq!(sniff("name: media-player\nmodifiers: []\nelements: []").unwrap(), InputFormat::Yaml);
+		assert_eq!(sniff("---\nname: media-player").unwrap(), InputFormat::Yaml);
+		assert_eq!(sniff("\u{FEFF}- name: media-player\n  modifiers: []").unwrap(), InputFormat::Yaml);
+		assert_eq!(sniff("media-player[hover:dark]").unwrap(), InputFormat::Bem);
+		assert!(sniff("name:media-player").is_err());
+		assert_eq!("yaml".parse::<InputFormat>().unwrap(), InputFormat::Yaml);
+		assert_eq!(InputFormat::Yaml.to_string(), "yaml");
+	}
+
+	#[test]
+	fn test_sniff_failure() {
+		let error = sniff("<media-player />\n").unwrap_err();
+		let expected = "parse error at line 1, column 1: cannot detect the input format (json: does not start with `{` or `[`; \
+			bem: `<media-player />` is not a block line";
+
+		#[cfg(not(feature = "yaml"))]
+		assert_eq!(error.to_string(), format!("{})", expected));
+		#[cfg(feature = "yaml")]
+		assert_eq!(error.to_string(), format!("{}; yaml: does not start with `---` or a `key:` line)", expected));
+		assert!(sniff("   ").unwrap_err().to_string().contains("bem: the input is empty"));
+		assert_eq!("json".parse::<InputFormat>().unwrap(), InputFormat::Json);
+		#[cfg(not(feature = "yaml"))]
+		assert!("yaml".parse::<InputFormat>().is_err());
+		assert!("toml".parse::<InputFormat>().is_err());
+	}
+}
@@ -0,0 +1,281 @@
+//! This module derives `data-testid` values from BEM blocks, so end-to-end tests can target
+//! the same entities as the stylesheets without keeping the ids in sync by hand.
+
+use crate::abbreviation::AbbreviationMap;
+use crate::ident::{ convert_case, unique_identifiers, Case, IdentifierError };
+use crate::models::BEMBlock;
+
+/// The casing applied to every part (block, element, modifier) of a test id.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TestIdCasing {
+	/// Keeps the names as written: `media-player.button`.
+	#[default]
+	Preserve,
+	/// `mediaPlayer.button`, or `mediaPlayerButton` with an empty joiner.
+	Camel,
+	/// `MediaPlayer.Button`
+	Pascal,
+	/// `media_player.button`
+	Snake,
+}
+
+/// The format of the mapping generated by [`to_testid_map`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TestIdFormat {
+	/// A JSON object.
+	#[default]
+	Json,
+	/// A TypeScript module exporting the mapping `as const`, along with a `TestId` union type.
+	TypeScript,
+}
+
+/// Configures how [`to_testid_map`] composes test ids.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ TestIdCasing, TestIdOptions };
+///
+/// let options = TestIdOptions {
+///     joiner: String::new(),
+///     casing: TestIdCasing::Camel,
+///     ..TestIdOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestIdOptions {
+	/// The string placed between the parts of a test id. Defaults to `.`.
+	pub joiner: String,
+	pub casing: TestIdCasing,
+	/// Whether modifier states get their own test ids. Defaults to `false`.
+	pub include_modifiers: bool,
+	pub format: TestIdFormat,
+	/// The abbreviations of the segments of every part, applied before casing. Defaults to none.
+	pub abbreviations: AbbreviationMap,
+}
+
+impl Default for TestIdOptions {
+	fn default() -> Self {
+		TestIdOptions {
+			joiner: ".".to_string(),
+			casing: TestIdCasing::default(),
+			include_modifiers: false,
+			format: TestIdFormat::default(),
+			abbreviations: AbbreviationMap::default(),
+		}
+	}
+}
+
+impl TestIdOptions {
+	/// Composes the test id of an entity from its parts, e.g. `["media-player", "button"]`.
+	fn test_id(&self, parts: &[&str]) -> String {
+		let cased: Vec<String> = parts
+			.iter()
+			.enumerate()
+			.map(|(index, part)| {
+				let part = self.abbreviations.abbreviate(part);
+
+				match self.casing {
+					TestIdCasing::Preserve => part.into_owned(),
+					// Without a joiner, later parts are capitalized so the id stays camel-cased.
+					TestIdCasing::Camel if index > 0 && self.joiner.is_empty() => convert_case(&part, Case::Pascal),
+					TestIdCasing::Camel => convert_case(&part, Case::Camel),
+					TestIdCasing::Pascal => convert_case(&part, Case::Pascal),
+					TestIdCasing::Snake => convert_case(&part, Case::Snake),
+				}
+			})
+			.collect();
+
+		cased.join(&self.joiner)
+	}
+}
+
+/// Generates a mapping from the BEM classes of a block to `data-testid` values.
+///
+/// The block and every element get a test id composed of their names, cased and joined as set
+/// in `options`. With `include_modifiers`, every modifier state gets one too, with the modifier
+/// as the last part. Entries follow the order of the block, and repeated classes only produce
+/// one entry.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to generate test ids for.
+/// * `options`: &TestIdOptions - How test ids are composed and the mapping is formatted.
+///
+/// # Returns
+///
+/// * `Result<String, IdentifierError>` - The mapping as JSON or as a TypeScript module, or an
+///   error if two classes end up with the same test id once cased and joined.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_testid_map, TestIdCasing, TestIdOptions };
+///
+/// let bem_block = parse("media-player\nbutton").unwrap();
+/// let options = TestIdOptions {
+///     joiner: String::new(),
+///     casing: TestIdCasing::Camel,
+///     ..TestIdOptions::default()
+/// };
+/// let json = to_testid_map(&bem_block, &options).unwrap();
+///
+/// assert!(json.contains("\"media-player__button\": \"mediaPlayerButton\""));
+/// ```
+pub fn to_testid_map(bem_block: &BEMBlock, options: &TestIdOptions) -> Result<String, IdentifierError> {
+	let block = bem_block.name.as_str();
+	let mut pairs = vec![(block.to_string(), options.test_id(&[block]))];
+
+	if options.include_modifiers {
+		for modifier in &bem_block.modifiers {
+			pairs.push((format!("{}--{}", block, modifier.class_name()), options.test_id(&[block, &modifier.class_name()])));
+		}
+	}
+
+	for element in &bem_block.elements {
+		let class = format!("{}__{}", block, element.name);
+
+		pairs.push((class.clone(), options.test_id(&[block, &element.name])));
+
+		if options.include_modifiers {
+			for modifier in &element.modifiers {
+				pairs.push((format!("{}--{}", class, modifier.class_name()), options.test_id(&[block, &element.name, &modifier.class_name()])));
+			}
+		}
+	}
+
+	let test_ids = unique_identifiers(pairs)?;
+
+	Ok(match options.format {
+		TestIdFormat::Json => format!("{{\n{}\n}}\n", entries(&test_ids, "  ").join(",\n")),
+		TestIdFormat::TypeScript => format!(
+			"export const testIds = {{\n{},\n}} as const;\n\nexport type TestId = (typeof testIds)[keyof typeof testIds];\n",
+			entries(&test_ids, "\t").join(",\n")
+		),
+	})
+}
+
+/// Renders `"class": "test-id"` entries, escaping both sides as JSON strings.
+fn entries(test_ids: &[(String, String)], indent: &str) -> Vec<String> {
+	test_ids
+		.iter()
+		.map(|(class, test_id)| format!("{}{}: {}", indent, json_string(class), json_string(test_id)))
+		.collect()
+}
+
+fn json_string(value: &str) -> String {
+	serde_json::to_string(value).expect("strings always serialize")
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use super::{ TestIdCasing, TestIdFormat, TestIdOptions };
+	use crate::models::BEMBlock;
+	use crate::parse;
+
+	fn media_player() -> BEMBlock {
+		parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap()
+	}
+
+	fn camel_case() -> TestIdOptions {
+		TestIdOptions {
+			joiner: String::new(),
+			casing: TestIdCasing::Camel,
+			..TestIdOptions::default()
+		}
+	}
+
+	#[test]
+	fn test_to_testid_map_json() {
+		let json = super::to_testid_map(&media_player(), &TestIdOptions::default()).unwrap();
+		let map: HashMap<String, String> = serde_json::from_str(&json).unwrap();
+
+		assert_eq!(map.len(), 3);
+		assert_eq!(map["media-player__button"], "media-player.button");
+
+		insta::assert_snapshot!(json);
+	}
+
+	#[test]
+	fn test_to_testid_map_json_camel_case_with_modifiers() {
+		let options = TestIdOptions { include_modifiers: true, ..camel_case() };
+		let json = super::to_testid_map(&media_player(), &options).unwrap();
+		let map: HashMap<String, String> = serde_json::from_str(&json).unwrap();
+
+		assert_eq!(map.len(), 6);
+		assert_eq!(map["media-player__button--fast-forward"], "mediaPlayerButtonFastForward");
+
+		insta::assert_snapshot!(json);
+	}
+
+	#[test]
+	fn test_to_testid_map_typescript() {
+		let options = TestIdOptions { format: TestIdFormat::TypeScript, ..TestIdOptions::default() };
+
+		insta::assert_snapshot!(super::to_testid_map(&media_player(), &options).unwrap());
+	}
+
+	#[test]
+	fn test_to_testid_map_typescript_camel_case() {
+		let options = TestIdOptions { format: TestIdFormat::TypeScript, ..camel_case() };
+
+		insta::assert_snapshot!(super::to_testid_map(&media_player(), &options).unwrap());
+	}
+
+	#[test]
+	fn test_to_testid_map_with_abbreviations() {
+		let abbreviations = crate::AbbreviationMap::from_json("{\"media\": \"m\", \"button\": \"btn\"}").unwrap();
+		let options = TestIdOptions { abbreviations, ..camel_case() };
+		let json = super::to_testid_map(&parse("media-player\nplay-button\nbuttons").unwrap(), &options).unwrap();
+		let map: HashMap<String, String> = serde_json::from_str(&json).unwrap();
+
+		assert_eq!(map["media-player__play-button"], "mPlayerPlayBtn");
+		assert_eq!(map["media-player__buttons"], "mPlayerButtons");
+		assert_eq!(
+			super::to_testid_map(&parse("media-player\nplay-btn\nplay-button").unwrap(), &options).unwrap_err().identifier,
+			"mPlayerPlayBtn"
+		);
+	}
+
+	#[test]
+	fn test_to_testid_map_casing() {
+		let bem_block = parse("media-player\nplay-button").unwrap();
+		let test_id = |casing| {
+			let options = TestIdOptions { casing, ..TestIdOptions::default() };
+			let json = super::to_testid_map(&bem_block, &options).unwrap();
+			let map: HashMap<String, String> = serde_json::from_str(&json).unwrap();
+
+			map["media-player__play-button"].clone()
+		};
+
+		assert_eq!(test_id(TestIdCasing::Preserve), "media-player.play-button");
+		assert_eq!(test_id(TestIdCasing::Camel), "mediaPlayer.playButton");
+		assert_eq!(test_id(TestIdCasing::Pascal), "MediaPlayer.PlayButton");
+		assert_eq!(test_id(TestIdCasing::Snake), "media_player.play_button");
+	}
+
+	#[test]
+	fn test_to_testid_map_collision() {
+		let options = TestIdOptions {
+			joiner: "_".to_string(),
+			casing: TestIdCasing::Snake,
+			include_modifiers: true,
+			..TestIdOptions::default()
+		};
+		let error = super::to_testid_map(&parse("media-player\nbutton[rewind]\nbutton-rewind").unwrap(), &options).unwrap_err();
+
+		assert_eq!(error.identifier, "media_player_button_rewind");
+		assert_eq!(error.sources, vec![
+			"media-player__button--rewind".to_string(),
+			"media-player__button-rewind".to_string()
+		]);
+	}
+
+	#[test]
+	fn test_to_testid_map_repeated_elements() {
+		let json = super::to_testid_map(&parse("foo\nbar\nbar").unwrap(), &TestIdOptions::default()).unwrap();
+
+		assert_eq!(json, "{\n  \"foo\": \"foo\",\n  \"foo__bar\": \"foo.bar\"\n}\n");
+	}
+}
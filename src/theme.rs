@@ -0,0 +1,324 @@
+//! This module generates the key space of CSS-in-JS theme objects from BEM blocks, so a theme
+//! keyed by component entity is type-checked against the same structure as the markup.
+
+use std::slice;
+use crate::abbreviation::AbbreviationMap;
+use crate::ident::{ abbreviated_identifier, identifier, identifiers, numbered_identifiers, Case, IdentifierError };
+use crate::models::BEMBlock;
+
+/// A key of the theme object. Keys without children hold a CSS value.
+struct ThemeKey {
+	ident: String,
+	/// Whether the key stands for a block or an element, as opposed to a modifier.
+	entity: bool,
+	children: Vec<ThemeKey>,
+}
+
+impl BEMBlock {
+	/// Generates a TypeScript theme object for CSS-in-JS libraries such as Emotion or
+	/// styled-components, keyed by the entities of the block.
+	///
+	/// The block is nested under the `components` namespace of the theme, with one key per block
+	/// modifier and element inside it, elements in turn nesting their modifiers. Keys are
+	/// camelCased like in [`to_theme_keys_ts`], modifiers hold an empty string as a placeholder
+	/// for a CSS value, and elements without modifiers hold an empty object. A `Theme` type is
+	/// exported alongside the object for declaring the theme type of the library. A name whose key
+	/// is taken by an earlier name at the same level gets it numbered instead, e.g. `button_2` for
+	/// the `button` element after the `button` modifier of the block.
+	///
+	/// # Returns
+	///
+	/// * `String` - The module source.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let theme = parse("media-player\nbutton[rewind]").unwrap().to_bem_css_in_js_theme();
+	///
+	/// assert!(theme.contains("\t\tmediaPlayer: {\n\t\t\tbutton: {\n\t\t\t\trewind: \"\",\n"));
+	/// ```
+	pub fn to_bem_css_in_js_theme(&self) -> String {
+		let numbered = |sources: Vec<(String, String)>| {
+			let pairs = sources
+				.into_iter()
+				.map(|(label, name)| {
+					let ident = identifier(&name, Case::Camel);
+
+					(label, ident)
+				});
+
+			Ok(numbered_identifiers(pairs))
+		};
+		let keys = theme_keys(slice::from_ref(self), numbered).expect("numbered keys never collide");
+
+		format!(
+			"export const theme = {{\n\tcomponents: {{\n{}\t}},\n}};\n\nexport type Theme = typeof theme;\n",
+			object_members(&keys, 2)
+		)
+	}
+}
+
+/// Generates TypeScript types describing a theme object keyed by the given blocks.
+///
+/// The `ThemeKeys` interface nests one key per block, and inside it one key per block modifier
+/// and element, elements in turn nesting their modifiers. Keys are camelCased, and every leaf is
+/// typed as `string` to hold a CSS value. The `ThemeKeyPath` union lists the dotted path of every
+/// leaf, e.g. `"mediaPlayer.button.rewind"`. Blocks sharing a name, and repeated elements, are
+/// merged into one key.
+///
+/// # Arguments
+///
+/// * `blocks`: &[BEMBlock] - The blocks the theme is keyed by.
+///
+/// # Returns
+///
+/// * `Result<String, IdentifierError>` - The TypeScript declarations, or an error if two names
+///   at the same level of the theme object map to the same key.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_theme_keys_ts };
+///
+/// let bem_block = parse("media-player\nbutton[rewind]").unwrap();
+/// let ts = to_theme_keys_ts(&[bem_block]).unwrap();
+///
+/// assert!(ts.contains("\t\tbutton: {\n\t\t\trewind: string;\n\t\t};\n"));
+/// assert!(ts.contains("| \"mediaPlayer.button.rewind\""));
+/// ```
+pub fn to_theme_keys_ts(blocks: &[BEMBlock]) -> Result<String, IdentifierError> {
+	to_theme_keys_ts_with_abbreviations(blocks, &AbbreviationMap::default())
+}
+
+/// Generates the TypeScript types of [`to_theme_keys_ts`], with the segments of every key
+/// abbreviated before it is camelCased, e.g. `navDrawer` for `navigation-drawer` with
+/// `navigation → nav`.
+///
+/// # Arguments
+///
+/// * `blocks`: &[BEMBlock] - The blocks the theme is keyed by.
+/// * `abbreviations`: &AbbreviationMap - The abbreviations of name segments.
+///
+/// # Returns
+///
+/// * `Result<String, IdentifierError>` - The TypeScript declarations, or an error if two names
+///   at the same level of the theme object map to the same key once abbreviated.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_theme_keys_ts_with_abbreviations, AbbreviationMap };
+///
+/// let bem_block = parse("navigation-drawer\ncollapsible-section").unwrap();
+/// let abbreviations = AbbreviationMap::from_json("{\"navigation\": \"nav\"}").unwrap();
+/// let ts = to_theme_keys_ts_with_abbreviations(&[bem_block], &abbreviations).unwrap();
+///
+/// assert!(ts.contains("| \"navDrawer.collapsibleSection\""));
+/// ```
+pub fn to_theme_keys_ts_with_abbreviations(blocks: &[BEMBlock], abbreviations: &AbbreviationMap) -> Result<String, IdentifierError> {
+	let keys = theme_keys(blocks, |sources| identifiers(sources, |name| abbreviated_identifier(name, Case::Camel, abbreviations)))?;
+	let mut paths = Vec::new();
+
+	for key in &keys {
+		leaf_paths(key, "", &mut paths);
+	}
+
+	let mut output = String::new();
+
+	if keys.is_empty() {
+		output.push_str("export interface ThemeKeys {}\n\n");
+		output.push_str("export type ThemeKeyPath = never;\n");
+	} else {
+		output.push_str(&format!("export interface ThemeKeys {{\n{}}}\n\n", interface_members(&keys, 1)));
+		output.push_str("export type ThemeKeyPath =\n");
+
+		let members: Vec<String> = paths
+			.iter()
+			.map(|path| format!("\t| \"{}\"", path))
+			.collect();
+
+		output.push_str(&format!("{};\n", members.join("\n")));
+	}
+
+	Ok(output)
+}
+
+/// Builds the keys of the blocks, level by level, with `camel_case` turning the `(class, name)`
+/// pairs of a level into `(class, key)` pairs.
+fn theme_keys<F>(blocks: &[BEMBlock], camel_case: F) -> Result<Vec<ThemeKey>, IdentifierError>
+	where F: Fn(Vec<(String, String)>) -> Result<Vec<(String, String)>, IdentifierError>
+{
+	let mut keys = Vec::new();
+
+	for (block_name, ident) in camel_case(blocks.iter().map(|block| (block.name.clone(), block.name.clone())).collect())? {
+		let same_name: Vec<&BEMBlock> = blocks
+			.iter()
+			.filter(|block| block.name == block_name)
+			.collect();
+		let mut sources = Vec::new();
+
+		for block in &same_name {
+			for modifier in &block.modifiers {
+				sources.push((format!("{}--{}", block_name, modifier.class_name()), modifier.class_name().into_owned()));
+			}
+		}
+		for block in &same_name {
+			for element in &block.elements {
+				sources.push((format!("{}__{}", block_name, element.name), element.name.clone()));
+			}
+		}
+
+		let mut children = Vec::new();
+
+		for (class, child_ident) in camel_case(sources)? {
+			let modifiers = same_name
+				.iter()
+				.flat_map(|block| &block.elements)
+				.filter(|element| format!("{}__{}", block_name, element.name) == class)
+				.flat_map(|element| &element.modifiers)
+				.map(|modifier| (format!("{}--{}", class, modifier.class_name()), modifier.class_name().into_owned()))
+				.collect();
+			let grandchildren = camel_case(modifiers)?
+				.into_iter()
+				.map(|(_, ident)| ThemeKey { ident, entity: false, children: Vec::new() })
+				.collect();
+
+			children.push(ThemeKey {
+				ident: child_ident,
+				entity: class.contains("__"),
+				children: grandchildren,
+			});
+		}
+
+		keys.push(ThemeKey { ident, entity: true, children });
+	}
+
+	Ok(keys)
+}
+
+fn interface_members(keys: &[ThemeKey], depth: usize) -> String {
+	let indent = "\t".repeat(depth);
+	let mut members = String::new();
+
+	for key in keys {
+		if key.children.is_empty() {
+			members.push_str(&format!("{}{}: string;\n", indent, key.ident));
+		} else {
+			members.push_str(&format!("{}{}: {{\n{}{}}};\n", indent, key.ident, interface_members(&key.children, depth + 1), indent));
+		}
+	}
+
+	members
+}
+
+fn object_members(keys: &[ThemeKey], depth: usize) -> String {
+	let indent = "\t".repeat(depth);
+	let mut members = String::new();
+
+	for key in keys {
+		if !key.entity {
+			members.push_str(&format!("{}{}: \"\",\n", indent, key.ident));
+		} else if key.children.is_empty() {
+			members.push_str(&format!("{}{}: {{}},\n", indent, key.ident));
+		} else {
+			members.push_str(&format!("{}{}: {{\n{}{}}},\n", indent, key.ident, object_members(&key.children, depth + 1), indent));
+		}
+	}
+
+	members
+}
+
+fn leaf_paths(key: &ThemeKey, prefix: &str, paths: &mut Vec<String>) {
+	let path = if prefix.is_empty() { key.ident.clone() } else { format!("{}.{}", prefix, key.ident) };
+
+	if key.children.is_empty() {
+		paths.push(path);
+	} else {
+		for child in &key.children {
+			leaf_paths(child, &path, paths);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::parse;
+
+	#[test]
+	fn test_to_theme_keys_ts() {
+		let blocks = vec![
+			parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap(),
+			parse("play-list\nitem[active]\nitem[playing]").unwrap(),
+		];
+		let ts = super::to_theme_keys_ts(&blocks).unwrap();
+
+		assert!(ts.contains("| \"mediaPlayer.button.fastForward\""));
+		assert!(ts.contains("| \"playList.item.playing\""));
+
+		insta::assert_snapshot!(ts);
+	}
+
+	#[test]
+	fn test_to_bem_css_in_js_theme() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let theme = bem_block.to_bem_css_in_js_theme();
+
+		assert!(theme.contains(
+			"\tcomponents: {\n\
+			\t\tmediaPlayer: {\n\
+			\t\t\tdark: \"\",\n\
+			\t\t\tbutton: {\n\
+			\t\t\t\tfastForward: \"\",\n\
+			\t\t\t\trewind: \"\",\n\
+			\t\t\t},\n\
+			\t\t\ttimeline: {},\n\
+			\t\t},\n"
+		));
+
+		insta::assert_snapshot!(theme);
+	}
+
+	#[test]
+	fn test_to_bem_css_in_js_theme_collision() {
+		let theme = parse("media-player[button]\nbutton[button]\nbutton-2").unwrap().to_bem_css_in_js_theme();
+
+		assert!(theme.contains("\t\t\tbutton: \"\",\n\t\t\tbutton_2: {\n\t\t\t\tbutton: \"\",\n\t\t\t},\n\t\t\tbutton2: {},\n"));
+	}
+
+	#[test]
+	fn test_to_theme_keys_ts_without_blocks() {
+		assert_eq!(
+			super::to_theme_keys_ts(&[]).unwrap(),
+			"export interface ThemeKeys {}\n\nexport type ThemeKeyPath = never;\n"
+		);
+	}
+
+	#[test]
+	fn test_to_theme_keys_ts_with_abbreviations() {
+		let abbreviations = crate::AbbreviationMap::from_json("{\"navigation\": \"nav\", \"collapsible\": \"collapse\"}").unwrap();
+		let blocks = [parse("navigation-drawer[collapsible]\ncollapsible-section").unwrap()];
+		let ts = super::to_theme_keys_ts_with_abbreviations(&blocks, &abbreviations).unwrap();
+
+		assert!(ts.contains("\tnavDrawer: {\n\t\tcollapse: string;\n\t\tcollapseSection: string;\n\t};\n"));
+
+		let colliding = [parse("navigation-drawer").unwrap(), parse("nav-drawer").unwrap()];
+		let error = super::to_theme_keys_ts_with_abbreviations(&colliding, &abbreviations).unwrap_err();
+
+		assert_eq!(error.identifier, "navDrawer");
+		assert_eq!(error.sources, vec!["navigation-drawer".to_string(), "nav-drawer".to_string()]);
+	}
+
+	#[test]
+	fn test_to_theme_keys_ts_collision() {
+		let error = super::to_theme_keys_ts(&[parse("media-player[button]\nbutton").unwrap()]).unwrap_err();
+
+		assert_eq!(error.sources, vec![
+			"media-player--button".to_string(),
+			"media-player__button".to_string()
+		]);
+
+		insta::assert_snapshot!(error.to_string());
+	}
+}
@@ -0,0 +1,135 @@
+//! This module reads and writes blocks as TOML, for projects keeping the metadata of their
+//! components in TOML files. It is only compiled with the `toml` feature.
+
+use crate::error::{ Error, Result };
+use crate::format::BemFormat;
+use crate::models::BEMBlock;
+
+/// Converts a `BEMBlock` into TOML, in the [schema](Toml#schema) of [`Toml`].
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to be converted to TOML.
+///
+/// # Returns
+///
+/// * `Result<String>` - A result containing the TOML string, or an [`Error::TomlSerialize`] if
+///   there is a problem during serialization.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_toml };
+///
+/// let toml = to_toml(&parse("media-player[dark]\ntimeline").unwrap()).unwrap();
+///
+/// assert_eq!(toml, "name = \"media-player\"\nmodifiers = [\"dark\"]\n\n[[elements]]\nname = \"timeline\"\nmodifiers = []\n");
+/// ```
+pub fn to_toml(bem_block: &BEMBlock) -> Result<String> {
+	toml::to_string(bem_block).map_err(Error::TomlSerialize)
+}
+
+/// Converts a TOML string, in the [schema](Toml#schema) of [`Toml`], into a `BEMBlock`.
+///
+/// # Arguments
+///
+/// * `toml_str`: &str - The TOML string to be converted to a `BEMBlock`.
+///
+/// # Returns
+///
+/// * `Result<BEMBlock>` - A result containing the `BEMBlock`, or an [`Error::TomlDeserialize`] if
+///   the TOML is malformed or does not describe a block.
+///
+/// # Invariants
+///
+/// For every block `x`, `from_toml(&to_toml(&x)?)` returns `x`.
+///
+/// # Examples
+///
+/// ```
+/// use bem::from_toml;
+///
+/// let bem_block = from_toml("name = \"media-player\"\nmodifiers = []\n\n[[elements]]\nname = \"button\"\nmodifiers = [\"rewind\"]").unwrap();
+///
+/// assert_eq!(bem_block.elements[0].modifiers, vec!["rewind"]);
+/// ```
+pub fn from_toml(toml_str: &str) -> Result<BEMBlock> {
+	toml::from_str(toml_str).map_err(Error::TomlDeserialize)
+}
+
+/// The TOML representation of blocks, as written by [`to_toml`] and read by [`from_toml`].
+///
+/// # Schema
+///
+/// A block is a table with a `name` string and a `modifiers` array of strings. Its elements
+/// form an array of tables, each with its own `name` and `modifiers`:
+///
+/// ```toml
+/// name = "media-player"
+/// modifiers = ["dark"]
+///
+/// [[elements]]
+/// name = "button"
+/// modifiers = ["fast-forward", "rewind"]
+///
+/// [[elements]]
+/// name = "timeline"
+/// modifiers = []
+/// ```
+///
+/// A block without elements has an empty `elements = []` array instead. Every key is required.
+pub struct Toml;
+
+impl BemFormat for Toml {
+	type Error = Error;
+
+	fn serialize(block: &BEMBlock) -> Result<String> {
+		to_toml(block)
+	}
+
+	fn deserialize(s: &str) -> Result<BEMBlock> {
+		from_toml(s)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ from_toml, to_toml, Toml };
+	use crate::generate::{ generate, GenerateConfig };
+	use crate::{ decode, encode, parse };
+
+	#[test]
+	fn test_round_trip() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let toml = to_toml(&bem_block).unwrap();
+
+		insta::assert_snapshot!(toml);
+		assert_eq!(from_toml(&toml).unwrap(), bem_block);
+		assert_eq!(decode::<Toml>(&encode::<Toml>(&bem_block).unwrap()).unwrap(), bem_block);
+	}
+
+	#[test]
+	fn test_round_trip_generated_blocks() {
+		for seed in 0..64 {
+			let bem_block = generate(seed, &GenerateConfig::default());
+
+			assert_eq!(from_toml(&to_toml(&bem_block).unwrap()).unwrap(), bem_block, "seed {}", seed);
+		}
+
+		let bare = parse("media-player").unwrap();
+
+		assert_eq!(to_toml(&bare).unwrap(), "name = \"media-player\"\nmodifiers = []\nelements = []\n");
+		assert_eq!(from_toml(&to_toml(&bare).unwrap()).unwrap(), bare);
+	}
+
+	#[test]
+	fn test_invalid_toml() {
+		let missing = from_toml("name = \"media-player\"").unwrap_err();
+		let malformed = from_toml("name = ").unwrap_err();
+
+		assert_eq!(missing.code(), "toml");
+		assert!(missing.to_string().starts_with("invalid TOML: "));
+		assert!(missing.to_string().contains("missing field `modifiers`"));
+		assert_eq!(malformed.code(), "toml");
+	}
+}
@@ -0,0 +1,275 @@
+//! This module generates configuration for third-party CSS tooling, so the BEM structure stays
+//! the single source of truth for which class names are allowed.
+
+use serde_json::json;
+use crate::models::BEMBlock;
+use crate::naming::{ regex_escape, BemConfig, NamingScheme };
+
+/// The regex matching a single BEM name, mirroring the `name` rule of the `.bem` grammar.
+const NAME_PATTERN: &str = "[a-z](?:-?[a-z0-9])*";
+
+/// Selects what [`to_purgecss_safelist`] lists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SafelistMode {
+	/// Every class the blocks produce, as literal strings.
+	#[default]
+	Exact,
+	/// One anchored regex per block, matching any element or modifier class of that block.
+	Pattern,
+}
+
+/// Generates a safelist for PurgeCSS (or any CSS minifier with a class safelist) that keeps the
+/// classes of the given blocks from being purged.
+///
+/// In [`SafelistMode::Exact`] mode the safelist is a JSON array of every class the blocks
+/// produce with the separators of `config`. In [`SafelistMode::Pattern`] mode it is a JSON array of regex sources, one per block,
+/// such as `^media-player(__[a-z0-9-]+)?(--[a-z0-9-]+)?$`, suitable for `safelist.standard`
+/// once passed to `new RegExp`. Patterns also cover classes composed at runtime that the
+/// blocks don't list. Block names are escaped, so regex metacharacters in them are matched
+/// literally.
+///
+/// # Arguments
+///
+/// * `blocks`: &[BEMBlock] - The blocks whose classes must be kept.
+/// * `mode`: SafelistMode - Whether to list exact classes or per-block patterns.
+/// * `config`: &BemConfig - The separators the classes and patterns are composed with.
+///
+/// # Returns
+///
+/// * `String` - The safelist as a pretty-printed JSON array.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_purgecss_safelist, BemConfig, SafelistMode };
+///
+/// let blocks = vec![parse("media-player[dark]").unwrap()];
+/// let safelist = to_purgecss_safelist(&blocks, SafelistMode::Exact, &BemConfig::default());
+///
+/// assert_eq!(safelist, "[\n  \"media-player\",\n  \"media-player--dark\"\n]");
+/// ```
+pub fn to_purgecss_safelist(blocks: &[BEMBlock], mode: SafelistMode, config: &BemConfig) -> String {
+	let mut entries: Vec<String> = Vec::new();
+
+	for bem_block in blocks {
+		let block_entries = match mode {
+			SafelistMode::Exact => config.class_names(bem_block),
+			SafelistMode::Pattern =>
+				vec![
+					format!(
+						"^{}({}[a-z0-9-]+)?({}[a-z0-9-]+)?$",
+						regex_escape(&bem_block.name),
+						regex_escape(&config.element_separator),
+						regex_escape(&config.modifier_separator)
+					)
+				],
+		};
+
+		for entry in block_entries {
+			if !entries.contains(&entry) {
+				entries.push(entry);
+			}
+		}
+	}
+
+	serde_json::to_string_pretty(&entries).expect("serializing strings never fails")
+}
+
+/// Generates a stylelint configuration enforcing BEM class names.
+///
+/// The configuration sets stylelint's `selector-class-pattern` rule. Without blocks, the
+/// pattern accepts any class composed of BEM names joined by the scheme's separators, e.g.
+/// `^[a-z](?:-?[a-z0-9])*(?:__[a-z](?:-?[a-z0-9])*)?(?:--[a-z](?:-?[a-z0-9])*)?$`. With blocks,
+/// the pattern is an explicit allow-list: an alternation of every class the blocks produce, so
+/// unknown blocks, elements, and modifiers are rejected as well. Separators and names are
+/// escaped, so regex metacharacters in them are matched literally.
+///
+/// # Arguments
+///
+/// * `blocks`: &[BEMBlock] - The known blocks. Pass an empty slice to only enforce the naming scheme.
+/// * `scheme`: &NamingScheme - The separators used to compose class names.
+///
+/// # Returns
+///
+/// * `String` - The pretty-printed JSON configuration.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_stylelint_config, NamingScheme };
+///
+/// let blocks = vec![parse("media-player[dark]").unwrap()];
+/// let config = to_stylelint_config(&blocks, &NamingScheme::default());
+///
+/// assert!(config.contains("^(?:media-player|media-player--dark)$"));
+/// ```
+pub fn to_stylelint_config(blocks: &[BEMBlock], scheme: &NamingScheme) -> String {
+	let (pattern, message) = if blocks.is_empty() {
+		let pattern = format!(
+			"^{name}(?:{element_separator}{name})?(?:{modifier_separator}{name})?$",
+			name = NAME_PATTERN,
+			element_separator = regex_escape(&scheme.element_separator),
+			modifier_separator = regex_escape(&scheme.modifier_separator)
+		);
+
+		let message = format!(
+			"Expected class \"%s\" to follow the block{}element{}modifier convention",
+			scheme.element_separator,
+			scheme.modifier_separator
+		);
+
+		(pattern, message)
+	} else {
+		let mut classes: Vec<String> = Vec::new();
+
+		for bem_block in blocks {
+			for class in scheme.class_names(bem_block) {
+				if !classes.contains(&class) {
+					classes.push(class);
+				}
+			}
+		}
+
+		let alternatives: Vec<String> = classes
+			.iter()
+			.map(|class| regex_escape(class))
+			.collect();
+
+		let message = "Expected class \"%s\" to be a known BEM class".to_string();
+
+		(format!("^(?:{})$", alternatives.join("|")), message)
+	};
+
+	let config = json!({
+		"rules": {
+			"selector-class-pattern": [pattern, { "message": message }]
+		}
+	});
+
+	serde_json::to_string_pretty(&config).expect("serializing a JSON value never fails")
+}
+
+#[cfg(test)]
+mod tests {
+	use regex::Regex;
+	use crate::models::BEMBlock;
+	use crate::naming::NamingScheme;
+	use crate::parse;
+
+	fn fixture() -> Vec<BEMBlock> {
+		vec![
+			parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap(),
+			parse("card[featured]\ntitle").unwrap()
+		]
+	}
+
+	fn class_pattern(config: &str) -> Regex {
+		let config: serde_json::Value = serde_json::from_str(config).unwrap();
+		let pattern = config["rules"]["selector-class-pattern"][0].as_str().unwrap();
+
+		Regex::new(pattern).unwrap()
+	}
+
+	fn safelist(blocks: &[BEMBlock], mode: super::SafelistMode) -> Vec<String> {
+		serde_json::from_str(&super::to_purgecss_safelist(blocks, mode, &NamingScheme::default())).unwrap()
+	}
+
+	#[test]
+	fn test_to_purgecss_safelist_exact() {
+		let classes = safelist(&fixture(), super::SafelistMode::Exact);
+		let scheme = NamingScheme::default();
+		let expected: Vec<String> = fixture()
+			.iter()
+			.flat_map(|bem_block| scheme.class_names(bem_block))
+			.collect();
+
+		assert_eq!(classes.len(), 9);
+		assert_eq!(classes, expected);
+	}
+
+	#[test]
+	fn test_to_purgecss_safelist_pattern() {
+		let patterns = safelist(&fixture(), super::SafelistMode::Pattern);
+		let regexes: Vec<Regex> = patterns
+			.iter()
+			.map(|pattern| Regex::new(pattern).unwrap())
+			.collect();
+
+		assert_eq!(patterns, vec![
+			"^media-player(__[a-z0-9-]+)?(--[a-z0-9-]+)?$",
+			"^card(__[a-z0-9-]+)?(--[a-z0-9-]+)?$"
+		]);
+
+		for class in safelist(&fixture(), super::SafelistMode::Exact) {
+			assert!(regexes.iter().any(|regex| regex.is_match(&class)), "{} is not safelisted", class);
+		}
+
+		assert!(!regexes.iter().any(|regex| regex.is_match("media-player-x")));
+		assert!(!regexes.iter().any(|regex| regex.is_match("button")));
+	}
+
+	#[test]
+	fn test_to_purgecss_safelist_with_config() {
+		let blocks = vec![parse("media-player[dark]\nbutton").unwrap()];
+		let config = crate::BemConfig { element_separator: "_".to_string(), modifier_separator: "-".to_string() };
+		let exact: Vec<String> = serde_json::from_str(&super::to_purgecss_safelist(&blocks, super::SafelistMode::Exact, &config)).unwrap();
+		let pattern: Vec<String> = serde_json::from_str(&super::to_purgecss_safelist(&blocks, super::SafelistMode::Pattern, &config)).unwrap();
+
+		assert_eq!(exact, vec!["media-player", "media-player-dark", "media-player_button"]);
+		assert_eq!(pattern, vec!["^media-player(_[a-z0-9-]+)?(-[a-z0-9-]+)?$"]);
+	}
+
+	#[test]
+	fn test_to_purgecss_safelist_pattern_escapes_names() {
+		let bem_block = BEMBlock {
+			name: "a.b".to_string(),
+			modifiers: vec![],
+			elements: vec![].into(),
+		};
+		let patterns = safelist(&[bem_block], super::SafelistMode::Pattern);
+		let regex = Regex::new(&patterns[0]).unwrap();
+
+		assert!(regex.is_match("a.b--dark"));
+		assert!(!regex.is_match("axb--dark"));
+	}
+
+	#[test]
+	fn test_to_stylelint_config() {
+		insta::assert_snapshot!(super::to_stylelint_config(&fixture(), &NamingScheme::default()));
+	}
+
+	#[test]
+	fn test_to_stylelint_config_allow_list() {
+		let scheme = NamingScheme::default();
+		let pattern = class_pattern(&super::to_stylelint_config(&fixture(), &scheme));
+
+		for bem_block in fixture() {
+			for class in scheme.class_names(&bem_block) {
+				assert!(pattern.is_match(&class), "{} should match {}", class, pattern);
+			}
+		}
+
+		assert!(!pattern.is_match("media-player--light"));
+		assert!(!pattern.is_match("media-player__button__icon"));
+		assert!(!pattern.is_match("xmedia-player"));
+	}
+
+	#[test]
+	fn test_to_stylelint_config_scheme_pattern() {
+		let scheme = NamingScheme {
+			element_separator: ".".to_string(),
+			modifier_separator: "+".to_string(),
+		};
+		let pattern = class_pattern(&super::to_stylelint_config(&[], &scheme));
+
+		for bem_block in fixture() {
+			for class in scheme.class_names(&bem_block) {
+				assert!(pattern.is_match(&class), "{} should match {}", class, pattern);
+			}
+		}
+
+		assert!(!pattern.is_match("media-player__button"));
+		assert!(!pattern.is_match("media-player:button"));
+		assert!(!pattern.is_match("Media-player"));
+	}
+}
@@ -0,0 +1,273 @@
+//! This module counts how often the entities of a design system are used across the markup,
+//! stylesheets, and component code of a project, combining the scanners of this crate into a
+//! single report.
+
+use std::collections::HashMap;
+use std::fmt;
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use crate::css::scan_selectors;
+use crate::error::Error;
+use crate::js::js_classes;
+use crate::models::BEMBlock;
+use crate::path::BEMPath;
+
+/// The kinds of sources [`usage_report`] counts separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+	/// Markup, whose `class` attributes are scanned.
+	Html,
+	/// Stylesheets, whose selectors are scanned.
+	Css,
+	/// JavaScript or TypeScript, whose string literals are scanned like [`scan_js`](crate::scan_js) does.
+	Js,
+}
+
+impl fmt::Display for SourceKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			SourceKind::Html => write!(f, "html"),
+			SourceKind::Css => write!(f, "css"),
+			SourceKind::Js => write!(f, "js"),
+		}
+	}
+}
+
+/// The classes found in one source file, with the kind of the source.
+///
+/// # Example
+///
+/// ```
+/// use bem::{ ScanSource, SourceKind };
+///
+/// let html = ScanSource::html("<div class=\"card card--active\"><h2 class='card__title'></h2></div>");
+/// let css = ScanSource::css(".card { }\n.card--active .card__title { }");
+///
+/// assert_eq!(html.classes, vec!["card", "card--active", "card__title"]);
+/// assert_eq!(css.kind, SourceKind::Css);
+/// assert_eq!(css.classes, vec!["card", "card--active", "card__title"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanSource {
+	/// The kind of the source.
+	pub kind: SourceKind,
+	/// Every class found, in source order and with repetitions.
+	pub classes: Vec<String>,
+}
+
+impl ScanSource {
+	/// Scans the `class` attributes of markup, quoted or not. Comments are skipped.
+	pub fn html(markup: &str) -> ScanSource {
+		let comment = Regex::new(r"(?s)<!--.*?-->").expect("the comment pattern is valid");
+		let attribute = Regex::new(r#"(?i)[\s<]class\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+))"#)
+			.expect("the attribute pattern is valid");
+		let markup = comment.replace_all(markup, "");
+		let classes = attribute
+			.captures_iter(&markup)
+			.filter_map(|captures| captures.get(1).or(captures.get(2)).or(captures.get(3)))
+			.flat_map(|value| value.as_str().split_whitespace().map(str::to_string).collect::<Vec<_>>())
+			.collect();
+
+		ScanSource { kind: SourceKind::Html, classes }
+	}
+
+	/// Scans the selectors of a stylesheet, counting a class once per selector it appears in.
+	pub fn css(css: &str) -> ScanSource {
+		let classes = scan_selectors(css)
+			.into_iter()
+			.flat_map(|selector| selector.compounds.into_iter().flatten())
+			.collect();
+
+		ScanSource { kind: SourceKind::Css, classes }
+	}
+
+	/// Scans the string literals of JavaScript or TypeScript, see [`scan_js`](crate::scan_js).
+	/// Template literals with interpolation are skipped.
+	pub fn js(source: &str) -> Result<ScanSource, Error> {
+		let (classes, _) = js_classes(source)?;
+
+		Ok(ScanSource { kind: SourceKind::Js, classes })
+	}
+}
+
+/// How often one entity of the schema is used, see [`usage_report`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageRow {
+	/// The entity.
+	pub path: BEMPath,
+	/// The number of uses in markup.
+	pub html: usize,
+	/// The number of uses in stylesheets.
+	pub css: usize,
+	/// The number of uses in JavaScript or TypeScript.
+	pub js: usize,
+	/// The number of uses in every source.
+	pub total: usize,
+}
+
+/// How often every entity of a schema is used, produced by [`usage_report`].
+///
+/// Serialized as a JSON object with one row per entity, e.g.
+/// `{"rows":[{"path":"card","html":2,"css":1,"js":0,"total":3}]}`, or as CSV with
+/// [`to_csv`](UsageReport::to_csv).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageReport {
+	/// One row per entity of the schema, in the order of
+	/// [`NamingScheme::class_names`](crate::NamingScheme::class_names), including unused entities.
+	pub rows: Vec<UsageRow>,
+}
+
+impl UsageReport {
+	/// Returns the row of `path`, if it is an entity of the schema.
+	pub fn get(&self, path: &BEMPath) -> Option<&UsageRow> {
+		self.rows.iter().find(|row| row.path == *path)
+	}
+
+	/// Returns the entities no source uses.
+	pub fn unused(&self) -> Vec<&BEMPath> {
+		self.rows.iter().filter(|row| row.total == 0).map(|row| &row.path).collect()
+	}
+
+	/// Writes the report as CSV, with a `path,html,css,js,total` header and one line per row.
+	pub fn to_csv(&self) -> String {
+		let mut csv = String::from("path,html,css,js,total\n");
+
+		for row in &self.rows {
+			csv.push_str(&format!("{},{},{},{},{}\n", row.path, row.html, row.css, row.js, row.total));
+		}
+
+		csv
+	}
+}
+
+/// Counts the uses of every block, element, and modifier of a schema across scanned sources,
+/// answering how hot each entity is.
+///
+/// Every class of every source that names an entity of the schema counts as one use of it, per
+/// kind of source. Classes naming anything else are ignored. Entities that are never used get a
+/// row of zeros, so they can be found and retired.
+///
+/// # Arguments
+///
+/// * `schema`: &[BEMBlock] - The blocks of the design system.
+/// * `sources`: &[ScanSource] - The scanned sources of the project.
+///
+/// # Returns
+///
+/// * `UsageReport` - One row per entity of the schema.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, usage_report, BEMPath, ScanSource };
+///
+/// let schema = vec![parse("card[active]\ntitle").unwrap()];
+/// let sources = vec![
+///     ScanSource::html("<div class=\"card\"></div><div class=\"card card--active\"></div>"),
+///     ScanSource::css(".card { }"),
+/// ];
+/// let report = usage_report(&schema, &sources);
+///
+/// assert_eq!(report.get(&BEMPath::new("card")).unwrap().total, 3);
+/// assert_eq!(report.unused(), vec![&BEMPath::new("card").with_element("title")]);
+/// assert!(report.to_csv().starts_with("path,html,css,js,total\ncard,2,1,0,3\n"));
+/// ```
+pub fn usage_report(schema: &[BEMBlock], sources: &[ScanSource]) -> UsageReport {
+	let mut counts: HashMap<(&str, SourceKind), usize> = HashMap::new();
+
+	for source in sources {
+		for class in &source.classes {
+			*counts.entry((class.as_str(), source.kind)).or_insert(0) += 1;
+		}
+	}
+
+	let mut rows: Vec<UsageRow> = Vec::new();
+
+	for bem_block in schema {
+		let block = BEMPath::new(&bem_block.name);
+		let mut paths = vec![block.clone()];
+
+		paths.extend(bem_block.modifiers.iter().map(|modifier| block.clone().with_modifier(&modifier.class_name())));
+
+		for element in &bem_block.elements {
+			let element_path = block.clone().with_element(&element.name);
+
+			paths.push(element_path.clone());
+			paths.extend(element.modifiers.iter().map(|modifier| element_path.clone().with_modifier(&modifier.class_name())));
+		}
+
+		for path in paths {
+			if rows.iter().any(|row| row.path == path) {
+				continue;
+			}
+
+			let class = path.to_string();
+			let count = |kind| counts.get(&(class.as_str(), kind)).copied().unwrap_or(0);
+			let (html, css, js) = (count(SourceKind::Html), count(SourceKind::Css), count(SourceKind::Js));
+
+			rows.push(UsageRow { path, html, css, js, total: html + css + js });
+		}
+	}
+
+	UsageReport { rows }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ usage_report, ScanSource, SourceKind, UsageReport };
+	use crate::path::BEMPath;
+	use crate::parse;
+
+	const PAGE: &str = include_str!("../fixtures/usage/index.html");
+	const STYLES: &str = include_str!("../fixtures/usage/styles.css");
+	const COMPONENT: &str = include_str!("../fixtures/usage/Card.tsx");
+
+	fn report() -> UsageReport {
+		let schema = vec![parse("card[active,compact]\ntitle[large]\nbody").unwrap(), parse("badge").unwrap()];
+		let sources = vec![ScanSource::html(PAGE), ScanSource::css(STYLES), ScanSource::js(COMPONENT).unwrap()];
+
+		usage_report(&schema, &sources)
+	}
+
+	#[test]
+	fn test_scan_sources() {
+		assert_eq!(ScanSource::html(PAGE).classes, vec!["card", "card--active", "card__title", "card", "card__body", "page"]);
+		assert_eq!(ScanSource::css(STYLES).classes, vec!["card", "card", "card--active", "card__title", "card__title", "card__title--large", "page"]);
+		assert_eq!(ScanSource::js(COMPONENT).unwrap().classes, vec!["card", "card--active", "card__title"]);
+		assert_eq!(ScanSource::html("<p class=card>").classes, vec!["card"]);
+		assert!(ScanSource::js("const a = \"card").is_err());
+	}
+
+	#[test]
+	fn test_usage_report() {
+		let report = report();
+		let counts: Vec<(String, usize, usize, usize, usize)> = report.rows
+			.iter()
+			.map(|row| (row.path.to_string(), row.html, row.css, row.js, row.total))
+			.collect();
+
+		assert_eq!(counts, vec![
+			("card".to_string(), 2, 2, 1, 5),
+			("card--active".to_string(), 1, 1, 1, 3),
+			("card--compact".to_string(), 0, 0, 0, 0),
+			("card__title".to_string(), 1, 2, 1, 4),
+			("card__title--large".to_string(), 0, 1, 0, 1),
+			("card__body".to_string(), 1, 0, 0, 1),
+			("badge".to_string(), 0, 0, 0, 0),
+		]);
+		assert_eq!(report.unused(), vec![&BEMPath::new("card").with_modifier("compact"), &BEMPath::new("badge")]);
+		assert!(report.get(&BEMPath::new("page")).is_none());
+	}
+
+	#[test]
+	fn test_usage_report_serialization() {
+		let report = report();
+		let json = serde_json::to_value(&report).unwrap();
+
+		assert_eq!(json["rows"][1], serde_json::json!({ "path": "card--active", "html": 1, "css": 1, "js": 1, "total": 3 }));
+		assert_eq!(serde_json::from_value::<UsageReport>(json).unwrap(), report);
+		insta::assert_snapshot!(report.to_csv());
+		assert_eq!(serde_json::to_string(&SourceKind::Html).unwrap(), "\"html\"");
+	}
+}
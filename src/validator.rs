@@ -0,0 +1,363 @@
+//! This module checks BEM structures against the BEM naming conventions. The grammar already
+//! rejects most malformed names, but blocks built by hand or deserialized from JSON can
+//! contain anything, so the same rules are enforced here on the data model.
+
+use std::fmt;
+use regex::Regex;
+use crate::length::{ abbreviate_for_display, LengthMode, DISPLAY_LEN };
+use crate::models::{ BEMBlock, BEMModifier };
+use crate::naming::BemConfig;
+
+/// The naming rule a name violates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+	/// The name is empty.
+	Empty,
+	/// The name contains uppercase letters.
+	Uppercase,
+	/// The name contains two or more dashes in a row.
+	ConsecutiveDashes,
+	/// The name starts with a dash.
+	LeadingDash,
+	/// The name ends with a dash.
+	TrailingDash,
+	/// The name is longer than [`ValidationOptions::max_name_length`], counted in `mode`.
+	TooLong {
+		len: usize,
+		max: usize,
+		mode: LengthMode,
+	},
+	/// The name contains a separator of the [`BemConfig`](crate::BemConfig) its classes are
+	/// composed with, see [`parse_with_config`](crate::parse_with_config).
+	ContainsSeparator,
+	/// The name does not match `^[a-z][a-z0-9-]*$`, e.g. a numeric modifier value, see
+	/// [`parse_strict`](crate::parse_strict).
+	NotStrict,
+}
+
+impl fmt::Display for ValidationErrorKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let description = match self {
+			ValidationErrorKind::Empty => "is empty",
+			ValidationErrorKind::Uppercase => "contains uppercase letters",
+			ValidationErrorKind::ConsecutiveDashes => "contains consecutive dashes",
+			ValidationErrorKind::LeadingDash => "starts with a dash",
+			ValidationErrorKind::TrailingDash => "ends with a dash",
+			ValidationErrorKind::ContainsSeparator => "contains a separator of the naming scheme",
+			ValidationErrorKind::NotStrict => "does not match ^[a-z][a-z0-9-]*$",
+			ValidationErrorKind::TooLong { len, max, mode } => {
+				return write!(f, "is {} {} long, the maximum is {}", len, mode.unit(), max);
+			}
+		};
+
+		write!(f, "{}", description)
+	}
+}
+
+/// A single naming convention violation found by [`validate`].
+///
+/// `location` is the BEM class the offending name belongs to, e.g. `media-player__button--Rewind`
+/// for the `Rewind` modifier of the `button` element, so violations can be traced back to the
+/// entity that caused them. When displayed, long locations and names are abbreviated after 48
+/// grapheme clusters, never within a character or cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+	pub location: String,
+	pub name: String,
+	pub kind: ValidationErrorKind,
+}
+
+impl fmt::Display for ValidationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}: \"{}\" {}",
+			abbreviate_for_display(&self.location, DISPLAY_LEN),
+			abbreviate_for_display(&self.name, DISPLAY_LEN),
+			self.kind
+		)
+	}
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks every name in a `BEMBlock` against the BEM naming conventions.
+///
+/// Block names, element names, and modifiers must not be empty, must not contain uppercase
+/// letters, must not contain consecutive dashes, and must not start or end with a dash.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to validate.
+///
+/// # Returns
+///
+/// * `Vec<ValidationError>` - Every violation found, in document order. An empty vector means the block is valid.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ BEMBlock, validate };
+///
+/// let bem_block = BEMBlock::new("Media--Player");
+/// let errors = validate(&bem_block);
+///
+/// assert_eq!(errors.len(), 2);
+/// ```
+pub fn validate(bem_block: &BEMBlock) -> Vec<ValidationError> {
+	validate_with_options(bem_block, &ValidationOptions::default())
+}
+
+/// Optional rules of [`validate_with_options`], on top of the naming conventions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationOptions {
+	/// The longest name allowed, if any, counted in `length_mode`.
+	pub max_name_length: Option<usize>,
+	/// How name lengths are counted. Characters by default.
+	pub length_mode: LengthMode,
+}
+
+/// Checks every name in a `BEMBlock` like [`validate`], and against the optional rules of
+/// `options`.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to validate.
+/// * `options`: &ValidationOptions - The optional rules to check.
+///
+/// # Returns
+///
+/// * `Vec<ValidationError>` - Every violation found, in document order.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ validate_with_options, BEMBlock, LengthMode, ValidationErrorKind, ValidationOptions };
+///
+/// let bem_block = BEMBlock::new("cafe\u{301}");
+/// let options = |length_mode| ValidationOptions { max_name_length: Some(4), length_mode };
+///
+/// assert!(validate_with_options(&bem_block, &options(LengthMode::Graphemes)).is_empty());
+/// assert_eq!(
+///     validate_with_options(&bem_block, &options(LengthMode::Chars))[0].kind,
+///     ValidationErrorKind::TooLong { len: 5, max: 4, mode: LengthMode::Chars }
+/// );
+/// ```
+pub fn validate_with_options(bem_block: &BEMBlock, options: &ValidationOptions) -> Vec<ValidationError> {
+	let mut errors = Vec::new();
+
+	check_name(&bem_block.name, &bem_block.name, options, &mut errors);
+
+	for modifier in &bem_block.modifiers {
+		check_name(&format!("{}--{}", bem_block.name, modifier.class_name()), &modifier.class_name(), options, &mut errors);
+	}
+
+	for element in &bem_block.elements {
+		let element_class = format!("{}__{}", bem_block.name, element.name);
+
+		check_name(&element_class, &element.name, options, &mut errors);
+
+		for modifier in &element.modifiers {
+			check_name(&format!("{}--{}", element_class, modifier.class_name()), &modifier.class_name(), options, &mut errors);
+		}
+	}
+
+	errors
+}
+
+/// Returns every naming rule `name` violates, in a fixed order.
+pub(crate) fn naming_violations(name: &str) -> Vec<ValidationErrorKind> {
+	if name.is_empty() {
+		return vec![ValidationErrorKind::Empty];
+	}
+
+	let mut kinds = Vec::new();
+
+	if name.chars().any(char::is_uppercase) {
+		kinds.push(ValidationErrorKind::Uppercase);
+	}
+	if name.contains("--") {
+		kinds.push(ValidationErrorKind::ConsecutiveDashes);
+	}
+	if name.starts_with('-') {
+		kinds.push(ValidationErrorKind::LeadingDash);
+	}
+	if name.ends_with('-') {
+		kinds.push(ValidationErrorKind::TrailingDash);
+	}
+
+	kinds
+}
+
+/// Returns a violation for every name of `bem_block` that contains a separator of `config`,
+/// which would make its classes ambiguous, in document order.
+pub(crate) fn separator_violations(bem_block: &BEMBlock, config: &BemConfig) -> Vec<ValidationError> {
+	let separators = [config.element_separator.as_str(), config.modifier_separator.as_str()];
+	let mut errors = Vec::new();
+	let mut check = |location: String, name: &str| {
+		if separators.iter().any(|separator| !separator.is_empty() && name.contains(separator)) {
+			errors.push(ValidationError { location, name: name.to_string(), kind: ValidationErrorKind::ContainsSeparator });
+		}
+	};
+
+	check(bem_block.name.clone(), &bem_block.name);
+
+	for modifier in &bem_block.modifiers {
+		check(config.modifier_class(&bem_block.name, &modifier.class_name()), &modifier.class_name());
+	}
+
+	for element in &bem_block.elements {
+		let element_class = config.element_class(&bem_block.name, &element.name);
+
+		check(element_class.clone(), &element.name);
+
+		for modifier in &element.modifiers {
+			check(config.modifier_class(&element_class, &modifier.class_name()), &modifier.class_name());
+		}
+	}
+
+	errors
+}
+
+/// Returns a violation for every name, modifier name, and modifier value of `bem_block` that
+/// does not match `^[a-z][a-z0-9-]*$`, in document order.
+pub(crate) fn strict_violations(bem_block: &BEMBlock) -> Vec<ValidationError> {
+	let pattern = Regex::new("^[a-z][a-z0-9-]*$").expect("the strict pattern is valid");
+	let mut tokens = vec![(bem_block.name.clone(), bem_block.name.clone())];
+	let modifier_tokens = |base_class: &str, modifier: &BEMModifier| {
+		let location = format!("{}--{}", base_class, modifier.class_name());
+
+		std::iter::once(modifier.name.clone())
+			.chain(modifier.value.clone())
+			.map(move |name| (location.clone(), name))
+	};
+
+	for modifier in &bem_block.modifiers {
+		tokens.extend(modifier_tokens(&bem_block.name, modifier));
+	}
+
+	for element in &bem_block.elements {
+		let element_class = format!("{}__{}", bem_block.name, element.name);
+
+		tokens.push((element_class.clone(), element.name.clone()));
+
+		for modifier in &element.modifiers {
+			tokens.extend(modifier_tokens(&element_class, modifier));
+		}
+	}
+
+	tokens
+		.into_iter()
+		.filter(|(_, name)| !pattern.is_match(name))
+		.map(|(location, name)| ValidationError { location, name, kind: ValidationErrorKind::NotStrict })
+		.collect()
+}
+
+fn check_name(location: &str, name: &str, options: &ValidationOptions, errors: &mut Vec<ValidationError>) {
+	let mut kinds = naming_violations(name);
+	let len = options.length_mode.len(name);
+
+	if let Some(max) = options.max_name_length.filter(|max| len > *max) {
+		kinds.push(ValidationErrorKind::TooLong { len, max, mode: options.length_mode });
+	}
+
+	for kind in kinds {
+		errors.push(ValidationError {
+			location: location.to_string(),
+			name: name.to_string(),
+			kind,
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ ValidationError, ValidationErrorKind, ValidationOptions };
+	use crate::length::LengthMode;
+	use crate::models::{ BEMBlock, BEMElement };
+
+	#[test]
+	fn test_validate_valid_block() {
+		let bem_block = crate::parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+
+		assert!(super::validate(&bem_block).is_empty());
+	}
+
+	#[test]
+	fn test_validate_reports_every_violation() {
+		let bem_block = BEMBlock {
+			name: "Media-player".to_string(),
+			modifiers: vec!["-dark".into()],
+			elements: vec![BEMElement {
+				name: "play--button".to_string(),
+				modifiers: vec!["fast-".into(), "".into()],
+			}].into(),
+		};
+
+		assert_eq!(super::validate(&bem_block), vec![
+			ValidationError {
+				location: "Media-player".to_string(),
+				name: "Media-player".to_string(),
+				kind: ValidationErrorKind::Uppercase,
+			},
+			ValidationError {
+				location: "Media-player---dark".to_string(),
+				name: "-dark".to_string(),
+				kind: ValidationErrorKind::LeadingDash,
+			},
+			ValidationError {
+				location: "Media-player__play--button".to_string(),
+				name: "play--button".to_string(),
+				kind: ValidationErrorKind::ConsecutiveDashes,
+			},
+			ValidationError {
+				location: "Media-player__play--button--fast-".to_string(),
+				name: "fast-".to_string(),
+				kind: ValidationErrorKind::TrailingDash,
+			},
+			ValidationError {
+				location: "Media-player__play--button--".to_string(),
+				name: "".to_string(),
+				kind: ValidationErrorKind::Empty,
+			}
+		]);
+	}
+
+	#[test]
+	fn test_validation_error_display() {
+		let error = ValidationError {
+			location: "media-player__button--Rewind".to_string(),
+			name: "Rewind".to_string(),
+			kind: ValidationErrorKind::Uppercase,
+		};
+
+		assert_eq!(error.to_string(), "media-player__button--Rewind: \"Rewind\" contains uppercase letters");
+	}
+
+	#[test]
+	fn test_max_name_length_in_every_mode() {
+		// A combining accent, and a family emoji joined by zero-width joiners.
+		let name = "ve\u{301}lo-\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+		let bem_block = BEMBlock { name: "nav".into(), modifiers: vec![name.into()], elements: vec![].into() };
+		let too_long = |max: usize, length_mode: LengthMode| -> Option<ValidationErrorKind> {
+			let options = ValidationOptions { max_name_length: Some(max), length_mode };
+
+			super::validate_with_options(&bem_block, &options).first().map(|error| error.kind)
+		};
+
+		assert_eq!(too_long(6, LengthMode::Graphemes), None);
+		assert_eq!(too_long(5, LengthMode::Graphemes), Some(ValidationErrorKind::TooLong { len: 6, max: 5, mode: LengthMode::Graphemes }));
+		assert_eq!(too_long(10, LengthMode::Chars), Some(ValidationErrorKind::TooLong { len: 11, max: 10, mode: LengthMode::Chars }));
+		assert_eq!(too_long(20, LengthMode::Bytes), Some(ValidationErrorKind::TooLong { len: 25, max: 20, mode: LengthMode::Bytes }));
+		assert!(super::validate(&bem_block).is_empty());
+	}
+
+	#[test]
+	fn test_display_abbreviates_long_names_safely() {
+		let name = format!("{}e\u{301}\u{1F1EB}\u{1F1F7}", "a".repeat(47));
+		let error = ValidationError { location: name.clone(), name, kind: ValidationErrorKind::TooLong { len: 51, max: 40, mode: LengthMode::Chars } };
+		let abbreviated = format!("{}e\u{301}…", "a".repeat(47));
+
+		assert_eq!(error.to_string(), format!("{0}: \"{0}\" is 51 characters long, the maximum is 40", abbreviated));
+	}
+}
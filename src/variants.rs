@@ -0,0 +1,190 @@
+//! This module derives variants of BEM blocks whose modifiers are scoped to a context, such as
+//! a responsive breakpoint or a theme, so existing generators can emit the scoped classes.
+
+use std::collections::HashMap;
+use crate::models::{ BEMBlock, BEMElement, BEMModifier };
+
+/// A responsive breakpoint, active from `min_width` pixels upwards.
+///
+/// # Example
+///
+/// ```
+/// use bem::Breakpoint;
+///
+/// let breakpoint = Breakpoint { name: "desktop".to_string(), min_width: 1024 };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakpoint {
+	pub name: String,
+	pub min_width: u32,
+}
+
+impl BEMBlock {
+	/// Returns one variant of the block per breakpoint, with every modifier of the block and its
+	/// elements scoped to that breakpoint as `{modifier}@{breakpoint}`, e.g. `dark@mobile`.
+	///
+	/// Block and element names are unchanged, so the variants can be passed to any generator to
+	/// produce the breakpoint-specific classes (`media-player--dark@mobile`), to be wrapped in a
+	/// `@media (min-width: ...)` query using the breakpoint's `min_width`.
+	///
+	/// # Arguments
+	///
+	/// * `breakpoints`: &[Breakpoint] - The breakpoints to derive variants for.
+	///
+	/// # Returns
+	///
+	/// * `Vec<BEMBlock>` - One block per breakpoint, in the order of `breakpoints`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::{ parse, Breakpoint };
+	///
+	/// let bem_block = parse("media-player[dark]").unwrap();
+	/// let variants = bem_block.to_bem_responsive_variants(&[Breakpoint { name: "mobile".to_string(), min_width: 0 }]);
+	///
+	/// assert_eq!(variants[0].modifiers, vec!["dark@mobile".to_string()]);
+	/// ```
+	pub fn to_bem_responsive_variants(&self, breakpoints: &[Breakpoint]) -> Vec<BEMBlock> {
+		breakpoints
+			.iter()
+			.map(|breakpoint| self.map_modifiers(|modifier| format!("{}@{}", modifier, breakpoint.name)))
+			.collect()
+	}
+
+	/// Returns one variant of the block per theme, with every modifier of the block and its
+	/// elements prefixed with the theme as `{theme}-{modifier}`, e.g. `brand-a-dark`.
+	///
+	/// Block and element names are unchanged, so the CSS generated for each variant can be
+	/// scoped under a theme class selector.
+	///
+	/// # Arguments
+	///
+	/// * `themes`: &[&str] - The names of the themes to derive variants for.
+	///
+	/// # Returns
+	///
+	/// * `HashMap<String, BEMBlock>` - The themed blocks, keyed by theme name.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use bem::parse;
+	///
+	/// let bem_block = parse("media-player[dark]").unwrap();
+	/// let variants = bem_block.to_bem_theme_variants(&["brand-a"]);
+	///
+	/// assert_eq!(variants["brand-a"].modifiers, vec!["brand-a-dark".to_string()]);
+	/// ```
+	pub fn to_bem_theme_variants(&self, themes: &[&str]) -> HashMap<String, BEMBlock> {
+		themes
+			.iter()
+			.map(|theme| (theme.to_string(), self.map_modifiers(|modifier| format!("{}-{}", theme, modifier))))
+			.collect()
+	}
+
+	/// Returns a copy of the block with `f` applied to the class form of the modifiers of the
+	/// block and its elements, e.g. `color_red`.
+	fn map_modifiers<F: Fn(&str) -> String>(&self, f: F) -> BEMBlock {
+		let map = |modifiers: &[BEMModifier]| -> Vec<BEMModifier> {
+			modifiers
+				.iter()
+				.map(|modifier| BEMModifier::new(f(&modifier.class_name())))
+				.collect()
+		};
+
+		BEMBlock {
+			name: self.name.clone(),
+			modifiers: map(&self.modifiers),
+			elements: self.elements
+				.iter()
+				.map(|element| BEMElement {
+					name: element.name.clone(),
+					modifiers: map(&element.modifiers),
+				})
+				.collect(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Breakpoint;
+	use crate::models::{ BEMBlock, BEMElement };
+	use crate::parse;
+
+	fn breakpoints() -> Vec<Breakpoint> {
+		vec![
+			Breakpoint {
+				name: "mobile".to_string(),
+				min_width: 0,
+			},
+			Breakpoint {
+				name: "desktop".to_string(),
+				min_width: 1024,
+			}
+		]
+	}
+
+	#[test]
+	fn test_to_bem_responsive_variants() {
+		let bem_block = parse("media-player[dark]").unwrap();
+		let variants = bem_block.to_bem_responsive_variants(&breakpoints());
+
+		assert_eq!(variants.len(), 2);
+		assert_eq!(variants[0].modifiers, vec!["dark@mobile".to_string()]);
+		assert_eq!(variants[1].modifiers, vec!["dark@desktop".to_string()]);
+	}
+
+	#[test]
+	fn test_to_bem_responsive_variants_scopes_element_modifiers() {
+		let bem_block = parse("media-player\nbutton[rewind]\ntimeline").unwrap();
+		let variants = bem_block.to_bem_responsive_variants(&breakpoints()[..1]);
+
+		assert_eq!(variants, vec![BEMBlock {
+			name: "media-player".to_string(),
+			modifiers: vec![],
+			elements: vec![
+				BEMElement {
+					name: "button".to_string(),
+					modifiers: vec!["rewind@mobile".into()],
+				},
+				BEMElement {
+					name: "timeline".to_string(),
+					modifiers: vec![],
+				}
+			].into(),
+		}]);
+	}
+
+	#[test]
+	fn test_to_bem_responsive_variants_without_breakpoints() {
+		assert!(parse("media-player[dark]").unwrap().to_bem_responsive_variants(&[]).is_empty());
+	}
+
+	#[test]
+	fn test_to_bem_theme_variants() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let variants = bem_block.to_bem_theme_variants(&["brand-a", "brand-b"]);
+
+		assert_eq!(variants.len(), 2);
+		assert_eq!(variants["brand-a"].modifiers, vec!["brand-a-dark".to_string()]);
+		assert_eq!(variants["brand-b"].elements[0].modifiers, vec![
+			"brand-b-fast-forward".to_string(),
+			"brand-b-rewind".to_string()
+		]);
+
+		for variant in variants.values() {
+			assert_eq!(variant.name, bem_block.name);
+			assert_eq!(
+				variant.elements.iter().map(|element| &element.name).collect::<Vec<_>>(),
+				bem_block.elements.iter().map(|element| &element.name).collect::<Vec<_>>()
+			);
+		}
+	}
+
+	#[test]
+	fn test_to_bem_theme_variants_without_themes() {
+		assert!(parse("media-player[dark]").unwrap().to_bem_theme_variants(&[]).is_empty());
+	}
+}
@@ -0,0 +1,112 @@
+//! This module reads and writes blocks as YAML, the configuration format of many web tooling
+//! pipelines. It is only compiled with the `yaml` feature.
+//!
+//! The YAML form mirrors the JSON form of [`to_json`](crate::to_json): a mapping with `name`,
+//! `modifiers`, and `elements`.
+
+use crate::error::{ Error, Result };
+use crate::format::BemFormat;
+use crate::models::BEMBlock;
+
+/// Converts a `BEMBlock` into YAML.
+///
+/// # Arguments
+///
+/// * `bem_block`: &BEMBlock - A reference to the `BEMBlock` to be converted to YAML.
+///
+/// # Returns
+///
+/// * `Result<String>` - A result containing the YAML string, or an [`Error::Yaml`] if there is a
+///   problem during serialization.
+///
+/// # Examples
+///
+/// ```
+/// use bem::{ parse, to_yaml };
+///
+/// let yaml = to_yaml(&parse("media-player[dark]\ntimeline").unwrap()).unwrap();
+///
+/// assert_eq!(yaml, "name: media-player\nmodifiers:\n- dark\nelements:\n- name: timeline\n  modifiers: []\n");
+/// ```
+pub fn to_yaml(bem_block: &BEMBlock) -> Result<String> {
+	serde_yaml::to_string(bem_block).map_err(Error::Yaml)
+}
+
+/// Converts a YAML string into a `BEMBlock`.
+///
+/// # Arguments
+///
+/// * `yaml`: &str - The YAML string to be converted to a `BEMBlock`.
+///
+/// # Returns
+///
+/// * `Result<BEMBlock>` - A result containing the `BEMBlock`, or an [`Error::Yaml`] if the YAML
+///   is malformed or does not describe a block.
+///
+/// # Invariants
+///
+/// For every block `x`, `from_yaml(&to_yaml(&x)?)` returns `x`.
+///
+/// # Examples
+///
+/// ```
+/// use bem::from_yaml;
+///
+/// let bem_block = from_yaml("name: media-player\nmodifiers: [dark]\nelements: []").unwrap();
+///
+/// assert_eq!(bem_block.modifiers, vec!["dark"]);
+/// ```
+pub fn from_yaml(yaml: &str) -> Result<BEMBlock> {
+	serde_yaml::from_str(yaml).map_err(Error::Yaml)
+}
+
+/// The YAML representation of blocks, as written by [`to_yaml`] and read by [`from_yaml`].
+pub struct Yaml;
+
+impl BemFormat for Yaml {
+	type Error = Error;
+
+	fn serialize(block: &BEMBlock) -> Result<String> {
+		to_yaml(block)
+	}
+
+	fn deserialize(s: &str) -> Result<BEMBlock> {
+		from_yaml(s)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ from_yaml, to_yaml, Yaml };
+	use crate::generate::{ generate, GenerateConfig };
+	use crate::{ decode, encode, parse };
+
+	#[test]
+	fn test_round_trip() {
+		let bem_block = parse("media-player[dark]\nbutton[fast-forward,rewind]\ntimeline").unwrap();
+		let yaml = to_yaml(&bem_block).unwrap();
+
+		insta::assert_snapshot!(yaml);
+		assert_eq!(from_yaml(&yaml).unwrap(), bem_block);
+		assert_eq!(decode::<Yaml>(&encode::<Yaml>(&bem_block).unwrap()).unwrap(), bem_block);
+	}
+
+	#[test]
+	fn test_round_trip_generated_blocks() {
+		for seed in 0..64 {
+			let bem_block = generate(seed, &GenerateConfig::default());
+
+			assert_eq!(from_yaml(&to_yaml(&bem_block).unwrap()).unwrap(), bem_block, "seed {}", seed);
+		}
+	}
+
+	#[test]
+	fn test_invalid_yaml() {
+		let missing = from_yaml("name: media-player").unwrap_err();
+		let malformed = from_yaml("name: [media-player").unwrap_err();
+
+		assert_eq!(missing.code(), "yaml");
+		assert!(missing.to_string().starts_with("invalid YAML: missing field `modifiers`"));
+		assert_eq!(malformed.code(), "yaml");
+	}
+}